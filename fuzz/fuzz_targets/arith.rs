@@ -0,0 +1,67 @@
+//! Differential fuzzing of `ApInt` arithmetic against `num_bigint::BigInt`.
+//!
+//! The input is split into two little-endian two's complement byte strings,
+//! the first byte giving the length of the first. Both are parsed into each
+//! type and the results of every operation must agree.
+//!
+//! Run with `cargo +nightly fuzz run arith` from the repository root.
+
+#![no_main]
+
+use apa::ApInt;
+use libfuzzer_sys::fuzz_target;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::Zero;
+
+/// The largest shift tried, to keep the shifted values small.
+const MAX_SHIFT: usize = 1 << 10;
+
+fn check(op: &str, int: &ApInt, big: &BigInt) {
+    int.assert_valid();
+    assert_eq!(
+        int.to_signed_bytes_le(),
+        big.to_signed_bytes_le(),
+        "`{}` differs: apa = {}, num-bigint = {}",
+        op,
+        int,
+        big,
+    );
+}
+
+fuzz_target!(|data: &[u8]| {
+    let (len, data) = match data.split_first() {
+        Some((&len, data)) => (len as usize, data),
+        None => return,
+    };
+    let (a, b) = data.split_at(len.min(data.len()));
+
+    let (x, y) = (ApInt::from_signed_bytes_le(a), ApInt::from_signed_bytes_le(b));
+    let (bx, by) = (BigInt::from_signed_bytes_le(a), BigInt::from_signed_bytes_le(b));
+    check("parse a", &x, &bx);
+    check("parse b", &y, &by);
+
+    check("a + b", &(&x + &y), &(&bx + &by));
+    check("a - b", &(&x - &y), &(&bx - &by));
+    check("a * b", &(&x * &y), &(&bx * &by));
+    check("-a", &(-&x), &(-&bx));
+
+    if !by.is_zero() {
+        check("a / b", &(&x / &y), &(&bx / &by));
+        check("a % b", &(&x % &y), &(&bx % &by));
+        check("a.div_floor(b)", &x.div_floor(&y), &bx.div_floor(&by));
+        check("a.mod_floor(b)", &x.mod_floor(&y), &bx.mod_floor(&by));
+    }
+
+    check("gcd(a, b)", &x.gcd(&y), &bx.gcd(&by));
+
+    // The first two bytes of `b` give the shift, so that every amount below
+    // `MAX_SHIFT` can be reached.
+    let shift = match *b {
+        [] => 0,
+        [lo] => usize::from(lo),
+        [lo, hi, ..] => usize::from(u16::from_le_bytes([lo, hi])) % MAX_SHIFT,
+    };
+    check("a << n", &(&x << shift), &(&bx << shift));
+    check("a >> n", &(&x >> shift), &(&bx >> shift));
+});
@@ -2,12 +2,30 @@ cfg_if::cfg_if! {
     if #[cfg(feature = "std")] {
         pub use std::alloc::{alloc, alloc_zeroed, dealloc, handle_alloc_error, realloc};
 
+        pub use std::borrow::Cow;
+        pub use std::boxed::Box;
+        pub use std::collections::HashMap;
+        #[cfg(feature = "pool")]
+        pub use std::collections::BTreeSet;
+        pub use std::string::String;
+        #[cfg(feature = "pool")]
+        pub use std::sync::Arc;
+        pub use std::vec;
         pub use std::vec::Vec;
     } else {
         extern crate alloc;
 
         pub use alloc::alloc::{alloc, alloc_zeroed, dealloc, handle_alloc_error, realloc};
 
+        pub use alloc::borrow::Cow;
+        pub use alloc::boxed::Box;
+        pub use alloc::collections::BTreeMap;
+        #[cfg(feature = "pool")]
+        pub use alloc::collections::BTreeSet;
+        pub use alloc::string::String;
+        #[cfg(feature = "pool")]
+        pub use alloc::sync::Arc;
+        pub use alloc::vec;
         pub use alloc::vec::Vec;
     }
 }
@@ -0,0 +1,794 @@
+use core::convert::TryFrom;
+use core::num::NonZeroUsize;
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+use core::ops::{Shl, ShlAssign, Shr, ShrAssign};
+
+use crate::alloc::Vec;
+use crate::apint::ApInt;
+use crate::limb::Limb;
+use crate::ll;
+
+impl ApInt {
+    /// Inverts every bit of `self` in place.
+    ///
+    /// This never allocates, since the bitwise complement of an int always
+    /// fits in the same number of limbs.
+    pub fn bitnot_assign(&mut self) {
+        // The complement of a normalized int is also normalized.
+        ll::not_assign(self.as_limbs_mut());
+    }
+}
+
+impl ApInt {
+    /// Checked shift left.
+    ///
+    /// Computes `self << bits`, returning `None` if the [`bits`](ApInt::bits)
+    /// of the result would exceed the bit budget `max_bits`. This prevents an
+    /// untrusted shift amount from causing an enormous allocation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let int = ApInt::from(-3);
+    /// assert_eq!(int.checked_shl(62, 64), Some(ApInt::from(-3i128 << 62)));
+    /// assert_eq!(int.checked_shl(63, 64), None);
+    /// ```
+    pub fn checked_shl(&self, bits: usize, max_bits: usize) -> Option<ApInt> {
+        match self.bits() {
+            // Zero stays zero however far it is shifted.
+            0 => Some(ApInt::ZERO),
+            len => match len.checked_add(bits) {
+                Some(len) if len <= max_bits => Some(shl(self, bits)),
+                _ => None,
+            },
+        }
+    }
+
+    /// Checked shift left by an `ApInt` amount.
+    ///
+    /// Computes `self << bits`, returning `None` if `bits` is negative or the
+    /// [`bits`](ApInt::bits) of the result would exceed the bit budget
+    /// `max_bits`. Unlike the `<<` operator, this never panics, so it is
+    /// suitable for shift amounts from untrusted input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let int = ApInt::from(5);
+    /// assert_eq!(int.checked_shl_by(&ApInt::from(4), 64), Some(ApInt::from(80)));
+    /// assert_eq!(int.checked_shl_by(&ApInt::from(-1), 64), None);
+    /// assert_eq!(int.checked_shl_by(&(ApInt::ONE << 100), 64), None);
+    /// ```
+    pub fn checked_shl_by(&self, bits: &ApInt, max_bits: usize) -> Option<ApInt> {
+        if bits.is_negative() {
+            return None;
+        }
+
+        match shift_amount(bits) {
+            Some(bits) => self.checked_shl(bits, max_bits),
+            None if self.bits() == 0 => Some(ApInt::ZERO),
+            None => None,
+        }
+    }
+
+    /// Checked shift right.
+    ///
+    /// Computes `self >> bits`, returning `None` if `bits` exceeds the bit
+    /// budget `max_bits`.
+    #[inline]
+    pub fn checked_shr(&self, bits: usize, max_bits: usize) -> Option<ApInt> {
+        if bits > max_bits {
+            None
+        } else {
+            Some(shr(self, bits))
+        }
+    }
+}
+
+impl ApInt {
+    /// Returns `self * 2^k`.
+    ///
+    /// This is always computed with a shift, never a general multiplication.
+    #[inline]
+    pub fn mul_pow2(&self, k: usize) -> ApInt {
+        shl(self, k)
+    }
+
+    /// Returns `self / 2^k`, rounding towards negative infinity.
+    ///
+    /// This is always computed with a shift, never a general division.
+    #[inline]
+    pub fn div_pow2_floor(&self, k: usize) -> ApInt {
+        shr(self, k)
+    }
+
+    /// Returns `self / 2^k`, rounding towards positive infinity.
+    ///
+    /// This is always computed with a shift, never a general division.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// assert_eq!(ApInt::from(7).div_pow2_ceil(2), ApInt::from(2));
+    /// assert_eq!(ApInt::from(-7).div_pow2_ceil(2), ApInt::from(-1));
+    /// ```
+    pub fn div_pow2_ceil(&self, k: usize) -> ApInt {
+        let q = shr(self, k);
+        // Round up if any of the bits shifted out are set.
+        match self.trailing_zeros() {
+            Some(tz) if tz < k => q + ApInt::ONE,
+            _ => q,
+        }
+    }
+
+    /// Returns `self` modulo `2^k`, which is in the range `0..2^k` even when
+    /// `self` is negative.
+    ///
+    /// This is always computed with a mask, never a general division, and is
+    /// the same as [`low_bits(k)`](ApInt::low_bits).
+    #[inline]
+    pub fn mod_pow2(&self, k: usize) -> ApInt {
+        low_bits(self, k)
+    }
+
+    /// Returns the inverse of `self` modulo `2^k`, in the range `0..2^k`, or
+    /// `None` if `self` is even.
+    ///
+    /// The inverse is found by Newton iteration from the inverse of the low
+    /// limb, with each step doubling the number of correct bits, which is much
+    /// cheaper than a general modular inverse.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let a = ApInt::from(-7);
+    /// let inv = a.inv_mod_pow2(256).unwrap();
+    /// assert_eq!((a * inv).mod_pow2(256), ApInt::ONE);
+    ///
+    /// assert_eq!(ApInt::from(6).inv_mod_pow2(64), None);
+    /// ```
+    pub fn inv_mod_pow2(&self, k: usize) -> Option<ApInt> {
+        if self.is_even() {
+            return None;
+        }
+
+        // The inverse of the low limb is correct modulo `2^BITS`.
+        let mut inv = ApInt::from(ll::inverse_1(self.as_limbs()[0]).repr());
+        let mut bits = Limb::BITS;
+        while bits < k {
+            bits = bits.saturating_mul(2).min(k);
+            // If `a * x == 1 - e` then `a * x * (1 + e) == 1 - e^2`, which
+            // doubles the number of correct bits.
+            let e = ApInt::ONE - low_bits(&(low_bits(self, bits) * &inv), bits);
+            inv = low_bits(&(&inv + &inv * e), bits);
+        }
+        Some(low_bits(&inv, k))
+    }
+}
+
+impl ApInt {
+    /// Splits `self` at bit `n`, returning the low `n` bits and the remaining
+    /// high part.
+    ///
+    /// The low part is always non-negative and the high part is `self >> n`,
+    /// so that `self == (high << n) + low`, even for negative values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let (low, high) = ApInt::from(-3).split_at_bit(4);
+    /// assert_eq!(low, ApInt::from(13));
+    /// assert_eq!(high, ApInt::from(-1));
+    /// ```
+    pub fn split_at_bit(&self, n: usize) -> (ApInt, ApInt) {
+        (low_bits(self, n), shr(self, n))
+    }
+
+    /// Returns the low `n` bits of the two's complement representation of
+    /// `self`.
+    ///
+    /// This is `self` reduced modulo `2^n`, so the result is never negative.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// assert_eq!(ApInt::from(-1).low_bits(8), ApInt::from(0xff));
+    /// ```
+    #[inline]
+    pub fn low_bits(&self, n: usize) -> ApInt {
+        low_bits(self, n)
+    }
+
+    /// Returns the bits of `self` above the low `n` bits.
+    ///
+    /// This is `self >> n`, rounding towards negative infinity.
+    #[inline]
+    pub fn high_bits(&self, n: usize) -> ApInt {
+        shr(self, n)
+    }
+
+    /// Reduces `self` modulo `2^n` in place, keeping only its low `n` bits.
+    ///
+    /// The storage is reallocated to fit the result, so this grows `self` when
+    /// it is negative and has fewer limbs than the result, and shrinks it when
+    /// the result needs fewer limbs. A non-negative value already below `2^n`
+    /// is left untouched.
+    pub fn mask_assign(&mut self, n: usize) {
+        // Every bit of a small enough non-negative value is kept.
+        if !self.is_negative() && self.significant_bits() <= n {
+            return;
+        }
+
+        let limb_len = n / Limb::BITS;
+        let bit_len = (n % Limb::BITS) as u32;
+
+        // An extra limb is needed for the partial high limb, or the zero sign.
+        // SAFETY: `limb_len + 1` is at least 1.
+        self.resize(unsafe { NonZeroUsize::new_unchecked(limb_len + 1) });
+        let high = &mut self.as_limbs_mut()[limb_len];
+        *high = Limb(high.repr() & ((1 << bit_len) - 1));
+
+        self.normalize();
+    }
+
+    /// Concatenates the bits of `high` above the low `low_width` bits of `low`.
+    ///
+    /// This is the inverse of [`split_at_bit`](ApInt::split_at_bit), returning
+    /// `(high << low_width) + low` where `low` is first reduced modulo
+    /// `2^low_width`.
+    pub fn concat_bits(high: &ApInt, low: &ApInt, low_width: usize) -> ApInt {
+        shl(high, low_width) | low_bits(low, low_width)
+    }
+
+    /// Returns the number of bits in the magnitude of `self`, which is zero for
+    /// zero.
+    ///
+    /// This is the bit length of the absolute value, so `2^(bits - 1) <=
+    /// |self| < 2^bits` for non-zero values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// assert_eq!(ApInt::from(255).bits(), 8);
+    /// assert_eq!(ApInt::from(-256).bits(), 9);
+    /// assert_eq!(ApInt::ZERO.bits(), 0);
+    /// ```
+    pub fn bits(&self) -> usize {
+        let mag = self.magnitude();
+        match mag.last() {
+            Some(high) => mag.len() * Limb::BITS - high.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    /// Returns an upper bound on the [`bits`](ApInt::bits) of `self * other`,
+    /// without computing the product.
+    ///
+    /// The bound is exact or one more than the result, so it can be used to
+    /// reserve space for a product or reject one that would be too large.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let (a, b) = (ApInt::from(255), ApInt::from(-3));
+    /// assert_eq!(a.estimate_product_bits(&b), 10);
+    /// assert_eq!((a * b).bits(), 10);
+    /// ```
+    pub fn estimate_product_bits(&self, other: &ApInt) -> usize {
+        let (m, n) = (self.bits(), other.bits());
+        if m == 0 || n == 0 {
+            return 0;
+        }
+        // `2^(m - 1) <= |self| < 2^m`, so the product has `m + n - 1` or
+        // `m + n` bits.
+        m + n
+    }
+
+    /// Returns an upper bound on the [`bits`](ApInt::bits) of `self` raised to
+    /// the power `exp`, without computing the power, or `None` if the bound
+    /// does not fit in a `usize`.
+    ///
+    /// The bound is at most `exp - 1` more than the result.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let base = ApInt::from(10);
+    /// assert_eq!(base.estimate_pow_bits(3), Some(12));
+    /// assert_eq!(base.pow_int(&ApInt::from(3)).unwrap().bits(), 10);
+    /// assert_eq!(base.estimate_pow_bits(u64::MAX), None);
+    /// ```
+    pub fn estimate_pow_bits(&self, exp: u64) -> Option<usize> {
+        if exp == 0 {
+            return Some(1);
+        }
+        // `2^(b - 1) <= |self| < 2^b`, so `|self^exp|` has between
+        // `exp * (b - 1) + 1` and `exp * b` bits.
+        usize::try_from(exp).ok()?.checked_mul(self.bits())
+    }
+
+    /// Returns the bit at index `i` of the two's complement representation of
+    /// `self`.
+    ///
+    /// Bits beyond the stored limbs are copies of the sign bit.
+    #[inline]
+    pub fn bit(&self, i: usize) -> bool {
+        let limb = match self.as_limbs().get(i / Limb::BITS) {
+            Some(&limb) => limb,
+            None => self.high_limb().sign_mask(),
+        };
+        limb.repr() >> (i % Limb::BITS) & 1 == 1
+    }
+
+    /// Returns the number of ones in the binary representation of `self`, or
+    /// `None` if `self` is negative and so has infinitely many ones.
+    pub fn count_ones(&self) -> Option<usize> {
+        if self.is_negative() {
+            None
+        } else {
+            Some(
+                self.as_limbs()
+                    .iter()
+                    .map(|limb| limb.repr().count_ones() as usize)
+                    .sum(),
+            )
+        }
+    }
+
+    /// Returns the number of zeros in the two's complement representation of
+    /// `self`, or `None` if `self` is non-negative and so has infinitely many
+    /// zeros.
+    pub fn count_zeros(&self) -> Option<usize> {
+        if self.is_negative() {
+            Some(
+                self.as_limbs()
+                    .iter()
+                    .map(|limb| limb.repr().count_zeros() as usize)
+                    .sum(),
+            )
+        } else {
+            None
+        }
+    }
+
+    /// Returns the number of bits that differ between the two's complement
+    /// representations of `self` and `other`, or `None` if their signs differ
+    /// and so infinitely many bits do.
+    ///
+    /// This is the number of ones in `self ^ other`, counted without computing
+    /// it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// assert_eq!(ApInt::from(0b1011).hamming_distance(&ApInt::from(0b0110)), Some(3));
+    /// assert_eq!(ApInt::from(-1).hamming_distance(&ApInt::from(-2)), Some(1));
+    /// assert_eq!(ApInt::from(-1).hamming_distance(&ApInt::ZERO), None);
+    /// ```
+    pub fn hamming_distance(&self, other: &ApInt) -> Option<usize> {
+        if self.is_negative() != other.is_negative() {
+            return None;
+        }
+
+        let (x, y) = (self.as_limbs(), other.as_limbs());
+        let sign = self.high_limb().sign_mask();
+        let len = x.len().max(y.len());
+        Some(
+            (0..len)
+                .map(|i| {
+                    let x = x.get(i).copied().unwrap_or(sign);
+                    let y = y.get(i).copied().unwrap_or(sign);
+                    (x ^ y).repr().count_ones() as usize
+                })
+                .sum(),
+        )
+    }
+
+    /// Returns the number of trailing zeros in the binary representation of
+    /// `self`, or `None` if `self` is zero.
+    pub fn trailing_zeros(&self) -> Option<usize> {
+        let limbs = self.as_limbs();
+        let i = limbs.iter().position(|&limb| limb != Limb::ZERO)?;
+        Some(i * Limb::BITS + limbs[i].trailing_zeros() as usize)
+    }
+
+    /// Returns the number of trailing ones in the two's complement
+    /// representation of `self`, or `None` if `self` is `-1`.
+    pub fn trailing_ones(&self) -> Option<usize> {
+        let limbs = self.as_limbs();
+        let i = limbs.iter().position(|&limb| limb != Limb::ONES)?;
+        Some(i * Limb::BITS + (!limbs[i]).trailing_zeros() as usize)
+    }
+
+    /// Returns the non-adjacent form of `self`, least significant digit first.
+    ///
+    /// This is the same as [`to_wnaf(2)`](ApInt::to_wnaf), with every digit
+    /// being `-1`, `0` or `1` and no two adjacent digits being non-zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// // 7 = 8 - 1
+    /// assert_eq!(ApInt::from(7).to_naf(), [-1, 0, 0, 1]);
+    /// ```
+    #[inline]
+    pub fn to_naf(&self) -> Vec<i8> {
+        self.to_wnaf(2)
+    }
+
+    /// Returns the width-`width` non-adjacent form of `self`, least
+    /// significant digit first.
+    ///
+    /// Each non-zero digit is odd with an absolute value less than
+    /// `2^(width - 1)`, and is followed by at least `width - 1` zero digits.
+    /// The digits `d` satisfy `self == sum(d[i] * 2^i)`, and zero has no
+    /// digits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is not within the range `2..=8`.
+    pub fn to_wnaf(&self, width: u32) -> Vec<i8> {
+        assert!(
+            (2..=8).contains(&width),
+            "width must be within the range 2..=8"
+        );
+
+        let negative = self.is_negative();
+        let k = self.abs();
+        let bits = k.significant_bits();
+
+        let mut digits = Vec::with_capacity(bits + 1);
+        let mut carry = 0;
+        let mut i = 0;
+        while i < bits || carry != 0 {
+            match k.bit(i) as u32 + carry {
+                // The digit is zero, and the carry passes on unchanged.
+                0 | 2 => {
+                    digits.push(0);
+                    i += 1;
+                }
+                _ => {
+                    // Take the window of `width` bits, which is odd, as a
+                    // signed digit. A negative digit leaves a carry out of the
+                    // top of the window.
+                    let mut window = carry;
+                    for j in 0..width {
+                        window += (k.bit(i + j as usize) as u32) << j;
+                    }
+                    window &= (1 << width) - 1;
+
+                    let digit = if window < 1 << (width - 1) {
+                        carry = 0;
+                        window as i32
+                    } else {
+                        carry = 1;
+                        window as i32 - (1 << width)
+                    };
+
+                    digits.push(if negative { -digit } else { digit } as i8);
+                    digits.resize(digits.len() + width as usize - 1, 0);
+                    i += width as usize;
+                }
+            }
+        }
+
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        digits
+    }
+
+    /// Returns the binary reflected Gray code of `self`, `self ^ (self >> 1)`.
+    ///
+    /// Consecutive ints have Gray codes differing in exactly one bit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is negative.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// assert_eq!(ApInt::from(6).to_gray(), ApInt::from(0b101));
+    /// assert_eq!(ApInt::from(0b101).from_gray(), ApInt::from(6));
+    /// ```
+    pub fn to_gray(&self) -> ApInt {
+        assert!(
+            !self.is_negative(),
+            "Gray codes of negative values are undefined"
+        );
+        bitxor(self, &shr(self, 1))
+    }
+
+    /// Returns the int whose binary reflected Gray code is `self`, the inverse
+    /// of [`to_gray`](ApInt::to_gray).
+    ///
+    /// Each bit of the result is the parity of the bits of `self` at or above
+    /// it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is negative.
+    pub fn from_gray(&self) -> ApInt {
+        assert!(
+            !self.is_negative(),
+            "Gray codes of negative values are undefined"
+        );
+
+        let mut r = self.clone();
+        // The parity of the bits above the current limb, as a mask.
+        let mut parity = Limb::ZERO;
+        for limb in r.as_limbs_mut().iter_mut().rev() {
+            // Take the parity of the bits at or above each bit of the limb.
+            let mut x = limb.repr();
+            let mut shift = 1;
+            while shift < Limb::BITS {
+                x ^= x >> shift;
+                shift <<= 1;
+            }
+            *limb = Limb(x) ^ parity;
+            parity = Limb(limb.repr() << (Limb::BITS - 1)).sign_mask();
+        }
+        // Only zeros lie above the highest set bit of `self`, so that bit keeps
+        // its position and everything above it stays clear. The result has the
+        // same significant bits and is still normalized.
+        r
+    }
+}
+
+impl Not for ApInt {
+    type Output = ApInt;
+
+    #[inline]
+    fn not(mut self) -> ApInt {
+        self.bitnot_assign();
+        self
+    }
+}
+
+impl Not for &ApInt {
+    type Output = ApInt;
+
+    #[inline]
+    fn not(self) -> ApInt {
+        !self.clone()
+    }
+}
+
+/// Returns `x << bits`.
+pub(crate) fn shl(x: &ApInt, bits: usize) -> ApInt {
+    // Zero stays zero, however far it is shifted.
+    if *x == ApInt::ZERO {
+        return ApInt::ZERO;
+    }
+
+    let limb_shift = bits / Limb::BITS;
+    let bit_shift = (bits % Limb::BITS) as u32;
+
+    let src = x.as_limbs();
+    let sign = x.high_limb().sign_mask();
+
+    // An extra limb is needed for the bits shifted out of the high limb.
+    let len = src.len() + limb_shift + 1;
+    // SAFETY: `len` is at least 2.
+    let mut r = ApInt::zeroed(unsafe { NonZeroUsize::new_unchecked(len) });
+
+    let dst = r.as_limbs_mut();
+    let high = ll::shl(&mut dst[limb_shift..len - 1], src, bit_shift);
+    dst[len - 1] = if bit_shift == 0 {
+        sign
+    } else {
+        Limb((sign.repr() << bit_shift) | high.repr())
+    };
+
+    r.normalize();
+    r
+}
+
+/// Returns `x >> bits`, rounding towards negative infinity.
+pub(crate) fn shr(x: &ApInt, bits: usize) -> ApInt {
+    let limb_shift = bits / Limb::BITS;
+    let bit_shift = (bits % Limb::BITS) as u32;
+
+    let src = x.as_limbs();
+    let sign = x.high_limb().sign_mask();
+
+    // Every bit is shifted out, leaving only the sign.
+    if limb_shift >= src.len() {
+        return ApInt::from_limb(sign);
+    }
+
+    let len = src.len() - limb_shift;
+    // SAFETY: `len` is at least 1.
+    let mut r = ApInt::zeroed(unsafe { NonZeroUsize::new_unchecked(len) });
+
+    let dst = r.as_limbs_mut();
+    ll::shr(dst, &src[limb_shift..], bit_shift);
+    if bit_shift != 0 {
+        // Shift the sign into the vacated high bits.
+        dst[len - 1] = Limb(dst[len - 1].repr() | (sign.repr() << (Limb::BITS as u32 - bit_shift)));
+    }
+
+    r.normalize();
+    r
+}
+
+/// Returns the low `bits` bits of `x`, as a non-negative int.
+///
+/// This is `x` reduced modulo `2^bits`.
+pub(crate) fn low_bits(x: &ApInt, bits: usize) -> ApInt {
+    // Every bit of a small enough non-negative value is kept.
+    if !x.is_negative() && x.significant_bits() <= bits {
+        return x.clone();
+    }
+
+    let limb_len = bits / Limb::BITS;
+    let bit_len = (bits % Limb::BITS) as u32;
+
+    let src = x.as_limbs();
+    let sign = x.high_limb().sign_mask();
+
+    // An extra limb is needed for the partial high limb, or the zero sign.
+    let len = limb_len + 1;
+    // SAFETY: `len` is at least 1.
+    let mut r = ApInt::zeroed(unsafe { NonZeroUsize::new_unchecked(len) });
+
+    let dst = r.as_limbs_mut();
+    for (i, limb) in dst.iter_mut().enumerate() {
+        *limb = src.get(i).copied().unwrap_or(sign);
+    }
+    dst[limb_len] = Limb(dst[limb_len].repr() & ((1 << bit_len) - 1));
+
+    r.normalize();
+    r
+}
+
+/// Applies the bitwise operation `op` to each pair of limbs of `x` and `y`,
+/// sign extending the shorter operand.
+///
+/// The limbs both operands hold are combined by `op_n`, and the limbs above
+/// the shorter operand by `op`.
+#[inline]
+fn bitop(
+    x: &ApInt,
+    y: &ApInt,
+    op_n: fn(&mut [Limb], &[Limb], &[Limb]),
+    op: impl Fn(Limb, Limb) -> Limb,
+) -> ApInt {
+    // Order the operands so that `x` is the longer one, which is fine for a
+    // commutative operation.
+    let (x, y) = if x.len >= y.len { (x, y) } else { (y, x) };
+    let y_sign = y.high_limb().sign_mask();
+    let (x, y) = (x.as_limbs(), y.as_limbs());
+
+    // SAFETY: `x` has at least 1 limb.
+    let mut r = ApInt::zeroed(unsafe { NonZeroUsize::new_unchecked(x.len()) });
+
+    let (low, high) = r.as_limbs_mut().split_at_mut(y.len());
+    op_n(low, &x[..y.len()], y);
+    for (limb, &x) in high.iter_mut().zip(&x[y.len()..]) {
+        *limb = op(x, y_sign);
+    }
+
+    r.normalize();
+    r
+}
+
+fn bitand(x: &ApInt, y: &ApInt) -> ApInt {
+    bitop(x, y, ll::and_n, |x, y| x & y)
+}
+
+fn bitor(x: &ApInt, y: &ApInt) -> ApInt {
+    bitop(x, y, ll::or_n, |x, y| x | y)
+}
+
+fn bitxor(x: &ApInt, y: &ApInt) -> ApInt {
+    bitop(x, y, ll::xor_n, |x, y| x ^ y)
+}
+
+impl_binop! {
+    BitAnd, bitand, BitAndAssign, bitand_assign => bitand;
+    BitOr, bitor, BitOrAssign, bitor_assign => bitor;
+    BitXor, bitxor, BitXorAssign, bitxor_assign => bitxor;
+}
+
+macro_rules! impl_shift {
+    ($($trait:ident, $fn:ident, $assign_trait:ident, $assign_fn:ident => $op:ident;)*) => {
+        $(
+            impl $trait<usize> for ApInt {
+                type Output = ApInt;
+
+                #[inline]
+                fn $fn(self, bits: usize) -> ApInt {
+                    $op(&self, bits)
+                }
+            }
+
+            impl $trait<usize> for &ApInt {
+                type Output = ApInt;
+
+                #[inline]
+                fn $fn(self, bits: usize) -> ApInt {
+                    $op(self, bits)
+                }
+            }
+
+            impl $assign_trait<usize> for ApInt {
+                #[inline]
+                fn $assign_fn(&mut self, bits: usize) {
+                    *self = $op(self, bits);
+                }
+            }
+        )*
+    };
+}
+
+impl_shift! {
+    Shl, shl, ShlAssign, shl_assign => shl;
+    Shr, shr, ShrAssign, shr_assign => shr;
+}
+
+/// Returns the shift amount held by `bits`, or `None` if it is too large to
+/// fit in a `usize`.
+///
+/// # Panics
+///
+/// Panics if `bits` is negative.
+fn shift_amount(bits: &ApInt) -> Option<usize> {
+    if bits.is_negative() {
+        panic!("attempt to shift by a negative amount");
+    }
+
+    match *bits.magnitude() {
+        [] => Some(0),
+        [limb] => Some(limb.repr() as usize),
+        _ => None,
+    }
+}
+
+fn shl_by(x: &ApInt, bits: &ApInt) -> ApInt {
+    match shift_amount(bits) {
+        Some(bits) => shl(x, bits),
+        None => panic!("attempt to shift left with overflow"),
+    }
+}
+
+fn shr_by(x: &ApInt, bits: &ApInt) -> ApInt {
+    match shift_amount(bits) {
+        Some(bits) => shr(x, bits),
+        // Every bit is shifted out, leaving only the sign.
+        None => ApInt::from_limb(x.high_limb().sign_mask()),
+    }
+}
+
+// Shifting by an `ApInt` panics if the shift amount is negative, or if a left
+// shift amount does not fit in a `usize`. Use `ApInt::checked_shl_by` for
+// untrusted shift amounts.
+impl_binop! {
+    Shl, shl, ShlAssign, shl_assign => shl_by;
+    Shr, shr, ShrAssign, shr_assign => shr_by;
+}
@@ -0,0 +1,61 @@
+use borsh::io::{Error, ErrorKind, Read, Result, Write};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::alloc::Vec;
+use crate::apint::ApInt;
+use crate::sign::Sign;
+
+/// Serializes the value as its sign, as an `i8` of `-1`, `0` or `1`, followed
+/// by the little-endian bytes of its magnitude as a `Vec<u8>`.
+///
+/// The encoding is canonical: the magnitude has no high zero bytes, so zero has
+/// an empty magnitude, and each value has exactly one encoding.
+impl BorshSerialize for ApInt {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        let (words, sign) = self.clone().into_limbs_le();
+        let mut mag: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+        while mag.last() == Some(&0) {
+            mag.pop();
+        }
+
+        sign.to_i8().serialize(writer)?;
+        mag.serialize(writer)
+    }
+}
+
+/// Deserializes a value serialized by [`BorshSerialize`], rejecting encodings
+/// that are not canonical.
+impl BorshDeserialize for ApInt {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<ApInt> {
+        let sign = match i8::deserialize_reader(reader)? {
+            -1 => Sign::Negative,
+            0 => Sign::Zero,
+            1 => Sign::Positive,
+            _ => return Err(Error::new(ErrorKind::InvalidData, "invalid sign")),
+        };
+        let mag = Vec::<u8>::deserialize_reader(reader)?;
+
+        if mag.last() == Some(&0) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "magnitude has high zero bytes",
+            ));
+        }
+        if mag.is_empty() != (sign == Sign::Zero) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "sign does not match magnitude",
+            ));
+        }
+
+        let words = mag
+            .chunks(8)
+            .map(|chunk| {
+                let mut bytes = [0; 8];
+                bytes[..chunk.len()].copy_from_slice(chunk);
+                u64::from_le_bytes(bytes)
+            })
+            .collect();
+        Ok(ApInt::from_limbs_le(words, sign))
+    }
+}
@@ -1,8 +1,60 @@
 use core::cmp::{Eq, Ord, Ordering, PartialEq, PartialOrd};
+use core::hash::{Hash, Hasher};
 
+use crate::apint::convert::f64_parts;
 use crate::apint::{ApInt, LimbData};
 use crate::limb::Limb;
 
+impl ApInt {
+    /// Returns a reference to the smaller of `self` and `other`, without
+    /// cloning either.
+    ///
+    /// Returns `self` if the two are equal, matching [`core::cmp::min`].
+    #[inline]
+    pub fn min_ref<'a>(&'a self, other: &'a ApInt) -> &'a ApInt {
+        if other < self { other } else { self }
+    }
+
+    /// Returns a reference to the larger of `self` and `other`, without
+    /// cloning either.
+    ///
+    /// Returns `other` if the two are equal, matching [`core::cmp::max`].
+    #[inline]
+    pub fn max_ref<'a>(&'a self, other: &'a ApInt) -> &'a ApInt {
+        if other < self { self } else { other }
+    }
+
+    /// Returns a reference to `self` restricted to the interval `lo..=hi`,
+    /// without cloning any of the values.
+    ///
+    /// This is named so as not to be shadowed by [`Ord::clamp`], which takes
+    /// its arguments by value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lo > hi`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let (lo, hi) = (ApInt::from(-10), ApInt::from(10));
+    /// assert_eq!(*ApInt::from(-11).clamp_ref(&lo, &hi), lo);
+    /// assert_eq!(*ApInt::from(3).clamp_ref(&lo, &hi), ApInt::from(3));
+    /// ```
+    pub fn clamp_ref<'a>(&'a self, lo: &'a ApInt, hi: &'a ApInt) -> &'a ApInt {
+        assert!(lo <= hi, "lower bound must not exceed the upper bound");
+        if self < lo {
+            lo
+        } else if self > hi {
+            hi
+        } else {
+            self
+        }
+    }
+}
+
 impl PartialEq for ApInt {
     fn eq(&self, other: &Self) -> bool {
         match (self.data(), other.data()) {
@@ -42,6 +94,13 @@ impl PartialEq for ApInt {
 
 impl Eq for ApInt {}
 
+impl Hash for ApInt {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Every int is normalized, so equal ints have equal limbs.
+        self.as_limbs().hash(state);
+    }
+}
+
 impl PartialOrd for ApInt {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -63,8 +122,8 @@ impl Ord for ApInt {
                 let r = unsafe { *r_ptr.add(r_len.get() - 1) };
 
                 // Compare sign bits.
-                let l_bit = l.repr_ne() >> SHIFT;
-                let r_bit = r.repr_ne() >> SHIFT;
+                let l_bit = l.repr() >> SHIFT;
+                let r_bit = r.repr() >> SHIFT;
 
                 match (l_bit, r_bit) {
                     (0, 1) => return Ordering::Greater,
@@ -100,7 +159,7 @@ impl Ord for ApInt {
                     let l = unsafe { *l_ptr.add(i as usize) };
                     // SAFETY: `i` is within the bounds of `r_ptr`.
                     let r = unsafe { *r_ptr.add(i as usize) };
-                    match l.repr_ne().cmp(&r.repr_ne()) {
+                    match l.repr().cmp(&r.repr()) {
                         Ordering::Equal => {}
                         ordering => return ordering,
                     }
@@ -120,7 +179,7 @@ impl Ord for ApInt {
 
                 // The heap value has a larger absolute value, so check its
                 // sign bit.
-                let r_bit = r.repr_ne() >> SHIFT;
+                let r_bit = r.repr() >> SHIFT;
 
                 match r_bit {
                     // Heap value is negative.
@@ -136,7 +195,7 @@ impl Ord for ApInt {
 
                 // The heap value has a larger absolute value, so check its
                 // sign bit.
-                let l_bit = l.repr_ne() >> SHIFT;
+                let l_bit = l.repr() >> SHIFT;
 
                 match l_bit {
                     // Heap value is negative.
@@ -149,12 +208,64 @@ impl Ord for ApInt {
     }
 }
 
+// Comparisons with floats are exact, never converting the int to a float, so
+// they remain correct for ints beyond the precision of `f64`.
+impl PartialEq<f64> for ApInt {
+    fn eq(&self, other: &f64) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd<f64> for ApInt {
+    fn partial_cmp(&self, other: &f64) -> Option<Ordering> {
+        if other.is_nan() {
+            return None;
+        }
+        if other.is_infinite() {
+            return Some(if *other > 0.0 {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            });
+        }
+
+        let (m, e) = f64_parts(*other);
+        let m = ApInt::from(m);
+        if e >= 0 {
+            return Some(self.cmp(&m.mul_pow2(e as usize)));
+        }
+
+        // Compare against the floor of the float, which is only equal to the
+        // float if no fractional bits are shifted out.
+        let shift = -e as usize;
+        let floor = m.div_pow2_floor(shift);
+        let exact = m.trailing_zeros().map_or(true, |tz| tz >= shift);
+        Some(match self.cmp(&floor) {
+            Ordering::Equal if !exact => Ordering::Less,
+            ordering => ordering,
+        })
+    }
+}
+
+impl PartialEq<ApInt> for f64 {
+    fn eq(&self, other: &ApInt) -> bool {
+        other == self
+    }
+}
+
+impl PartialOrd<ApInt> for f64 {
+    fn partial_cmp(&self, other: &ApInt) -> Option<Ordering> {
+        other.partial_cmp(self).map(Ordering::reverse)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     use core::cmp::Ordering;
-    use core::num::NonZeroUsize;
+
+    use crate::limb::LimbRepr;
 
     macro_rules! assert_cmp {
         ($l:expr, $r:expr, $ord:ident) => {{
@@ -260,15 +371,9 @@ mod tests {
         assert_cmp!(l, r, Less);
     }
 
-    // FIXME: Replace raw byte writing to set ApInt when API allows for it.
-
     #[test]
     fn heap_heap_neg_pos_3_2() {
-        let l = unsafe {
-            let mut l = ApInt::with_capacity(NonZeroUsize::new_unchecked(3));
-            l.limbs_mut().write_bytes(0xff, 3);
-            l
-        };
+        let l = ApInt::from_limbs(&[0, 0, LimbRepr::MAX]);
 
         #[cfg(target_pointer_width = "32")]
         let r = ApInt::from(i64::MAX);
@@ -285,11 +390,7 @@ mod tests {
         #[cfg(target_pointer_width = "64")]
         let l = ApInt::from(i128::MAX);
 
-        let r = unsafe {
-            let mut r = ApInt::with_capacity(NonZeroUsize::new_unchecked(3));
-            r.limbs_mut().write_bytes(0xff, 3);
-            r
-        };
+        let r = ApInt::from_limbs(&[0, 0, LimbRepr::MAX]);
 
         assert_cmp!(l, r, Greater);
     }
@@ -301,22 +402,14 @@ mod tests {
         #[cfg(target_pointer_width = "64")]
         let l = ApInt::from(i128::MIN);
 
-        let r = unsafe {
-            let mut r = ApInt::with_capacity(NonZeroUsize::new_unchecked(3));
-            r.limbs_mut().write_bytes(0xff, 3);
-            r
-        };
+        let r = ApInt::from_limbs(&[0, 0, LimbRepr::MAX]);
 
         assert_cmp!(l, r, Greater);
     }
 
     #[test]
     fn heap_heap_neg_neg_3_2() {
-        let l = unsafe {
-            let mut l = ApInt::with_capacity(NonZeroUsize::new_unchecked(3));
-            l.limbs_mut().write_bytes(0xff, 3);
-            l
-        };
+        let l = ApInt::from_limbs(&[0, 0, LimbRepr::MAX]);
 
         #[cfg(target_pointer_width = "32")]
         let r = ApInt::from(i64::MIN);
@@ -356,3 +449,24 @@ mod tests {
         assert_cmp!(l, r, Greater);
     }
 }
+
+/// Proof harnesses for [Kani](https://github.com/model-checking/kani), run
+/// with `cargo kani`.
+///
+/// The comparisons read limbs through raw pointers, so these check them on
+/// every pair of stack and heap ints of up to two limbs against the order of
+/// the same values as `i128`s.
+#[cfg(kani)]
+mod verification {
+    use super::*;
+
+    #[kani::proof]
+    #[kani::unwind(5)]
+    fn cmp_matches_i128() {
+        let (l, r): (i128, i128) = (kani::any(), kani::any());
+        let (l_int, r_int) = (ApInt::from(l), ApInt::from(r));
+
+        assert_eq!(l_int.cmp(&r_int), l.cmp(&r));
+        assert_eq!(l_int == r_int, l == r);
+    }
+}
@@ -1,8 +1,94 @@
-use core::mem::MaybeUninit;
-use core::num::NonZeroUsize;
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::fmt;
+use core::iter;
+use core::num::{NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroIsize};
+use core::num::{NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128, NonZeroUsize};
 
-use crate::apint::{ApInt, LimbData};
+use num_traits::ToPrimitive;
+
+use crate::alloc::{Box, Vec};
+use crate::apint::{ApInt, RoundingMode};
 use crate::limb::{Limb, LimbRepr};
+use crate::sign::Sign;
+
+impl ApInt {
+    /// Returns the two's complement bytes of `self`, least significant byte
+    /// first.
+    ///
+    /// The fewest bytes able to hold the value and its sign bit are returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// assert_eq!(ApInt::from(-129).to_signed_bytes_le(), [0x7f, 0xff]);
+    /// assert_eq!(ApInt::from(128).to_signed_bytes_le(), [0x80, 0x00]);
+    /// ```
+    pub fn to_signed_bytes_le(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.as_limbs().len() * Limb::SIZE);
+        for limb in self.as_limbs() {
+            bytes.extend_from_slice(&limb.repr().to_le_bytes());
+        }
+
+        // A high byte is redundant if it only holds the sign of the byte below.
+        while bytes.len() > 1 {
+            let (high, next) = (bytes[bytes.len() - 1], bytes[bytes.len() - 2]);
+            let sign = if next & 0x80 == 0 { 0x00 } else { 0xff };
+            if high != sign {
+                break;
+            }
+            bytes.pop();
+        }
+        bytes
+    }
+
+    /// Returns the two's complement bytes of `self`, most significant byte
+    /// first.
+    ///
+    /// The fewest bytes able to hold the value and its sign bit are returned.
+    pub fn to_signed_bytes_be(&self) -> Vec<u8> {
+        let mut bytes = self.to_signed_bytes_le();
+        bytes.reverse();
+        bytes
+    }
+
+    /// Creates an int from its two's complement bytes, least significant byte
+    /// first.
+    ///
+    /// No bytes are read as zero.
+    pub fn from_signed_bytes_le(bytes: &[u8]) -> ApInt {
+        let sign = match bytes.last() {
+            Some(&high) if high & 0x80 != 0 => 0xff,
+            Some(_) => 0x00,
+            None => return ApInt::ZERO,
+        };
+
+        let len = (bytes.len() + Limb::SIZE - 1) / Limb::SIZE;
+        // SAFETY: `bytes` is not empty, so `len` is at least 1.
+        let mut int = ApInt::zeroed(unsafe { NonZeroUsize::new_unchecked(len) });
+        for (limb, chunk) in int.as_limbs_mut().iter_mut().zip(bytes.chunks(Limb::SIZE)) {
+            // The high limb is sign extended if the bytes do not fill it.
+            let mut buf = [sign; Limb::SIZE];
+            buf[..chunk.len()].copy_from_slice(chunk);
+            *limb = Limb(LimbRepr::from_le_bytes(buf));
+        }
+
+        int.normalize();
+        int
+    }
+
+    /// Creates an int from its two's complement bytes, most significant byte
+    /// first.
+    ///
+    /// No bytes are read as zero.
+    pub fn from_signed_bytes_be(bytes: &[u8]) -> ApInt {
+        let mut bytes = bytes.to_vec();
+        bytes.reverse();
+        ApInt::from_signed_bytes_le(&bytes)
+    }
+}
 
 macro_rules! impl_from_prim {
     (unsigned: $($ty:ident),* $(,)?) => {
@@ -29,17 +115,15 @@ macro_rules! impl_from_prim {
                         // SAFETY: `factor + 1` is guaranteed to be greater than 1.
                         let capacity = unsafe { NonZeroUsize::new_unchecked(capacity) };
 
-                        let mut int = ApInt::with_capacity(capacity);
-
-                        let val = val.to_le();
-                        // SAFETY: This is safe since we are copying as many bytes as the smaller of
-                        //         the size of the value type or the capacity of the ApInt in bytes.
-                        unsafe {
-                            core::ptr::copy_nonoverlapping(
-                                &val as *const $ty as *const u8,
-                                int.limbs_mut().as_ptr() as *mut u8,
-                                SIZE_TY.min(capacity.get() * SIZE_LIMB),
-                            );
+                        let mut int = ApInt::zeroed(capacity);
+
+                        // Copy as many limbs as the value fills, any remaining limbs are
+                        // already zeroed.
+                        for (i, limb) in int.as_limbs_mut().iter_mut().enumerate() {
+                            let shift = i * BITS_LIMB;
+                            if shift < BITS_TY {
+                                *limb = Limb((val >> shift) as LimbRepr);
+                            }
                         }
 
                         int
@@ -88,17 +172,15 @@ macro_rules! impl_from_prim {
                         //          since `bits_val` >= `BITS_LIMB`.
                         let capacity = unsafe { NonZeroUsize::new_unchecked(capacity) };
 
-                        let mut int = ApInt::with_capacity(capacity);
-
-                        let val = val.to_le();
-                        // SAFETY: This is safe since we are copying as many bytes as the smaller of
-                        //         the size of the value type or the capacity of the ApInt in bytes.
-                        unsafe {
-                            core::ptr::copy_nonoverlapping(
-                                &val as *const $ty as *const u8,
-                                int.limbs_mut().as_ptr() as *mut u8,
-                                SIZE_TY.min(capacity.get() * SIZE_LIMB),
-                            );
+                        let mut int = ApInt::zeroed(capacity);
+
+                        // Copy as many limbs as the value fills, any remaining limbs are
+                        // already zeroed.
+                        for (i, limb) in int.as_limbs_mut().iter_mut().enumerate() {
+                            let shift = i * BITS_LIMB;
+                            if shift < BITS_TY {
+                                *limb = Limb((val >> shift) as LimbRepr);
+                            }
                         }
 
                         int
@@ -109,44 +191,293 @@ macro_rules! impl_from_prim {
     };
 }
 
+impl ApInt {
+    /// Creates an int from the 64-bit words of its magnitude, least significant
+    /// word first, and its sign.
+    ///
+    /// On 64-bit targets the vector's buffer becomes the storage of the int,
+    /// so the words are not copied unless the buffer must grow or shrink, or
+    /// the storage of ints is over-aligned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sign` is [`Sign::Zero`] and the magnitude is not zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::{ApInt, Sign};
+    ///
+    /// let int = ApInt::from_limbs_le(vec![0, 1], Sign::Negative);
+    /// assert_eq!(int, ApInt::from(-1i128 << 64));
+    /// ```
+    pub fn from_limbs_le(limbs: Vec<u64>, sign: Sign) -> ApInt {
+        cfg_if::cfg_if! {
+            if #[cfg(target_pointer_width = "64")] {
+                from_magnitude(limbs, sign)
+            } else {
+                let limbs = limbs
+                    .iter()
+                    .flat_map(|&limb| iter::once(limb as u32).chain(iter::once((limb >> 32) as u32)));
+                from_magnitude(limbs.collect(), sign)
+            }
+        }
+    }
+
+    /// Creates an int from the 32-bit words of its magnitude, least significant
+    /// word first, and its sign.
+    ///
+    /// On 32-bit targets the vector's buffer becomes the storage of the int,
+    /// so the words are not copied unless the buffer must grow or shrink, or
+    /// the storage of ints is over-aligned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sign` is [`Sign::Zero`] and the magnitude is not zero.
+    pub fn from_u32_limbs_le(limbs: Vec<u32>, sign: Sign) -> ApInt {
+        cfg_if::cfg_if! {
+            if #[cfg(target_pointer_width = "64")] {
+                let limbs = limbs.chunks(2).map(|pair| {
+                    u64::from(pair[0]) | pair.get(1).map_or(0, |&high| u64::from(high) << 32)
+                });
+                from_magnitude(limbs.collect(), sign)
+            } else {
+                from_magnitude(limbs, sign)
+            }
+        }
+    }
+
+    /// Returns the 64-bit words of the magnitude of `self`, least significant
+    /// word first and without high zero words, and its sign.
+    ///
+    /// On 64-bit targets the storage of the int becomes the vector's buffer,
+    /// so the words are not copied unless the storage of ints is over-aligned.
+    pub fn into_limbs_le(self) -> (Vec<u64>, Sign) {
+        let (limbs, sign) = into_magnitude(self);
+        cfg_if::cfg_if! {
+            if #[cfg(target_pointer_width = "64")] {
+                (limbs, sign)
+            } else {
+                let limbs = limbs.chunks(2).map(|pair| {
+                    u64::from(pair[0]) | pair.get(1).map_or(0, |&high| u64::from(high) << 32)
+                });
+                (limbs.collect(), sign)
+            }
+        }
+    }
+
+    /// Returns the 32-bit words of the magnitude of `self`, least significant
+    /// word first and without high zero words, and its sign.
+    ///
+    /// On 32-bit targets the storage of the int becomes the vector's buffer,
+    /// so the words are not copied unless the storage of ints is over-aligned.
+    pub fn into_u32_limbs_le(self) -> (Vec<u32>, Sign) {
+        let (limbs, sign) = into_magnitude(self);
+        cfg_if::cfg_if! {
+            if #[cfg(target_pointer_width = "64")] {
+                let mut limbs: Vec<u32> = limbs
+                    .iter()
+                    .flat_map(|&limb| iter::once(limb as u32).chain(iter::once((limb >> 32) as u32)))
+                    .collect();
+                if limbs.last() == Some(&0) {
+                    limbs.pop();
+                }
+                (limbs, sign)
+            } else {
+                (limbs, sign)
+            }
+        }
+    }
+}
+
+impl ApInt {
+    /// Returns a mantissa `d` and exponent `exp` such that `d * 2^exp`
+    /// approximates `self`, with `0.5 <= |d| < 1`, or `(0.0, 0)` for zero.
+    ///
+    /// The mantissa is truncated towards zero to the precision of an `f64`, as
+    /// by `mpz_get_d_2exp` in GMP. Unlike converting to an `f64`, this never
+    /// overflows, so the magnitudes of huge values can be compared and
+    /// combined as floats, such as with `d.ln() + exp as f64 * LN_2`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// assert_eq!(ApInt::from(-12).to_f64_exp(), (-0.75, 4));
+    /// assert_eq!((ApInt::ONE << 5000).to_f64_exp(), (0.5, 5001));
+    /// ```
+    pub fn to_f64_exp(&self) -> (f64, i64) {
+        let bits = self.bits();
+        if bits == 0 {
+            return (0.0, 0);
+        }
+
+        // Gather the leading bits of the magnitude, as many as fit exactly in
+        // the mantissa of an `f64`.
+        let mag = self.magnitude();
+        let start = bits.saturating_sub(53);
+        let m = (start..bits).rev().fold(0u64, |m, i| {
+            let bit = mag[i / Limb::BITS].repr() >> (i % Limb::BITS) & 1 == 1;
+            m << 1 | bit as u64
+        });
+
+        // Scale by `2^-(bits - start)`, built directly from its exponent bits.
+        let scale = f64::from_bits((1023 - (bits - start) as u64) << 52);
+        let d = m as f64 * scale;
+        (if self.is_negative() { -d } else { d }, bits as i64)
+    }
+
+    /// Converts the float `x` to an int, rounding any fraction in the given
+    /// mode.
+    ///
+    /// Returns `None` if `x` is infinite or NaN.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::{ApInt, RoundingMode};
+    ///
+    /// assert_eq!(ApInt::from_f64_round(2.5, RoundingMode::HalfEven), Some(ApInt::from(2)));
+    /// let rounded = ApInt::from_f64_round(-2.5, RoundingMode::HalfAwayFromZero);
+    /// assert_eq!(rounded, Some(ApInt::from(-3)));
+    /// assert_eq!(ApInt::from_f64_round(-0.1, RoundingMode::Floor), Some(ApInt::from(-1)));
+    /// assert_eq!(ApInt::from_f64_round(f64::NAN, RoundingMode::Floor), None);
+    /// ```
+    pub fn from_f64_round(x: f64, mode: RoundingMode) -> Option<ApInt> {
+        if !x.is_finite() {
+            return None;
+        }
+
+        let (m, e) = f64_parts(x);
+        if e >= 0 {
+            return Some(ApInt::from(m).mul_pow2(e as usize));
+        }
+
+        // Split the magnitude into its integer part and the discarded bits,
+        // comparing the discarded bits to a half.
+        let mag = m.abs() as u128;
+        let shift = -e as u32;
+        let (q, frac, exact) = if shift < 128 {
+            let rem = mag & ((1 << shift) - 1);
+            (mag >> shift, rem.cmp(&(1 << (shift - 1))), rem == 0)
+        } else {
+            (0, Ordering::Less, mag == 0)
+        };
+
+        let negative = m < 0;
+        let q = q + mode.round_up(negative, q & 1 == 1, frac, exact) as u128;
+        let int = ApInt::from(q);
+        Some(if negative { -int } else { int })
+    }
+}
+
+/// Creates an int from the words of its magnitude, least significant word
+/// first, and its sign, reusing the buffer of `mag` as its storage.
+fn from_magnitude(mut mag: Vec<LimbRepr>, sign: Sign) -> ApInt {
+    while mag.last() == Some(&0) {
+        mag.pop();
+    }
+    if mag.is_empty() {
+        return ApInt::ZERO;
+    }
+    assert!(sign != Sign::Zero, "non-zero magnitude with a zero sign");
+
+    // A zero sign limb is needed if the high bit of the magnitude is set.
+    if mag[mag.len() - 1] >> (Limb::BITS - 1) == 1 {
+        mag.push(0);
+    }
+    if sign == Sign::Negative {
+        // Negate in place as `!mag + 1`.
+        let mut carry = true;
+        for word in mag.iter_mut() {
+            let (neg, c) = (!*word).overflowing_add(carry as LimbRepr);
+            *word = neg;
+            carry = c;
+        }
+    }
+
+    let len = mag.len();
+    let ptr = Box::into_raw(mag.into_boxed_slice()).cast::<LimbRepr>();
+    // SAFETY: A boxed slice has the layout of an array of its length, which is
+    //         non-zero, and ownership passes to the int.
+    unsafe { ApInt::from_raw_parts(ptr, len) }
+}
+
+/// Returns the words of the magnitude of `x`, least significant word first and
+/// without high zero words, and its sign, reusing the storage of `x`.
+fn into_magnitude(x: ApInt) -> (Vec<LimbRepr>, Sign) {
+    let sign = x.sign();
+    let (ptr, len) = x.into_raw_parts();
+    // SAFETY: The parts of an int have the layout of an array of their length.
+    let mut mag = unsafe { Vec::from_raw_parts(ptr, len, len) };
+
+    if sign == Sign::Negative {
+        // Negate in place as `!x + 1`, which is the correct unsigned magnitude
+        // even for the most negative value of a width.
+        let mut carry = true;
+        for word in mag.iter_mut() {
+            let (neg, c) = (!*word).overflowing_add(carry as LimbRepr);
+            *word = neg;
+            carry = c;
+        }
+    }
+    while mag.last() == Some(&0) {
+        mag.pop();
+    }
+    (mag, sign)
+}
+
+/// Splits the finite float `f` into an integer mantissa `m` and exponent `e`,
+/// such that `f == m * 2^e` exactly.
+pub(crate) fn f64_parts(f: f64) -> (i64, i32) {
+    debug_assert!(f.is_finite());
+
+    let bits = f.to_bits();
+    let exp = ((bits >> 52) & 0x7ff) as i32;
+    let frac = (bits & ((1 << 52) - 1)) as i64;
+
+    // Subnormal numbers have no implicit leading bit.
+    let (m, e) = if exp == 0 {
+        (frac, -1074)
+    } else {
+        (frac | (1 << 52), exp - 1075)
+    };
+    if bits >> 63 == 1 { (-m, e) } else { (m, e) }
+}
+
 impl_from_prim!(unsigned: u8, u16, u32, u64, u128, usize);
 impl_from_prim!(signed: i8, i16, i32, i64, i128, isize);
 
+impl core::convert::From<bool> for ApInt {
+    /// Converts `false` to `0` and `true` to `1`.
+    #[inline]
+    fn from(val: bool) -> ApInt {
+        if val { ApInt::ONE } else { ApInt::ZERO }
+    }
+}
+
 macro_rules! impl_to_prim {
     ($($ty:ident),* $(,)?) => {
         $(
             impl<'a> core::convert::From<&'a ApInt> for $ty {
                 fn from(int: &'a ApInt) -> $ty {
-                    const SIZE_TY: usize = core::mem::size_of::<$ty>();
-                    const SIZE_LIMB: usize = Limb::SIZE;
+                    const BITS_TY: usize = core::mem::size_of::<$ty>() * 8;
                     const BITS_LIMB: usize = Limb::BITS;
-                    const SHIFT_LIMB: usize = BITS_LIMB - 1;
-
-                    unsafe {
-                        match int.data() {
-                            LimbData::Stack(limb) => limb.repr_signed() as $ty,
-                            LimbData::Heap(ptr, len) => match SIZE_LIMB * len.get() {
-                                size_int if SIZE_TY <= size_int => $ty::from_le(*ptr.as_ptr().cast()),
-                                _ => {
-                                    // The number of limbs that can fit in $t.
-                                    const FACTOR: usize = SIZE_TY / SIZE_LIMB;
-                                    // Copy as many limbs as we have or that can fit in $t.
-                                    let n_copy = len.get().min(FACTOR);
-
-                                    // Last limb has the sign.
-                                    let sign_limb = (*ptr.add(len.get() - 1)).repr_signed();
-                                    // Propagate the sign across the limb, taking advantage of signed shift.
-                                    let sign_byte = (sign_limb >> SHIFT_LIMB) as u8;
-
-                                    let mut val = MaybeUninit::uninit();
-                                    // Initialise with sign bits.
-                                    core::ptr::write_bytes(val.as_mut_ptr(), sign_byte, 1);
-                                    core::ptr::copy_nonoverlapping(ptr.as_ptr(), val.as_mut_ptr() as *mut Limb, n_copy);
-                                    $ty::from_le(val.assume_init())
-                                }
-                            },
-                        }
+
+                    let limbs = int.as_limbs();
+                    // Limbs beyond the length of the int are sign extended.
+                    let sign = int.high_limb().sign_mask();
+
+                    // Truncate the value in the same way as an `as` cast.
+                    let mut val: $ty = 0;
+                    let mut shift = 0;
+                    while shift < BITS_TY {
+                        let limb = limbs.get(shift / BITS_LIMB).copied().unwrap_or(sign);
+                        val |= (limb.repr() as $ty) << shift;
+                        shift += BITS_LIMB;
                     }
+                    val
                 }
             }
 
@@ -162,3 +493,64 @@ macro_rules! impl_to_prim {
 
 impl_to_prim!(u8, u16, u32, u64, u128, usize);
 impl_to_prim!(i8, i16, i32, i64, i128, isize);
+
+/// The error returned when a checked conversion from an [`ApInt`] fails.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct TryFromApIntError(());
+
+impl fmt::Display for TryFromApIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("out of range integral type conversion attempted")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TryFromApIntError {}
+
+macro_rules! impl_nonzero {
+    ($($nz:ident => $ty:ident, $to:ident;)*) => {
+        $(
+            impl core::convert::From<$nz> for ApInt {
+                #[inline]
+                fn from(val: $nz) -> ApInt {
+                    ApInt::from(val.get())
+                }
+            }
+
+            impl<'a> TryFrom<&'a ApInt> for $nz {
+                type Error = TryFromApIntError;
+
+                /// Converts the int if it is non-zero and in the range of the
+                /// underlying primitive type.
+                #[inline]
+                fn try_from(int: &'a ApInt) -> Result<$nz, TryFromApIntError> {
+                    int.$to().and_then($nz::new).ok_or(TryFromApIntError(()))
+                }
+            }
+
+            impl TryFrom<ApInt> for $nz {
+                type Error = TryFromApIntError;
+
+                #[inline]
+                fn try_from(int: ApInt) -> Result<$nz, TryFromApIntError> {
+                    $nz::try_from(&int)
+                }
+            }
+        )*
+    };
+}
+
+impl_nonzero! {
+    NonZeroU8 => u8, to_u8;
+    NonZeroU16 => u16, to_u16;
+    NonZeroU32 => u32, to_u32;
+    NonZeroU64 => u64, to_u64;
+    NonZeroU128 => u128, to_u128;
+    NonZeroUsize => usize, to_usize;
+    NonZeroI8 => i8, to_i8;
+    NonZeroI16 => i16, to_i16;
+    NonZeroI32 => i32, to_i32;
+    NonZeroI64 => i64, to_i64;
+    NonZeroI128 => i128, to_i128;
+    NonZeroIsize => isize, to_isize;
+}
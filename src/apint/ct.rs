@@ -0,0 +1,181 @@
+use core::num::NonZeroUsize;
+
+use subtle::{Choice, ConstantTimeEq, ConstantTimeGreater, ConstantTimeLess};
+
+use crate::apint::ApInt;
+use crate::limb::{Limb, LimbRepr};
+
+// The operations here run in time independent of the values of their
+// operands, given operands of the same number of limbs. Both operands are
+// first extended to a common length, every limb is processed whether or not
+// the choice is set, and the choice only ever selects bits through a mask.
+//
+// The results are normalized, so the number of limbs of a result, and so the
+// time of later operations on it, may depend on its value. Constant time code
+// should keep secrets at a fixed length, such as by reducing them modulo a
+// public modulus of that length.
+
+impl ApInt {
+    /// Adds `other` to `self` if `choice` is set, in time independent of the
+    /// values of both and of `choice`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    /// use subtle::Choice;
+    ///
+    /// let mut x = ApInt::from(7);
+    /// x.ct_add_assign_if(&ApInt::from(5), Choice::from(0));
+    /// assert_eq!(x, ApInt::from(7));
+    /// x.ct_add_assign_if(&ApInt::from(5), Choice::from(1));
+    /// assert_eq!(x, ApInt::from(12));
+    /// ```
+    pub fn ct_add_assign_if(&mut self, other: &ApInt, choice: Choice) {
+        add_or_sub_if(self, other, choice, false);
+    }
+
+    /// Subtracts `other` from `self` if `choice` is set, in time independent of
+    /// the values of both and of `choice`.
+    ///
+    /// This is the conditional subtraction that ends a Montgomery or Barrett
+    /// reduction, which must not branch on whether the value reached the
+    /// modulus.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    /// use subtle::ConstantTimeGreater;
+    ///
+    /// let m = ApInt::from(13);
+    /// let mut x = ApInt::from(20);
+    /// // Reduce `x` into `0..m`, for `x < 2m`.
+    /// let reduce = !m.ct_gt(&x);
+    /// x.ct_sub_assign_if(&m, reduce);
+    /// assert_eq!(x, ApInt::from(7));
+    /// ```
+    pub fn ct_sub_assign_if(&mut self, other: &ApInt, choice: Choice) {
+        add_or_sub_if(self, other, choice, true);
+    }
+
+    /// Replaces `self` with a copy of `other` if `choice` is set, in time
+    /// independent of the values of both and of `choice`.
+    pub fn ct_assign_if(&mut self, other: &ApInt, choice: Choice) {
+        let mask = mask(choice);
+        let len = self.as_limbs().len().max(other.as_limbs().len());
+        self.resize(nz(len));
+
+        let ext = other.high_limb().sign_mask();
+        for (i, a) in self.as_limbs_mut().iter_mut().enumerate() {
+            let b = other.as_limbs().get(i).copied().unwrap_or(ext);
+            *a = Limb(a.repr() ^ ((a.repr() ^ b.repr()) & mask));
+        }
+        self.normalize();
+    }
+
+    /// Swaps the values of `a` and `b` if `choice` is set, in time independent
+    /// of the values of both and of `choice`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    /// use subtle::Choice;
+    ///
+    /// let (mut a, mut b) = (ApInt::from(1), ApInt::ONE << 100);
+    /// ApInt::ct_swap_if(&mut a, &mut b, Choice::from(1));
+    /// assert_eq!((a, b), (ApInt::ONE << 100, ApInt::from(1)));
+    /// ```
+    pub fn ct_swap_if(a: &mut ApInt, b: &mut ApInt, choice: Choice) {
+        let mask = mask(choice);
+        let len = nz(a.as_limbs().len().max(b.as_limbs().len()));
+        a.resize(len);
+        b.resize(len);
+
+        for (x, y) in a.as_limbs_mut().iter_mut().zip(b.as_limbs_mut()) {
+            let t = (x.repr() ^ y.repr()) & mask;
+            *x = Limb(x.repr() ^ t);
+            *y = Limb(y.repr() ^ t);
+        }
+        a.normalize();
+        b.normalize();
+    }
+}
+
+impl ConstantTimeEq for ApInt {
+    fn ct_eq(&self, other: &ApInt) -> Choice {
+        let (a, b) = (self.as_limbs(), other.as_limbs());
+        let (ext_a, ext_b) = (self.high_limb().sign_mask(), other.high_limb().sign_mask());
+
+        let mut diff: LimbRepr = 0;
+        for i in 0..a.len().max(b.len()) {
+            let x = a.get(i).copied().unwrap_or(ext_a);
+            let y = b.get(i).copied().unwrap_or(ext_b);
+            diff |= x.repr() ^ y.repr();
+        }
+        diff.ct_eq(&0)
+    }
+}
+
+impl ConstantTimeGreater for ApInt {
+    fn ct_gt(&self, other: &ApInt) -> Choice {
+        let (a, b) = (self.as_limbs(), other.as_limbs());
+        let (ext_a, ext_b) = (self.high_limb().sign_mask(), other.high_limb().sign_mask());
+
+        // `self > other` exactly when `other - self` is negative, which is
+        // computed with one more limb than either, so it cannot overflow.
+        let mut borrow: LimbRepr = 0;
+        let mut high: LimbRepr = 0;
+        for i in 0..=a.len().max(b.len()) {
+            let x = b.get(i).copied().unwrap_or(ext_b).repr();
+            let y = a.get(i).copied().unwrap_or(ext_a).repr();
+            let (r, c1) = x.overflowing_sub(y);
+            let (r, c2) = r.overflowing_sub(borrow);
+            borrow = (c1 | c2) as LimbRepr;
+            high = r;
+        }
+        Choice::from((high >> (Limb::BITS - 1)) as u8)
+    }
+}
+
+impl ConstantTimeLess for ApInt {}
+
+/// Adds or subtracts `other` to or from `x` if `choice` is set.
+fn add_or_sub_if(x: &mut ApInt, other: &ApInt, choice: Choice, subtract: bool) {
+    let mask = mask(choice);
+    // One extra limb holds any carry out, and the sign of the result.
+    let len = x.as_limbs().len().max(other.as_limbs().len()) + 1;
+    x.resize(nz(len));
+
+    let ext = other.high_limb().sign_mask();
+    let mut carry: LimbRepr = 0;
+    for (i, a) in x.as_limbs_mut().iter_mut().enumerate() {
+        let b = other.as_limbs().get(i).copied().unwrap_or(ext).repr() & mask;
+        let (r, c1, c2) = if subtract {
+            let (r, c1) = a.repr().overflowing_sub(b);
+            let (r, c2) = r.overflowing_sub(carry);
+            (r, c1, c2)
+        } else {
+            let (r, c1) = a.repr().overflowing_add(b);
+            let (r, c2) = r.overflowing_add(carry);
+            (r, c1, c2)
+        };
+        *a = Limb(r);
+        carry = (c1 | c2) as LimbRepr;
+    }
+    x.normalize();
+}
+
+/// Returns a limb of all ones if `choice` is set, and of all zeros otherwise.
+#[inline]
+fn mask(choice: Choice) -> LimbRepr {
+    (choice.unwrap_u8() as LimbRepr).wrapping_neg()
+}
+
+#[inline]
+fn nz(len: usize) -> NonZeroUsize {
+    debug_assert!(len > 0);
+    // SAFETY: All callers calculate lengths of at least 1.
+    unsafe { NonZeroUsize::new_unchecked(len) }
+}
@@ -0,0 +1,91 @@
+use digest::Update;
+
+use crate::apint::ApInt;
+use crate::limb::Limb;
+
+/// The byte encoding an [`ApInt`] is fed to a digest in by
+/// [`ApInt::hash_into`].
+///
+/// Every encoding is canonical, so equal values always hash the same.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Encoding {
+    /// The fewest two's complement bytes able to hold the value and its sign
+    /// bit, most significant byte first, as returned by
+    /// [`ApInt::to_signed_bytes_be`].
+    SignedBytes,
+    /// The bytes of [`SignedBytes`](Encoding::SignedBytes), preceded by their
+    /// count as a big-endian `u64`, so that consecutive values hashed into the
+    /// same digest cannot run together.
+    LengthPrefixed,
+    /// The magnitude of a non-negative value as exactly the given number of
+    /// bytes, most significant byte first and padded with leading zeros, as by
+    /// I2OSP in RFC 8017.
+    Unsigned(usize),
+}
+
+impl ApInt {
+    /// Feeds the bytes of `self` in the given encoding to the digest `d`,
+    /// without collecting them into an intermediate buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics with [`Encoding::Unsigned`] if `self` is negative, or does not
+    /// fit in the given number of bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::{ApInt, Encoding};
+    /// # struct Bytes(Vec<u8>);
+    /// # impl digest::Update for Bytes {
+    /// #     fn update(&mut self, data: &[u8]) {
+    /// #         self.0.extend_from_slice(data);
+    /// #     }
+    /// # }
+    /// # let mut d = Bytes(Vec::new());
+    ///
+    /// // `d` is any digest, such as `sha2::Sha256`.
+    /// ApInt::from(-129).hash_into(&mut d, Encoding::SignedBytes);
+    /// ApInt::from(0xabc).hash_into(&mut d, Encoding::Unsigned(4));
+    /// # assert_eq!(d.0, [0xff, 0x7f, 0x00, 0x00, 0x0a, 0xbc]);
+    /// ```
+    pub fn hash_into<D: Update + ?Sized>(&self, d: &mut D, encoding: Encoding) {
+        match encoding {
+            Encoding::SignedBytes => update_be(d, self.as_limbs(), self.significant_bits() / 8 + 1),
+            Encoding::LengthPrefixed => {
+                let len = self.significant_bits() / 8 + 1;
+                d.update(&(len as u64).to_be_bytes());
+                update_be(d, self.as_limbs(), len);
+            }
+            Encoding::Unsigned(width) => {
+                assert!(!self.is_negative(), "cannot encode a negative value");
+
+                let len = (self.significant_bits() + 7) / 8;
+                assert!(len <= width, "value does not fit in the given width");
+
+                const ZEROS: [u8; 64] = [0; 64];
+                let mut pad = width - len;
+                while pad > 0 {
+                    let n = pad.min(ZEROS.len());
+                    d.update(&ZEROS[..n]);
+                    pad -= n;
+                }
+                update_be(d, self.as_limbs(), len);
+            }
+        }
+    }
+}
+
+/// Feeds the low `len` bytes of `limbs` to `d`, most significant byte first.
+fn update_be<D: Update + ?Sized>(d: &mut D, limbs: &[Limb], len: usize) {
+    let full = len / Limb::SIZE;
+    let partial = len % Limb::SIZE;
+
+    if partial > 0 {
+        let bytes = limbs[full].repr().to_be_bytes();
+        d.update(&bytes[Limb::SIZE - partial..]);
+    }
+    for limb in limbs[..full].iter().rev() {
+        d.update(&limb.repr().to_be_bytes());
+    }
+}
@@ -0,0 +1,469 @@
+use core::fmt;
+
+use crate::alloc::{String, Vec};
+use crate::apint::ApInt;
+use crate::apint::radix::to_radix_digits_le;
+use crate::sign::Sign;
+
+/// The Bitcoin base58 alphabet, which leaves out `0`, `O`, `I` and `l`.
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// The RFC 4648 base64 alphabet.
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// The RFC 4648 base32 alphabet.
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// The version byte of the canonical byte encoding.
+const CANONICAL_VERSION: u8 = 1;
+
+/// The error returned when decoding an [`ApInt`] from a hex, base58, base64
+/// or base32 string, from a lenient decimal string, or from canonical bytes,
+/// fails.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct DecodeError(pub(crate) ());
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid encoding of an integer")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+impl ApInt {
+    /// Returns the canonical byte encoding of `self`.
+    ///
+    /// The encoding is a version byte of `1`, followed by the fewest two's
+    /// complement bytes able to hold the value and its sign bit, most
+    /// significant byte first, as returned by
+    /// [`to_signed_bytes_be`](ApInt::to_signed_bytes_be). Each value has
+    /// exactly one encoding, so encodings can be compared and hashed in place
+    /// of values.
+    ///
+    /// This encoding is stable: it will never change in a later version of
+    /// the crate. A different encoding would use a new version byte, and
+    /// [`from_canonical_bytes`](ApInt::from_canonical_bytes) will continue to
+    /// accept version `1`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// assert_eq!(ApInt::ZERO.to_canonical_bytes(), [1, 0x00]);
+    /// assert_eq!(ApInt::from(255).to_canonical_bytes(), [1, 0x00, 0xff]);
+    /// assert_eq!(ApInt::from(-256).to_canonical_bytes(), [1, 0xff, 0x00]);
+    /// ```
+    pub fn to_canonical_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.to_signed_bytes_le();
+        bytes.push(CANONICAL_VERSION);
+        bytes.reverse();
+        bytes
+    }
+
+    /// Decodes an int from its canonical byte encoding, as returned by
+    /// [`to_canonical_bytes`](ApInt::to_canonical_bytes).
+    ///
+    /// Unknown versions, a missing value and redundant sign bytes are
+    /// rejected, so only canonical encodings are accepted.
+    pub fn from_canonical_bytes(bytes: &[u8]) -> Result<ApInt, DecodeError> {
+        let value = match bytes.split_first() {
+            Some((&CANONICAL_VERSION, value)) if !value.is_empty() => value,
+            _ => return Err(DecodeError(())),
+        };
+
+        // A leading byte is redundant if it only holds the sign of the byte
+        // after it.
+        if let [high, next, ..] = *value {
+            let sign = if next & 0x80 == 0 { 0x00 } else { 0xff };
+            if high == sign {
+                return Err(DecodeError(()));
+            }
+        }
+        Ok(ApInt::from_signed_bytes_be(value))
+    }
+
+    /// Returns the lowercase hexadecimal representation of `self`, without a
+    /// prefix.
+    ///
+    /// Negative values are prefixed with `-`. This is the same as
+    /// `format!("{:x}", self)`.
+    pub fn to_hex(&self) -> String {
+        self.to_str_radix(16)
+    }
+
+    /// Returns the lowercase hexadecimal representation of `self`, left-padded
+    /// with zeros to `width` digits.
+    ///
+    /// Negative values are prefixed with `-`, which does not count towards the
+    /// width. Values with more than `width` digits are not truncated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// assert_eq!(ApInt::from(0xabc).to_hex_padded(8), "00000abc");
+    /// assert_eq!(ApInt::from(-0xabc).to_hex_padded(4), "-0abc");
+    /// assert_eq!(ApInt::from(0xabcde).to_hex_padded(4), "abcde");
+    /// ```
+    pub fn to_hex_padded(&self, width: usize) -> String {
+        let digits = self.abs().to_str_radix(16);
+
+        let mut s = String::with_capacity(width.max(digits.len()) + 1);
+        if self.is_negative() {
+            s.push('-');
+        }
+        s.extend(core::iter::repeat('0').take(width.saturating_sub(digits.len())));
+        s.push_str(&digits);
+        s
+    }
+
+    /// Returns the lowercase hexadecimal digits of the two's complement
+    /// representation of `self` at the given bit width.
+    ///
+    /// The value is wrapped to its low `width` bits, as by
+    /// [`trunc`](ApInt::trunc), and written with exactly `width / 4` digits,
+    /// rounded up, without a prefix. This is how negative primitive integers
+    /// are formatted with `{:x}`, while `{:x}` on an [`ApInt`] writes a sign
+    /// and the magnitude.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let n = ApInt::from(-0x1f);
+    /// assert_eq!(format!("{:x}", n), "-1f");
+    /// assert_eq!(n.to_twos_complement_hex(32), "ffffffe1");
+    /// assert_eq!(n.to_twos_complement_hex(10), "3e1");
+    /// ```
+    pub fn to_twos_complement_hex(&self, width: usize) -> String {
+        to_twos_complement(self, width, 16)
+    }
+
+    /// Returns the binary digits of the two's complement representation of
+    /// `self` at the given bit width.
+    ///
+    /// The value is wrapped to its low `width` bits, as by
+    /// [`trunc`](ApInt::trunc), and written with exactly `width` digits,
+    /// without a prefix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// assert_eq!(ApInt::from(-3).to_twos_complement_binary(8), "11111101");
+    /// assert_eq!(ApInt::from(5).to_twos_complement_binary(4), "0101");
+    /// ```
+    pub fn to_twos_complement_binary(&self, width: usize) -> String {
+        to_twos_complement(self, width, 2)
+    }
+
+    /// Decodes an int from its hexadecimal representation.
+    ///
+    /// The digits may be of either case and may be separated by underscores,
+    /// and can be preceded by a `+` or `-` sign and a `0x` or `0X` prefix, in
+    /// that order. At least one digit is required.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// assert_eq!(ApInt::from_hex("0xDead_beef"), Ok(ApInt::from(0xdead_beefu32)));
+    /// assert_eq!(ApInt::from_hex("-ff"), Ok(ApInt::from(-255)));
+    /// assert!(ApInt::from_hex("0x").is_err());
+    /// ```
+    pub fn from_hex(s: &str) -> Result<ApInt, DecodeError> {
+        let (negative, s) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let s = if s.starts_with("0x") || s.starts_with("0X") {
+            &s[2..]
+        } else {
+            s
+        };
+
+        // Pack the digits into words, least significant digit first.
+        let mut words: Vec<u64> = Vec::with_capacity(s.len() / 16 + 1);
+        let mut n_digits = 0;
+        for c in s.bytes().rev() {
+            if c == b'_' {
+                continue;
+            }
+            let digit = match (c as char).to_digit(16) {
+                Some(digit) => u64::from(digit),
+                None => return Err(DecodeError(())),
+            };
+            if n_digits % 16 == 0 {
+                words.push(0);
+            }
+            let last = words.len() - 1;
+            words[last] |= digit << (4 * (n_digits % 16));
+            n_digits += 1;
+        }
+        if n_digits == 0 {
+            return Err(DecodeError(()));
+        }
+
+        let sign = match (words.iter().all(|&word| word == 0), negative) {
+            (true, _) => Sign::Zero,
+            (false, false) => Sign::Positive,
+            (false, true) => Sign::Negative,
+        };
+        Ok(ApInt::from_limbs_le(words, sign))
+    }
+
+    /// Returns the Bitcoin base58 encoding of `self`, as a big-endian byte
+    /// string.
+    ///
+    /// Zero is encoded as an empty string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is negative.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let int = ApInt::from(0x626262u32);
+    /// assert_eq!(int.to_base58(), "a3gV");
+    /// assert_eq!(ApInt::from_base58("a3gV"), Ok(int));
+    /// ```
+    pub fn to_base58(&self) -> String {
+        assert_non_negative(self);
+
+        let mag = self.magnitude();
+        if mag.is_empty() {
+            return String::new();
+        }
+        to_radix_digits_le(&mag, 58)
+            .iter()
+            .rev()
+            .map(|&digit| BASE58_ALPHABET[digit as usize] as char)
+            .collect()
+    }
+
+    /// Decodes an int from its Bitcoin base58 encoding, as a big-endian byte
+    /// string.
+    ///
+    /// Leading `1`s, which stand for leading zero bytes, do not change the
+    /// value, and an empty string is zero.
+    pub fn from_base58(s: &str) -> Result<ApInt, DecodeError> {
+        // The largest power of 58 that fits in a `u64`.
+        const CHUNK_DIGITS: usize = 10;
+
+        // Multiply in chunks of digits, so that each pass over the words adds
+        // many digits.
+        let mut words: Vec<u64> = Vec::new();
+        for chunk in s.as_bytes().chunks(CHUNK_DIGITS) {
+            let mut base = 1u64;
+            let mut carry = 0u64;
+            for &c in chunk {
+                let digit = decode_digit(BASE58_ALPHABET, c)?;
+                base *= 58;
+                carry = carry * 58 + u64::from(digit);
+            }
+
+            for word in words.iter_mut() {
+                let t = u128::from(*word) * u128::from(base) + u128::from(carry);
+                *word = t as u64;
+                carry = (t >> 64) as u64;
+            }
+            if carry != 0 {
+                words.push(carry);
+            }
+        }
+
+        let sign = if words.iter().all(|&word| word == 0) {
+            Sign::Zero
+        } else {
+            Sign::Positive
+        };
+        Ok(ApInt::from_limbs_le(words, sign))
+    }
+
+    /// Returns the padded RFC 4648 base64 encoding of `self`, as a big-endian
+    /// byte string.
+    ///
+    /// Zero is encoded as an empty string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is negative.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let int = ApInt::from(0x66_6f6fu32);
+    /// assert_eq!(int.to_base64(), "Zm9v");
+    /// assert_eq!(ApInt::from(0x66_6fu32).to_base64(), "Zm8=");
+    /// ```
+    pub fn to_base64(&self) -> String {
+        assert_non_negative(self);
+        encode_bits(&to_be_bytes(self), BASE64_ALPHABET, 6, 4)
+    }
+
+    /// Decodes an int from its padded RFC 4648 base64 encoding, as a big-endian
+    /// byte string.
+    ///
+    /// Leading zero bytes do not change the value, and an empty string is zero.
+    pub fn from_base64(s: &str) -> Result<ApInt, DecodeError> {
+        decode_bits(s, BASE64_ALPHABET, 6, 4).map(|bytes| from_be_bytes(&bytes))
+    }
+
+    /// Returns the padded RFC 4648 base32 encoding of `self`, as a big-endian
+    /// byte string.
+    ///
+    /// Zero is encoded as an empty string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is negative.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let int = ApInt::from(0x66_6f6fu32);
+    /// assert_eq!(int.to_base32(), "MZXW6===");
+    /// ```
+    pub fn to_base32(&self) -> String {
+        assert_non_negative(self);
+        encode_bits(&to_be_bytes(self), BASE32_ALPHABET, 5, 8)
+    }
+
+    /// Decodes an int from its padded RFC 4648 base32 encoding, as a big-endian
+    /// byte string.
+    ///
+    /// Leading zero bytes do not change the value, and an empty string is zero.
+    pub fn from_base32(s: &str) -> Result<ApInt, DecodeError> {
+        decode_bits(s, BASE32_ALPHABET, 5, 8).map(|bytes| from_be_bytes(&bytes))
+    }
+}
+
+fn assert_non_negative(x: &ApInt) {
+    assert!(!x.is_negative(), "cannot encode a negative value");
+}
+
+/// Returns the bytes of the non-negative `x`, most significant byte first and
+/// without leading zero bytes.
+fn to_be_bytes(x: &ApInt) -> Vec<u8> {
+    let mut bytes = x.to_signed_bytes_le();
+    while bytes.last() == Some(&0) {
+        bytes.pop();
+    }
+    bytes.reverse();
+    bytes
+}
+
+/// Creates a non-negative int from its bytes, most significant byte first.
+fn from_be_bytes(bytes: &[u8]) -> ApInt {
+    let mut le = Vec::with_capacity(bytes.len() + 1);
+    le.extend(bytes.iter().rev());
+    le.push(0);
+    ApInt::from_signed_bytes_le(&le)
+}
+
+fn decode_digit(alphabet: &[u8], c: u8) -> Result<u8, DecodeError> {
+    alphabet
+        .iter()
+        .position(|&a| a == c)
+        .map(|digit| digit as u8)
+        .ok_or(DecodeError(()))
+}
+
+/// Encodes `bytes` as digits of `bits` bits each, padding the digits with `=`
+/// to a multiple of `block` characters.
+fn encode_bits(bytes: &[u8], alphabet: &[u8], bits: u32, block: usize) -> String {
+    let mask = (1 << bits) - 1;
+
+    let n_digits = (bytes.len() * 8 + bits as usize - 1) / bits as usize;
+    let mut s = String::with_capacity((n_digits + block - 1) / block * block);
+
+    // The bits not yet written, and how many there are.
+    let mut acc: u32 = 0;
+    let mut acc_bits = 0;
+    for &byte in bytes {
+        acc = (acc << 8) | u32::from(byte);
+        acc_bits += 8;
+        while acc_bits >= bits {
+            acc_bits -= bits;
+            s.push(alphabet[((acc >> acc_bits) & mask) as usize] as char);
+        }
+        acc &= (1 << acc_bits) - 1;
+    }
+    if acc_bits > 0 {
+        s.push(alphabet[((acc << (bits - acc_bits)) & mask) as usize] as char);
+    }
+
+    while s.len() % block != 0 {
+        s.push('=');
+    }
+    s
+}
+
+/// Decodes the bytes encoded by [`encode_bits`], rejecting missing or
+/// misplaced padding and non-zero trailing bits.
+fn decode_bits(s: &str, alphabet: &[u8], bits: u32, block: usize) -> Result<Vec<u8>, DecodeError> {
+    if s.len() % block != 0 {
+        return Err(DecodeError(()));
+    }
+    let digits = s.trim_end_matches('=').as_bytes();
+    if s.len() - digits.len() >= block {
+        return Err(DecodeError(()));
+    }
+
+    let mut bytes = Vec::with_capacity(digits.len() * bits as usize / 8);
+    let mut acc: u32 = 0;
+    let mut acc_bits = 0;
+    for &c in digits {
+        acc = (acc << bits) | u32::from(decode_digit(alphabet, c)?);
+        acc_bits += bits;
+        if acc_bits >= 8 {
+            acc_bits -= 8;
+            bytes.push((acc >> acc_bits) as u8);
+            acc &= (1 << acc_bits) - 1;
+        }
+    }
+
+    // A whole digit left over means the length is impossible, and the bits
+    // left over must be zero for the encoding to be canonical.
+    if acc_bits >= bits || acc != 0 {
+        return Err(DecodeError(()));
+    }
+    Ok(bytes)
+}
+
+/// Returns the digits of `n` wrapped to `width` bits, in a power of two radix,
+/// padded with zeros to the number of digits of the width.
+fn to_twos_complement(n: &ApInt, width: usize, radix: u32) -> String {
+    let bits_per_digit = radix.trailing_zeros() as usize;
+    let len = (width + bits_per_digit - 1) / bits_per_digit;
+    let digits = n.trunc(width).to_str_radix(radix);
+
+    let mut s = String::with_capacity(len);
+    s.extend(core::iter::repeat('0').take(len - digits.len()));
+    s.push_str(&digits);
+    s
+}
@@ -0,0 +1,173 @@
+use core::mem;
+
+use crate::apint::ApInt;
+
+impl ApInt {
+    /// Returns the greatest common divisor of `self` and `other`.
+    ///
+    /// The result is always non-negative, and the greatest common divisor of
+    /// zero and zero is zero.
+    pub fn gcd(&self, other: &ApInt) -> ApInt {
+        binary_gcd(self.abs(), other.abs())
+    }
+
+    /// Returns `true` if `self` and `other` are coprime, having no common
+    /// divisor other than `1` and `-1`.
+    ///
+    /// Every int is coprime to `1` and `-1`, and only they are coprime to
+    /// zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// assert!(ApInt::from(35).is_coprime(&ApInt::from(-12)));
+    /// assert!(!ApInt::from(35).is_coprime(&ApInt::from(14)));
+    /// ```
+    pub fn is_coprime(&self, other: &ApInt) -> bool {
+        self.gcd(other) == ApInt::ONE
+    }
+
+    /// Returns the least common multiple of `self` and `other`.
+    ///
+    /// The result is always non-negative, and is zero if either `self` or
+    /// `other` is zero.
+    pub fn lcm(&self, other: &ApInt) -> ApInt {
+        self.gcd_lcm(other).1
+    }
+
+    /// Returns the greatest common divisor and least common multiple of `self`
+    /// and `other`.
+    ///
+    /// This is cheaper than calling [`gcd`] and [`lcm`] separately, since the
+    /// least common multiple is derived from the greatest common divisor.
+    ///
+    /// [`gcd`]: ApInt::gcd
+    /// [`lcm`]: ApInt::lcm
+    pub fn gcd_lcm(&self, other: &ApInt) -> (ApInt, ApInt) {
+        let gcd = self.gcd(other);
+        if gcd == ApInt::ZERO {
+            return (gcd, ApInt::ZERO);
+        }
+
+        // Dividing before multiplying keeps the intermediate value small.
+        let lcm = (self / &gcd * other).abs();
+        (gcd, lcm)
+    }
+
+    /// Returns the greatest common divisor `g` of `self` and `other`, along
+    /// with cofactors `x` and `y` such that `self * x + other * y == g`.
+    ///
+    /// The cofactors are computed with the binary extended GCD algorithm,
+    /// which only needs shifts, additions and subtractions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let (a, b) = (ApInt::from(240), ApInt::from(-46));
+    /// let (g, x, y) = a.extended_gcd(&b);
+    ///
+    /// assert_eq!(g, ApInt::from(2));
+    /// assert_eq!(&a * &x + &b * &y, g);
+    /// ```
+    pub fn extended_gcd(&self, other: &ApInt) -> (ApInt, ApInt, ApInt) {
+        let (a, b) = (self.abs(), other.abs());
+
+        let (g, mut x, mut y) = if a == ApInt::ZERO {
+            (b, ApInt::ZERO, ApInt::ONE)
+        } else if b == ApInt::ZERO {
+            (a, ApInt::ONE, ApInt::ZERO)
+        } else {
+            binary_extended_gcd(a, b)
+        };
+
+        if g == ApInt::ZERO {
+            return (g, ApInt::ZERO, ApInt::ZERO);
+        }
+        if self.is_negative() {
+            x.negate_to_fit();
+        }
+        if other.is_negative() {
+            y.negate_to_fit();
+        }
+        (g, x, y)
+    }
+}
+
+/// Returns the greatest common divisor of the non-negative `u` and `v`.
+fn binary_gcd(mut u: ApInt, mut v: ApInt) -> ApInt {
+    let (u_zeros, v_zeros) = match (u.trailing_zeros(), v.trailing_zeros()) {
+        (Some(u_zeros), Some(v_zeros)) => (u_zeros, v_zeros),
+        _ => return u + v,
+    };
+    let shift = u_zeros.min(v_zeros);
+
+    u >>= u_zeros;
+    v >>= v_zeros;
+
+    // Both `u` and `v` are odd at the start of each iteration.
+    loop {
+        if u > v {
+            mem::swap(&mut u, &mut v);
+        }
+        v -= &u;
+        match v.trailing_zeros() {
+            Some(zeros) => v >>= zeros,
+            None => return u << shift,
+        }
+    }
+}
+
+/// Returns `(g, x, y)` such that `a * x + b * y == g`, where `g` is the
+/// greatest common divisor of the positive `a` and `b`.
+///
+/// This is Algorithm 14.61 of the Handbook of Applied Cryptography.
+fn binary_extended_gcd(a: ApInt, b: ApInt) -> (ApInt, ApInt, ApInt) {
+    // Factors of two common to `a` and `b` are removed, so that at least one
+    // of them is odd. Since the cofactors are unchanged by scaling both inputs
+    // the factors are only restored to the divisor.
+    let shift = a.trailing_zeros().unwrap().min(b.trailing_zeros().unwrap());
+    let (x, y) = (a >> shift, b >> shift);
+
+    let (mut u, mut v) = (x.clone(), y.clone());
+    let (mut a, mut b) = (ApInt::ONE, ApInt::ZERO);
+    let (mut c, mut d) = (ApInt::ZERO, ApInt::ONE);
+
+    // Invariants: `a * x + b * y == u` and `c * x + d * y == v`.
+    loop {
+        halve(&mut u, &mut a, &mut b, &x, &y);
+        halve(&mut v, &mut c, &mut d, &x, &y);
+
+        if u >= v {
+            u -= &v;
+            a -= &c;
+            b -= &d;
+        } else {
+            v -= &u;
+            c -= &a;
+            d -= &b;
+        }
+
+        if u == ApInt::ZERO {
+            return (v << shift, c, d);
+        }
+    }
+}
+
+/// Divides `u` by two until it is odd, maintaining `a * x + b * y == u`.
+fn halve(u: &mut ApInt, a: &mut ApInt, b: &mut ApInt, x: &ApInt, y: &ApInt) {
+    while u.is_even() {
+        *u >>= 1;
+        if !a.is_even() || !b.is_even() {
+            // Adding `y` to `a` and subtracting `x` from `b` preserves the
+            // invariant and makes both even.
+            *a += y;
+            *b -= x;
+        }
+        *a >>= 1;
+        *b >>= 1;
+    }
+}
@@ -0,0 +1,155 @@
+use std::io::{self, Read, Write};
+
+use crate::alloc::Vec;
+use crate::apint::ApInt;
+use crate::apint::parse::{CHUNK_DIGITS, combine_chunks, pow10};
+
+impl ApInt {
+    /// Parses a decimal integer from `reader`, reading until the end of the
+    /// input.
+    ///
+    /// The digits may be preceded by a `+` or `-` sign, and the input may be
+    /// surrounded by ASCII whitespace. The input is read in blocks and its
+    /// digits are packed as they arrive, so the whole string is never held in
+    /// memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind [`io::ErrorKind::InvalidData`] if the input is
+    /// not a decimal integer, and passes on any error from `reader`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let input = "-123456789012345678901234567890\n";
+    /// let int = ApInt::read_decimal(input.as_bytes()).unwrap();
+    /// assert_eq!(int, ApInt::from(-123456789012345678901234567890i128));
+    /// ```
+    pub fn read_decimal<R: Read>(mut reader: R) -> io::Result<ApInt> {
+        let mut parser = DecimalParser::default();
+        let mut buf = [0; 8192];
+        loop {
+            let n = match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            for &byte in &buf[..n] {
+                parser.push(byte)?;
+            }
+        }
+        parser.finish()
+    }
+
+    /// Writes the decimal representation of `self` to `writer`.
+    ///
+    /// The digits are written in chunks as they are computed, so the whole
+    /// string is never held in memory.
+    ///
+    /// # Errors
+    ///
+    /// Passes on any error from `writer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let mut out = Vec::new();
+    /// ApInt::from(-1234).write_decimal(&mut out).unwrap();
+    /// assert_eq!(out, b"-1234");
+    /// ```
+    pub fn write_decimal<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        if self.is_negative() {
+            writer.write_all(b"-")?;
+        }
+        for (i, chunk) in self.decimal_chunks().enumerate() {
+            if i == 0 {
+                write!(writer, "{}", chunk)?;
+            } else {
+                write!(writer, "{:019}", chunk)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum State {
+    /// Before the sign or first digit.
+    Start,
+    /// After the sign, before the first digit.
+    Sign,
+    /// Within the digits.
+    Digits,
+    /// After the trailing whitespace has started.
+    End,
+}
+
+impl Default for State {
+    fn default() -> State {
+        State::Start
+    }
+}
+
+/// A parser that packs decimal digits into chunks of [`CHUNK_DIGITS`] digits.
+#[derive(Debug, Default)]
+struct DecimalParser {
+    state: State,
+    negative: bool,
+    /// The completed chunks, most significant first.
+    chunks: Vec<u64>,
+    /// The digits of the chunk being built.
+    chunk: u64,
+    chunk_len: usize,
+}
+
+impl DecimalParser {
+    fn push(&mut self, byte: u8) -> io::Result<()> {
+        match (self.state, byte) {
+            (State::Start, b'+') | (State::Start, b'-') => {
+                self.negative = byte == b'-';
+                self.state = State::Sign;
+            }
+            (State::Start, b'0'..=b'9')
+            | (State::Sign, b'0'..=b'9')
+            | (State::Digits, b'0'..=b'9') => {
+                self.state = State::Digits;
+                self.chunk = self.chunk * 10 + u64::from(byte - b'0');
+                self.chunk_len += 1;
+                if self.chunk_len == CHUNK_DIGITS {
+                    self.chunks.push(self.chunk);
+                    self.chunk = 0;
+                    self.chunk_len = 0;
+                }
+            }
+            (State::Start, b) | (State::End, b) if b.is_ascii_whitespace() => {}
+            (State::Digits, b) if b.is_ascii_whitespace() => self.state = State::End,
+            _ => return Err(invalid_digit()),
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> io::Result<ApInt> {
+        match self.state {
+            State::Digits | State::End => {}
+            _ => return Err(invalid_digit()),
+        }
+
+        let mut int = combine_chunks(&self.chunks);
+        if self.chunk_len > 0 {
+            int = int * pow10(self.chunk_len) + ApInt::from(self.chunk);
+        }
+        if self.negative {
+            int.negate_to_fit();
+        }
+        Ok(int)
+    }
+}
+
+fn invalid_digit() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "invalid decimal integer")
+}
@@ -0,0 +1,178 @@
+use core::num::NonZeroUsize;
+
+use crate::apint::ops::add_mul_limbs;
+use crate::apint::{ApInt, normalized_len};
+use crate::ll;
+
+/// An int for long chains of additions and multiply-accumulates, which leaves
+/// its limbs unnormalized until [`normalize`](LazyInt::normalize) is called.
+///
+/// An [`ApInt`] is normalized after every operation, trimming redundant sign
+/// limbs and reallocating to fit. A `LazyInt` instead keeps a buffer that only
+/// grows, accumulating results into it in place, so a chain of operations
+/// reallocates only when the value outgrows the buffer and builds no
+/// temporary products.
+///
+/// # Examples
+///
+/// ```
+/// use apa::{ApInt, LazyInt};
+///
+/// let xs = [ApInt::from(3), ApInt::from(-4), ApInt::from(u64::MAX)];
+/// let ys = [ApInt::from(5), ApInt::from(6), ApInt::from(u64::MAX)];
+///
+/// // The dot product of `xs` and `ys`.
+/// let mut acc = LazyInt::new();
+/// for (x, y) in xs.iter().zip(&ys) {
+///     acc.add_mul(x, y);
+/// }
+/// let expected = ApInt::from(15 - 24) + ApInt::from(u64::MAX) * ApInt::from(u64::MAX);
+/// assert_eq!(acc.normalize(), expected);
+/// ```
+#[derive(Clone, Debug)]
+pub struct LazyInt {
+    /// The value, which may hold redundant sign limbs.
+    int: ApInt,
+}
+
+impl LazyInt {
+    /// Creates a `LazyInt` with value `0`.
+    #[inline]
+    pub fn new() -> LazyInt {
+        LazyInt { int: ApInt::ZERO }
+    }
+
+    /// Creates a `LazyInt` with value `0` and room for values of `limbs`
+    /// limbs, so that smaller values need no reallocation.
+    pub fn with_capacity(limbs: usize) -> LazyInt {
+        // One limb is kept free above any operand, for the carry of a sum.
+        let len = limbs.saturating_add(1);
+        // SAFETY: `len` is at least 1.
+        let int = ApInt::zeroed(unsafe { NonZeroUsize::new_unchecked(len) });
+        LazyInt { int }
+    }
+
+    /// Returns the number of limbs of the buffer, including any redundant sign
+    /// limbs.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.int.len.get()
+    }
+
+    /// Returns the number of bytes of heap memory owned by the buffer.
+    ///
+    /// This is the memory usage of an [`ApInt`] of
+    /// [`capacity`](LazyInt::capacity) limbs, which can be far more than the
+    /// value needs after cancellation.
+    #[inline]
+    pub fn memory_usage(&self) -> usize {
+        self.int.memory_usage()
+    }
+
+    /// Shrinks the buffer to fit the value, with room for the carry of a sum.
+    ///
+    /// The buffer only grows otherwise, so a long-lived `LazyInt` whose value
+    /// has shrunk can call this to release the memory it no longer needs.
+    pub fn shrink_to_fit(&mut self) {
+        self.shrink_to(0);
+    }
+
+    /// Shrinks the buffer to fit the value, but leaves room for values of at
+    /// least `limbs` limbs, as in [`with_capacity`](LazyInt::with_capacity).
+    ///
+    /// The buffer is left as is if it is already smaller.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::{ApInt, LazyInt};
+    ///
+    /// let big = ApInt::from(u128::MAX) << 1000;
+    /// let mut acc = LazyInt::new();
+    /// acc.add_assign(&big);
+    /// acc.sub_assign(&big);
+    /// acc.add_assign(&ApInt::from(7));
+    ///
+    /// let grown = acc.memory_usage();
+    /// acc.shrink_to(4);
+    /// assert!(acc.memory_usage() < grown);
+    /// assert_eq!(acc.capacity(), 5);
+    /// assert_eq!(acc.normalize(), ApInt::from(7));
+    /// ```
+    pub fn shrink_to(&mut self, limbs: usize) {
+        let len = normalized_len(self.int.as_limbs())
+            .max(limbs)
+            .saturating_add(1);
+        if len < self.capacity() {
+            // SAFETY: `len` is at least 1.
+            self.int.resize(unsafe { NonZeroUsize::new_unchecked(len) });
+        }
+    }
+
+    /// Adds `y` in place.
+    pub fn add_assign(&mut self, y: &ApInt) {
+        self.reserve(y.len.get());
+        ll::add_signed_assign(self.int.as_limbs_mut(), y.as_limbs());
+    }
+
+    /// Subtracts `y` in place.
+    pub fn sub_assign(&mut self, y: &ApInt) {
+        self.reserve(y.len.get());
+        ll::sub_signed_assign(self.int.as_limbs_mut(), y.as_limbs());
+    }
+
+    /// Adds the product `x * y` in place, without computing the product
+    /// separately.
+    pub fn add_mul(&mut self, x: &ApInt, y: &ApInt) {
+        self.mul_acc(x, y, false);
+    }
+
+    /// Subtracts the product `x * y` in place, without computing the product
+    /// separately.
+    pub fn sub_mul(&mut self, x: &ApInt, y: &ApInt) {
+        self.mul_acc(x, y, true);
+    }
+
+    /// Returns the value as a normalized [`ApInt`].
+    #[inline]
+    pub fn normalize(mut self) -> ApInt {
+        self.int.normalize();
+        self.int
+    }
+
+    /// Adds or subtracts `x * y` in place.
+    fn mul_acc(&mut self, x: &ApInt, y: &ApInt, sub: bool) {
+        if *x == ApInt::ZERO || *y == ApInt::ZERO {
+            return;
+        }
+        self.reserve(x.len.get() + y.len.get());
+        add_mul_limbs(self.int.as_limbs_mut(), x, y, sub);
+    }
+
+    /// Grows the buffer, if needed, so that the sum of the value and a value
+    /// of `n` limbs fits in it.
+    fn reserve(&mut self, n: usize) {
+        // Both values fit in `len` limbs, so their sum fits in `len + 1`. The
+        // value is only scanned from the top, past any redundant sign limbs.
+        let len = normalized_len(self.int.as_limbs()).max(n) + 1;
+        if len > self.capacity() {
+            // SAFETY: `len` is at least 1.
+            self.int.resize(unsafe { NonZeroUsize::new_unchecked(len) });
+        }
+    }
+}
+
+impl Default for LazyInt {
+    /// Returns a `LazyInt` with value `0`.
+    #[inline]
+    fn default() -> LazyInt {
+        LazyInt::new()
+    }
+}
+
+impl From<ApInt> for LazyInt {
+    #[inline]
+    fn from(int: ApInt) -> LazyInt {
+        LazyInt { int }
+    }
+}
@@ -1,34 +1,204 @@
 use core::fmt;
 use core::marker::PhantomData;
 use core::num::NonZeroUsize;
-use core::ptr::NonNull;
+use core::ptr::{self, NonNull};
 
-use crate::limb::Limb;
-use crate::limbs::{Limbs, LimbsMut};
+use crate::alloc::{Box, Cow, Vec};
+use crate::limb::{Limb, LimbRepr, LimbReprSigned};
+use crate::limbs::Limbs;
+use crate::ll;
 use crate::mem;
+use crate::oom::AllocError;
 
+// Implements a binary operator and its assign form for all combinations of
+// owned and borrowed operands, delegating to a function taking references.
+//
+// An assign function taking `&mut ApInt` and `&ApInt` may be given after the
+// operator function, for assign forms that can skip work.
+macro_rules! impl_binop {
+    (@assign $assign_trait:ident, $assign_fn:ident, $op:ident) => {
+        impl $assign_trait<ApInt> for ApInt {
+            #[inline]
+            fn $assign_fn(&mut self, rhs: ApInt) {
+                *self = $op(self, &rhs);
+            }
+        }
+
+        impl $assign_trait<&ApInt> for ApInt {
+            #[inline]
+            fn $assign_fn(&mut self, rhs: &ApInt) {
+                *self = $op(self, rhs);
+            }
+        }
+    };
+    (@assign $assign_trait:ident, $assign_fn:ident, $op:ident, $op_assign:ident) => {
+        impl $assign_trait<ApInt> for ApInt {
+            #[inline]
+            fn $assign_fn(&mut self, rhs: ApInt) {
+                $op_assign(self, &rhs);
+            }
+        }
+
+        impl $assign_trait<&ApInt> for ApInt {
+            #[inline]
+            fn $assign_fn(&mut self, rhs: &ApInt) {
+                $op_assign(self, rhs);
+            }
+        }
+    };
+    ($($trait:ident, $fn:ident, $assign_trait:ident, $assign_fn:ident => $op:ident $(, $op_assign:ident)?;)*) => {
+        $(
+            impl $trait<ApInt> for ApInt {
+                type Output = ApInt;
+
+                #[inline]
+                fn $fn(self, rhs: ApInt) -> ApInt {
+                    $op(&self, &rhs)
+                }
+            }
+
+            impl $trait<&ApInt> for ApInt {
+                type Output = ApInt;
+
+                #[inline]
+                fn $fn(self, rhs: &ApInt) -> ApInt {
+                    $op(&self, rhs)
+                }
+            }
+
+            impl $trait<ApInt> for &ApInt {
+                type Output = ApInt;
+
+                #[inline]
+                fn $fn(self, rhs: ApInt) -> ApInt {
+                    $op(self, &rhs)
+                }
+            }
+
+            impl $trait<&ApInt> for &ApInt {
+                type Output = ApInt;
+
+                #[inline]
+                fn $fn(self, rhs: &ApInt) -> ApInt {
+                    $op(self, rhs)
+                }
+            }
+
+            impl_binop!(@assign $assign_trait, $assign_fn, $op $(, $op_assign)?);
+        )*
+    };
+}
+
+mod bitwise;
+#[cfg(feature = "borsh")]
+mod borsh;
 mod cmp;
 mod convert;
+#[cfg(feature = "subtle")]
+mod ct;
+#[cfg(feature = "digest")]
+mod digest;
+mod encode;
+mod gcd;
+#[cfg(feature = "std")]
+mod io;
+mod lazy;
+mod multimod;
 mod num;
 mod ops;
-mod radix;
+mod parse;
+mod pow;
+pub(crate) mod radix;
+#[cfg(feature = "rand")]
+mod random;
+mod range;
+#[cfg(feature = "rkyv")]
+mod rkyv;
+mod root;
+mod round;
+mod slice;
+#[cfg(feature = "wasm")]
+mod wasm;
+mod width;
+
+pub use self::convert::TryFromApIntError;
+#[cfg(feature = "digest")]
+pub use self::digest::Encoding;
+pub use self::encode::DecodeError;
+pub use self::lazy::LazyInt;
+pub(crate) use self::parse::pow10;
+pub use self::pow::PowOverflow;
+pub(crate) use self::pow::pow_bits;
+pub use self::radix::DecimalChunks;
+pub use self::range::ApIntRange;
+#[cfg(feature = "rkyv")]
+pub use self::rkyv::ArchivedApInt;
+pub use self::round::RoundingMode;
+pub use self::slice::ApIntSlice;
+pub use self::width::FixedInt;
 
 // SAFETY: This is safe since `1` is non-zero.
 const NZUSIZE_ONE: NonZeroUsize = unsafe { NonZeroUsize::new_unchecked(1) };
 
+cfg_if::cfg_if! {
+    if #[cfg(feature = "inline-4")] {
+        /// The number of limbs stored inline, without a heap allocation.
+        const INLINE_LIMBS: usize = 4;
+    } else if #[cfg(feature = "inline-2")] {
+        /// The number of limbs stored inline, without a heap allocation.
+        const INLINE_LIMBS: usize = 2;
+    } else {
+        /// The number of limbs stored inline, without a heap allocation.
+        const INLINE_LIMBS: usize = 1;
+    }
+}
+
+/// Returns `true` if an int of `len` limbs stores them inline.
+#[inline]
+const fn is_inline(len: NonZeroUsize) -> bool {
+    len.get() <= INLINE_LIMBS
+}
+
 /// An arbitrary-precision integer.
+///
+/// The value is stored in two's complement form, in the fewest limbs able to
+/// hold it. Values that fit in [`INLINE_LIMBS`](ApInt::INLINE_LIMBS) limbs
+/// are stored inline, and larger values on the heap.
+///
+/// Bitwise operations and shifts treat the value as infinitely sign extended,
+/// like the integers of Python, rather than operating on the magnitude. A
+/// negative value has infinitely many leading ones, so `-1 & x == x` for every
+/// `x`, and a right shift rounds towards negative infinity, so `-1 >> n == -1`.
+///
+/// ```
+/// use apa::ApInt;
+///
+/// let x = ApInt::from(u128::MAX) << 100;
+/// assert_eq!(ApInt::NEG_ONE & &x, x);
+/// assert_eq!(!&x, -&x - ApInt::ONE);
+/// assert_eq!(ApInt::NEG_ONE >> 1000, ApInt::NEG_ONE);
+/// assert_eq!(ApInt::from(-7) >> 1, ApInt::from(-4));
+/// ```
 pub struct ApInt {
     /// The number of limbs used to store data.
     len: NonZeroUsize,
+    /// The number of limbs of the heap allocation, or `0` if the limbs are
+    /// stored inline.
+    ///
+    /// This is `len` for every heap allocated int, except the destinations of
+    /// the `_into` operations and ints changed by `negate` or `make_abs`,
+    /// which keep a larger allocation for reuse.
+    cap: usize,
     /// The data holding the bits of the integer.
     data: ApIntData,
 }
 
-/// A single stack allocated limb or pointer to heap allocated limbs.
+/// Limbs stored inline or a pointer to heap allocated limbs.
 union ApIntData {
-    /// Inlined storage for values able to be stored within a single machine word.
-    value: Limb,
-    /// Heap allocated storage for values unable to be stored within a single machine word.
+    /// Inline storage for values able to be stored within `INLINE_LIMBS` limbs,
+    /// of which only the first `len` are meaningful.
+    inline: [Limb; INLINE_LIMBS],
+    /// Heap allocated storage for values unable to be stored inline.
     ptr: NonNull<Limb>,
 }
 
@@ -44,183 +214,702 @@ impl ApInt {
     pub const ZERO: ApInt = ApInt::from_limb(Limb::ZERO);
     /// Represents an `ApInt` with value `1`.
     pub const ONE: ApInt = ApInt::from_limb(Limb::ONE);
+    /// Represents an `ApInt` with value `2`.
+    pub const TWO: ApInt = ApInt::small(2);
+    /// Represents an `ApInt` with value `-1`.
+    pub const NEG_ONE: ApInt = ApInt::from_limb(Limb::ONES);
+
+    /// The alignment in bytes of the heap allocated limbs of an int.
+    ///
+    /// This is the alignment of a limb, unless raised to 32 or 64 bytes by the
+    /// `align-32` or `align-64` feature for aligned vector loads.
+    pub const LIMB_ALIGN: usize = mem::LIMB_ALIGN;
+
+    /// The number of limbs of the largest int stored inline, without a heap
+    /// allocation.
+    ///
+    /// This is a single limb, unless raised to 2 or 4 limbs by the `inline-2`
+    /// or `inline-4` feature, at the cost of a larger `ApInt`.
+    pub const INLINE_LIMBS: usize = INLINE_LIMBS;
+
+    /// Creates an `ApInt` from a small value.
+    ///
+    /// Small values are always stored inline, so this never allocates and can
+    /// be used in constant expressions.
+    #[inline]
+    pub const fn small(n: i8) -> ApInt {
+        ApInt::from_limb(Limb(n as LimbReprSigned as LimbRepr))
+    }
 
     /// Creates an `ApInt` with a single limb.
     const fn from_limb(value: Limb) -> ApInt {
         ApInt {
             len: NZUSIZE_ONE,
-            data: ApIntData { value },
+            cap: 0,
+            data: ApIntData {
+                inline: [value; INLINE_LIMBS],
+            },
         }
     }
 
-    /// Creates an `ApInt` with space allocated for the given capacity.
+    /// Creates an `ApInt` with space allocated for the given capacity, or
+    /// returns an error if it cannot be allocated.
     ///
     /// Data is zeroed.
     ///
     /// # Safety
     ///
-    /// Calling this function with a capacity of `1` will result in undefined
-    /// behaviour.
-    fn with_capacity(capacity: NonZeroUsize) -> ApInt {
+    /// Calling this function with a capacity stored inline will result in
+    /// undefined behaviour.
+    pub(crate) fn try_with_capacity(capacity: NonZeroUsize) -> Result<ApInt, AllocError> {
         // Sanity check when testing. Since this is an internal function we
-        // should be able to guarantee it is never called with a capacity of 1.
+        // should be able to guarantee it is never called with an inline
+        // capacity.
         debug_assert!(
-            capacity.get() > 1,
-            "allocating `ApInt` with capacity 1 is not supported"
+            !is_inline(capacity),
+            "allocating `ApInt` with an inline capacity is not supported"
         );
 
         // SAFETY: This is safe since we will track this allocation.
-        let ptr = unsafe { mem::alloc_limbs(capacity) };
-        ApInt {
+        let ptr = unsafe { mem::try_alloc_limbs(capacity)? };
+        Ok(ApInt {
             len: capacity,
+            cap: capacity.get(),
             data: ApIntData { ptr },
+        })
+    }
+}
+
+impl ApInt {
+    /// Decomposes the int into a pointer to its limbs and the number of limbs,
+    /// without copying them.
+    ///
+    /// The limbs hold the value in two's complement form, least significant
+    /// limb first, so the sign is the sign bit of the last limb. Values stored
+    /// inline are moved to a new allocation of `len` limbs, and so are the
+    /// limbs of any int when the `align-32` or `align-64` feature over-aligns
+    /// the storage of ints.
+    ///
+    /// The caller becomes responsible for the memory, which was allocated by
+    /// the global allocator with the layout of a `[LimbRepr; len]` array. The
+    /// easiest way to free it is to pass the parts back to
+    /// [`from_raw_parts`](ApInt::from_raw_parts).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let int = ApInt::from(-1i128 << 64);
+    /// let (ptr, len) = int.into_raw_parts();
+    /// assert_eq!(len, 128 / (8 * core::mem::size_of::<apa::LimbRepr>()));
+    ///
+    /// // SAFETY: The parts came from `into_raw_parts` and are used once.
+    /// let int = unsafe { ApInt::from_raw_parts(ptr, len) };
+    /// assert_eq!(int, ApInt::from(-1i128 << 64));
+    /// ```
+    pub fn into_raw_parts(self) -> (*mut LimbRepr, usize) {
+        if mem::OVER_ALIGNED {
+            let limbs: Vec<LimbRepr> = self.as_limbs().iter().map(|limb| limb.repr()).collect();
+            let len = limbs.len();
+            return (Box::into_raw(limbs.into_boxed_slice()).cast(), len);
+        }
+
+        let mut int = core::mem::ManuallyDrop::new(self);
+        // The parts have the layout of an array of `len` limbs.
+        let len = int.len;
+        int.resize(len);
+        let ptr = if int.cap == 0 {
+            // SAFETY: This is safe since ownership passes to the caller.
+            let ptr = unsafe { mem::alloc_limbs_uninit(int.len) };
+            // SAFETY: `ptr` is a valid allocation of `len` limbs, which does
+            //         not overlap the inline limbs.
+            unsafe {
+                ptr::copy_nonoverlapping(int.as_limbs().as_ptr(), ptr.as_ptr(), int.len.get())
+            };
+            ptr
+        } else {
+            // SAFETY: A heap allocated int has a valid pointer.
+            unsafe { int.data.ptr }
+        };
+        mem::release_limbs(int.len);
+        (ptr.as_ptr().cast(), int.len.get())
+    }
+
+    /// Creates an int from a pointer to its limbs and the number of limbs,
+    /// taking ownership of the memory without copying it.
+    ///
+    /// The limbs are read as a two's complement value, least significant limb
+    /// first. Limbs that are not needed to hold the value are freed, so the
+    /// memory may be reallocated. When the `align-32` or `align-64` feature
+    /// over-aligns the storage of ints, the limbs are instead copied to an
+    /// aligned allocation and the memory is freed.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` must have been allocated by the global allocator with the layout
+    ///   of a `[LimbRepr; len]` array, such as by
+    ///   [`into_raw_parts`](ApInt::into_raw_parts) or a `Vec<LimbRepr>` with a
+    ///   capacity of exactly `len`.
+    /// - `len` must be greater than zero, and all `len` limbs must be
+    ///   initialized.
+    /// - The memory must not be used or freed by anything else afterwards.
+    pub unsafe fn from_raw_parts(ptr: *mut LimbRepr, len: usize) -> ApInt {
+        debug_assert!(!ptr.is_null() && len > 0);
+
+        if mem::OVER_ALIGNED {
+            let limbs = Vec::from_raw_parts(ptr, len, len);
+            return ApInt::from_limbs(&limbs);
+        }
+
+        let ptr = NonNull::new_unchecked(ptr.cast::<Limb>());
+        let len = NonZeroUsize::new_unchecked(len);
+        mem::adopt_limbs(len);
+        let mut int = if is_inline(len) {
+            let mut int = ApInt::zeroed(len);
+            ptr::copy_nonoverlapping(ptr.as_ptr(), int.as_limbs_mut().as_mut_ptr(), len.get());
+            mem::dealloc_limbs(ptr, len);
+            int
+        } else {
+            ApInt {
+                len,
+                cap: len.get(),
+                data: ApIntData { ptr },
+            }
+        };
+        int.normalize();
+        int
+    }
+
+    /// Creates an int from a slice of limbs, copying them.
+    ///
+    /// The limbs are read as a two's complement value, least significant limb
+    /// first, so the sign is the sign bit of the last limb. The slice may hold
+    /// more limbs than the value needs, and an empty slice is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::{ApInt, LimbRepr};
+    ///
+    /// let int = ApInt::from_limbs(&[LimbRepr::MAX, 0]);
+    /// assert_eq!(int, ApInt::from(LimbRepr::MAX));
+    ///
+    /// assert_eq!(ApInt::from_limbs(&[LimbRepr::MAX; 3]), ApInt::from(-1));
+    /// assert_eq!(ApInt::from_limbs(&[]), ApInt::ZERO);
+    /// ```
+    pub fn from_limbs(limbs: &[LimbRepr]) -> ApInt {
+        let len = match NonZeroUsize::new(limbs.len()) {
+            Some(len) => len,
+            None => return ApInt::ZERO,
+        };
+
+        let mut int = ApInt::zeroed(len);
+        for (dst, &src) in int.as_limbs_mut().iter_mut().zip(limbs) {
+            *dst = Limb(src);
+        }
+        int.normalize();
+        int
+    }
+
+    /// Returns the number of bytes of heap memory owned by the int, which is
+    /// zero for an int stored inline.
+    ///
+    /// This excludes the `ApInt` itself. Every int is reallocated to fit its
+    /// value after each operation, so an int that shrinks, such as by `%=`,
+    /// releases the memory it no longer needs, and this is the size of the
+    /// value rounded up to whole limbs. The only exceptions are the
+    /// destination of [`add_into`](ApInt::add_into) and the other `_into`
+    /// operations, and an int changed in place by [`negate`](ApInt::negate)
+    /// or [`make_abs`](ApInt::make_abs), which keep their allocation for
+    /// reuse.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::{ApInt, LimbRepr};
+    ///
+    /// let mut int = ApInt::from(-1) << 1000;
+    /// let limb = core::mem::size_of::<LimbRepr>();
+    /// assert_eq!(int.memory_usage(), (1000 / (8 * limb) + 1) * limb);
+    ///
+    /// int %= ApInt::from(7);
+    /// assert_eq!(int.memory_usage(), 0);
+    /// ```
+    pub fn memory_usage(&self) -> usize {
+        self.cap * Limb::SIZE
+    }
+
+    /// Checks the representation invariants of the int, panicking if any is
+    /// broken.
+    ///
+    /// Every int is stored in the fewest limbs able to hold its value, so each
+    /// value has exactly one representation and zero is a single zero limb.
+    /// An int of at most [`INLINE_LIMBS`](ApInt::INLINE_LIMBS) limbs is stored
+    /// inline, unless it reuses the allocation of a larger value, and any
+    /// other int points to a heap allocation of its limbs aligned to
+    /// [`LIMB_ALIGN`](ApInt::LIMB_ALIGN). Every public operation upholds
+    /// these invariants; this is meant for tests and debug assertions in code
+    /// building ints with [`from_raw_parts`](ApInt::from_raw_parts).
+    ///
+    /// The checks are only made in debug builds, and this does nothing when
+    /// debug assertions are disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let int = ApInt::from(u128::MAX) * ApInt::from(-3);
+    /// int.assert_valid();
+    /// ```
+    #[track_caller]
+    pub fn assert_valid(&self) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+
+        if self.cap != 0 {
+            // SAFETY: A heap allocated int has a valid pointer.
+            let ptr = unsafe { self.data.ptr };
+            // `align_offset` checks the alignment without casting the pointer
+            // to an integer, keeping to strict provenance.
+            assert!(
+                ptr.as_ptr().cast::<u8>().align_offset(mem::LIMB_ALIGN) == 0,
+                "int has a misaligned limb pointer"
+            );
+            assert!(
+                self.cap >= self.len.get(),
+                "int has more limbs than its allocation"
+            );
         }
+        assert_eq!(
+            normalized_len(self.as_limbs()),
+            self.len.get(),
+            "int has redundant sign extension limbs"
+        );
     }
 }
 
 impl Drop for ApInt {
     fn drop(&mut self) {
-        match self.len {
-            NZUSIZE_ONE => {}
-            // SAFETY: `ptr` is a valid pointer, since `len > 1`.
-            len => unsafe { mem::dealloc_limbs(self.data.ptr, len) },
+        if self.cap != 0 {
+            // SAFETY: `ptr` is heap allocated with length `cap`.
+            unsafe { mem::dealloc_limbs(self.data.ptr, nz(self.cap)) };
         }
     }
 }
 
-impl Clone for ApInt {
-    fn clone(&self) -> Self {
-        match self.data() {
-            LimbData::Stack(value) => ApInt::from_limb(value),
-            LimbData::Heap(src, len) => {
-                let mut n = ApInt::with_capacity(len);
-
-                // SAFETY: This is safe since `n` and `self` have the same
-                //         number of limbs and do not overlap.
-                unsafe { n.limbs_mut().copy_nonoverlapping(src, len) };
+impl ApInt {
+    /// Returns a copy of `self`, or an error if its limbs cannot be allocated.
+    ///
+    /// This is the fallible form of [`clone`](Clone::clone), which calls the
+    /// [OOM handler](crate::set_oom_handler) instead.
+    pub fn try_clone(&self) -> Result<ApInt, AllocError> {
+        let len = self.len;
+        if self.cap == 0 {
+            return Ok(ApInt {
+                len,
+                cap: 0,
+                // SAFETY: An inline int has initialized inline limbs.
+                data: ApIntData {
+                    inline: unsafe { self.data.inline },
+                },
+            });
+        }
 
-                n
+        let mut int = if is_inline(len) {
+            ApInt::zeroed(len)
+        } else {
+            // The limbs are all overwritten, so there is no need to zero them
+            // first.
+            // SAFETY: This is safe since we will track this allocation.
+            let ptr = unsafe { mem::try_alloc_limbs_uninit(len)? };
+            ApInt {
+                len,
+                cap: len.get(),
+                data: ApIntData { ptr },
             }
-        }
+        };
+        // SAFETY: This is safe since both ints hold `len` limbs and do not
+        //         overlap.
+        unsafe {
+            ptr::copy_nonoverlapping(
+                self.as_limbs().as_ptr(),
+                int.as_limbs_mut().as_mut_ptr(),
+                len.get(),
+            )
+        };
+        Ok(int)
+    }
+}
+
+impl Clone for ApInt {
+    fn clone(&self) -> Self {
+        self.try_clone().unwrap_or_else(|err| err.handle())
     }
 
     fn clone_from(&mut self, source: &Self) {
-        match (self.len, source.len) {
-            // Both stack allocated.
-            (NZUSIZE_ONE, NZUSIZE_ONE) => {
-                // SAFETY: This is safe since both ints are stack allocated.
-                self.data.value = unsafe { source.data.value };
-            }
-            // Self heap allocated, source stack allocated.
-            (dst_len, NZUSIZE_ONE) => {
-                // SAFETY: This is safe since self is heap allocated and has length `dst_len`.
-                unsafe { mem::dealloc_limbs(self.data.ptr, dst_len) };
-
-                // SAFETY: This is safe since source is stack allocated.
-                self.data.value = unsafe { source.data.value };
-                self.len = NZUSIZE_ONE;
+        let src_len = source.len;
+        if is_inline(src_len) {
+            let mut inline = [Limb::ZERO; INLINE_LIMBS];
+            inline[..src_len.get()].copy_from_slice(source.as_limbs());
+            if self.cap != 0 {
+                // SAFETY: `ptr` is heap allocated with length `cap`.
+                unsafe { mem::dealloc_limbs(self.data.ptr, nz(self.cap)) };
             }
-            // Self stack allocated, source heap allocated.
-            (NZUSIZE_ONE, src_len) => {
-                // SAFETY: This is safe since we will track this allocation.
-                let dst = unsafe { mem::alloc_limbs(src_len) };
 
-                self.data.ptr = dst;
-                self.len = src_len;
+            self.data.inline = inline;
+            self.len = src_len;
+            self.cap = 0;
+            return;
+        }
 
-                // SAFETY: This is safe since `self` and `source` have the same
-                //         number of limbs and do not overlap.
-                unsafe {
-                    self.limbs_mut()
-                        .copy_nonoverlapping(source.limbs(), src_len);
-                }
-            }
-            // Both heap allocated.
-            (old_len, src_len) => {
-                // Reallocate destination if lengths differ.
-                if old_len != src_len {
-                    // SAFETY: This is safe since self is heap allocated and has length `old_len`.
-                    unsafe { self.data.ptr = mem::realloc_limbs(self.data.ptr, old_len, src_len) };
-                }
+        if self.cap == 0 {
+            // SAFETY: This is safe since we will track this allocation.
+            self.data.ptr = unsafe { mem::alloc_limbs_uninit(src_len) };
+        } else if self.cap != src_len.get() {
+            // SAFETY: `ptr` is heap allocated with length `cap`.
+            self.data.ptr = unsafe { mem::realloc_limbs(self.data.ptr, nz(self.cap), src_len) };
+        }
+        self.len = src_len;
+        self.cap = src_len.get();
 
-                // SAFETY: This is safe since `self` and `source` have the same
-                //         number of limbs and do not overlap.
-                unsafe {
-                    self.limbs_mut()
-                        .copy_nonoverlapping(source.limbs(), src_len);
-                }
-            }
+        // SAFETY: This is safe since `self` and `source` have the same number
+        //         of limbs and do not overlap.
+        unsafe {
+            ptr::copy_nonoverlapping(
+                source.as_limbs().as_ptr(),
+                self.data.ptr.as_ptr(),
+                src_len.get(),
+            );
         }
     }
 }
 
+impl Default for ApInt {
+    /// Returns an `ApInt` with value `0`.
+    #[inline]
+    fn default() -> ApInt {
+        ApInt::ZERO
+    }
+}
+
 impl fmt::Debug for ApInt {
+    /// Formats the sign, the bit length and the two's complement limbs of the
+    /// int, most significant limb first, in hexadecimal.
+    ///
+    /// The alternate form `{:#?}` is pretty printed, and elides all but the
+    /// most and least significant limbs of a large int.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let limbs = DebugLimbs {
+            limbs: self.as_limbs(),
+            elide: f.alternate(),
+        };
+        f.debug_struct("ApInt")
+            .field("sign", &self.sign())
+            .field("bits", &self.bits())
+            .field("limbs", &limbs)
+            .finish()
+    }
+}
+
+/// The limbs of an int, formatted in hexadecimal, most significant first.
+struct DebugLimbs<'a> {
+    limbs: &'a [Limb],
+    /// Whether to elide the limbs between the ends of a large int.
+    elide: bool,
+}
+
+impl DebugLimbs<'_> {
+    /// The number of limbs kept at each end of elided limbs.
+    const ENDS: usize = 4;
+}
+
+impl fmt::Debug for DebugLimbs<'_> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut int = f.debug_struct("ApInt");
+        /// A limb, formatted in hexadecimal with all its digits.
+        struct Hex(Limb);
 
-        int.field("len", &self.len);
+        impl fmt::Debug for Hex {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{:#01$x}", self.0.repr(), 2 + 2 * Limb::SIZE)
+            }
+        }
 
-        // TODO: Improve debug implementation.
-        match self.data() {
-            LimbData::Stack(value) => int.field("limbs", &[value]),
-            // SAFETY: `limbs` are for reads up to `len`.
-            LimbData::Heap(limbs, len) => int.field("limbs", unsafe {
-                &core::slice::from_raw_parts(limbs.as_ptr(), len.get())
-            }),
-        };
+        /// The number of elided limbs.
+        struct Elided(usize);
+
+        impl fmt::Debug for Elided {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "... {} limbs ...", self.0)
+            }
+        }
 
-        int.finish()
+        let (n, ends) = (self.limbs.len(), DebugLimbs::ENDS);
+        let mut list = f.debug_list();
+        if self.elide && n > 2 * ends + 1 {
+            list.entries(self.limbs[n - ends..].iter().rev().map(|&limb| Hex(limb)));
+            list.entry(&Elided(n - 2 * ends));
+            list.entries(self.limbs[..ends].iter().rev().map(|&limb| Hex(limb)));
+        } else {
+            list.entries(self.limbs.iter().rev().map(|&limb| Hex(limb)));
+        }
+        list.finish()
     }
 }
 
+/// The limbs of an int, split into the single limb case, which has fast paths
+/// for most operations, and the case of several limbs, which may be stored
+/// inline or on the heap.
 pub(crate) enum LimbData<'a> {
     Stack(Limb),
     Heap(Limbs<'a>, NonZeroUsize),
 }
 
-pub(crate) enum LimbDataMut<'a> {
-    Stack(&'a mut Limb),
-    Heap(LimbsMut<'a>, NonZeroUsize),
-}
-
 impl ApInt {
     /// Returns an accessor to the limb data.
     #[inline]
-    pub(crate) fn data(&self) -> LimbData {
+    pub(crate) fn data(&self) -> LimbData<'_> {
         match self.len {
-            // SAFETY: A len of 1 guarantees that value is a valid limb.
-            NZUSIZE_ONE => LimbData::Stack(unsafe { self.data.value }),
-            // SAFETY: A len greater than 1 guarantees that ptr is a valid pointer.
+            NZUSIZE_ONE => LimbData::Stack(self.as_limbs()[0]),
+            // SAFETY: A len greater than 1 can be accessed by pointer.
             len => LimbData::Heap(unsafe { self.limbs() }, len),
         }
     }
 
-    /// Returns a mutable accessor to the limb data.
+    /// Returns a pointer accessor to the limb data.
+    ///
+    /// This function doesn't check that the int has more than one limb, as
+    /// required by the accessor.
     #[inline]
-    pub(crate) fn data_mut(&mut self) -> LimbDataMut {
-        match self.len {
-            // SAFETY: A len of 1 guarantees that value is a valid limb.
-            NZUSIZE_ONE => LimbDataMut::Stack(unsafe { &mut self.data.value }),
-            // SAFETY: A len greater than 1 guarantees that ptr is a valid pointer.
-            len => LimbDataMut::Heap(unsafe { self.limbs_mut() }, len),
-        }
+    pub(crate) unsafe fn limbs(&self) -> Limbs<'_> {
+        let ptr = NonNull::new_unchecked(self.as_limbs().as_ptr() as *mut Limb);
+        Limbs::new(ptr, self.len, &PhantomData)
     }
+}
 
-    /// Returns a pointer accessor to the limb data.
+impl ApInt {
+    /// Creates an `ApInt` with `len` zeroed limbs.
     ///
-    /// This function doesn't check that the internal data representation is a
-    /// valid pointer.
+    /// Unlike `with_capacity`, a length stored inline is valid and results in
+    /// an int without a heap allocation.
+    #[inline]
+    pub(crate) fn zeroed(len: NonZeroUsize) -> ApInt {
+        ApInt::try_zeroed(len).unwrap_or_else(|err| err.handle())
+    }
+
+    /// Creates an `ApInt` with `len` zeroed limbs like `zeroed`, but returns an
+    /// error if they cannot be allocated.
+    #[inline]
+    pub(crate) fn try_zeroed(len: NonZeroUsize) -> Result<ApInt, AllocError> {
+        if is_inline(len) {
+            Ok(ApInt {
+                len,
+                cap: 0,
+                data: ApIntData {
+                    inline: [Limb::ZERO; INLINE_LIMBS],
+                },
+            })
+        } else {
+            ApInt::try_with_capacity(len)
+        }
+    }
+
+    /// Returns the limbs of the int as a slice, least significant limb first.
     #[inline]
-    pub(crate) unsafe fn limbs(&self) -> Limbs {
-        Limbs::new(self.data.ptr, self.len, &PhantomData)
+    pub(crate) fn as_limbs(&self) -> &[Limb] {
+        let len = self.len.get();
+        if self.cap == 0 {
+            // SAFETY: An inline int has valid inline limbs up to `len`.
+            unsafe { &self.data.inline[..len] }
+        } else {
+            // SAFETY: A heap allocated int has a valid pointer to at least
+            //         `len` limbs.
+            unsafe { core::slice::from_raw_parts(self.data.ptr.as_ptr(), len) }
+        }
     }
 
-    /// Returns a mutable pointer accessor to the limb data.
+    /// Returns the limbs of the int as a mutable slice, least significant limb
+    /// first.
     ///
-    /// This function doesn't check that the internal data representation is a
-    /// valid pointer.
+    /// Writing to the limbs may leave the int denormalized, so callers must
+    /// call `normalize` before the int is observed again.
+    #[inline]
+    pub(crate) fn as_limbs_mut(&mut self) -> &mut [Limb] {
+        let len = self.len.get();
+        if self.cap == 0 {
+            // SAFETY: An inline int has valid inline limbs up to `len`.
+            unsafe { &mut self.data.inline[..len] }
+        } else {
+            // SAFETY: A heap allocated int has a valid pointer to at least
+            //         `len` limbs.
+            unsafe { core::slice::from_raw_parts_mut(self.data.ptr.as_ptr(), len) }
+        }
+    }
+
+    /// Returns the most significant limb, which holds the sign bit.
+    #[inline]
+    pub(crate) fn high_limb(&self) -> Limb {
+        let limbs = self.as_limbs();
+        limbs[limbs.len() - 1]
+    }
+
+    /// Returns `true` if the int is negative.
+    #[inline]
+    pub(crate) fn is_negative(&self) -> bool {
+        self.high_limb().is_negative()
+    }
+
+    /// Returns the number of bits required to hold the value of the int in two's
+    /// complement, excluding the sign bit.
     #[inline]
-    pub(crate) unsafe fn limbs_mut(&mut self) -> LimbsMut {
-        LimbsMut::new(self.data.ptr, self.len, &PhantomData)
+    pub(crate) fn significant_bits(&self) -> usize {
+        let high = self.high_limb();
+        let high = if high.is_negative() { !high } else { high };
+        self.len.get() * Limb::BITS - high.leading_zeros() as usize
+    }
+
+    /// Returns `true` if the int is even.
+    #[inline]
+    pub(crate) fn is_even(&self) -> bool {
+        self.as_limbs()[0].repr() & 1 == 0
+    }
+
+    /// Returns the unsigned magnitude of the int, without any high zero limbs.
+    ///
+    /// The magnitude of zero is empty.
+    pub(crate) fn magnitude(&self) -> Cow<'_, [Limb]> {
+        self.try_magnitude().unwrap_or_else(|err| err.handle())
+    }
+
+    /// Returns the unsigned magnitude of the int like `magnitude`, but returns
+    /// an error if the magnitude of a negative int cannot be allocated.
+    pub(crate) fn try_magnitude(&self) -> Result<Cow<'_, [Limb]>, AllocError> {
+        if self.is_negative() {
+            // The negation of the most negative value of a width is itself, which
+            // is also its correct unsigned magnitude.
+            let mut limbs = mem::try_scratch(self.len.get())?;
+            limbs.copy_from_slice(self.as_limbs());
+            ll::neg_assign(&mut limbs);
+            let len = ll::normalized_len(&limbs);
+            limbs.truncate(len);
+            Ok(Cow::Owned(limbs))
+        } else {
+            let limbs = self.as_limbs();
+            Ok(Cow::Borrowed(&limbs[..ll::normalized_len(limbs)]))
+        }
+    }
+
+    /// Resizes the int to `len` limbs, sign extending or truncating as needed,
+    /// and reallocates its storage to fit.
+    ///
+    /// Truncating may change the value of the int and growing leaves the int
+    /// denormalized.
+    pub(crate) fn resize(&mut self, len: NonZeroUsize) {
+        self.try_resize(len).unwrap_or_else(|err| err.handle())
+    }
+
+    /// Resizes the int to `len` limbs like `resize`, but returns an error,
+    /// leaving the int unchanged, if its storage cannot be reallocated.
+    pub(crate) fn try_resize(&mut self, len: NonZeroUsize) -> Result<(), AllocError> {
+        self.try_resize_storage(len, false)
+    }
+
+    /// Resizes the int to `len` limbs like `resize`, but keeps a heap
+    /// allocation that already has room for them rather than shrinking it.
+    pub(crate) fn resize_within(&mut self, len: NonZeroUsize) {
+        self.try_resize_storage(len, true)
+            .unwrap_or_else(|err| err.handle())
+    }
+
+    fn try_resize_storage(&mut self, len: NonZeroUsize, keep: bool) -> Result<(), AllocError> {
+        let old_len = self.len;
+        let sign = self.high_limb().sign_mask();
+
+        if self.cap == 0 {
+            if !is_inline(len) {
+                // Growing from inline storage.
+                // SAFETY: This is safe since we will track this allocation.
+                let ptr = unsafe { mem::try_alloc_limbs_uninit(len)? };
+                // SAFETY: `ptr` holds `len > old_len` limbs, and the inline
+                //         limbs are valid up to `old_len`.
+                unsafe {
+                    ptr::copy_nonoverlapping(self.data.inline.as_ptr(), ptr.as_ptr(), old_len.get())
+                };
+
+                self.data.ptr = ptr;
+                self.cap = len.get();
+            }
+            // Otherwise resizing inline, where the limbs past `len` are left
+            // as is.
+        } else if keep && len.get() <= self.cap {
+            // Resizing within the allocation.
+        } else if is_inline(len) && !keep {
+            // Shrinking to inline storage.
+            // SAFETY: A heap allocated int has a valid pointer.
+            let ptr = unsafe { self.data.ptr };
+            let mut inline = [Limb::ZERO; INLINE_LIMBS];
+            // SAFETY: `ptr` holds at least `len` limbs, as does `inline`.
+            unsafe { ptr::copy_nonoverlapping(ptr.as_ptr(), inline.as_mut_ptr(), len.get()) };
+            // SAFETY: `ptr` is heap allocated with length `cap`.
+            unsafe { mem::dealloc_limbs(ptr, nz(self.cap)) };
+
+            self.data.inline = inline;
+            self.cap = 0;
+        } else if self.cap != len.get() {
+            // Resizing on the heap.
+            // SAFETY: `ptr` is heap allocated with length `cap`.
+            self.data.ptr = unsafe { mem::try_realloc_limbs(self.data.ptr, nz(self.cap), len)? };
+            self.cap = len.get();
+        }
+        self.len = len;
+
+        if len > old_len {
+            for limb in &mut self.as_limbs_mut()[old_len.get()..] {
+                *limb = sign;
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes redundant sign extension limbs, so that the int is stored in
+    /// the fewest limbs able to hold its value.
+    pub(crate) fn normalize(&mut self) {
+        let len = normalized_len(self.as_limbs());
+        self.resize(nz(len));
+    }
+
+    /// Removes redundant sign extension limbs like `normalize`, but returns an
+    /// error, leaving the int unchanged, if its storage cannot be reallocated.
+    pub(crate) fn try_normalize(&mut self) -> Result<(), AllocError> {
+        let len = normalized_len(self.as_limbs());
+        self.try_resize(nz(len))
+    }
+
+    /// Removes redundant sign extension limbs like `normalize`, but keeps the
+    /// heap allocation of the int for reuse.
+    pub(crate) fn normalize_within(&mut self) {
+        let len = normalized_len(self.as_limbs());
+        self.resize_within(nz(len));
+    }
+}
+
+/// Returns a non-zero length of `len` limbs.
+#[inline]
+fn nz(len: usize) -> NonZeroUsize {
+    debug_assert!(len > 0);
+    // SAFETY: All callers pass lengths of at least 1.
+    unsafe { NonZeroUsize::new_unchecked(len) }
+}
+
+/// Returns the fewest number of limbs required to hold the value of the two's
+/// complement `limbs`.
+pub(crate) fn normalized_len(limbs: &[Limb]) -> usize {
+    let mut len = limbs.len();
+    // A high limb is redundant if it only holds the sign of the limb below.
+    while len > 1 && limbs[len - 1] == limbs[len - 2].sign_mask() {
+        len -= 1;
     }
+    len
 }
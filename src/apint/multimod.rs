@@ -0,0 +1,117 @@
+use crate::alloc::{Vec, vec};
+use crate::apint::ApInt;
+use crate::number_theory::rem_euclid;
+
+impl ApInt {
+    /// Returns the remainders of `self` modulo each of `moduli`, each in the
+    /// range `0..m`.
+    ///
+    /// The remainders are computed with a remainder tree: `self` is reduced
+    /// modulo the product of all of `moduli`, and then modulo the products of
+    /// ever smaller groups of them, so that most reductions are of a small
+    /// value by a small modulus.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of `moduli` is not positive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let moduli = [ApInt::from(7), ApInt::from(11), ApInt::from(13)];
+    /// let rems = ApInt::from(-1000).rem_multi(&moduli);
+    /// assert_eq!(rems, [ApInt::from(1), ApInt::from(1), ApInt::from(1)]);
+    /// ```
+    pub fn rem_multi(&self, moduli: &[ApInt]) -> Vec<ApInt> {
+        assert_positive(moduli);
+
+        let tree = ApInt::product_tree(moduli);
+        let mut rems = match tree.last() {
+            Some(root) => vec![rem_euclid(self, &root[0])],
+            None => return Vec::new(),
+        };
+        // Each node is a factor of its parent at index `i / 2`, so reducing
+        // the remainder of the parent gives the remainder of the node.
+        for level in tree.iter().rev().skip(1) {
+            rems = level
+                .iter()
+                .enumerate()
+                .map(|(i, m)| &rems[i / 2] % m)
+                .collect();
+        }
+        rems
+    }
+
+    /// Returns the value in the range `0..M` congruent to each of `residues`
+    /// modulo the corresponding modulus of `moduli`, where `M` is the product
+    /// of `moduli`, or `None` if `moduli` are not pairwise coprime.
+    ///
+    /// This is the inverse of [`rem_multi`]. The residues are combined up a
+    /// product tree, so the reconstruction is fast for many moduli.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `residues` and `moduli` have different lengths, or if any of
+    /// `moduli` is not positive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let moduli = [ApInt::from(3), ApInt::from(5), ApInt::from(7)];
+    /// let residues = [ApInt::from(2), ApInt::from(3), ApInt::from(2)];
+    /// assert_eq!(ApInt::crt(&residues, &moduli), Some(ApInt::from(23)));
+    /// ```
+    ///
+    /// [`rem_multi`]: ApInt::rem_multi
+    pub fn crt(residues: &[ApInt], moduli: &[ApInt]) -> Option<ApInt> {
+        assert_eq!(
+            residues.len(),
+            moduli.len(),
+            "slices must have the same length"
+        );
+        assert_positive(moduli);
+
+        let tree = ApInt::product_tree(moduli);
+        let product = match tree.last() {
+            Some(root) => &root[0],
+            None => return Some(ApInt::ZERO),
+        };
+
+        // `(M mod m^2) / m` is the cofactor `M / m` reduced modulo `m`.
+        let squares: Vec<ApInt> = moduli.iter().map(|m| m * m).collect();
+        let cofactors = product.rem_multi(&squares);
+        let mut values = Vec::with_capacity(moduli.len());
+        for ((r, m), c) in residues.iter().zip(moduli).zip(cofactors) {
+            let (g, inv, _) = (&c / m).extended_gcd(m);
+            if g != ApInt::ONE {
+                return None;
+            }
+            values.push(rem_euclid(&(r * inv), m));
+        }
+
+        // Combine the values of sibling nodes as `x * n + y * m`, where `m` and
+        // `n` are the products of the moduli below them.
+        for level in &tree[..tree.len() - 1] {
+            values = values
+                .chunks(2)
+                .zip(level.chunks(2))
+                .map(|(v, m)| match (v, m) {
+                    ([x, y], [m, n]) => x * n + y * m,
+                    ([x], _) => x.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+        }
+        Some(rem_euclid(&values[0], product))
+    }
+}
+
+fn assert_positive(moduli: &[ApInt]) {
+    if moduli.iter().any(|m| *m <= ApInt::ZERO) {
+        panic!("modulus must be positive");
+    }
+}
@@ -1,7 +1,11 @@
 use core::mem;
 
-use num_traits::{FromPrimitive, NumCast, One, ToPrimitive, Zero};
+use num_traits::{
+    FromBytes, FromPrimitive, MulAdd, MulAddAssign, NumCast, One, ToBytes, ToPrimitive, Zero,
+};
 
+use crate::alloc::Vec;
+use crate::apint::convert::f64_parts;
 use crate::apint::{ApInt, LimbData};
 use crate::limb::Limb;
 
@@ -35,6 +39,75 @@ impl One for ApInt {
 
 // TODO: Implement Num for ApInt.
 
+impl MulAdd for ApInt {
+    type Output = ApInt;
+
+    #[inline]
+    fn mul_add(self, a: ApInt, b: ApInt) -> ApInt {
+        ApInt::mul_add(&self, &a, &b)
+    }
+}
+
+impl MulAdd<&ApInt, &ApInt> for ApInt {
+    type Output = ApInt;
+
+    #[inline]
+    fn mul_add(self, a: &ApInt, b: &ApInt) -> ApInt {
+        ApInt::mul_add(&self, a, b)
+    }
+}
+
+impl MulAdd<&ApInt, &ApInt> for &ApInt {
+    type Output = ApInt;
+
+    #[inline]
+    fn mul_add(self, a: &ApInt, b: &ApInt) -> ApInt {
+        ApInt::mul_add(self, a, b)
+    }
+}
+
+impl MulAddAssign for ApInt {
+    #[inline]
+    fn mul_add_assign(&mut self, a: ApInt, b: ApInt) {
+        *self = ApInt::mul_add(self, &a, &b);
+    }
+}
+
+impl MulAddAssign<&ApInt, &ApInt> for ApInt {
+    #[inline]
+    fn mul_add_assign(&mut self, a: &ApInt, b: &ApInt) {
+        *self = ApInt::mul_add(self, a, b);
+    }
+}
+
+/// The bytes are the signed two's complement bytes of the int, as returned by
+/// [`ApInt::to_signed_bytes_le`].
+impl ToBytes for ApInt {
+    type Bytes = Vec<u8>;
+
+    fn to_be_bytes(&self) -> Vec<u8> {
+        self.to_signed_bytes_be()
+    }
+
+    fn to_le_bytes(&self) -> Vec<u8> {
+        self.to_signed_bytes_le()
+    }
+}
+
+/// The bytes are the signed two's complement bytes of the int, as read by
+/// [`ApInt::from_signed_bytes_le`].
+impl FromBytes for ApInt {
+    type Bytes = [u8];
+
+    fn from_be_bytes(bytes: &[u8]) -> ApInt {
+        ApInt::from_signed_bytes_be(bytes)
+    }
+
+    fn from_le_bytes(bytes: &[u8]) -> ApInt {
+        ApInt::from_signed_bytes_le(bytes)
+    }
+}
+
 impl FromPrimitive for ApInt {
     fn from_isize(n: isize) -> Option<ApInt> {
         Some(From::from(n))
@@ -84,129 +157,115 @@ impl FromPrimitive for ApInt {
         Some(From::from(n))
     }
 
-    // FIXME: Replace from float functions with custom implementation.
-
+    // Every `f32` is exactly representable as an `f64`.
     fn from_f32(n: f32) -> Option<ApInt> {
-        n.to_i128().and_then(FromPrimitive::from_i128)
+        FromPrimitive::from_f64(n as f64)
     }
 
+    /// Converts any finite float exactly, truncating fractional values toward
+    /// zero as `as` casts do, and returns `None` for infinities and NaN.
     fn from_f64(n: f64) -> Option<ApInt> {
-        n.to_i128().and_then(FromPrimitive::from_i128)
-    }
-}
+        if !n.is_finite() {
+            return None;
+        }
 
-macro_rules! to_prim {
-    ($self:ident, $conv:ident) => {{
-        match $self.data() {
-            LimbData::Stack(value) => value.repr_signed().$conv(),
-            _ => None,
+        let (m, e) = f64_parts(n);
+        if e >= 0 {
+            return Some(<ApInt as From<i64>>::from(m).mul_pow2(e as usize));
         }
-    }};
-}
 
-macro_rules! to_int {
-    ($self:ident, $ty:ident, $conv:ident) => {{
-        const LEN: usize = mem::size_of::<$ty>() / Limb::SIZE;
+        // Shift the magnitude so the discarded bits round toward zero.
+        let shift = -e as u32;
+        let mag = if shift < 64 { m.abs() >> shift } else { 0 };
+        Some(From::from(if m < 0 { -mag } else { mag }))
+    }
+}
 
-        match $self.data() {
-            // Stack allocated int can use a direct ToPrimitive call.
-            LimbData::Stack(value) => value.repr_signed().$conv(),
-            // Heap allocated int requires some checks.
-            LimbData::Heap(_, len) => match len.get() {
-                // Fewer than or exactly `LEN` limbs.
-                0..=LEN => Some(From::from($self)),
-                // The int value doesn't fit within a $ty.
-                _ => None,
-            },
+/// Returns `true` if the two's complement value of `limbs` is in the range of
+/// an integer type with `bits` bits.
+fn fits_in(limbs: &[Limb], bits: usize, signed: bool) -> bool {
+    let sign = limbs[limbs.len() - 1].sign_mask();
+    if !signed && sign != Limb::ZERO {
+        return false;
+    }
+
+    // Every bit from the sign bit of the type upward must match the sign.
+    let from = bits - signed as usize;
+    let (idx, offset) = (from / Limb::BITS, from % Limb::BITS);
+    limbs.iter().enumerate().skip(idx).all(|(i, limb)| {
+        let diff = limb.repr() ^ sign.repr();
+        if i == idx {
+            diff >> offset == 0
+        } else {
+            diff == 0
         }
-    }};
+    })
 }
 
-macro_rules! to_uint {
-    ($self:ident, $ty:ident, $conv:ident) => {{
-        const LEN: usize = (mem::size_of::<$ty>() / Limb::SIZE) + 1;
-        const LEN_M1: usize = LEN - 1;
-
+macro_rules! to_prim {
+    ($self:ident, $ty:ident, $conv:ident, $signed:expr) => {{
         match $self.data() {
             // Stack allocated int can use a direct ToPrimitive call.
             LimbData::Stack(value) => value.repr_signed().$conv(),
-            // Heap allocated int requires some checks.
-            LimbData::Heap(ptr, len) => match len.get() {
-                // Fewer than `LEN` limbs.
-                0..=LEN_M1 => Some(From::from($self)),
-                // Has `LEN` limbs, but last limb is zero.
-                // SAFETY: This is safe since, the match guarantees
-                LEN if unsafe { *ptr.add(LEN_M1) == Limb::ZERO } => Some(From::from($self)),
-                // The int value doesn't fit within a $ty.
-                _ => None,
-            },
+            // Heap allocated int needs a range check over all of its limbs.
+            LimbData::Heap(..) => {
+                if fits_in($self.as_limbs(), mem::size_of::<$ty>() * 8, $signed) {
+                    Some(From::from($self))
+                } else {
+                    None
+                }
+            }
         }
     }};
 }
 
 impl ToPrimitive for ApInt {
     fn to_isize(&self) -> Option<isize> {
-        to_prim!(self, to_isize)
+        to_prim!(self, isize, to_isize, true)
     }
 
     fn to_i8(&self) -> Option<i8> {
-        to_prim!(self, to_i8)
+        to_prim!(self, i8, to_i8, true)
     }
 
     fn to_i16(&self) -> Option<i16> {
-        to_prim!(self, to_i16)
+        to_prim!(self, i16, to_i16, true)
     }
 
     fn to_i32(&self) -> Option<i32> {
-        to_prim!(self, to_i32)
+        to_prim!(self, i32, to_i32, true)
     }
 
     fn to_i64(&self) -> Option<i64> {
-        #[cfg(target_pointer_width = "32")]
-        {
-            to_int!(self, i64, to_i64)
-        }
-
-        #[cfg(target_pointer_width = "64")]
-        {
-            to_prim!(self, to_i64)
-        }
+        to_prim!(self, i64, to_i64, true)
     }
 
     fn to_i128(&self) -> Option<i128> {
-        to_int!(self, i128, to_i128)
+        to_prim!(self, i128, to_i128, true)
     }
 
     fn to_usize(&self) -> Option<usize> {
-        to_prim!(self, to_usize)
+        to_prim!(self, usize, to_usize, false)
     }
 
     fn to_u8(&self) -> Option<u8> {
-        to_prim!(self, to_u8)
+        to_prim!(self, u8, to_u8, false)
     }
 
     fn to_u16(&self) -> Option<u16> {
-        to_prim!(self, to_u16)
+        to_prim!(self, u16, to_u16, false)
     }
 
     fn to_u32(&self) -> Option<u32> {
-        #[cfg(target_pointer_width = "32")]
-        {
-            to_uint!(self, u32, to_u32)
-        }
-
-        #[cfg(target_pointer_width = "64")]
-        {
-            to_prim!(self, to_u32)
-        }
+        to_prim!(self, u32, to_u32, false)
     }
 
     fn to_u64(&self) -> Option<u64> {
-        to_uint!(self, u64, to_u64)
+        to_prim!(self, u64, to_u64, false)
     }
 
     fn to_u128(&self) -> Option<u128> {
-        to_uint!(self, u128, to_u128)
+        to_prim!(self, u128, to_u128, false)
     }
 
     // FIXME: Replace to float functions with custom implementation.
@@ -1,21 +1,1108 @@
-use core::ops::{Add, Mul};
+use core::iter::Product;
+use core::num::NonZeroUsize;
+use core::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
+};
+use core::ptr;
 
-use crate::apint::ApInt;
+use num_traits::{One, ToPrimitive, Zero};
 
-// TODO: Add implementations for core operations.
+use crate::alloc::{Cow, Vec, vec};
+use crate::apint::bitwise::shl;
+use crate::apint::{ApInt, LimbData};
+use crate::limb::{Limb, LimbRepr};
+use crate::ll;
+use crate::oom::AllocError;
+use crate::sign::Sign;
 
-impl Add<ApInt> for ApInt {
+impl ApInt {
+    /// Negates `self` in place.
+    ///
+    /// This only allocates when `self` is the most negative value able to fit
+    /// in its current number of limbs, since the negation of that value needs
+    /// an extra limb. A heap allocation is kept as is when the negation needs
+    /// fewer limbs, so negating never shrinks the storage.
+    pub fn negate(&mut self) {
+        self.negate_storage(true);
+    }
+
+    /// Negates `self` in place like `negate`, but reallocates its storage to
+    /// fit the result.
+    pub(crate) fn negate_to_fit(&mut self) {
+        self.negate_storage(false);
+    }
+
+    fn negate_storage(&mut self, keep: bool) {
+        let limbs = self.as_limbs();
+        let (high, low) = limbs.split_last().unwrap();
+
+        // The only value whose negation overflows is `-2^(n * BITS - 1)`.
+        if *high == Limb::SIGN_BIT && low.iter().all(|&limb| limb == Limb::ZERO) {
+            let len = limbs.len() + 1;
+            // SAFETY: `len` is at least 2.
+            let len = unsafe { NonZeroUsize::new_unchecked(len) };
+            if keep {
+                self.resize_within(len);
+            } else {
+                self.resize(len);
+            }
+        }
+
+        ll::neg_assign(self.as_limbs_mut());
+        if keep {
+            self.normalize_within();
+        } else {
+            self.normalize();
+        }
+    }
+
+    /// Replaces `self` with its absolute value in place.
+    ///
+    /// Like [`negate`](ApInt::negate), this only allocates when the absolute
+    /// value needs an extra limb.
+    pub fn make_abs(&mut self) {
+        if self.is_negative() {
+            self.negate();
+        }
+    }
+
+    /// Returns the absolute value of `self`.
+    pub fn abs(&self) -> ApInt {
+        let mut abs = self.clone();
+        abs.make_abs();
+        abs
+    }
+
+    /// Returns the sign of `self`.
+    pub fn sign(&self) -> Sign {
+        if self.is_negative() {
+            Sign::Negative
+        } else if self.is_zero() {
+            Sign::Zero
+        } else {
+            Sign::Positive
+        }
+    }
+
+    /// Returns the quotient and remainder of `self / other`.
+    ///
+    /// The quotient is truncated towards zero and the remainder has the same
+    /// sign as `self`, matching the behaviour of the primitive integer types
+    /// and the `/` and `%` operators. See [`divmod`](ApInt::divmod) for
+    /// floor division.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` is zero.
+    pub fn div_rem(&self, other: &ApInt) -> (ApInt, ApInt) {
+        div_rem(self, other)
+    }
+
+    /// Returns `self + other`, or an error if the limbs of the sum cannot be
+    /// allocated.
+    ///
+    /// This is the fallible form of `+`, which calls the
+    /// [OOM handler](crate::set_oom_handler) instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let sum = ApInt::from(u64::MAX).try_add(&ApInt::from(1))?;
+    /// assert_eq!(sum, ApInt::from(1u128 << 64));
+    /// # Ok::<(), apa::AllocError>(())
+    /// ```
+    pub fn try_add(&self, other: &ApInt) -> Result<ApInt, AllocError> {
+        try_add(self, other)
+    }
+
+    /// Returns `self - other`, or an error if the limbs of the difference
+    /// cannot be allocated.
+    ///
+    /// This is the fallible form of `-`, which calls the
+    /// [OOM handler](crate::set_oom_handler) instead.
+    pub fn try_sub(&self, other: &ApInt) -> Result<ApInt, AllocError> {
+        try_sub(self, other)
+    }
+
+    /// Returns `self * other`, or an error if the limbs of the product or of
+    /// the temporary buffers used to compute it cannot be allocated.
+    ///
+    /// This is the fallible form of `*`, which calls the
+    /// [OOM handler](crate::set_oom_handler) instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let x = ApInt::from(u128::MAX);
+    /// assert_eq!(x.try_mul(&x)?, &x * &x);
+    /// # Ok::<(), apa::AllocError>(())
+    /// ```
+    pub fn try_mul(&self, other: &ApInt) -> Result<ApInt, AllocError> {
+        try_mul(self, other)
+    }
+
+    /// Returns the quotient and remainder of `self / other` like
+    /// [`div_rem`](ApInt::div_rem), or an error if the limbs of the results or
+    /// of the temporary buffers used to compute them cannot be allocated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` is zero.
+    pub fn try_div_rem(&self, other: &ApInt) -> Result<(ApInt, ApInt), AllocError> {
+        try_div_rem(self, other)
+    }
+
+    /// Returns the quotient and remainder of the floor division of `self` by
+    /// `other`, like Python's `divmod`.
+    ///
+    /// The quotient is rounded towards negative infinity and the remainder has
+    /// the same sign as `other`, so `self == q * other + r` with `r` in the
+    /// range `0..other` for a positive `other`, or `other+1..=0` for a negative
+    /// one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let (q, r) = ApInt::from(-7).divmod(&ApInt::from(2));
+    /// assert_eq!((q, r), (ApInt::from(-4), ApInt::from(1)));
+    ///
+    /// let (q, r) = ApInt::from(7).divmod(&ApInt::from(-2));
+    /// assert_eq!((q, r), (ApInt::from(-4), ApInt::from(-1)));
+    /// ```
+    pub fn divmod(&self, other: &ApInt) -> (ApInt, ApInt) {
+        let (mut q, mut r) = div_rem(self, other);
+        // Truncation rounded towards positive infinity when the remainder is
+        // non-zero and has the opposite sign to the divisor.
+        if !r.is_zero() && r.is_negative() != other.is_negative() {
+            q -= ApInt::ONE;
+            r += other;
+        }
+        (q, r)
+    }
+
+    /// Returns the quotient of `self / other`, rounded towards negative
+    /// infinity, like Python's `//` operator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` is zero.
+    pub fn div_floor(&self, other: &ApInt) -> ApInt {
+        self.divmod(other).0
+    }
+
+    /// Returns the remainder of the floor division of `self` by `other`, which
+    /// has the same sign as `other`, like Python's `%` operator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` is zero.
+    pub fn mod_floor(&self, other: &ApInt) -> ApInt {
+        self.divmod(other).1
+    }
+
+    /// Returns `self * a + b`.
+    ///
+    /// The product and sum are computed in a single buffer, so only one
+    /// allocation is made.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let x = ApInt::from(i64::MAX);
+    /// let r = x.mul_add(&ApInt::from(2), &ApInt::from(-1));
+    /// assert_eq!(r, ApInt::from(2 * i64::MAX as i128 - 1));
+    /// ```
+    pub fn mul_add(&self, a: &ApInt, b: &ApInt) -> ApInt {
+        mul_add(self, a, b)
+    }
+
+    /// Adds `a * b` to `self` in place.
+    ///
+    /// The product is added into the limbs of `self` as it is computed, and a
+    /// product with a single limb factor is never formed at all, so this is
+    /// the cheapest way to update a row in fraction-free elimination.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let mut x = ApInt::from(10);
+    /// x.add_mul_assign(&ApInt::from(3), &ApInt::from(-4));
+    /// assert_eq!(x, ApInt::from(-2));
+    /// ```
+    pub fn add_mul_assign(&mut self, a: &ApInt, b: &ApInt) {
+        add_mul_assign(self, a, b, false);
+    }
+
+    /// Subtracts `a * b` from `self` in place.
+    ///
+    /// This is computed in the same way as
+    /// [`add_mul_assign`](ApInt::add_mul_assign).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let mut x = ApInt::from(10);
+    /// x.sub_mul_assign(&ApInt::from(3), &ApInt::from(-4));
+    /// assert_eq!(x, ApInt::from(22));
+    /// ```
+    pub fn sub_mul_assign(&mut self, a: &ApInt, b: &ApInt) {
+        add_mul_assign(self, a, b, true);
+    }
+
+    /// Writes `a + b` to `dst`, reusing the allocation of `dst`.
+    ///
+    /// The sum is written directly into the heap allocation of `dst`, which
+    /// is only grown if the sum may not fit in it and is never shrunk, so a
+    /// `dst` reused across a loop of operations of similar size makes no
+    /// allocations at all. This also makes it useful to measure the cost of
+    /// the addition without the allocator.
+    ///
+    /// Unlike other operations, this leaves `dst` holding more memory than its
+    /// value needs, as reported by [`memory_usage`](ApInt::memory_usage),
+    /// until it is assigned by any other operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let mut dst = ApInt::from(u128::MAX);
+    /// ApInt::add_into(&mut dst, &ApInt::from(i128::MIN), &ApInt::from(-1));
+    /// assert_eq!(dst, ApInt::from(i128::MIN) - ApInt::ONE);
+    /// ```
+    pub fn add_into(dst: &mut ApInt, a: &ApInt, b: &ApInt) {
+        add_into(dst, a, b, false);
+    }
+
+    /// Writes `a - b` to `dst`, reusing the allocation of `dst`.
+    ///
+    /// This is computed in the same way as [`add_into`](ApInt::add_into).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let mut dst = ApInt::ZERO;
+    /// ApInt::sub_into(&mut dst, &ApInt::from(3), &ApInt::from(i128::MIN));
+    /// assert_eq!(dst, ApInt::from(i128::MAX) + ApInt::from(4));
+    /// ```
+    pub fn sub_into(dst: &mut ApInt, a: &ApInt, b: &ApInt) {
+        add_into(dst, a, b, true);
+    }
+
+    /// Writes `a * b` to `dst`, reusing the allocation of `dst`.
+    ///
+    /// This is computed in the same way as [`add_into`](ApInt::add_into), and
+    /// makes no allocations when the product fits in the allocation of `dst`
+    /// and the factors are below the
+    /// [Karatsuba threshold](crate::tuning::karatsuba_threshold). Scratch space
+    /// for subquadratic multiplication is still allocated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let mut dst = ApInt::ZERO;
+    /// ApInt::mul_into(&mut dst, &ApInt::from(u64::MAX), &ApInt::from(-3));
+    /// assert_eq!(dst, ApInt::from(u64::MAX as i128 * -3));
+    /// ```
+    pub fn mul_into(dst: &mut ApInt, a: &ApInt, b: &ApInt) {
+        mul_into(dst, a, b);
+    }
+
+    /// Returns the dot product of `xs` and `ys`, which is the sum of the
+    /// products of their corresponding elements.
+    ///
+    /// The products are added into a single accumulator as they are computed,
+    /// and a product with a single limb factor is fused into the accumulator
+    /// without being formed, so no intermediate int is allocated per term.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `xs` and `ys` have different lengths.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let xs = [ApInt::from(1), ApInt::from(-2), ApInt::from(3)];
+    /// let ys = [ApInt::from(4), ApInt::from(5), ApInt::from(6)];
+    /// assert_eq!(ApInt::dot(&xs, &ys), ApInt::from(12));
+    /// ```
+    pub fn dot(xs: &[ApInt], ys: &[ApInt]) -> ApInt {
+        assert_eq!(xs.len(), ys.len(), "slices must have the same length");
+
+        // Each product fits in the sum of the lengths of its factors, and a
+        // sum of fewer than `2^BITS` products needs at most one more limb,
+        // plus one for the sign.
+        let len = xs
+            .iter()
+            .zip(ys)
+            .map(|(x, y)| x.as_limbs().len() + y.as_limbs().len())
+            .max()
+            .unwrap_or(0)
+            + 2;
+
+        let mut acc = ApInt::zeroed(nz(len));
+        for (x, y) in xs.iter().zip(ys) {
+            add_mul_limbs(acc.as_limbs_mut(), x, y, false);
+        }
+        acc.normalize();
+        acc
+    }
+
+    /// Returns the product of the values of `iter`, or `1` if it is empty.
+    ///
+    /// The values are multiplied in a balanced binary tree, so that the
+    /// factors of each multiplication are of similar size and benefit from
+    /// subquadratic multiplication. This is much faster than multiplying them
+    /// in turn when there are many factors, such as when computing a primorial
+    /// or expanding a polynomial from its roots.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let primorial = ApInt::product([2, 3, 5, 7, 11].iter().map(|&p| ApInt::from(p)));
+    /// assert_eq!(primorial, ApInt::from(2310));
+    /// ```
+    pub fn product<I>(iter: I) -> ApInt
+    where
+        I: IntoIterator<Item = ApInt>,
+    {
+        let mut level: Vec<ApInt> = iter.into_iter().collect();
+        while level.len() > 1 {
+            level = product_level(&level);
+        }
+        level.pop().unwrap_or(ApInt::ONE)
+    }
+
+    /// Returns every level of the balanced product tree of `factors`.
+    ///
+    /// The first level is a copy of `factors`, each following level holds the
+    /// products of adjacent pairs of the level below it, with an odd value out
+    /// carried up unchanged, and the last level holds the product of all of
+    /// `factors`. The tree is empty if `factors` is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let factors = [ApInt::from(2), ApInt::from(3), ApInt::from(5)];
+    /// let tree = ApInt::product_tree(&factors);
+    /// assert_eq!(tree[1], [ApInt::from(6), ApInt::from(5)]);
+    /// assert_eq!(tree[2], [ApInt::from(30)]);
+    /// ```
+    pub fn product_tree(factors: &[ApInt]) -> Vec<Vec<ApInt>> {
+        if factors.is_empty() {
+            return Vec::new();
+        }
+
+        let mut tree = vec![factors.to_vec()];
+        while tree[tree.len() - 1].len() > 1 {
+            let next = product_level(&tree[tree.len() - 1]);
+            tree.push(next);
+        }
+        tree
+    }
+
+    /// Returns `self` raised to the power `exp`.
+    pub(crate) fn pow(&self, mut exp: u32) -> ApInt {
+        let mut base = self.clone();
+        let mut r = ApInt::ONE;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                r *= &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+        r
+    }
+
+    /// Returns the remainder of dividing `self` by `d`, without allocating.
+    ///
+    /// The remainder is in the range `0..d` even when `self` is negative, so it
+    /// is the remainder of Euclidean division.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `d` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// assert_eq!(ApInt::from(-7).rem_u64(3), 2);
+    /// ```
+    pub fn rem_u64(&self, d: u64) -> u64 {
+        let mut r = initial_rem_u64(self, d);
+        for limb in self.as_limbs().iter().rev() {
+            r = (((r as u128) << Limb::BITS | limb.repr() as u128) % d as u128) as u64;
+        }
+        r
+    }
+
+    /// Divides `self` by `d`, returning the quotient and the remainder.
+    ///
+    /// The quotient is rounded towards negative infinity, so that the remainder
+    /// is in the range `0..d` as with [`rem_u64`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `d` is zero.
+    ///
+    /// [`rem_u64`]: ApInt::rem_u64
+    pub fn div_rem_u64(&self, d: u64) -> (ApInt, u64) {
+        let mut r = initial_rem_u64(self, d);
+
+        let limbs = self.as_limbs();
+        // An extra limb holds the sign of the quotient.
+        let mut quot = ApInt::zeroed(nz(limbs.len() + 1));
+        {
+            let quot = quot.as_limbs_mut();
+            for (q, limb) in quot.iter_mut().zip(limbs).rev() {
+                // Since `r < d`, the quotient of each step fits in a limb.
+                let n = (r as u128) << Limb::BITS | limb.repr() as u128;
+                *q = Limb((n / d as u128) as LimbRepr);
+                r = (n % d as u128) as u64;
+            }
+            quot[limbs.len()] = self.high_limb().sign_mask();
+        }
+
+        quot.normalize();
+        (quot, r)
+    }
+
+    /// Returns `true` if `self` is divisible by `d`.
+    ///
+    /// Divisibility is tested by multiplying with the inverse of the odd part
+    /// of `d`, which is much cheaper than computing a remainder. Only zero is
+    /// divisible by zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let int = ApInt::from(3u128.pow(70));
+    /// assert!(int.divisible_by_u32(81));
+    /// assert!(!int.divisible_by_u32(6));
+    /// ```
+    pub fn divisible_by_u32(&self, d: u32) -> bool {
+        let zeros = match self.trailing_zeros() {
+            Some(zeros) => zeros,
+            None => return true,
+        };
+        if d == 0 {
+            return false;
+        }
+
+        // Test the factors of two and the odd part of `d` separately.
+        let d_zeros = d.trailing_zeros();
+        let d = d >> d_zeros;
+        d_zeros as usize <= zeros
+            && (d == 1 || ll::divisible_1_odd(&self.magnitude(), Limb(d as LimbRepr)))
+    }
+
+    /// Divides `self` by `other`, where `self` is known to be an exact multiple
+    /// of `other`.
+    ///
+    /// This uses an exact division algorithm, which is faster than the general
+    /// division of [`div_rem`] and the `/` operator. The result is unspecified
+    /// if `other` does not divide `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` is zero.
+    ///
+    /// [`div_rem`]: ApInt::div_rem
+    pub fn div_exact(&self, other: &ApInt) -> ApInt {
+        div_exact(self, other)
+    }
+
+    /// Returns `floor(2^bits / self)`, computed by Newton iteration.
+    ///
+    /// A reciprocal turns repeated divisions by the same value into
+    /// multiplications: for a positive `self` and any `x` in the range
+    /// `0..=2^bits`, `(x * r) >> bits` is either `x / self` or one less than
+    /// it, where `r` is the reciprocal.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let d = ApInt::from(7);
+    /// let r = d.reciprocal(64);
+    /// assert_eq!(r, ApInt::from((1u128 << 64) / 7));
+    ///
+    /// let x = ApInt::from(1_000_000);
+    /// let q = (&x * &r) >> 64;
+    /// assert!(q == &x / &d || q == &x / &d - ApInt::ONE);
+    /// ```
+    pub fn reciprocal(&self, bits: usize) -> ApInt {
+        reciprocal(self, bits)
+    }
+}
+
+impl Neg for ApInt {
     type Output = ApInt;
 
-    fn add(self, _rhs: Self) -> ApInt {
-        todo!()
+    #[inline]
+    fn neg(mut self) -> ApInt {
+        self.negate_to_fit();
+        self
     }
 }
 
-impl Mul<ApInt> for ApInt {
+impl Neg for &ApInt {
     type Output = ApInt;
 
-    fn mul(self, _rhs: Self) -> ApInt {
-        todo!()
+    #[inline]
+    fn neg(self) -> ApInt {
+        -self.clone()
+    }
+}
+
+/// Returns the remainder to start a division of `x` by `d` from, most
+/// significant limb first.
+///
+/// A negative `x` is treated as the limbs of `x` with an extra limb of `-1`
+/// above them, whose floored division by `d` leaves a remainder of `d - 1`.
+fn initial_rem_u64(x: &ApInt, d: u64) -> u64 {
+    if d == 0 {
+        panic!("attempt to divide by zero");
+    }
+    if x.is_negative() { d - 1 } else { 0 }
+}
+
+/// Returns a non-zero length of `len` limbs.
+#[inline]
+fn nz(len: usize) -> NonZeroUsize {
+    debug_assert!(len > 0);
+    // SAFETY: All callers calculate lengths of at least 1.
+    unsafe { NonZeroUsize::new_unchecked(len) }
+}
+
+fn add(x: &ApInt, y: &ApInt) -> ApInt {
+    if ptr::eq(x, y) {
+        return shl(x, 1);
+    }
+    try_add(x, y).unwrap_or_else(|err| err.handle())
+}
+
+fn try_add(x: &ApInt, y: &ApInt) -> Result<ApInt, AllocError> {
+    if let (LimbData::Stack(x), LimbData::Stack(y)) = (x.data(), y.data()) {
+        if let Some(sum) = x.repr_signed().checked_add(y.repr_signed()) {
+            return Ok(ApInt::from_limb(Limb(sum as LimbRepr)));
+        }
+    }
+
+    let (x, y) = (x.as_limbs(), y.as_limbs());
+    // An extra limb is needed for the carry.
+    let mut sum = ApInt::try_zeroed(nz(x.len().max(y.len()) + 1))?;
+    ll::add_signed(sum.as_limbs_mut(), x, y);
+    sum.try_normalize()?;
+    Ok(sum)
+}
+
+fn sub(x: &ApInt, y: &ApInt) -> ApInt {
+    try_sub(x, y).unwrap_or_else(|err| err.handle())
+}
+
+fn try_sub(x: &ApInt, y: &ApInt) -> Result<ApInt, AllocError> {
+    if ptr::eq(x, y) {
+        return Ok(ApInt::ZERO);
+    }
+    if let (LimbData::Stack(x), LimbData::Stack(y)) = (x.data(), y.data()) {
+        if let Some(diff) = x.repr_signed().checked_sub(y.repr_signed()) {
+            return Ok(ApInt::from_limb(Limb(diff as LimbRepr)));
+        }
+    }
+
+    let (x, y) = (x.as_limbs(), y.as_limbs());
+    // An extra limb is needed for the borrow.
+    let mut diff = ApInt::try_zeroed(nz(x.len().max(y.len()) + 1))?;
+    ll::sub_signed(diff.as_limbs_mut(), x, y);
+    diff.try_normalize()?;
+    Ok(diff)
+}
+
+fn mul(x: &ApInt, y: &ApInt) -> ApInt {
+    try_mul(x, y).unwrap_or_else(|err| err.handle())
+}
+
+fn try_mul(x: &ApInt, y: &ApInt) -> Result<ApInt, AllocError> {
+    if let (LimbData::Stack(x), LimbData::Stack(y)) = (x.data(), y.data()) {
+        if let Some(prod) = x.repr_signed().checked_mul(y.repr_signed()) {
+            return Ok(ApInt::from_limb(Limb(prod as LimbRepr)));
+        }
+    }
+
+    let negative = x.is_negative() != y.is_negative();
+    let aliased = ptr::eq(x, y);
+    let x = x.try_magnitude()?;
+    // A square only needs the magnitude computed once.
+    let y = if aliased {
+        Cow::Borrowed(&*x)
+    } else {
+        y.try_magnitude()?
+    };
+    if x.is_empty() || y.is_empty() {
+        return Ok(ApInt::ZERO);
+    }
+
+    // An extra limb is needed for the sign.
+    let len = x.len() + y.len() + 1;
+    let mut prod = ApInt::try_zeroed(nz(len))?;
+    let limbs = prod.as_limbs_mut();
+    ll::try_mul(&mut limbs[..len - 1], &x, &y)?;
+    if negative {
+        ll::neg_assign(limbs);
+    }
+    prod.try_normalize()?;
+    Ok(prod)
+}
+
+fn mul_add(x: &ApInt, y: &ApInt, z: &ApInt) -> ApInt {
+    if let (LimbData::Stack(x), LimbData::Stack(y), LimbData::Stack(z)) =
+        (x.data(), y.data(), z.data())
+    {
+        let r = x.repr_signed().checked_mul(y.repr_signed());
+        if let Some(r) = r.and_then(|prod| prod.checked_add(z.repr_signed())) {
+            return ApInt::from_limb(Limb(r as LimbRepr));
+        }
+    }
+
+    let negative = x.is_negative() != y.is_negative();
+    let (x, y) = (x.magnitude(), y.magnitude());
+    if x.is_empty() || y.is_empty() {
+        return z.clone();
+    }
+
+    let z = z.as_limbs();
+    // An extra limb is needed for the sign of the product, and another for
+    // the carry of the sum.
+    let len = (x.len() + y.len() + 1).max(z.len()) + 1;
+    let mut r = ApInt::zeroed(nz(len));
+    let limbs = r.as_limbs_mut();
+    ll::mul(&mut limbs[..x.len() + y.len()], &x, &y);
+    if negative {
+        ll::neg_assign(limbs);
+    }
+    ll::add_signed_assign(limbs, z);
+    r.normalize();
+    r
+}
+
+fn add_mul_assign(w: &mut ApInt, x: &ApInt, y: &ApInt, subtract: bool) {
+    if x.is_zero() || y.is_zero() {
+        return;
+    }
+    // An extra limb holds the carry out of the sum.
+    let len = w
+        .as_limbs()
+        .len()
+        .max(x.as_limbs().len() + y.as_limbs().len())
+        + 1;
+    w.resize(nz(len));
+    add_mul_limbs(w.as_limbs_mut(), x, y, subtract);
+    w.normalize();
+}
+
+/// Adds `x * y` to the two's complement value `w` in place, or subtracts it
+/// if `subtract` is set.
+///
+/// `w` must be long enough to hold the result and the product, plus one more
+/// limb to hold the carry. A product with a single limb factor is fused into
+/// `w`.
+pub(crate) fn add_mul_limbs(w: &mut [Limb], x: &ApInt, y: &ApInt, subtract: bool) {
+    let negative = (x.is_negative() != y.is_negative()) != subtract;
+    let (x, y) = (x.magnitude(), y.magnitude());
+    let (x, y) = if x.len() < y.len() { (y, x) } else { (x, y) };
+    if y.is_empty() {
+        return;
+    }
+    debug_assert!(w.len() > x.len() + y.len());
+
+    let (n, carry) = if let [y] = *y {
+        let n = x.len();
+        let carry = if negative {
+            ll::submul_1(&mut w[..n], &x, y)
+        } else {
+            ll::addmul_1(&mut w[..n], &x, y)
+        };
+        (n, carry)
+    } else {
+        let n = x.len() + y.len();
+        let mut prod = vec![Limb::ZERO; n];
+        ll::mul(&mut prod, &x, &y);
+        let carry = if negative {
+            ll::sub_n_assign(&mut w[..n], &prod)
+        } else {
+            ll::add_n_assign(&mut w[..n], &prod)
+        };
+        (n, carry)
+    };
+
+    // Propagating the carry through the sign extension limbs wraps to the
+    // correct two's complement result.
+    if negative {
+        ll::sub_1_assign(&mut w[n..], carry);
+    } else {
+        ll::add_1_assign(&mut w[n..], carry);
+    }
+}
+
+fn add_into(w: &mut ApInt, x: &ApInt, y: &ApInt, subtract: bool) {
+    let (x, y) = (x.as_limbs(), y.as_limbs());
+    // An extra limb is needed for the carry.
+    w.resize_within(nz(x.len().max(y.len()) + 1));
+    if subtract {
+        ll::sub_signed(w.as_limbs_mut(), x, y);
+    } else {
+        ll::add_signed(w.as_limbs_mut(), x, y);
+    }
+    w.normalize_within();
+}
+
+fn mul_into(w: &mut ApInt, x: &ApInt, y: &ApInt) {
+    let (x, y) = (x.as_limbs(), y.as_limbs());
+    let (m, n) = (x.len(), y.len());
+    // The product of values of `m` and `n` signed limbs fits in `m + n` signed
+    // limbs.
+    w.resize_within(nz(m + n));
+    let limbs = w.as_limbs_mut();
+
+    // The unsigned limbs of a negative `x` are `x + 2^(m * BITS)`, so the
+    // unsigned product is corrected by subtracting the other factor shifted
+    // for each negative factor, with the result wrapping to `m + n` limbs.
+    ll::mul(limbs, x, y);
+    if x[m - 1].is_negative() {
+        ll::sub_n_assign(&mut limbs[m..], y);
+    }
+    if y[n - 1].is_negative() {
+        ll::sub_n_assign(&mut limbs[n..], x);
+    }
+    w.normalize_within();
+}
+
+fn div_rem(x: &ApInt, y: &ApInt) -> (ApInt, ApInt) {
+    try_div_rem(x, y).unwrap_or_else(|err| err.handle())
+}
+
+fn try_div_rem(x: &ApInt, y: &ApInt) -> Result<(ApInt, ApInt), AllocError> {
+    if y.is_zero() {
+        panic!("attempt to divide by zero");
+    }
+    if ptr::eq(x, y) {
+        return Ok((ApInt::ONE, ApInt::ZERO));
+    }
+
+    if let (LimbData::Stack(x), LimbData::Stack(y)) = (x.data(), y.data()) {
+        // Only `MIN / -1` overflows.
+        if let Some(quot) = x.repr_signed().checked_div(y.repr_signed()) {
+            let rem = x.repr_signed() % y.repr_signed();
+            return Ok((
+                ApInt::from_limb(Limb(quot as LimbRepr)),
+                ApInt::from_limb(Limb(rem as LimbRepr)),
+            ));
+        }
+    }
+
+    let x_negative = x.is_negative();
+    let quot_negative = x_negative != y.is_negative();
+
+    let (xm, ym) = (x.try_magnitude()?, y.try_magnitude()?);
+    if xm.len() < ym.len() {
+        return Ok((ApInt::ZERO, x.try_clone()?));
+    }
+
+    // Extra limbs are needed for the signs.
+    let quot_len = xm.len() - ym.len() + 1;
+    let rem_len = ym.len();
+    let mut quot = ApInt::try_zeroed(nz(quot_len + 1))?;
+    let mut rem = ApInt::try_zeroed(nz(rem_len + 1))?;
+
+    {
+        let quot = quot.as_limbs_mut();
+        let rem = rem.as_limbs_mut();
+        ll::try_divrem(&mut quot[..quot_len], &mut rem[..rem_len], &xm, &ym)?;
+
+        if quot_negative {
+            ll::neg_assign(quot);
+        }
+        if x_negative {
+            ll::neg_assign(rem);
+        }
+    }
+
+    quot.try_normalize()?;
+    rem.try_normalize()?;
+    Ok((quot, rem))
+}
+
+fn div_exact(x: &ApInt, y: &ApInt) -> ApInt {
+    if y.is_zero() {
+        panic!("attempt to divide by zero");
+    }
+
+    if let (LimbData::Stack(x), LimbData::Stack(y)) = (x.data(), y.data()) {
+        if let Some(quot) = x.repr_signed().checked_div(y.repr_signed()) {
+            return ApInt::from_limb(Limb(quot as LimbRepr));
+        }
+    }
+
+    let negative = x.is_negative() != y.is_negative();
+
+    // The exact division algorithm needs an odd divisor, so remove the factors
+    // of two from the divisor, which the dividend must share.
+    let shift = y.trailing_zeros().unwrap();
+    let (x, y) = (x >> shift, y >> shift);
+
+    let (xm, ym) = (x.magnitude(), y.magnitude());
+    if xm.len() < ym.len() {
+        return ApInt::ZERO;
+    }
+
+    // An extra limb is needed for the sign.
+    let quot_len = xm.len() - ym.len() + 1;
+    let mut quot = ApInt::zeroed(nz(quot_len + 1));
+    {
+        let quot = quot.as_limbs_mut();
+        ll::divexact(&mut quot[..quot_len], &mut xm.into_owned(), &ym);
+        if negative {
+            ll::neg_assign(quot);
+        }
+    }
+
+    quot.normalize();
+    quot
+}
+
+fn reciprocal(d: &ApInt, bits: usize) -> ApInt {
+    if d.is_zero() {
+        panic!("attempt to divide by zero");
+    }
+
+    let negative = d.is_negative();
+    let d = d.abs();
+    let n = ApInt::ONE << bits;
+
+    // Start from the reciprocal of the top 62 bits of `d` rounded up, which
+    // is below the true reciprocal and has about 60 correct bits.
+    let t = d.significant_bits().saturating_sub(62);
+    let top = (&d >> t).to_u64().unwrap() + 1;
+    let mut r = if bits >= t {
+        (ApInt::ONE << (bits - t)).div_rem_u64(top).0
+    } else {
+        ApInt::ZERO
+    };
+
+    // Each iteration roughly doubles the number of correct bits, and keeps
+    // `r` below the true reciprocal, so that the error `e` is never negative.
+    let mut e = &n - &d * &r;
+    while e >= d {
+        let step = (&r * &e) >> bits;
+        if step.is_zero() {
+            // The error is too small for an iteration to make progress.
+            r += &e / &d;
+            e = &n - &d * &r;
+            break;
+        }
+        r += step;
+        e = &n - &d * &r;
+    }
+
+    if negative {
+        // `floor(n / -d) == -ceil(n / d)`.
+        if !e.is_zero() {
+            r += ApInt::ONE;
+        }
+        r.negate_to_fit();
+    }
+    r
+}
+
+fn div(x: &ApInt, y: &ApInt) -> ApInt {
+    div_rem(x, y).0
+}
+
+fn rem(x: &ApInt, y: &ApInt) -> ApInt {
+    div_rem(x, y).1
+}
+
+// The borrow checker rejects `x += &x`, so an int can only be combined with
+// itself through two shared references, as in `&x + &x`, which the operator
+// functions above detect. The assign functions below instead skip the work for
+// right hand sides that leave the value unchanged or trivially changed.
+
+fn add_assign(x: &mut ApInt, y: &ApInt) {
+    if y.is_zero() {
+        return;
+    }
+    if x.is_zero() {
+        x.clone_from(y);
+        return;
+    }
+    *x = add(x, y);
+}
+
+fn sub_assign(x: &mut ApInt, y: &ApInt) {
+    if y.is_zero() {
+        return;
+    }
+    if x.is_zero() {
+        *x = -y;
+        return;
+    }
+    *x = sub(x, y);
+}
+
+fn mul_assign(x: &mut ApInt, y: &ApInt) {
+    if x.is_zero() || y.is_one() {
+        return;
+    }
+    if y.is_zero() {
+        *x = ApInt::ZERO;
+    } else if *y == ApInt::NEG_ONE {
+        x.negate_to_fit();
+    } else {
+        *x = mul(x, y);
+    }
+}
+
+fn div_assign(x: &mut ApInt, y: &ApInt) {
+    if y.is_zero() {
+        panic!("attempt to divide by zero");
+    }
+    if y.is_one() {
+        return;
+    }
+    if *y == ApInt::NEG_ONE {
+        x.negate_to_fit();
+    } else {
+        *x = div(x, y);
+    }
+}
+
+fn rem_assign(x: &mut ApInt, y: &ApInt) {
+    if y.is_zero() {
+        panic!("attempt to divide by zero");
+    }
+    if y.is_one() || *y == ApInt::NEG_ONE {
+        *x = ApInt::ZERO;
+    } else {
+        *x = rem(x, y);
+    }
+}
+
+impl_binop! {
+    Add, add, AddAssign, add_assign => add, add_assign;
+    Sub, sub, SubAssign, sub_assign => sub, sub_assign;
+    Mul, mul, MulAssign, mul_assign => mul, mul_assign;
+    Div, div, DivAssign, div_assign => div, div_assign;
+    Rem, rem, RemAssign, rem_assign => rem, rem_assign;
+}
+
+// Division with a primitive integer on the left hand side, so that generic code
+// can divide in either orientation without wrapping literals in `ApInt::from`.
+// The result is an `ApInt`, since `i64::MIN / -1` does not fit in an `i64`.
+macro_rules! impl_prim_lhs {
+    ($($ty:ident),* $(,)?) => {
+        $(
+            impl Div<&ApInt> for $ty {
+                type Output = ApInt;
+
+                #[inline]
+                fn div(self, rhs: &ApInt) -> ApInt {
+                    div(&ApInt::from(self), rhs)
+                }
+            }
+
+            impl Div<ApInt> for $ty {
+                type Output = ApInt;
+
+                #[inline]
+                fn div(self, rhs: ApInt) -> ApInt {
+                    div(&ApInt::from(self), &rhs)
+                }
+            }
+
+            impl Rem<&ApInt> for $ty {
+                type Output = ApInt;
+
+                #[inline]
+                fn rem(self, rhs: &ApInt) -> ApInt {
+                    rem(&ApInt::from(self), rhs)
+                }
+            }
+
+            impl Rem<ApInt> for $ty {
+                type Output = ApInt;
+
+                #[inline]
+                fn rem(self, rhs: ApInt) -> ApInt {
+                    rem(&ApInt::from(self), &rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_prim_lhs!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+);
+
+/// Multiplies adjacent pairs of `level`, carrying an odd value out up as is.
+fn product_level(level: &[ApInt]) -> Vec<ApInt> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [a, b] => a * b,
+            [a] => a.clone(),
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+impl Product for ApInt {
+    fn product<I: Iterator<Item = ApInt>>(iter: I) -> ApInt {
+        ApInt::product(iter)
+    }
+}
+
+impl<'a> Product<&'a ApInt> for ApInt {
+    fn product<I: Iterator<Item = &'a ApInt>>(iter: I) -> ApInt {
+        ApInt::product(iter.cloned())
     }
 }
@@ -0,0 +1,272 @@
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+
+use crate::alloc::Vec;
+use crate::apint::{ApInt, DecodeError, RoundingMode};
+
+/// The number of decimal digits packed into each chunk while parsing.
+pub(crate) const CHUNK_DIGITS: usize = 19;
+
+/// The largest power of ten that the digits of a parsed decimal are scaled by.
+///
+/// This stops a short string such as `"1e4000000000"` from requesting an
+/// enormous result, while `10^MAX_SHIFT` is still only about 415 KB.
+const MAX_SHIFT: u64 = 1_000_000;
+
+impl ApInt {
+    /// Parses a decimal string, accepting a fraction and an exponent as long
+    /// as the value they give is an integer.
+    ///
+    /// The string is an optional `+` or `-` sign, digits with an optional `.`
+    /// and fractional digits, and an optional exponent of `e` or `E` followed
+    /// by an optionally signed decimal integer. At least one digit is required
+    /// before the exponent, and the string may be surrounded by ASCII
+    /// whitespace.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string is not of this form, if its value is not
+    /// an integer, or if the digits are scaled by more than `10^1000000`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// assert_eq!(ApInt::from_decimal_str_lenient("2.5e3"), Ok(ApInt::from(2500)));
+    /// assert_eq!(ApInt::from_decimal_str_lenient("-1200e-2"), Ok(ApInt::from(-12)));
+    /// assert_eq!(ApInt::from_decimal_str_lenient(" 42 "), Ok(ApInt::from(42)));
+    /// assert_eq!(
+    ///     ApInt::from_decimal_str_lenient("1e30"),
+    ///     Ok(ApInt::from(10u128.pow(30))),
+    /// );
+    ///
+    /// assert!(ApInt::from_decimal_str_lenient("2.5").is_err());
+    /// assert!(ApInt::from_decimal_str_lenient("1e").is_err());
+    /// assert!(ApInt::from_decimal_str_lenient("1e4000000000").is_err());
+    /// ```
+    pub fn from_decimal_str_lenient(s: &str) -> Result<ApInt, DecodeError> {
+        let decimal = Decimal::parse(s).ok_or(DecodeError(()))?;
+        if decimal.exp < 0 {
+            return Err(DecodeError(()));
+        }
+        decimal.to_int(decimal.exp as u64)
+    }
+
+    /// Parses a decimal string, rounding any fraction in the given mode.
+    ///
+    /// The string has the same form as accepted by
+    /// [`from_decimal_str_lenient`](ApInt::from_decimal_str_lenient), but its
+    /// value need not be an integer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string is not of this form, or if the digits are
+    /// scaled up by more than `10^1000000`. Any negative exponent is accepted,
+    /// since a fraction only makes the result smaller.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::{ApInt, RoundingMode};
+    ///
+    /// assert_eq!(ApInt::parse_rounded("3.7", RoundingMode::TowardZero), Ok(ApInt::from(3)));
+    /// assert_eq!(ApInt::parse_rounded("-3.5", RoundingMode::HalfEven), Ok(ApInt::from(-4)));
+    /// assert_eq!(ApInt::parse_rounded("1.25e1", RoundingMode::Ceiling), Ok(ApInt::from(13)));
+    /// assert!(ApInt::parse_rounded("3.7.1", RoundingMode::Floor).is_err());
+    /// assert!(ApInt::parse_rounded("1e4000000000", RoundingMode::Floor).is_err());
+    /// ```
+    pub fn parse_rounded(s: &str, mode: RoundingMode) -> Result<ApInt, DecodeError> {
+        let mut decimal = Decimal::parse(s).ok_or(DecodeError(()))?;
+        if decimal.exp >= 0 {
+            return decimal.to_int(decimal.exp as u64);
+        }
+
+        // Split off the fractional digits, of which there is at least one
+        // since the digits have no trailing zeros. The fraction is exactly a
+        // half only if its sole digit is a 5.
+        let frac_len = usize::try_from(decimal.exp.wrapping_neg() as u64).unwrap_or(usize::MAX);
+        let int_len = decimal.digits.len().saturating_sub(frac_len);
+        let frac = match decimal.digits.len().checked_sub(frac_len) {
+            Some(i) => match decimal.digits[i].cmp(&5) {
+                Ordering::Equal if i + 1 < decimal.digits.len() => Ordering::Greater,
+                ord => ord,
+            },
+            // The fraction has leading zeros, so is below a half.
+            None => Ordering::Less,
+        };
+        decimal.digits.truncate(int_len);
+
+        let odd = decimal.digits.last().map_or(false, |&d| d % 2 == 1);
+        let mut int = decimal.to_int(0)?;
+        if mode.round_up(decimal.negative, odd, frac, false) {
+            if decimal.negative {
+                int -= ApInt::ONE;
+            } else {
+                int += ApInt::ONE;
+            }
+        }
+        Ok(int)
+    }
+}
+
+/// A decimal number of the value `digits * 10^exp`.
+///
+/// The digits are most significant first and have no trailing zeros, so a
+/// number is an integer exactly when `exp` is not negative. Zero has no
+/// digits.
+#[derive(Debug)]
+pub(crate) struct Decimal {
+    pub negative: bool,
+    pub digits: Vec<u8>,
+    pub exp: i64,
+}
+
+impl Decimal {
+    /// Parses a decimal number in scientific notation, as accepted by
+    /// [`ApInt::from_decimal_str_lenient`].
+    pub fn parse(s: &str) -> Option<Decimal> {
+        let s = s.trim_matches(|c: char| c.is_ascii_whitespace()).as_bytes();
+
+        let (negative, s) = match s.first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+
+        let int_len = s.iter().take_while(|c| c.is_ascii_digit()).count();
+        let (int, s) = s.split_at(int_len);
+        let (frac, s) = match s.first() {
+            Some(b'.') => {
+                let s = &s[1..];
+                let frac_len = s.iter().take_while(|c| c.is_ascii_digit()).count();
+                s.split_at(frac_len)
+            }
+            _ => (&[][..], s),
+        };
+        if int.is_empty() && frac.is_empty() {
+            return None;
+        }
+
+        let exp = match s.first() {
+            Some(b'e') | Some(b'E') => parse_exp(&s[1..])?,
+            Some(_) => return None,
+            None => 0,
+        };
+
+        let mut digits: Vec<u8> = int
+            .iter()
+            .chain(frac)
+            .skip_while(|&&c| c == b'0')
+            .map(|&c| c - b'0')
+            .collect();
+        let mut exp = exp.checked_sub(frac.len() as i64)?;
+        while digits.last() == Some(&0) {
+            digits.pop();
+            exp = exp.checked_add(1)?;
+        }
+        if digits.is_empty() {
+            exp = 0;
+        }
+
+        Some(Decimal {
+            negative,
+            digits,
+            exp,
+        })
+    }
+
+    /// Returns the signed value of the digits multiplied by `10^shift`.
+    ///
+    /// Fails without doing any work if a non-zero value is shifted by more
+    /// than [`MAX_SHIFT`].
+    pub fn to_int(&self, shift: u64) -> Result<ApInt, DecodeError> {
+        if !self.digits.is_empty() && shift > MAX_SHIFT {
+            return Err(DecodeError(()));
+        }
+
+        let mut int = digits_to_int(&self.digits);
+        if int != ApInt::ZERO && shift > 0 {
+            int *= pow10(shift as usize);
+        }
+        if self.negative {
+            int.negate_to_fit();
+        }
+        Ok(int)
+    }
+}
+
+/// Parses an optionally signed decimal exponent, failing if it overflows.
+fn parse_exp(s: &[u8]) -> Option<i64> {
+    let (negative, s) = match s.first() {
+        Some(b'-') => (true, &s[1..]),
+        Some(b'+') => (false, &s[1..]),
+        _ => (false, s),
+    };
+    if s.is_empty() {
+        return None;
+    }
+
+    let mut exp: i64 = 0;
+    for &c in s {
+        if !c.is_ascii_digit() {
+            return None;
+        }
+        exp = exp.checked_mul(10)?.checked_add(i64::from(c - b'0'))?;
+    }
+    Some(if negative { -exp } else { exp })
+}
+
+/// Returns the value of the decimal digit values `digits`, most significant
+/// first.
+pub(crate) fn digits_to_int(digits: &[u8]) -> ApInt {
+    // The leading chunk takes the digits left over by the full chunks.
+    let lead = digits.len() % CHUNK_DIGITS;
+    let chunks: Vec<u64> = core::iter::once(&digits[..lead])
+        .filter(|lead| !lead.is_empty())
+        .chain(digits[lead..].chunks(CHUNK_DIGITS))
+        .map(|chunk| chunk.iter().fold(0, |acc, &d| acc * 10 + u64::from(d)))
+        .collect();
+    combine_chunks(&chunks)
+}
+
+/// Returns `10^n`.
+///
+/// # Panics
+///
+/// Panics if `n` does not fit in a `u32`.
+pub(crate) fn pow10(n: usize) -> ApInt {
+    match u32::try_from(n) {
+        Ok(n) => ApInt::from(10).pow(n),
+        Err(_) => panic!("power of ten exponent {} is too large", n),
+    }
+}
+
+/// Returns the value of the base `10^CHUNK_DIGITS` digits `chunks`, most
+/// significant first.
+///
+/// Neighbouring chunks are combined pairwise, which keeps the operands of each
+/// multiplication balanced.
+pub(crate) fn combine_chunks(chunks: &[u64]) -> ApInt {
+    let mut values: Vec<ApInt> = chunks.iter().map(|&chunk| ApInt::from(chunk)).collect();
+    // The base of the values at the current level.
+    let mut base = pow10(CHUNK_DIGITS);
+
+    while values.len() > 1 {
+        // Pair from the least significant end, so that every value but the
+        // leading one holds the same number of digits.
+        let odd = values.len() % 2;
+        let mut next = Vec::with_capacity(values.len() / 2 + odd);
+        if odd == 1 {
+            next.push(values[0].clone());
+        }
+        for pair in values[odd..].chunks(2) {
+            next.push(&pair[0] * &base + &pair[1]);
+        }
+
+        values = next;
+        base = &base * &base;
+    }
+
+    values.pop().unwrap_or(ApInt::ZERO)
+}
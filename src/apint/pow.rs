@@ -0,0 +1,148 @@
+use core::fmt;
+
+use num_traits::ToPrimitive;
+
+use crate::alloc::Vec;
+use crate::apint::ApInt;
+
+/// The error returned by [`ApInt::pow_int`] when the result is too large to be
+/// stored in memory.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct PowOverflow;
+
+impl fmt::Display for PowOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("result of exponentiation is too large")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PowOverflow {}
+
+/// The largest number of bits of a result that is attempted.
+///
+/// 64-bit targets have at most a 48-bit virtual address space in practice, so
+/// no result larger than `2^48` bytes could ever be allocated.
+#[cfg(target_pointer_width = "64")]
+const MAX_BITS: usize = 1 << (48 + 3);
+
+/// The largest number of bits of a result that is attempted.
+///
+/// A bit count that fits in a `usize` is at most `usize::MAX / 8` bytes, well
+/// within the `isize::MAX` byte limit on an allocation.
+#[cfg(not(target_pointer_width = "64"))]
+const MAX_BITS: usize = usize::MAX;
+
+impl ApInt {
+    /// Returns `self` raised to the power `exp`, using sliding window
+    /// exponentiation.
+    ///
+    /// The size of the result is estimated before any work is done, so an
+    /// absurd exponent fails fast with [`PowOverflow`] rather than attempting
+    /// an enormous allocation. To limit results to a practical size, use
+    /// [`Guard::pow`](crate::Guard::pow).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `exp` is negative and `self` is not `1` or `-1`, since the
+    /// result would not be an integer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let r = ApInt::from(3).pow_int(&ApInt::from(5)).unwrap();
+    /// assert_eq!(r, ApInt::from(243));
+    ///
+    /// let huge = ApInt::from(u128::MAX);
+    /// assert!(ApInt::from(10).pow_int(&huge).is_err());
+    /// ```
+    pub fn pow_int(&self, exp: &ApInt) -> Result<ApInt, PowOverflow> {
+        if *exp < ApInt::ZERO {
+            return match self.to_i8() {
+                Some(1) => Ok(ApInt::ONE),
+                Some(-1) if exp.is_even() => Ok(ApInt::ONE),
+                Some(-1) => Ok(ApInt::NEG_ONE),
+                _ => panic!("attempt to raise to a negative power"),
+            };
+        }
+
+        match self.to_i8() {
+            Some(0) if *exp == ApInt::ZERO => return Ok(ApInt::ONE),
+            Some(0) | Some(1) => return Ok(self.clone()),
+            Some(-1) if exp.is_even() => return Ok(ApInt::ONE),
+            Some(-1) => return Ok(ApInt::NEG_ONE),
+            _ => {}
+        }
+
+        match pow_bits(self, exp) {
+            Some(bits) if bits <= MAX_BITS => Ok(sliding_window_pow(self, exp)),
+            _ => Err(PowOverflow),
+        }
+    }
+}
+
+/// Returns a lower bound on the number of significant bits of `base^exp`, or
+/// `None` if it does not fit in a `usize`, where `|base| >= 2` and `exp` is
+/// non-negative.
+pub(crate) fn pow_bits(base: &ApInt, exp: &ApInt) -> Option<usize> {
+    // `|base| >= 2^(b - 1)`, so `|base^exp| >= 2^(exp * (b - 1))`, which needs
+    // at least `exp * (b - 1)` bits besides the sign, even when negative.
+    let b = base.abs().significant_bits();
+    exp.to_usize()?.checked_mul(b - 1)
+}
+
+/// Returns `base^exp` for a non-negative `exp`.
+fn sliding_window_pow(base: &ApInt, exp: &ApInt) -> ApInt {
+    let n = exp.significant_bits();
+    if n == 0 {
+        return ApInt::ONE;
+    }
+
+    // Larger windows need fewer multiplications, at the cost of a larger
+    // table of odd powers.
+    let k = match n {
+        0..=8 => 1,
+        9..=24 => 2,
+        25..=80 => 3,
+        81..=240 => 4,
+        _ => 5,
+    };
+
+    // The odd powers `base^1, base^3, ..., base^(2^k - 1)`.
+    let square = base * base;
+    let mut odd = Vec::with_capacity(1 << (k - 1));
+    odd.push(base.clone());
+    for i in 1..1 << (k - 1) {
+        let next = &odd[i - 1] * &square;
+        odd.push(next);
+    }
+
+    let mut r = ApInt::ONE;
+    let mut i = n;
+    while i > 0 {
+        if !exp.bit(i - 1) {
+            r = &r * &r;
+            i -= 1;
+            continue;
+        }
+
+        // Take the longest window of at most `k` bits ending in a one.
+        let mut j = i.saturating_sub(k);
+        while !exp.bit(j) {
+            j += 1;
+        }
+        let mut window = 0;
+        for bit in (j..i).rev() {
+            window = (window << 1) | exp.bit(bit) as usize;
+        }
+
+        for _ in j..i {
+            r = &r * &r;
+        }
+        r *= &odd[window >> 1];
+        i = j;
+    }
+    r
+}
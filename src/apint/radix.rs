@@ -1,12 +1,25 @@
-use crate::alloc::Vec;
+use core::fmt::Write;
+
+use crate::alloc::{String, Vec, vec};
 use crate::apint::ApInt;
+use crate::limb::{Limb, LimbRepr};
+use crate::ll;
+use crate::tuning;
 
+// Negative values are formatted as a sign and magnitude in every radix, so
+// `{:x}` of `-31` is `-1f`, rather than the two's complement form used for
+// primitive integers, which needs a width and is written by
+// `ApInt::to_twos_complement_hex`.
 macro_rules! impl_fmt {
     ($trait:ident, $radix:expr, $upper:expr, $prefix:expr) => {
         impl core::fmt::$trait for ApInt {
-            fn fmt(&self, _f: &mut core::fmt::Formatter) -> core::fmt::Result {
-                // TODO: f.pad_integral(...)
-                todo!()
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                let mut digits = to_str_radix_reversed(self, $radix, $upper);
+                digits.reverse();
+
+                // SAFETY: The digits are all ASCII characters.
+                let digits = unsafe { core::str::from_utf8_unchecked(&digits) };
+                f.pad_integral(!self.is_negative(), $prefix, digits)
             }
         }
     };
@@ -21,12 +34,621 @@ impl_fmt!(Display, 10, "");
 impl_fmt!(LowerHex, 16, false, "0x");
 impl_fmt!(UpperHex, 16, true, "0x");
 
-// Since we store data in `ApInt` in little-endian form, the string form will be reversed.
-fn to_str_radix_reversed(n: &ApInt, radix: u32, upper: bool) -> Vec<u8> {
+macro_rules! impl_fmt_exp {
+    ($trait:ident, $e:expr) => {
+        impl core::fmt::$trait for ApInt {
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                let mut digits = to_str_radix_reversed(self, 10, false);
+                digits.reverse();
+
+                let s = to_exp_string(digits, f.precision(), $e);
+                f.pad_integral(!self.is_negative(), "", &s)
+            }
+        }
+    };
+}
+
+impl_fmt_exp!(LowerExp, 'e');
+impl_fmt_exp!(UpperExp, 'E');
+
+impl ApInt {
+    /// Returns the string representation of `self` in the given radix.
+    ///
+    /// Negative values are prefixed with `-`, and letters are lowercase for
+    /// radixes greater than 10.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radix` is not within the range `2..=36`.
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        let mut digits = to_str_radix_reversed(self, radix, false);
+        if self.is_negative() {
+            digits.push(b'-');
+        }
+        digits.reverse();
+
+        // SAFETY: The digits are all ASCII characters.
+        unsafe { String::from_utf8_unchecked(digits) }
+    }
+
+    /// Returns the decimal string representation of `self`, with `separator`
+    /// inserted between each group of `group` digits counting from the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `group` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let int = ApInt::from(-1234567);
+    /// assert_eq!(int.to_string_grouped(',', 3), "-1,234,567");
+    /// assert_eq!(int.to_string_grouped('_', 4), "-123_4567");
+    /// ```
+    pub fn to_string_grouped(&self, separator: char, group: usize) -> String {
+        assert!(group > 0, "group size must be non-zero");
+
+        let digits = to_str_radix_reversed(self, 10, false);
+        let mut s =
+            String::with_capacity(digits.len() + digits.len() / group * separator.len_utf8() + 1);
+        if self.is_negative() {
+            s.push('-');
+        }
+        for (i, &digit) in digits.iter().enumerate().rev() {
+            s.push(digit as char);
+            if i > 0 && i % group == 0 {
+                s.push(separator);
+            }
+        }
+        s
+    }
+
+    /// Returns the number of digits in the magnitude of `self` in the given
+    /// radix.
+    ///
+    /// Zero has a single digit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radix` is not within the range `2..=36`.
+    pub fn digit_count(&self, radix: u32) -> usize {
+        assert_radix(radix);
+
+        let mag = self.magnitude();
+        if mag.is_empty() {
+            return 1;
+        }
+
+        if radix.is_power_of_two() {
+            let bits = radix.trailing_zeros() as usize;
+            let total_bits = mag.len() * Limb::BITS - mag[mag.len() - 1].leading_zeros() as usize;
+            return (total_bits + bits - 1) / bits;
+        }
+
+        let (_, power) = big_base(radix);
+        let mut count = 0;
+        for_each_radix_chunk(&mag, radix, |mut chunk, last| {
+            if last {
+                while chunk != 0 {
+                    count += 1;
+                    chunk /= radix as LimbRepr;
+                }
+            } else {
+                count += power;
+            }
+        });
+        count
+    }
+
+    /// Returns the sum of the digits in the magnitude of `self` in the given
+    /// radix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radix` is not within the range `2..=36`.
+    pub fn digit_sum(&self, radix: u32) -> u64 {
+        assert_radix(radix);
+
+        let mut sum = 0;
+        for_each_radix_chunk(&self.magnitude(), radix, |mut chunk, _| {
+            while chunk != 0 {
+                sum += u64::from((chunk % radix as LimbRepr) as u8);
+                chunk /= radix as LimbRepr;
+            }
+        });
+        sum
+    }
+
+    /// Returns an iterator over the decimal digits of the magnitude of `self`,
+    /// in chunks of 19 digits, most significant chunk first.
+    ///
+    /// Every chunk is less than `10^19`. The first chunk has no leading zeros
+    /// and every later chunk stands for exactly 19 digits, so should be written
+    /// padded with zeros. The sign of `self` is not included.
+    ///
+    /// The chunks are computed as they are needed, which allows a huge value
+    /// to be written out incrementally without building its whole string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let int = ApInt::from(-12_345_678_901_234_567_890_123_456_789i128);
+    ///
+    /// let mut s = String::new();
+    /// for (i, chunk) in int.decimal_chunks().enumerate() {
+    ///     if i == 0 {
+    ///         s += &chunk.to_string();
+    ///     } else {
+    ///         s += &format!("{:019}", chunk);
+    ///     }
+    /// }
+    /// assert_eq!(s, "12345678901234567890123456789");
+    /// ```
+    pub fn decimal_chunks(&self) -> DecimalChunks {
+        DecimalChunks {
+            powers: vec![ApInt::from(DecimalChunks::BASE)],
+            pieces: vec![(self.abs(), None)],
+            leading: true,
+        }
+    }
+
+    /// Returns `self` in engineering notation, such as `12.3e+3`, rounded half
+    /// to even to `sig_digits` significant digits.
+    ///
+    /// The exponent is always a multiple of three, written with its sign, and
+    /// the mantissa has one to three digits before the decimal point. Only the
+    /// leading digits are computed: the low digits are removed with a single
+    /// division by a power of ten, so this is much faster than formatting
+    /// every digit of a large value. The output never depends on the locale.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sig_digits` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// assert_eq!(ApInt::from(12345).to_engineering_string(3), "12.3e+3");
+    /// assert_eq!(ApInt::from(-999_999).to_engineering_string(2), "-1.0e+6");
+    /// assert_eq!(ApInt::from(7).to_engineering_string(1), "7e+0");
+    /// ```
+    pub fn to_engineering_string(&self, sig_digits: usize) -> String {
+        assert!(
+            sig_digits > 0,
+            "number of significant digits must be positive"
+        );
+
+        // `10^low <= 2^(bits - 1) <= |self|`, so `low` is less than the number
+        // of digits, and removing `low - sig_digits` digits keeps at least one
+        // digit beyond those needed. One is subtracted from `low` in case the
+        // float product rounds up past an integer.
+        let mag = self.abs();
+        let bits = mag.significant_bits();
+        let low = ((bits.saturating_sub(1) as f64 * core::f64::consts::LOG10_2) as usize)
+            .saturating_sub(1);
+        let skip = low.saturating_sub(sig_digits);
+
+        let (q, r) = if skip > 0 {
+            mag.div_rem(&ApInt::from(10).pow(skip as u32))
+        } else {
+            (mag, ApInt::ZERO)
+        };
+        let mut digits = q.to_str_radix(10).into_bytes();
+        let mut exp = digits.len() - 1 + skip;
+        // A non-zero remainder only matters to break a tie when rounding.
+        if r != ApInt::ZERO {
+            digits.push(b'1');
+        }
+        if round_digits(&mut digits, sig_digits) {
+            exp += 1;
+        }
+
+        let int_len = exp % 3 + 1;
+        if digits.len() < int_len {
+            digits.resize(int_len, b'0');
+        }
+
+        let mut s = String::with_capacity(digits.len() + 24);
+        if self.is_negative() {
+            s.push('-');
+        }
+        s.extend(digits[..int_len].iter().map(|&d| d as char));
+        if digits.len() > int_len {
+            s.push('.');
+            s.extend(digits[int_len..].iter().map(|&d| d as char));
+        }
+        // Writing to a `String` cannot fail.
+        let _ = write!(s, "e+{}", exp - exp % 3);
+        s
+    }
+}
+
+/// An iterator over the decimal digits of an [`ApInt`], in chunks of 19
+/// digits.
+///
+/// This `struct` is created by [`ApInt::decimal_chunks`].
+#[derive(Clone, Debug)]
+pub struct DecimalChunks {
+    /// The powers `BASE^(2^k)` used to split pieces.
+    powers: Vec<ApInt>,
+    /// The pieces of the value yet to be split, with the least significant
+    /// piece at the bottom. Each piece is paired with its level, such that it
+    /// holds exactly `2^level` chunks, or `None` if it is the leading piece.
+    pieces: Vec<(ApInt, Option<usize>)>,
+    /// Whether no chunk has been yielded yet.
+    leading: bool,
+}
+
+impl DecimalChunks {
+    const BASE: u64 = 10_000_000_000_000_000_000;
+
+    /// Returns the smallest level of a piece able to hold `value`.
+    fn level(&mut self, value: &ApInt) -> usize {
+        let mut level = 0;
+        loop {
+            if *value < self.powers[level] {
+                return level;
+            }
+            level += 1;
+            if level == self.powers.len() {
+                let power = &self.powers[level - 1];
+                self.powers.push(power * power);
+            }
+        }
+    }
+}
+
+impl Iterator for DecimalChunks {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        loop {
+            let (value, level) = self.pieces.pop()?;
+            let level = match level {
+                Some(level) => level,
+                None => self.level(&value),
+            };
+
+            if level == 0 {
+                self.leading = false;
+                return Some(u64::from(&value));
+            }
+
+            // Split the piece into halves holding `2^(level - 1)` chunks each.
+            let (high, low) = value.div_rem(&self.powers[level - 1]);
+            self.pieces.push((low, Some(level - 1)));
+            self.pieces
+                .push((high, if self.leading { None } else { Some(level - 1) }));
+        }
+    }
+}
+
+/// Formats the decimal `digits` of a magnitude in scientific notation, such
+/// as `1.2345e4`, without a sign.
+///
+/// With a `precision` the mantissa is rounded half to even to that many
+/// fractional digits, otherwise trailing zeros are removed from it.
+fn to_exp_string(mut digits: Vec<u8>, precision: Option<usize>, e: char) -> String {
+    let mut exp = digits.len() - 1;
+
+    if let Some(precision) = precision {
+        if round_digits(&mut digits, precision + 1) {
+            exp += 1;
+        }
+    } else {
+        while digits.len() > 1 && digits.last() == Some(&b'0') {
+            digits.pop();
+        }
+    }
+
+    let mut s = String::with_capacity(digits.len() + 24);
+    s.push(digits[0] as char);
+    if digits.len() > 1 {
+        s.push('.');
+        s.extend(digits[1..].iter().map(|&d| d as char));
+    }
+    // Writing to a `String` cannot fail.
+    let _ = write!(s, "{}{}", e, exp);
+    s
+}
+
+/// Rounds the decimal `digits` half to even to `len` digits, padding them with
+/// zeros if there are fewer, and returns `true` if rounding carried into a new
+/// leading digit, which increases the exponent by one.
+fn round_digits(digits: &mut Vec<u8>, len: usize) -> bool {
+    let mut carried = false;
+    if len < digits.len() {
+        let rest = digits.split_off(len);
+        let last_odd = (digits[len - 1] - b'0') % 2 == 1;
+        let round_up = match rest[0] {
+            b'6'..=b'9' => true,
+            b'5' => last_odd || rest[1..].iter().any(|&d| d != b'0'),
+            _ => false,
+        };
+
+        if round_up {
+            let mut i = digits.len();
+            loop {
+                if i == 0 {
+                    // Every digit was a nine, so rounding carries into a new
+                    // leading digit.
+                    digits.insert(0, b'1');
+                    digits.pop();
+                    carried = true;
+                    break;
+                }
+                i -= 1;
+                if digits[i] == b'9' {
+                    digits[i] = b'0';
+                } else {
+                    digits[i] += 1;
+                    break;
+                }
+            }
+        }
+    }
+    digits.resize(len, b'0');
+    carried
+}
+
+fn assert_radix(radix: u32) {
     assert!(
-        2 <= radix && radix <= 36,
+        (2..=36).contains(&radix),
         "radix must be within the range 2..=36"
     );
+}
+
+// Since we store data in `ApInt` in little-endian form, the string form will be reversed.
+fn to_str_radix_reversed(n: &ApInt, radix: u32, upper: bool) -> Vec<u8> {
+    assert_radix(radix);
+
+    let mag = n.magnitude();
+    if mag.is_empty() {
+        return vec![b'0'];
+    }
+
+    let mut digits = match radix {
+        // Radixes that evenly divide a limb.
+        2 | 4 | 16 => to_bitwise_digits_le(&mag, radix.trailing_zeros()),
+        // Other powers of two.
+        8 | 32 => to_inexact_bitwise_digits_le(&mag, radix.trailing_zeros()),
+        _ => to_radix_digits_le(&mag, radix),
+    };
+
+    let letter = if upper { b'A' } else { b'a' };
+    for digit in digits.iter_mut() {
+        *digit = match *digit {
+            d @ 0..=9 => b'0' + d,
+            d => letter + (d - 10),
+        };
+    }
+    digits
+}
+
+/// Converts a non-zero magnitude to digits of `bits` bits each, least
+/// significant digit first, where `bits` evenly divides the bits in a limb.
+fn to_bitwise_digits_le(mag: &[Limb], bits: u32) -> Vec<u8> {
+    debug_assert!(Limb::BITS as u32 % bits == 0);
+
+    let mask: LimbRepr = (1 << bits) - 1;
+    let digits_per_limb = Limb::BITS as u32 / bits;
+
+    let total_bits = mag.len() * Limb::BITS - mag[mag.len() - 1].leading_zeros() as usize;
+    let n_digits = (total_bits + bits as usize - 1) / bits as usize;
+
+    let mut digits = Vec::with_capacity(n_digits);
+    for limb in mag {
+        let mut r = limb.repr();
+        for _ in 0..digits_per_limb {
+            digits.push((r & mask) as u8);
+            r >>= bits;
+        }
+    }
+    // Remove the leading zeros of the high limb.
+    digits.truncate(n_digits);
+    digits
+}
+
+/// Converts a non-zero magnitude to digits of `bits` bits each, least
+/// significant digit first, where digits may span across limbs.
+fn to_inexact_bitwise_digits_le(mag: &[Limb], bits: u32) -> Vec<u8> {
+    debug_assert!(Limb::BITS as u32 % bits != 0);
+
+    const LIMB_BITS: u32 = Limb::BITS as u32;
+
+    let mask: LimbRepr = (1 << bits) - 1;
+
+    let total_bits = mag.len() * Limb::BITS - mag[mag.len() - 1].leading_zeros() as usize;
+    let n_digits = (total_bits + bits as usize - 1) / bits as usize;
+
+    let mut digits = Vec::with_capacity(n_digits);
+
+    // The bits left over from the previous limb, and how many there are.
+    let mut r: LimbRepr = 0;
+    let mut r_bits = 0;
+    for limb in mag {
+        let limb = limb.repr();
+
+        // Complete the digit spanning from the previous limb.
+        if r_bits > 0 {
+            digits.push(((r | (limb << r_bits)) & mask) as u8);
+        }
+        let mut shift = if r_bits > 0 { bits - r_bits } else { 0 };
+
+        while shift + bits <= LIMB_BITS {
+            digits.push(((limb >> shift) & mask) as u8);
+            shift += bits;
+        }
+
+        r_bits = LIMB_BITS - shift;
+        r = if r_bits > 0 { limb >> shift } else { 0 };
+    }
+    if r_bits > 0 {
+        digits.push(r as u8);
+    }
+
+    // Remove the leading zeros of the high limb.
+    digits.truncate(n_digits);
+    digits
+}
+
+/// Returns the largest power of `radix` that fits in a limb, along with the
+/// exponent of the power.
+fn big_base(radix: u32) -> (Limb, usize) {
+    let radix = radix as LimbRepr;
+
+    let mut base = radix;
+    let mut power = 1;
+    while let Some(next) = base.checked_mul(radix) {
+        base = next;
+        power += 1;
+    }
+    (Limb(base), power)
+}
+
+/// Calls `f` with each chunk of digits of the magnitude `mag` in `radix`, least
+/// significant chunk first, along with whether it is the last chunk.
+///
+/// Each chunk is a value less than the base returned by [`big_base`], and only
+/// the last chunk is non-zero for a zero magnitude.
+fn for_each_radix_chunk(mag: &[Limb], radix: u32, mut f: impl FnMut(LimbRepr, bool)) {
+    let (base, _) = big_base(radix);
+
+    let mut mag = mag.to_vec();
+    loop {
+        let len = ll::normalized_len(&mag);
+        mag.truncate(len);
+
+        let chunk = match *mag {
+            [] => 0,
+            [limb] if limb < base => limb.repr(),
+            _ => {
+                f(ll::divrem_1_assign(&mut mag, base).repr(), false);
+                continue;
+            }
+        };
+        f(chunk, true);
+        return;
+    }
+}
+
+/// Converts a non-zero magnitude to digits in `radix`, least significant digit
+/// first.
+///
+/// A magnitude of at least the divide and conquer radix threshold is split by
+/// the powers `base^(2^k)` of the big base, each half converted separately, so
+/// that the work is in a few large divisions rather than many small ones.
+pub(crate) fn to_radix_digits_le(mag: &[Limb], radix: u32) -> Vec<u8> {
+    if mag.len() < tuning::dc_radix_threshold() {
+        return to_radix_digits_le_basecase(mag, radix);
+    }
+
+    // Square the big base until its square would exceed the magnitude.
+    let (base, power) = big_base(radix);
+    let mut powers = vec![vec![base]];
+    loop {
+        let last = &powers[powers.len() - 1];
+        if 2 * last.len() - 1 > mag.len() {
+            break;
+        }
+        let mut square = vec![Limb::ZERO; 2 * last.len()];
+        ll::mul(&mut square, last, last);
+        square.truncate(ll::normalized_len(&square));
+        powers.push(square);
+    }
+
+    let mut digits = Vec::new();
+    push_radix_digits_dc(&mut digits, mag, radix, &powers, power, None);
+    digits
+}
+
+/// Appends the digits of the magnitude `mag`, which is less than the square of
+/// the last of `powers`, to `digits`, padded with zeros to `pad` digits if
+/// given.
+///
+/// Each of `powers` is the square of the one before it, the first being the
+/// big base of `power` digits.
+fn push_radix_digits_dc(
+    digits: &mut Vec<u8>,
+    mag: &[Limb],
+    radix: u32,
+    powers: &[Vec<Limb>],
+    power: usize,
+    pad: Option<usize>,
+) {
+    let mag = &mag[..ll::normalized_len(mag)];
+    let start = digits.len();
+
+    match powers.split_last() {
+        Some((pow, lower)) if mag.len() >= tuning::dc_radix_threshold() => {
+            let below = mag.len() < pow.len()
+                || (mag.len() == pow.len() && mag.iter().rev().lt(pow.iter().rev()));
+            if below {
+                return push_radix_digits_dc(digits, mag, radix, lower, power, pad);
+            }
+
+            // The low part has exactly as many digits as the power, with
+            // leading zeros.
+            let mut q = vec![Limb::ZERO; mag.len() - pow.len() + 1];
+            let mut r = vec![Limb::ZERO; pow.len()];
+            ll::divrem(&mut q, &mut r, mag, pow);
+            let low = power << lower.len();
+            push_radix_digits_dc(digits, &r, radix, lower, power, Some(low));
+            push_radix_digits_dc(digits, &q, radix, lower, power, pad.map(|pad| pad - low));
+        }
+        _ => {
+            if !mag.is_empty() {
+                digits.extend(to_radix_digits_le_basecase(mag, radix));
+            }
+            if let Some(pad) = pad {
+                digits.resize(start + pad, 0);
+            }
+        }
+    }
+}
+
+/// Converts a non-zero magnitude to digits in `radix`, least significant digit
+/// first, by repeated division by the big base.
+fn to_radix_digits_le_basecase(mag: &[Limb], radix: u32) -> Vec<u8> {
+    // Overestimate the number of digits from the number of bits, using the
+    // floor of the bits per digit.
+    let total_bits = mag.len() * Limb::BITS;
+    let n_digits = total_bits / (31 - radix.leading_zeros()) as usize + 1;
+
+    let mut digits = Vec::with_capacity(n_digits);
+
+    let (base, power) = big_base(radix);
+    let radix = radix as LimbRepr;
+
+    // Divide by the largest power of the radix fitting in a limb, so that
+    // each division yields many digits.
+    let mut mag = mag.to_vec();
+    while mag.len() > 1 {
+        let mut r = ll::divrem_1_assign(&mut mag, base).repr();
+        for _ in 0..power {
+            digits.push((r % radix) as u8);
+            r /= radix;
+        }
+
+        let len = ll::normalized_len(&mag);
+        mag.truncate(len);
+    }
+
+    let mut r = mag.first().map_or(0, |limb| limb.repr());
+    while r != 0 {
+        digits.push((r % radix) as u8);
+        r /= radix;
+    }
 
-    todo!()
+    // Remove any leading zeros from the last chunk.
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+    digits
 }
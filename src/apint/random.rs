@@ -0,0 +1,161 @@
+use rand::Rng;
+
+use crate::alloc::{Vec, vec};
+use crate::apint::ApInt;
+use crate::number_theory::{is_prime, small_prime_filter};
+use crate::sign::Sign;
+
+impl ApInt {
+    /// Returns a random non-negative int below `2^bits` with exactly `ones`
+    /// bits set, chosen uniformly from all such ints.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ones` is greater than `bits`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let mut rng = rand::rngs::mock::StepRng::new(0, 0x9e37_79b9_7f4a_7c15);
+    /// let int = ApInt::random_with_weight(&mut rng, 256, 3);
+    /// assert_eq!(int.count_ones(), Some(3));
+    /// assert!(int < ApInt::ONE << 256);
+    /// ```
+    pub fn random_with_weight<R: Rng + ?Sized>(rng: &mut R, bits: usize, ones: usize) -> ApInt {
+        assert!(ones <= bits, "weight must not exceed the number of bits");
+
+        // Choose the positions of whichever of the ones and zeros are fewer,
+        // retrying positions that were already chosen.
+        let invert = ones > bits / 2;
+        let count = if invert { bits - ones } else { ones };
+
+        let mut words = vec![0u64; (bits + 63) / 64];
+        let mut chosen = 0;
+        while chosen < count {
+            let i = rng.gen_range(0..bits);
+            let (word, bit) = (i / 64, 1 << (i % 64));
+            if words[word] & bit == 0 {
+                words[word] |= bit;
+                chosen += 1;
+            }
+        }
+
+        if invert {
+            for word in words.iter_mut() {
+                *word = !*word;
+            }
+            mask_words(&mut words, bits);
+        }
+        from_words(words)
+    }
+
+    /// Returns a random int in the range `1..n` that is coprime to `n`, chosen
+    /// uniformly from all such ints.
+    ///
+    /// These are the invertible elements modulo `n`, as needed for the random
+    /// blinding factors of ElGamal and Paillier encryption.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is not greater than one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let mut rng = rand::rngs::mock::StepRng::new(7, 0x9e37_79b9_7f4a_7c15);
+    /// let n = ApInt::from(3 * 5 * 7 * 11);
+    /// let r = ApInt::random_coprime_below(&mut rng, &n);
+    /// assert!(r > ApInt::ZERO && r < n && r.is_coprime(&n));
+    /// ```
+    pub fn random_coprime_below<R: Rng + ?Sized>(rng: &mut R, n: &ApInt) -> ApInt {
+        assert!(*n > ApInt::ONE, "n must be greater than one");
+
+        // Sample below the next power of two, rejecting candidates out of
+        // range, so fewer than half are rejected on average.
+        let bits = n.bits();
+        loop {
+            let r = from_words(random_words(rng, bits));
+            if r != ApInt::ZERO && r < *n && r.is_coprime(n) {
+                return r;
+            }
+        }
+    }
+
+    /// Returns a random safe prime of exactly `bits` bits, which is a prime `p`
+    /// such that `(p - 1) / 2` is also prime.
+    ///
+    /// Candidates are tested with the Miller-Rabin test of
+    /// [`number_theory`](crate::number_theory), after trial division by small
+    /// primes. Safe primes are rare, so this is slow for large sizes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits` is less than 3, since there are no smaller safe primes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let mut rng = rand::rngs::mock::StepRng::new(1, 0x9e37_79b9_7f4a_7c15);
+    /// let p = ApInt::random_safe_prime(&mut rng, 32);
+    /// assert!(p >= ApInt::ONE << 31 && p < ApInt::ONE << 32);
+    /// ```
+    pub fn random_safe_prime<R: Rng + ?Sized>(rng: &mut R, bits: usize) -> ApInt {
+        assert!(bits >= 3, "there are no safe primes with fewer than 3 bits");
+
+        loop {
+            // `q` has exactly `bits - 1` bits, and is odd unless it is 2.
+            let mut words = random_words(rng, bits - 1);
+            let top = bits - 2;
+            words[top / 64] |= 1 << (top % 64);
+            if bits > 3 {
+                words[0] |= 1;
+            }
+            let q = from_words(words);
+            let p = (&q << 1) + ApInt::ONE;
+
+            // Candidates are large enough that a small factor means they are
+            // composite, which rules most of them out cheaply.
+            if bits > 8
+                && (small_prime_filter(&q, 25).is_some() || small_prime_filter(&p, 25).is_some())
+            {
+                continue;
+            }
+            if is_prime(&q) && is_prime(&p) {
+                return p;
+            }
+        }
+    }
+}
+
+/// Returns `bits` uniformly random bits, in the fewest 64-bit words able to
+/// hold them.
+fn random_words<R: Rng + ?Sized>(rng: &mut R, bits: usize) -> Vec<u64> {
+    let mut words: Vec<u64> = (0..(bits + 63) / 64).map(|_| rng.gen()).collect();
+    mask_words(&mut words, bits);
+    words
+}
+
+/// Clears the bits of `words` from bit `bits` upwards.
+fn mask_words(words: &mut [u64], bits: usize) {
+    if bits % 64 != 0 {
+        if let Some(last) = words.last_mut() {
+            *last &= (1 << (bits % 64)) - 1;
+        }
+    }
+}
+
+/// Creates a non-negative int from its words, least significant word first.
+fn from_words(words: Vec<u64>) -> ApInt {
+    let sign = if words.iter().all(|&word| word == 0) {
+        Sign::Zero
+    } else {
+        Sign::Positive
+    };
+    ApInt::from_limbs_le(words, sign)
+}
@@ -0,0 +1,137 @@
+use core::iter::FusedIterator;
+
+use num_traits::ToPrimitive;
+
+use crate::apint::ApInt;
+
+impl ApInt {
+    /// Returns an iterator over the ints in the half-open range `start..end`.
+    ///
+    /// Unlike a range of primitive integers, the bounds may be arbitrarily
+    /// large, and the iterator can be stepped by an [`ApInt`] using
+    /// [`ApIntRange::step_by_int`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let v: Vec<ApInt> = ApInt::range(ApInt::from(-1), ApInt::from(2)).collect();
+    /// assert_eq!(v, [ApInt::from(-1), ApInt::from(0), ApInt::from(1)]);
+    /// ```
+    pub fn range(start: ApInt, end: ApInt) -> ApIntRange {
+        ApIntRange {
+            start,
+            end,
+            step: ApInt::ONE,
+            inclusive: false,
+        }
+    }
+
+    /// Returns an iterator over the ints in the closed range `start..=end`.
+    pub fn range_inclusive(start: ApInt, end: ApInt) -> ApIntRange {
+        ApIntRange {
+            start,
+            end,
+            step: ApInt::ONE,
+            inclusive: true,
+        }
+    }
+}
+
+/// An iterator over a range of [`ApInt`]s.
+///
+/// This `struct` is created by [`ApInt::range`] and [`ApInt::range_inclusive`].
+#[derive(Clone, Debug)]
+pub struct ApIntRange {
+    /// The next value to be yielded.
+    start: ApInt,
+    end: ApInt,
+    step: ApInt,
+    /// Whether `end` is included in the range.
+    inclusive: bool,
+}
+
+impl ApIntRange {
+    /// Returns an iterator over the same range, advancing by `step` each time
+    /// rather than one.
+    ///
+    /// The first value yielded is still the start of the range. Unlike
+    /// [`Iterator::step_by`], which is also available, the step may be larger
+    /// than a `usize`, and the skipped values are never computed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is not positive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// let big = ApInt::from(u128::MAX);
+    /// let mut range = ApInt::range_inclusive(ApInt::ZERO, big.clone()).step_by_int(big.clone());
+    /// assert_eq!(range.next(), Some(ApInt::ZERO));
+    /// assert_eq!(range.next(), Some(big));
+    /// assert_eq!(range.next(), None);
+    /// ```
+    pub fn step_by_int(self, step: ApInt) -> ApIntRange {
+        assert!(step > ApInt::ZERO, "step must be positive");
+        ApIntRange {
+            step: step * self.step,
+            ..self
+        }
+    }
+
+    /// Returns the number of values remaining in the range.
+    pub fn remaining(&self) -> ApInt {
+        let span = &self.end - &self.start;
+        if self.inclusive {
+            if span < ApInt::ZERO {
+                ApInt::ZERO
+            } else {
+                span / &self.step + ApInt::ONE
+            }
+        } else if span <= ApInt::ZERO {
+            ApInt::ZERO
+        } else {
+            (span - ApInt::ONE) / &self.step + ApInt::ONE
+        }
+    }
+
+    /// Returns `true` if `start` is past the end of the range.
+    fn is_past_end(&self) -> bool {
+        if self.inclusive {
+            self.start > self.end
+        } else {
+            self.start >= self.end
+        }
+    }
+}
+
+impl Iterator for ApIntRange {
+    type Item = ApInt;
+
+    fn next(&mut self) -> Option<ApInt> {
+        if self.is_past_end() {
+            return None;
+        }
+        let next = &self.start + &self.step;
+        Some(core::mem::replace(&mut self.start, next))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<ApInt> {
+        // Skip over the first `n` values without visiting them.
+        self.start += ApInt::from(n) * &self.step;
+        self.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining().to_usize() {
+            Some(len) => (len, Some(len)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+impl FusedIterator for ApIntRange {}
@@ -0,0 +1,146 @@
+use core::fmt;
+use core::num::NonZeroUsize;
+
+use rkyv::rancor::Fallible;
+use rkyv::ser::{Allocator, Writer};
+use rkyv::vec::{ArchivedVec, VecResolver};
+use rkyv::{Archive, Archived, Deserialize, Place, Portable, Serialize};
+
+use crate::alloc::Vec;
+use crate::apint::ApInt;
+use crate::limb::Limb;
+
+/// An archived [`ApInt`].
+///
+/// The value is archived as the little-endian 64-bit words of its two's
+/// complement representation, least significant word first, so the same
+/// archive can be read on any target. The value can be inspected in place,
+/// without deserializing it.
+#[derive(Portable, rkyv::bytecheck::CheckBytes)]
+#[rkyv(crate = rkyv)]
+#[bytecheck(crate = rkyv::bytecheck)]
+#[repr(transparent)]
+pub struct ArchivedApInt {
+    words: ArchivedVec<Archived<u64>>,
+}
+
+impl ArchivedApInt {
+    /// Returns the words of the two's complement representation of the value,
+    /// least significant word first.
+    #[inline]
+    pub fn words(&self) -> &[Archived<u64>] {
+        self.words.as_slice()
+    }
+
+    /// Returns `true` if the value is negative.
+    #[inline]
+    pub fn is_negative(&self) -> bool {
+        self.words()
+            .last()
+            .map_or(false, |word| word.to_native() >> 63 == 1)
+    }
+
+    /// Returns the archived value as an [`ApInt`].
+    pub fn to_apint(&self) -> ApInt {
+        let words: Vec<u64> = self.words().iter().map(|word| word.to_native()).collect();
+        from_words_le(&words)
+    }
+}
+
+impl fmt::Debug for ArchivedApInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.to_apint(), f)
+    }
+}
+
+impl PartialEq<ApInt> for ArchivedApInt {
+    /// Compares the archived words against the value, without deserializing
+    /// them.
+    fn eq(&self, other: &ApInt) -> bool {
+        let (words, other) = (self.words(), to_words_le(other));
+        let sign = |negative: bool| if negative { u64::MAX } else { 0 };
+        let (words_sign, other_sign) = (
+            sign(self.is_negative()),
+            sign(other[other.len() - 1] >> 63 == 1),
+        );
+
+        // Compare the words, sign extending the shorter of the two.
+        (0..words.len().max(other.len())).all(|i| {
+            let word = words.get(i).map_or(words_sign, |word| word.to_native());
+            word == other.get(i).copied().unwrap_or(other_sign)
+        })
+    }
+}
+
+impl Archive for ApInt {
+    type Archived = ArchivedApInt;
+    type Resolver = VecResolver;
+
+    fn resolve(&self, resolver: VecResolver, out: Place<ArchivedApInt>) {
+        rkyv::munge::munge!(let ArchivedApInt { words } = out);
+        ArchivedVec::resolve_from_len(to_words_le(self).len(), resolver, words);
+    }
+}
+
+impl<S: Fallible + Allocator + Writer + ?Sized> Serialize<S> for ApInt {
+    fn serialize(&self, serializer: &mut S) -> Result<VecResolver, S::Error> {
+        ArchivedVec::serialize_from_slice(&to_words_le(self), serializer)
+    }
+}
+
+impl<D: Fallible + ?Sized> Deserialize<ApInt, D> for ArchivedApInt {
+    fn deserialize(&self, _: &mut D) -> Result<ApInt, D::Error> {
+        Ok(self.to_apint())
+    }
+}
+
+/// Returns the 64-bit words of the two's complement representation of
+/// `x`, least significant word first.
+///
+/// The fewest words able to hold the value and its sign bit are returned.
+fn to_words_le(x: &ApInt) -> Vec<u64> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_pointer_width = "64")] {
+            x.as_limbs().iter().map(|limb| limb.repr()).collect()
+        } else {
+            let sign = x.high_limb().sign_mask();
+            let words = x.as_limbs().chunks(2).map(|pair| {
+                let high = pair.get(1).copied().unwrap_or(sign);
+                u64::from(pair[0].repr()) | (u64::from(high.repr()) << 32)
+            });
+            words.collect()
+        }
+    }
+}
+
+/// Creates an int from the 64-bit words of its two's complement
+/// representation, least significant word first.
+///
+/// No words are read as zero.
+fn from_words_le(words: &[u64]) -> ApInt {
+    cfg_if::cfg_if! {
+        if #[cfg(target_pointer_width = "64")] {
+            let len = match NonZeroUsize::new(words.len()) {
+                Some(len) => len,
+                None => return ApInt::ZERO,
+            };
+            let mut int = ApInt::zeroed(len);
+            for (limb, &word) in int.as_limbs_mut().iter_mut().zip(words) {
+                *limb = Limb(word);
+            }
+        } else {
+            let len = match NonZeroUsize::new(2 * words.len()) {
+                Some(len) => len,
+                None => return ApInt::ZERO,
+            };
+            let mut int = ApInt::zeroed(len);
+            for (pair, &word) in int.as_limbs_mut().chunks_mut(2).zip(words) {
+                pair[0] = Limb(word as u32);
+                pair[1] = Limb((word >> 32) as u32);
+            }
+        }
+    }
+
+    int.normalize();
+    int
+}
@@ -0,0 +1,158 @@
+use crate::apint::ApInt;
+
+/// The squares modulo 64, 63, 65 and 11, as bit masks indexed by the residue.
+///
+/// Only about 1 in 170 non-squares passes all of these tests.
+const SQUARES_MOD_64: u128 = 0x0202_0212_0203_0213;
+const SQUARES_MOD_63: u128 = 0x0402_4830_1245_0293;
+const SQUARES_MOD_65: u128 = 0x0001_218a_0198_6601_4613;
+const SQUARES_MOD_11: u128 = 0x023b;
+
+impl ApInt {
+    /// Returns `true` if `self` is the square of an integer.
+    ///
+    /// Most non-squares are rejected by testing whether `self` is a quadratic
+    /// residue modulo a few small numbers, before any square root is taken.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// assert!(ApInt::from(1u128 << 100).is_perfect_square());
+    /// assert!(!ApInt::from(-4).is_perfect_square());
+    /// ```
+    pub fn is_perfect_square(&self) -> bool {
+        if self.is_negative() {
+            return false;
+        }
+
+        let low = self.as_limbs()[0].repr() as u128;
+        if SQUARES_MOD_64 >> (low % 64) & 1 == 0 {
+            return false;
+        }
+        let r = self.rem_u64(63 * 65 * 11) as u128;
+        if SQUARES_MOD_63 >> (r % 63) & 1 == 0
+            || SQUARES_MOD_65 >> (r % 65) & 1 == 0
+            || SQUARES_MOD_11 >> (r % 11) & 1 == 0
+        {
+            return false;
+        }
+
+        let root = self.sqrt_floor();
+        &root * &root == *self
+    }
+
+    /// Returns `(r, k)` such that `r^k == self` with the largest possible
+    /// exponent `k > 1`, or `None` if `self` is not a perfect power.
+    ///
+    /// A negative perfect power has a negative root and an odd exponent. Since
+    /// `0`, `1` and `-1` are powers with any exponent, the smallest exponent
+    /// is returned for them, which is `3` for `-1` and `2` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::ApInt;
+    ///
+    /// assert_eq!(ApInt::from(64).is_perfect_power(), Some((ApInt::from(2), 6)));
+    /// assert_eq!(ApInt::from(-64).is_perfect_power(), Some((ApInt::from(-4), 3)));
+    /// assert_eq!(ApInt::from(12).is_perfect_power(), None);
+    /// ```
+    pub fn is_perfect_power(&self) -> Option<(ApInt, u32)> {
+        if *self == ApInt::ZERO || *self == ApInt::ONE {
+            return Some((self.clone(), 2));
+        }
+        if *self == -ApInt::ONE {
+            return Some((self.clone(), 3));
+        }
+
+        let (mut root, mut k) = max_power(&self.abs());
+        if self.is_negative() {
+            // Only odd exponents give negative powers.
+            while k % 2 == 0 {
+                root = &root * &root;
+                k /= 2;
+            }
+            root.negate_to_fit();
+        }
+
+        if k > 1 { Some((root, k)) } else { None }
+    }
+
+    /// Returns the largest integer `r` such that `r^2 <= self`, for a
+    /// non-negative `self`.
+    pub(crate) fn sqrt_floor(&self) -> ApInt {
+        debug_assert!(!self.is_negative());
+
+        if *self == ApInt::ZERO {
+            return ApInt::ZERO;
+        }
+
+        // Newton's method converges to the root from any initial value above it.
+        let mut x = ApInt::ONE << ((self.significant_bits() + 1) / 2);
+        loop {
+            let y = (&x + self / &x) >> 1;
+            if y >= x {
+                return x;
+            }
+            x = y;
+        }
+    }
+
+    /// Returns the largest integer `r` such that `r^k <= self`, for a positive
+    /// `self` and `k`.
+    pub(crate) fn root_floor(&self, k: u32) -> ApInt {
+        debug_assert!(*self > ApInt::ZERO && k > 0);
+
+        if k == 2 {
+            return self.sqrt_floor();
+        }
+
+        let bits = self.significant_bits();
+        let mut x = ApInt::ONE << ((bits + k as usize - 1) / k as usize);
+        loop {
+            let y = (&x * ApInt::from(k - 1) + self / x.pow(k - 1)) / ApInt::from(k);
+            if y >= x {
+                return x;
+            }
+            x = y;
+        }
+    }
+}
+
+/// Returns `(r, k)` such that `r^k == n` with the largest possible `k`, for
+/// `n > 1`.
+fn max_power(n: &ApInt) -> (ApInt, u32) {
+    // The exponent of a power must divide the exponent of each of its prime
+    // factors, including two.
+    let zeros = n.trailing_zeros().unwrap();
+    let bits = n.significant_bits() as u32;
+
+    let mut p = 2;
+    while p <= bits {
+        let root = if p == 2 {
+            if n.is_perfect_square() {
+                Some(n.sqrt_floor())
+            } else {
+                None
+            }
+        } else if is_small_prime(p) && (zeros == 0 || zeros % p as usize == 0) {
+            Some(n.root_floor(p)).filter(|root| root.pow(p) == *n)
+        } else {
+            None
+        };
+
+        if let Some(root) = root {
+            let (root, k) = max_power(&root);
+            return (root, k * p);
+        }
+        p += 1;
+    }
+    (n.clone(), 1)
+}
+
+/// Returns `true` if `n` is prime, for a small `n`.
+fn is_small_prime(n: u32) -> bool {
+    n >= 2 && (2..).take_while(|d| d * d <= n).all(|d| n % d != 0)
+}
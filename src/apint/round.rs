@@ -0,0 +1,42 @@
+use core::cmp::Ordering;
+
+/// The direction in which to round a value that falls between two integers.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum RoundingMode {
+    /// Round toward zero, discarding the fraction.
+    TowardZero,
+    /// Round away from zero.
+    AwayFromZero,
+    /// Round toward negative infinity.
+    Floor,
+    /// Round toward positive infinity.
+    Ceiling,
+    /// Round to the nearest integer, with halves rounded away from zero.
+    HalfAwayFromZero,
+    /// Round to the nearest integer, with halves rounded toward zero.
+    HalfTowardZero,
+    /// Round to the nearest integer, with halves rounded to the even one.
+    HalfEven,
+}
+
+impl RoundingMode {
+    /// Returns `true` if a magnitude truncated toward zero should be
+    /// incremented to round it in this mode.
+    ///
+    /// `frac` is how the discarded fraction compares to one half, and `exact`
+    /// is whether the fraction is zero.
+    pub(crate) fn round_up(self, negative: bool, odd: bool, frac: Ordering, exact: bool) -> bool {
+        if exact {
+            return false;
+        }
+        match self {
+            RoundingMode::TowardZero => false,
+            RoundingMode::AwayFromZero => true,
+            RoundingMode::Floor => negative,
+            RoundingMode::Ceiling => !negative,
+            RoundingMode::HalfAwayFromZero => frac != Ordering::Less,
+            RoundingMode::HalfTowardZero => frac == Ordering::Greater,
+            RoundingMode::HalfEven => frac == Ordering::Greater || (frac == Ordering::Equal && odd),
+        }
+    }
+}
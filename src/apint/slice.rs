@@ -0,0 +1,157 @@
+use core::fmt;
+
+use crate::alloc::Vec;
+use crate::apint::ApInt;
+use crate::limb::{Limb, LimbRepr};
+
+impl ApInt {
+    /// Returns a read-only view of the limbs of the int, without copying them.
+    ///
+    /// The view can be split into windows of limbs, such as the low and high
+    /// halves of an operand in a divide and conquer algorithm, which are
+    /// themselves views borrowing the same limbs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::{ApInt, LimbRepr};
+    ///
+    /// let bits = 8 * core::mem::size_of::<LimbRepr>();
+    /// let int = (ApInt::from(-5) << bits) + ApInt::from(7);
+    /// let (low, high) = int.as_slice().split_at(1);
+    /// assert_eq!(low.to_int(), ApInt::from(7));
+    /// assert_eq!(high.to_int(), ApInt::from(-5));
+    /// ```
+    pub fn as_slice(&self) -> ApIntSlice<'_> {
+        ApIntSlice {
+            limbs: self.as_limbs(),
+            ext: self.high_limb().sign_mask(),
+        }
+    }
+}
+
+/// A read-only view of a window of the limbs of an [`ApInt`].
+///
+/// A window holds the limbs of an int from some limb up to some limb,
+/// least significant first, and has the value of those limbs shifted down to
+/// the bottom. A window reaching the top of the int is signed, and so is sign
+/// extended like the int; any other window is unsigned, and zero extended.
+///
+/// Splitting a window at a limb index `mid` gives a low and a high window
+/// such that the value of the window is `(high << (mid * LimbRepr::BITS)) +
+/// low`, with the low window unsigned.
+///
+/// This `struct` is created by [`ApInt::as_slice`].
+#[derive(Clone, Copy)]
+pub struct ApIntSlice<'a> {
+    limbs: &'a [Limb],
+    /// The limb the window is extended with, which is all zeros for an
+    /// unsigned window and the sign mask of the int for a signed window.
+    ext: Limb,
+}
+
+impl<'a> ApIntSlice<'a> {
+    /// Returns the number of limbs in the window.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.limbs.len()
+    }
+
+    /// Returns `true` if the window holds no limbs.
+    ///
+    /// An empty window is `0`, or `-1` if it is the signed window above the
+    /// top of a negative int.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Returns `true` if the value of the window is negative.
+    ///
+    /// Only a signed window of a negative int is negative.
+    #[inline]
+    pub fn is_negative(&self) -> bool {
+        self.ext == Limb::ONES
+    }
+
+    /// Returns the limbs of the window, least significant first.
+    ///
+    /// The limbs of an int of more than [`ApInt::INLINE_LIMBS`] limbs are heap
+    /// allocated, so the limbs of its whole view start at an address aligned
+    /// to [`ApInt::LIMB_ALIGN`].
+    #[inline]
+    pub fn limbs(&self) -> &'a [LimbRepr] {
+        // SAFETY: `Limb` is a transparent wrapper of `LimbRepr`.
+        unsafe { core::slice::from_raw_parts(self.limbs.as_ptr().cast(), self.limbs.len()) }
+    }
+
+    /// Divides the window into the windows of the limbs below `mid` and of the
+    /// limbs from `mid` up.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::{ApInt, LimbRepr};
+    ///
+    /// let int = ApInt::NEG_ONE;
+    /// let (low, high) = int.as_slice().split_at(1);
+    /// assert_eq!(low.to_int(), ApInt::from(LimbRepr::MAX));
+    /// assert!(high.is_empty());
+    /// assert_eq!(high.to_int(), ApInt::from(-1));
+    /// ```
+    pub fn split_at(&self, mid: usize) -> (ApIntSlice<'a>, ApIntSlice<'a>) {
+        let (low, high) = self.limbs.split_at(mid);
+        (
+            ApIntSlice {
+                limbs: low,
+                ext: Limb::ZERO,
+            },
+            ApIntSlice {
+                limbs: high,
+                ext: self.ext,
+            },
+        )
+    }
+
+    /// Returns the window of the `n` least significant limbs, which is
+    /// unsigned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n > len`.
+    #[inline]
+    pub fn low(&self, n: usize) -> ApIntSlice<'a> {
+        self.split_at(n).0
+    }
+
+    /// Returns the window of the limbs from index `n` up.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n > len`.
+    #[inline]
+    pub fn high(&self, n: usize) -> ApIntSlice<'a> {
+        self.split_at(n).1
+    }
+
+    /// Returns the value of the window as an int, copying its limbs.
+    pub fn to_int(&self) -> ApInt {
+        let mut limbs: Vec<LimbRepr> = Vec::with_capacity(self.len() + 1);
+        limbs.extend_from_slice(self.limbs());
+        limbs.push(self.ext.repr());
+        ApInt::from_limbs(&limbs)
+    }
+}
+
+impl fmt::Debug for ApIntSlice<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ApIntSlice")
+            .field("limbs", &self.limbs())
+            .field("negative", &self.is_negative())
+            .finish()
+    }
+}
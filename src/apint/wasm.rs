@@ -0,0 +1,38 @@
+use js_sys::{BigInt, JsString};
+
+use crate::alloc::String;
+use crate::apint::ApInt;
+
+// The conversions go through hexadecimal strings, which both sides can parse
+// and format in linear time.
+
+impl From<&ApInt> for BigInt {
+    fn from(int: &ApInt) -> BigInt {
+        // `BigInt` only accepts a `0x` prefix on non-negative values.
+        let mut hex = String::from("0x");
+        hex.push_str(&int.abs().to_hex());
+        let big = BigInt::new(&JsString::from(hex)).expect("valid hexadecimal BigInt");
+        if int.is_negative() { -big } else { big }
+    }
+}
+
+impl From<ApInt> for BigInt {
+    #[inline]
+    fn from(int: ApInt) -> BigInt {
+        BigInt::from(&int)
+    }
+}
+
+impl From<&BigInt> for ApInt {
+    fn from(big: &BigInt) -> ApInt {
+        let hex: JsString = big.to_string(16).expect("16 is a valid radix");
+        ApInt::from_hex(&String::from(hex)).expect("valid hexadecimal string")
+    }
+}
+
+impl From<BigInt> for ApInt {
+    #[inline]
+    fn from(big: BigInt) -> ApInt {
+        ApInt::from(&big)
+    }
+}
@@ -0,0 +1,612 @@
+// Fixed bit width operations, modelled after LLVM's `APInt`.
+//
+// An `ApInt` does not carry a bit width, so these operations take the width as
+// an argument. Only the low `width` bits of each operand are significant, and
+// results are wrapped to `width` bits. Unsigned operations return results
+// zero extended from `width` bits, while signed operations return results sign
+// extended from `width` bits. `FixedInt` carries the width with the value, so
+// it need not be passed to each operation.
+
+use core::num::NonZeroUsize;
+use core::ops::{Add, Mul, Neg, Sub};
+
+use crate::apint::{ApInt, bitwise};
+use crate::limb::{Limb, LimbRepr};
+
+/// Returns the number of limbs needed to hold `width` bits, and the number of
+/// bits used in the most significant of those limbs.
+#[inline]
+fn width_limbs(width: usize) -> (usize, usize) {
+    assert!(width != 0, "bit width must be non-zero");
+
+    let n = (width + Limb::BITS - 1) / Limb::BITS;
+    let bits = width - (n - 1) * Limb::BITS;
+    (n, bits)
+}
+
+/// Creates an `ApInt` from the low `width` bits of `int`, either sign or zero
+/// extending the value.
+fn from_width(int: &ApInt, width: usize, signed: bool) -> ApInt {
+    let (n, bits) = width_limbs(width);
+
+    // An extra limb is needed for the sign of zero extended values.
+    // SAFETY: `n + 1` is at least 2.
+    let mut r = ApInt::zeroed(unsafe { NonZeroUsize::new_unchecked(n + 1) });
+
+    let src = int.as_limbs();
+    let sign = int.high_limb().sign_mask();
+
+    let dst = r.as_limbs_mut();
+    for (i, limb) in dst[..n].iter_mut().enumerate() {
+        *limb = src.get(i).copied().unwrap_or(sign);
+    }
+
+    let shift = Limb::BITS - bits;
+    let high = dst[n - 1];
+    if signed {
+        dst[n - 1] = Limb(((high.repr_signed() << shift) >> shift) as LimbRepr);
+        dst[n] = dst[n - 1].sign_mask();
+    } else {
+        dst[n - 1] = Limb((high.repr() << shift) >> shift);
+    }
+
+    r.normalize();
+    r
+}
+
+/// Wraps `value` to `width` bits, returning the wrapped value and whether it
+/// differs from `value`.
+#[inline]
+fn wrap(value: ApInt, width: usize, signed: bool) -> (ApInt, bool) {
+    let wrapped = if signed {
+        value.sext(width)
+    } else {
+        value.zext(width)
+    };
+    let overflow = wrapped != value;
+    (wrapped, overflow)
+}
+
+impl ApInt {
+    /// Truncates `self` to its low `width` bits.
+    ///
+    /// The result is zero extended, so it is always in the range
+    /// `0..2^width`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero.
+    pub fn trunc(&self, width: usize) -> ApInt {
+        self.zext(width)
+    }
+
+    /// Zero extends `self` from `width` bits.
+    ///
+    /// Since an `ApInt` is unbounded, this is equivalent to
+    /// [`trunc`](ApInt::trunc).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero.
+    pub fn zext(&self, width: usize) -> ApInt {
+        from_width(self, width, false)
+    }
+
+    /// Sign extends `self` from `width` bits, treating bit `width - 1` as the
+    /// sign bit.
+    ///
+    /// The result is in the range `-2^(width - 1)..2^(width - 1)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero.
+    pub fn sext(&self, width: usize) -> ApInt {
+        from_width(self, width, true)
+    }
+
+    /// Calculates the unsigned quotient of `self / rhs` at `width` bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero or the low `width` bits of `rhs` are zero.
+    pub fn udiv(&self, rhs: &ApInt, width: usize) -> ApInt {
+        self.zext(width) / rhs.zext(width)
+    }
+
+    /// Calculates the signed quotient of `self / rhs` at `width` bits.
+    ///
+    /// Dividing the most negative value by `-1` wraps to the most negative
+    /// value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero or the low `width` bits of `rhs` are zero.
+    pub fn sdiv(&self, rhs: &ApInt, width: usize) -> ApInt {
+        self.sdiv_ov(rhs, width).0
+    }
+
+    /// Calculates the unsigned remainder of `self / rhs` at `width` bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero or the low `width` bits of `rhs` are zero.
+    pub fn urem(&self, rhs: &ApInt, width: usize) -> ApInt {
+        self.zext(width) % rhs.zext(width)
+    }
+
+    /// Calculates the signed remainder of `self / rhs` at `width` bits.
+    ///
+    /// The remainder has the same sign as `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero or the low `width` bits of `rhs` are zero.
+    pub fn srem(&self, rhs: &ApInt, width: usize) -> ApInt {
+        self.sext(width) % rhs.sext(width)
+    }
+
+    /// Calculates the unsigned sum of `self + rhs` at `width` bits.
+    ///
+    /// Returns a tuple of the wrapped sum along with a boolean indicating
+    /// whether an unsigned overflow occurred.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero.
+    pub fn uadd_ov(&self, rhs: &ApInt, width: usize) -> (ApInt, bool) {
+        wrap(self.zext(width) + rhs.zext(width), width, false)
+    }
+
+    /// Calculates the signed sum of `self + rhs` at `width` bits.
+    ///
+    /// Returns a tuple of the wrapped sum along with a boolean indicating
+    /// whether a signed overflow occurred.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero.
+    pub fn sadd_ov(&self, rhs: &ApInt, width: usize) -> (ApInt, bool) {
+        wrap(self.sext(width) + rhs.sext(width), width, true)
+    }
+
+    /// Calculates the unsigned difference of `self - rhs` at `width` bits.
+    ///
+    /// Returns a tuple of the wrapped difference along with a boolean
+    /// indicating whether an unsigned overflow occurred.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero.
+    pub fn usub_ov(&self, rhs: &ApInt, width: usize) -> (ApInt, bool) {
+        wrap(self.zext(width) - rhs.zext(width), width, false)
+    }
+
+    /// Calculates the signed difference of `self - rhs` at `width` bits.
+    ///
+    /// Returns a tuple of the wrapped difference along with a boolean
+    /// indicating whether a signed overflow occurred.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero.
+    pub fn ssub_ov(&self, rhs: &ApInt, width: usize) -> (ApInt, bool) {
+        wrap(self.sext(width) - rhs.sext(width), width, true)
+    }
+
+    /// Calculates the unsigned product of `self * rhs` at `width` bits.
+    ///
+    /// Returns a tuple of the wrapped product along with a boolean indicating
+    /// whether an unsigned overflow occurred.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero.
+    pub fn umul_ov(&self, rhs: &ApInt, width: usize) -> (ApInt, bool) {
+        wrap(self.zext(width) * rhs.zext(width), width, false)
+    }
+
+    /// Calculates the signed product of `self * rhs` at `width` bits.
+    ///
+    /// Returns a tuple of the wrapped product along with a boolean indicating
+    /// whether a signed overflow occurred.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero.
+    pub fn smul_ov(&self, rhs: &ApInt, width: usize) -> (ApInt, bool) {
+        wrap(self.sext(width) * rhs.sext(width), width, true)
+    }
+
+    /// Calculates the signed quotient of `self / rhs` at `width` bits.
+    ///
+    /// Returns a tuple of the wrapped quotient along with a boolean indicating
+    /// whether a signed overflow occurred, which only happens when dividing
+    /// the most negative value by `-1`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero or the low `width` bits of `rhs` are zero.
+    pub fn sdiv_ov(&self, rhs: &ApInt, width: usize) -> (ApInt, bool) {
+        wrap(self.sext(width) / rhs.sext(width), width, true)
+    }
+
+    /// Rotates the low `width` bits of `self` left by `amount` bits.
+    ///
+    /// The rotation wraps within `width` bits, so `amount` is taken modulo
+    /// `width`. The result is zero extended.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero.
+    pub fn rotl(&self, amount: usize, width: usize) -> ApInt {
+        self.fshl(self, amount, width)
+    }
+
+    /// Rotates the low `width` bits of `self` right by `amount` bits.
+    ///
+    /// The rotation wraps within `width` bits, so `amount` is taken modulo
+    /// `width`. The result is zero extended.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero.
+    pub fn rotr(&self, amount: usize, width: usize) -> ApInt {
+        self.fshr(self, amount, width)
+    }
+
+    /// Performs a funnel shift left.
+    ///
+    /// The low `width` bits of `self` and `low` are concatenated, with `self`
+    /// as the high half, then shifted left by `amount` modulo `width` bits.
+    /// The high `width` bits of the shifted value are returned, zero extended.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero.
+    pub fn fshl(&self, low: &ApInt, amount: usize, width: usize) -> ApInt {
+        let (high, low) = (self.zext(width), low.zext(width));
+        let amount = amount % width;
+        if amount == 0 {
+            return high;
+        }
+
+        // The shifted halves have no bits in common, so they can be added.
+        let shifted = bitwise::shl(&high, amount) + bitwise::shr(&low, width - amount);
+        shifted.zext(width)
+    }
+
+    /// Performs a funnel shift right.
+    ///
+    /// The low `width` bits of `self` and `low` are concatenated, with `self`
+    /// as the high half, then shifted right by `amount` modulo `width` bits.
+    /// The low `width` bits of the shifted value are returned, zero extended.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero.
+    pub fn fshr(&self, low: &ApInt, amount: usize, width: usize) -> ApInt {
+        let (high, low) = (self.zext(width), low.zext(width));
+        let amount = amount % width;
+        if amount == 0 {
+            return low;
+        }
+
+        // The shifted halves have no bits in common, so they can be added.
+        let shifted = bitwise::shr(&low, amount) + bitwise::shl(&high, width - amount);
+        shifted.zext(width)
+    }
+}
+
+/// An integer of a fixed bit width, like LLVM's `APInt`.
+///
+/// The value is held as its low `width` bits, and every operation wraps to
+/// the width. Operations on two `FixedInt`s panic if their widths differ.
+///
+/// # Example
+///
+/// ```
+/// use apa::{ApInt, FixedInt};
+///
+/// let x = FixedInt::new(&ApInt::from(200), 8);
+/// let y = FixedInt::new(&ApInt::from(100), 8);
+/// assert_eq!((&x + &y).as_unsigned(), &ApInt::from(44));
+/// assert_eq!(x.to_signed(), ApInt::from(-56));
+///
+/// let (sum, overflow) = x.sadd_ov(&y);
+/// assert_eq!(sum.to_signed(), ApInt::from(44));
+/// assert!(!overflow);
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct FixedInt {
+    /// The low `width` bits of the value, zero extended.
+    value: ApInt,
+    width: usize,
+}
+
+impl FixedInt {
+    /// Creates a `FixedInt` of `width` bits from the low `width` bits of
+    /// `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero.
+    pub fn new(value: &ApInt, width: usize) -> FixedInt {
+        FixedInt {
+            value: value.zext(width),
+            width,
+        }
+    }
+
+    /// Creates a `FixedInt` of `width` bits with a value of zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero.
+    pub fn zero(width: usize) -> FixedInt {
+        FixedInt::new(&ApInt::ZERO, width)
+    }
+
+    /// Returns the bit width of `self`.
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the value of `self` as an unsigned integer, in the range
+    /// `0..2^width`.
+    #[inline]
+    pub fn as_unsigned(&self) -> &ApInt {
+        &self.value
+    }
+
+    /// Returns the value of `self` as a signed integer, in the range
+    /// `-2^(width - 1)..2^(width - 1)`.
+    pub fn to_signed(&self) -> ApInt {
+        self.value.sext(self.width)
+    }
+
+    /// Wraps `value` to the width of `self`.
+    #[inline]
+    fn with_value(&self, value: &ApInt) -> FixedInt {
+        FixedInt::new(value, self.width)
+    }
+
+    /// Returns the common width of `self` and `rhs`.
+    #[inline]
+    fn common_width(&self, rhs: &FixedInt) -> usize {
+        assert!(
+            self.width == rhs.width,
+            "bit widths must match: {} and {}",
+            self.width,
+            rhs.width
+        );
+        self.width
+    }
+
+    /// Wraps the value of an overflowing operation to the width of `self`.
+    #[inline]
+    fn with_value_ov(&self, (value, overflow): (ApInt, bool)) -> (FixedInt, bool) {
+        (self.with_value(&value), overflow)
+    }
+
+    /// Truncates `self` to its low `width` bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero or greater than the width of `self`.
+    pub fn trunc(&self, width: usize) -> FixedInt {
+        assert!(width <= self.width, "truncation must not widen");
+        FixedInt::new(&self.value, width)
+    }
+
+    /// Zero extends `self` to `width` bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is less than the width of `self`.
+    pub fn zext(&self, width: usize) -> FixedInt {
+        assert!(width >= self.width, "extension must not narrow");
+        FixedInt {
+            value: self.value.clone(),
+            width,
+        }
+    }
+
+    /// Sign extends `self` to `width` bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is less than the width of `self`.
+    pub fn sext(&self, width: usize) -> FixedInt {
+        assert!(width >= self.width, "extension must not narrow");
+        FixedInt::new(&self.to_signed(), width)
+    }
+
+    /// Calculates the unsigned quotient of `self / rhs`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the widths differ or `rhs` is zero.
+    pub fn udiv(&self, rhs: &FixedInt) -> FixedInt {
+        self.with_value(&self.value.udiv(&rhs.value, self.common_width(rhs)))
+    }
+
+    /// Calculates the signed quotient of `self / rhs`.
+    ///
+    /// Dividing the most negative value by `-1` wraps to the most negative
+    /// value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the widths differ or `rhs` is zero.
+    pub fn sdiv(&self, rhs: &FixedInt) -> FixedInt {
+        self.with_value(&self.value.sdiv(&rhs.value, self.common_width(rhs)))
+    }
+
+    /// Calculates the unsigned remainder of `self / rhs`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the widths differ or `rhs` is zero.
+    pub fn urem(&self, rhs: &FixedInt) -> FixedInt {
+        self.with_value(&self.value.urem(&rhs.value, self.common_width(rhs)))
+    }
+
+    /// Calculates the signed remainder of `self / rhs`.
+    ///
+    /// The remainder has the same sign as `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the widths differ or `rhs` is zero.
+    pub fn srem(&self, rhs: &FixedInt) -> FixedInt {
+        self.with_value(&self.value.srem(&rhs.value, self.common_width(rhs)))
+    }
+
+    /// Calculates the unsigned sum of `self + rhs`.
+    ///
+    /// Returns a tuple of the wrapped sum along with a boolean indicating
+    /// whether an unsigned overflow occurred.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the widths differ.
+    pub fn uadd_ov(&self, rhs: &FixedInt) -> (FixedInt, bool) {
+        self.with_value_ov(self.value.uadd_ov(&rhs.value, self.common_width(rhs)))
+    }
+
+    /// Calculates the signed sum of `self + rhs`.
+    ///
+    /// Returns a tuple of the wrapped sum along with a boolean indicating
+    /// whether a signed overflow occurred.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the widths differ.
+    pub fn sadd_ov(&self, rhs: &FixedInt) -> (FixedInt, bool) {
+        self.with_value_ov(self.value.sadd_ov(&rhs.value, self.common_width(rhs)))
+    }
+
+    /// Calculates the unsigned difference of `self - rhs`.
+    ///
+    /// Returns a tuple of the wrapped difference along with a boolean
+    /// indicating whether an unsigned overflow occurred.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the widths differ.
+    pub fn usub_ov(&self, rhs: &FixedInt) -> (FixedInt, bool) {
+        self.with_value_ov(self.value.usub_ov(&rhs.value, self.common_width(rhs)))
+    }
+
+    /// Calculates the signed difference of `self - rhs`.
+    ///
+    /// Returns a tuple of the wrapped difference along with a boolean
+    /// indicating whether a signed overflow occurred.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the widths differ.
+    pub fn ssub_ov(&self, rhs: &FixedInt) -> (FixedInt, bool) {
+        self.with_value_ov(self.value.ssub_ov(&rhs.value, self.common_width(rhs)))
+    }
+
+    /// Calculates the unsigned product of `self * rhs`.
+    ///
+    /// Returns a tuple of the wrapped product along with a boolean indicating
+    /// whether an unsigned overflow occurred.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the widths differ.
+    pub fn umul_ov(&self, rhs: &FixedInt) -> (FixedInt, bool) {
+        self.with_value_ov(self.value.umul_ov(&rhs.value, self.common_width(rhs)))
+    }
+
+    /// Calculates the signed product of `self * rhs`.
+    ///
+    /// Returns a tuple of the wrapped product along with a boolean indicating
+    /// whether a signed overflow occurred.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the widths differ.
+    pub fn smul_ov(&self, rhs: &FixedInt) -> (FixedInt, bool) {
+        self.with_value_ov(self.value.smul_ov(&rhs.value, self.common_width(rhs)))
+    }
+
+    /// Calculates the signed quotient of `self / rhs`.
+    ///
+    /// Returns a tuple of the wrapped quotient along with a boolean indicating
+    /// whether a signed overflow occurred, which only happens when dividing
+    /// the most negative value by `-1`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the widths differ or `rhs` is zero.
+    pub fn sdiv_ov(&self, rhs: &FixedInt) -> (FixedInt, bool) {
+        self.with_value_ov(self.value.sdiv_ov(&rhs.value, self.common_width(rhs)))
+    }
+
+    /// Rotates `self` left by `amount` bits, modulo the width.
+    pub fn rotl(&self, amount: usize) -> FixedInt {
+        self.with_value(&self.value.rotl(amount, self.width))
+    }
+
+    /// Rotates `self` right by `amount` bits, modulo the width.
+    pub fn rotr(&self, amount: usize) -> FixedInt {
+        self.with_value(&self.value.rotr(amount, self.width))
+    }
+
+    /// Performs a funnel shift left, as [`ApInt::fshl`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the widths differ.
+    pub fn fshl(&self, low: &FixedInt, amount: usize) -> FixedInt {
+        self.with_value(&self.value.fshl(&low.value, amount, self.common_width(low)))
+    }
+
+    /// Performs a funnel shift right, as [`ApInt::fshr`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the widths differ.
+    pub fn fshr(&self, low: &FixedInt, amount: usize) -> FixedInt {
+        self.with_value(&self.value.fshr(&low.value, amount, self.common_width(low)))
+    }
+}
+
+// The wrapping operations are the same for signed and unsigned values.
+
+impl Add<&FixedInt> for &FixedInt {
+    type Output = FixedInt;
+
+    fn add(self, rhs: &FixedInt) -> FixedInt {
+        self.uadd_ov(rhs).0
+    }
+}
+
+impl Sub<&FixedInt> for &FixedInt {
+    type Output = FixedInt;
+
+    fn sub(self, rhs: &FixedInt) -> FixedInt {
+        self.usub_ov(rhs).0
+    }
+}
+
+impl Mul<&FixedInt> for &FixedInt {
+    type Output = FixedInt;
+
+    fn mul(self, rhs: &FixedInt) -> FixedInt {
+        self.umul_ov(rhs).0
+    }
+}
+
+impl Neg for &FixedInt {
+    type Output = FixedInt;
+
+    fn neg(self) -> FixedInt {
+        self.with_value(&-&self.value)
+    }
+}
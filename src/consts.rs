@@ -0,0 +1,130 @@
+//! Mathematical constants to any number of decimal digits.
+//!
+//! Each constant is returned as an integer scaled by a power of ten: the
+//! constant `c` to `digits` digits is `floor(c * 10^digits)`, so its decimal
+//! representation is the digits of `c` with the point removed. The digits are
+//! always correctly truncated, never rounded.
+//!
+//! The constants are summed from series with [`binary_split`], with guard
+//! digits that are doubled until the truncated result is certain.
+//!
+//! # Examples
+//!
+//! ```
+//! use apa::consts;
+//!
+//! assert_eq!(consts::pi(10).to_string(), "31415926535");
+//! assert_eq!(consts::e(10).to_string(), "27182818284");
+//! assert_eq!(consts::ln2(10).to_string(), "6931471805");
+//! ```
+
+use crate::apint::{ApInt, pow10};
+use crate::series::binary_split;
+
+/// The number of guard digits to start with.
+const GUARD_DIGITS: usize = 16;
+
+/// Returns `pi` to `digits` digits after the decimal point, as the integer
+/// `floor(pi * 10^digits)`.
+///
+/// This uses the Chudnovsky series, which gives about 14 digits per term.
+pub fn pi(digits: usize) -> ApInt {
+    truncate(digits, 3, |prec| {
+        // pi = 426880 * sqrt(10005) / sum((6k)! * (13591409 + 545140134k) /
+        //      ((3k)! * k!^3 * (-640320^3)^k))
+        let terms = prec as u64 / 14 + 2;
+        let sum = binary_split(
+            terms,
+            |k| ApInt::from(13_591_409 + 545_140_134 * k),
+            |_| ApInt::ONE,
+            |k| {
+                if k == 0 {
+                    ApInt::ONE
+                } else {
+                    -(ApInt::from(6 * k - 5) * ApInt::from(2 * k - 1) * ApInt::from(6 * k - 1))
+                }
+            },
+            |k| {
+                if k == 0 {
+                    ApInt::ONE
+                } else {
+                    ApInt::from(k).pow(3) * ApInt::from(10_939_058_860_032_000u64)
+                }
+            },
+        );
+
+        let sqrt = (ApInt::from(10_005) * pow10(2 * prec)).sqrt_floor();
+        (ApInt::from(426_880) * sqrt * sum.q).div_floor(&sum.t)
+    })
+}
+
+/// Returns `e` to `digits` digits after the decimal point, as the integer
+/// `floor(e * 10^digits)`.
+///
+/// This sums the series `1/n!`.
+pub fn e(digits: usize) -> ApInt {
+    truncate(digits, 3, |prec| {
+        // Take terms until `N! >= 10^prec`, after which the tail is less than
+        // `2 / N!`. Summing `floor(log2(n))` bounds `log2(N!)` from below.
+        let bits = decimal_bits(prec);
+        let (mut terms, mut acc) = (1u64, 0);
+        while acc < bits {
+            terms += 1;
+            acc += 63 - u64::from(terms.leading_zeros());
+        }
+
+        let one = |_| ApInt::ONE;
+        let sum = binary_split(terms, one, one, one, |n| {
+            if n == 0 { ApInt::ONE } else { ApInt::from(n) }
+        });
+        sum.scaled(&pow10(prec))
+    })
+}
+
+/// Returns `ln(2)` to `digits` digits after the decimal point, as the integer
+/// `floor(ln(2) * 10^digits)`.
+///
+/// This sums the series `1 / (k * 2^k)`.
+pub fn ln2(digits: usize) -> ApInt {
+    truncate(digits, 2, |prec| {
+        // The tail after `N` terms is less than `2^-N`.
+        let terms = decimal_bits(prec);
+        let sum = binary_split(
+            terms,
+            |_| ApInt::ONE,
+            |n| ApInt::from(n + 1),
+            |_| ApInt::ONE,
+            |_| ApInt::TWO,
+        );
+        sum.scaled(&pow10(prec))
+    })
+}
+
+/// Returns a number of bits `b` with `2^b >= 10^digits`.
+fn decimal_bits(digits: usize) -> u64 {
+    // log2(10) < 3.322
+    digits as u64 * 3322 / 1000 + 1
+}
+
+/// Returns `floor(c * 10^digits)` for an irrational constant `c`, given a
+/// function returning an `x` with `|c * 10^prec - x| <= err` for any `prec`.
+fn truncate<F>(digits: usize, err: u32, approx: F) -> ApInt
+where
+    F: Fn(usize) -> ApInt,
+{
+    let err = ApInt::from(err);
+    let mut guard = GUARD_DIGITS;
+    loop {
+        // The guard digits are certain once both ends of the error interval
+        // truncate to the same value, which happens eventually since `c` is
+        // irrational.
+        let x = approx(digits + guard);
+        let scale = pow10(guard);
+        let lo = (&x - &err).div_floor(&scale);
+        let hi = (&x + &err).div_floor(&scale);
+        if lo == hi {
+            return lo;
+        }
+        guard *= 2;
+    }
+}
@@ -0,0 +1,111 @@
+//! Diffie-Hellman group parameters.
+//!
+//! The groups are the subgroups of prime order `q` of the integers modulo a
+//! safe prime `p = 2q + 1`, which are the quadratic residues modulo `p`. Every
+//! element of such a subgroup other than `1` generates it, and it has no small
+//! subgroups for an attacker to confine a key to.
+
+#[cfg(feature = "rand")]
+use rand::Rng;
+
+use crate::apint::ApInt;
+use crate::number_theory::{is_prime, pow_mod};
+
+/// The parameters of a Diffie-Hellman group: a safe prime modulus `p`, and a
+/// generator `g` of the subgroup of prime order `(p - 1) / 2`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Params {
+    p: ApInt,
+    g: ApInt,
+}
+
+impl Params {
+    /// Creates the parameters of a group, or returns `None` if `p` is not a
+    /// safe prime or `g` does not generate its subgroup of prime order.
+    ///
+    /// This checks parameters received from elsewhere, and tests both `p` and
+    /// `(p - 1) / 2` for primality, so it is slow for large moduli.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::dh::Params;
+    /// use apa::ApInt;
+    ///
+    /// assert!(Params::new(ApInt::from(23), ApInt::from(2)).is_some());
+    /// // 5 generates the whole group modulo 23, not the subgroup of order 11.
+    /// assert!(Params::new(ApInt::from(23), ApInt::from(5)).is_none());
+    /// ```
+    pub fn new(p: ApInt, g: ApInt) -> Option<Params> {
+        if p <= ApInt::from(3) || !is_prime(&p) {
+            return None;
+        }
+        let q = &p >> 1;
+        if !is_prime(&q) {
+            return None;
+        }
+
+        // The order of `g` divides `q`, so is `q` unless `g` is `1`.
+        let in_range = g > ApInt::ONE && g < p;
+        if in_range && pow_mod(&g, &q, &p) == ApInt::ONE {
+            Some(Params { p, g })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the safe prime modulus `p`.
+    #[inline]
+    pub fn prime(&self) -> &ApInt {
+        &self.p
+    }
+
+    /// Returns the generator `g`.
+    #[inline]
+    pub fn generator(&self) -> &ApInt {
+        &self.g
+    }
+
+    /// Returns the prime order `(p - 1) / 2` of the group generated by `g`.
+    #[inline]
+    pub fn order(&self) -> ApInt {
+        &self.p >> 1
+    }
+}
+
+/// Generates the parameters of a group whose modulus is a random safe prime of
+/// exactly `bits` bits.
+///
+/// The modulus is found with [`ApInt::random_safe_prime`], and the generator is
+/// the smallest int from `2` upwards that generates the subgroup of prime
+/// order, which is always at most `4`. Finding a safe prime is slow for large
+/// sizes.
+///
+/// # Panics
+///
+/// Panics if `bits` is less than 3.
+///
+/// # Examples
+///
+/// ```
+/// use apa::ApInt;
+/// use apa::dh;
+///
+/// let mut rng = rand::rngs::mock::StepRng::new(1, 0x9e37_79b9_7f4a_7c15);
+/// let params = dh::generate_params(&mut rng, 64);
+/// assert!(*params.prime() >= ApInt::ONE << 63 && *params.prime() < ApInt::ONE << 64);
+/// assert!(dh::Params::new(params.prime().clone(), params.generator().clone()).is_some());
+/// ```
+#[cfg(feature = "rand")]
+pub fn generate_params<R: Rng + ?Sized>(rng: &mut R, bits: usize) -> Params {
+    let p = ApInt::random_safe_prime(rng, bits);
+    let q = &p >> 1;
+
+    // `4 = 2^2` is a non-trivial quadratic residue, so it always generates the
+    // subgroup.
+    let g = (2..=4)
+        .map(ApInt::from)
+        .find(|g| pow_mod(g, &q, &p) == ApInt::ONE)
+        .unwrap();
+    Params { p, g }
+}
@@ -0,0 +1,340 @@
+//! Elements of prime fields.
+//!
+//! An [`Fp`] is an element of the field of integers modulo a prime `p`, kept
+//! in Montgomery form under a shared [`MontgomeryCtx`] for the prime. The
+//! context is borrowed, so its constants are computed once however many
+//! elements use it.
+//!
+//! # Examples
+//!
+//! ```
+//! use apa::field::Fp;
+//! use apa::modular::MontgomeryCtx;
+//! use apa::ApInt;
+//!
+//! let ctx = MontgomeryCtx::new(&ApInt::from(101)).unwrap();
+//! let a = Fp::new(&ctx, &ApInt::from(20));
+//! let b = Fp::new(&ctx, &ApInt::from(-3));
+//!
+//! assert_eq!((&a * &b).value(), ApInt::from(41));
+//! assert_eq!((&a * &a.inv().unwrap()).value(), ApInt::ONE);
+//! ```
+
+use core::ops::{Add, Mul, Neg, Sub};
+
+use crate::alloc::Vec;
+use crate::apint::ApInt;
+use crate::modular::MontgomeryCtx;
+use crate::number_theory::rem_euclid;
+
+/// An element of the prime field of integers modulo the modulus of a
+/// [`MontgomeryCtx`].
+///
+/// The modulus must be prime for [`inv`](Fp::inv) and [`sqrt`](Fp::sqrt) to
+/// behave as in a field.
+///
+/// # Panics
+///
+/// Operations on two elements panic if their moduli differ.
+#[derive(Clone, Debug)]
+pub struct Fp<'a> {
+    ctx: &'a MontgomeryCtx,
+    /// The element in Montgomery form.
+    mont: ApInt,
+}
+
+impl<'a> Fp<'a> {
+    /// Creates the element congruent to `value`, which may be any int.
+    pub fn new(ctx: &'a MontgomeryCtx, value: &ApInt) -> Fp<'a> {
+        Fp {
+            ctx,
+            mont: ctx.to_montgomery(value),
+        }
+    }
+
+    /// Returns the element `0`.
+    pub fn zero(ctx: &'a MontgomeryCtx) -> Fp<'a> {
+        Fp {
+            ctx,
+            mont: ApInt::ZERO,
+        }
+    }
+
+    /// Returns the element `1`.
+    pub fn one(ctx: &'a MontgomeryCtx) -> Fp<'a> {
+        Fp {
+            ctx,
+            mont: ctx.one(),
+        }
+    }
+
+    /// Returns the context of the element.
+    #[inline]
+    pub fn ctx(&self) -> &'a MontgomeryCtx {
+        self.ctx
+    }
+
+    /// Returns the value of the element, in the range `0..p`.
+    pub fn value(&self) -> ApInt {
+        self.ctx.from_montgomery(&self.mont)
+    }
+
+    /// Returns `true` if the element is `0`.
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.mont == ApInt::ZERO
+    }
+
+    /// Returns the element raised to the power `exp`.
+    ///
+    /// A negative `exp` raises the inverse of the element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `exp` is negative and the element is `0`.
+    pub fn pow(&self, exp: &ApInt) -> Fp<'a> {
+        let base = if exp.is_negative() {
+            self.inv()
+                .expect("attempt to raise zero to a negative power")
+        } else {
+            self.clone()
+        };
+        Fp {
+            ctx: self.ctx,
+            mont: self.ctx.pow(&base.mont, &exp.abs()),
+        }
+    }
+
+    /// Returns the multiplicative inverse of the element, or `None` if it is
+    /// `0`.
+    pub fn inv(&self) -> Option<Fp<'a>> {
+        let p = self.ctx.modulus();
+        let (g, x, _) = self.value().extended_gcd(p);
+        if g != ApInt::ONE {
+            return None;
+        }
+        Some(Fp::new(self.ctx, &x))
+    }
+
+    /// Returns a square root of the element, or `None` if it is not a square.
+    ///
+    /// The root is found with the Tonelli-Shanks algorithm. The other root is
+    /// its negation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::field::Fp;
+    /// use apa::modular::MontgomeryCtx;
+    /// use apa::ApInt;
+    ///
+    /// let ctx = MontgomeryCtx::new(&ApInt::from(113)).unwrap();
+    /// let a = Fp::new(&ctx, &ApInt::from(2));
+    /// let r = a.sqrt().unwrap();
+    /// assert_eq!(&r * &r, a);
+    ///
+    /// assert!(Fp::new(&ctx, &ApInt::from(3)).sqrt().is_none());
+    /// ```
+    pub fn sqrt(&self) -> Option<Fp<'a>> {
+        if self.is_zero() {
+            return Some(self.clone());
+        }
+
+        // Write `p - 1 = q * 2^s` with `q` odd.
+        let p_minus_1 = self.ctx.modulus() - ApInt::ONE;
+        let s = p_minus_1.trailing_zeros().unwrap_or(0);
+        let q = &p_minus_1 >> s;
+
+        // Euler's criterion: squares satisfy `a^((p - 1) / 2) == 1`.
+        let one = Fp::one(self.ctx);
+        if self.pow(&(&p_minus_1 >> 1)) != one {
+            return None;
+        }
+
+        // Find a non-square `z`, which is half of all non-zero elements.
+        let minus_one = -&one;
+        let mut n = ApInt::TWO;
+        let z = loop {
+            if n >= *self.ctx.modulus() {
+                return None;
+            }
+            let candidate = Fp::new(self.ctx, &n);
+            if candidate.pow(&(&p_minus_1 >> 1)) == minus_one {
+                break candidate;
+            }
+            n += ApInt::ONE;
+        };
+
+        let mut m = s;
+        let mut c = z.pow(&q);
+        let mut t = self.pow(&q);
+        let mut r = self.pow(&((&q + ApInt::ONE) >> 1));
+        while t != one {
+            // Find the least `i` with `t^(2^i) == 1`.
+            let mut i = 0;
+            let mut t2 = t.clone();
+            while t2 != one {
+                t2 = &t2 * &t2;
+                i += 1;
+                if i == m {
+                    return None;
+                }
+            }
+
+            let mut b = c;
+            for _ in 0..m - i - 1 {
+                b = &b * &b;
+            }
+            m = i;
+            c = &b * &b;
+            t = &t * &c;
+            r = &r * &b;
+        }
+        Some(r)
+    }
+
+    /// Returns the inverses of all of `elems`, or `None` if any of them is
+    /// `0`.
+    ///
+    /// This uses Montgomery's trick, computing every inverse with a single
+    /// field inversion and three multiplications per element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the moduli of the elements differ.
+    pub fn batch_inv(elems: &[Fp<'a>]) -> Option<Vec<Fp<'a>>> {
+        let first = match elems.first() {
+            Some(first) => first,
+            None => return Some(Vec::new()),
+        };
+
+        // The prefix products `elems[0] * ... * elems[i]`.
+        let mut prefix = Vec::with_capacity(elems.len());
+        let mut acc = Fp::one(first.ctx);
+        for elem in elems {
+            acc = &acc * elem;
+            prefix.push(acc.clone());
+        }
+
+        // Walk back down, peeling one element off the inverse of the product
+        // at each step.
+        let mut inv = acc.inv()?;
+        let mut invs = Vec::with_capacity(elems.len());
+        for i in (0..elems.len()).rev() {
+            invs.push(if i == 0 {
+                inv.clone()
+            } else {
+                &inv * &prefix[i - 1]
+            });
+            inv = &inv * &elems[i];
+        }
+        invs.reverse();
+        Some(invs)
+    }
+
+    fn with(&self, other: &Fp, mont: ApInt) -> Fp<'a> {
+        assert_eq!(self.ctx.modulus(), other.ctx.modulus(), "moduli must match");
+        Fp {
+            ctx: self.ctx,
+            mont,
+        }
+    }
+}
+
+impl PartialEq for Fp<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.mont == other.mont && self.ctx.modulus() == other.ctx.modulus()
+    }
+}
+
+impl Eq for Fp<'_> {}
+
+impl<'a> Add<&Fp<'a>> for &Fp<'a> {
+    type Output = Fp<'a>;
+
+    fn add(self, other: &Fp<'a>) -> Fp<'a> {
+        let p = self.ctx.modulus();
+        let sum = &self.mont + &other.mont;
+        self.with(other, if sum >= *p { sum - p } else { sum })
+    }
+}
+
+impl<'a> Sub<&Fp<'a>> for &Fp<'a> {
+    type Output = Fp<'a>;
+
+    fn sub(self, other: &Fp<'a>) -> Fp<'a> {
+        let diff = &self.mont - &other.mont;
+        self.with(
+            other,
+            if diff.is_negative() {
+                diff + self.ctx.modulus()
+            } else {
+                diff
+            },
+        )
+    }
+}
+
+impl<'a> Mul<&Fp<'a>> for &Fp<'a> {
+    type Output = Fp<'a>;
+
+    fn mul(self, other: &Fp<'a>) -> Fp<'a> {
+        self.with(other, self.ctx.mul(&self.mont, &other.mont))
+    }
+}
+
+impl<'a> Neg for &Fp<'a> {
+    type Output = Fp<'a>;
+
+    fn neg(self) -> Fp<'a> {
+        self.with(self, rem_euclid(&-&self.mont, self.ctx.modulus()))
+    }
+}
+
+macro_rules! forward_fp_binop {
+    ($($trait:ident, $fn:ident;)*) => {
+        $(
+            impl<'a> $trait<Fp<'a>> for &Fp<'a> {
+                type Output = Fp<'a>;
+
+                #[inline]
+                fn $fn(self, other: Fp<'a>) -> Fp<'a> {
+                    self.$fn(&other)
+                }
+            }
+
+            impl<'a> $trait<&Fp<'a>> for Fp<'a> {
+                type Output = Fp<'a>;
+
+                #[inline]
+                fn $fn(self, other: &Fp<'a>) -> Fp<'a> {
+                    (&self).$fn(other)
+                }
+            }
+
+            impl<'a> $trait<Fp<'a>> for Fp<'a> {
+                type Output = Fp<'a>;
+
+                #[inline]
+                fn $fn(self, other: Fp<'a>) -> Fp<'a> {
+                    (&self).$fn(&other)
+                }
+            }
+        )*
+    };
+}
+
+forward_fp_binop! {
+    Add, add;
+    Sub, sub;
+    Mul, mul;
+}
+
+impl<'a> Neg for Fp<'a> {
+    type Output = Fp<'a>;
+
+    #[inline]
+    fn neg(self) -> Fp<'a> {
+        -&self
+    }
+}
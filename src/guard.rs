@@ -0,0 +1,166 @@
+//! Guarded evaluation of operations with a limit on the size of results.
+
+use core::fmt;
+
+use crate::apint::{ApInt, pow_bits};
+
+/// A limit on the number of bits in the results of guarded operations.
+///
+/// Evaluating untrusted expressions can produce enormous values, such as
+/// `10^10^10`. The operations of a guard return an error instead of a result
+/// that exceeds its limit, and avoid computing results that are certain to
+/// exceed it.
+///
+/// The size of a value is measured as the number of bits required to hold it
+/// in two's complement, excluding the sign bit.
+///
+/// # Examples
+///
+/// ```
+/// use apa::{ApInt, Guard};
+///
+/// let guard = Guard::new(64);
+/// let big = ApInt::from(u64::MAX);
+///
+/// assert!(guard.add(&big, &ApInt::ZERO).is_ok());
+/// assert!(guard.mul(&big, &big).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Guard {
+    max_bits: usize,
+}
+
+/// The error returned when the result of a guarded operation exceeds the limit
+/// of its [`Guard`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct LimitExceeded {
+    max_bits: usize,
+}
+
+impl LimitExceeded {
+    /// Returns the limit that was exceeded, in bits.
+    #[inline]
+    pub const fn max_bits(&self) -> usize {
+        self.max_bits
+    }
+}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "result exceeds the limit of {} bits", self.max_bits)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LimitExceeded {}
+
+impl Guard {
+    /// Creates a guard that limits results to `max_bits` bits.
+    #[inline]
+    pub const fn new(max_bits: usize) -> Guard {
+        Guard { max_bits }
+    }
+
+    /// Returns the limit of the guard, in bits.
+    #[inline]
+    pub const fn max_bits(&self) -> usize {
+        self.max_bits
+    }
+
+    /// Returns `value` if it is within the limit of the guard.
+    #[inline]
+    pub fn check(&self, value: ApInt) -> Result<ApInt, LimitExceeded> {
+        if value.significant_bits() > self.max_bits {
+            Err(self.error())
+        } else {
+            Ok(value)
+        }
+    }
+
+    /// Returns an error if a result of at least `bits` bits would exceed the
+    /// limit of the guard.
+    #[inline]
+    fn reserve(&self, bits: usize) -> Result<(), LimitExceeded> {
+        if bits > self.max_bits {
+            Err(self.error())
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline]
+    fn error(&self) -> LimitExceeded {
+        LimitExceeded {
+            max_bits: self.max_bits,
+        }
+    }
+
+    /// Computes `lhs + rhs`.
+    pub fn add(&self, lhs: &ApInt, rhs: &ApInt) -> Result<ApInt, LimitExceeded> {
+        self.check(lhs + rhs)
+    }
+
+    /// Computes `lhs - rhs`.
+    pub fn sub(&self, lhs: &ApInt, rhs: &ApInt) -> Result<ApInt, LimitExceeded> {
+        self.check(lhs - rhs)
+    }
+
+    /// Computes `lhs * rhs`.
+    ///
+    /// The product is not computed if it certainly exceeds the limit.
+    pub fn mul(&self, lhs: &ApInt, rhs: &ApInt) -> Result<ApInt, LimitExceeded> {
+        let (lhs_bits, rhs_bits) = (lhs.significant_bits(), rhs.significant_bits());
+        if lhs_bits != 0 && rhs_bits != 0 {
+            // A product of non-zero values of `m` and `n` bits has at least
+            // `m + n - 1` bits, less one more for the sign of negative values.
+            self.reserve(lhs_bits.saturating_add(rhs_bits).saturating_sub(2))?;
+        }
+        self.check(lhs * rhs)
+    }
+
+    /// Computes `lhs / rhs`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rhs` is zero.
+    pub fn div(&self, lhs: &ApInt, rhs: &ApInt) -> Result<ApInt, LimitExceeded> {
+        self.check(lhs / rhs)
+    }
+
+    /// Computes `lhs % rhs`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rhs` is zero.
+    pub fn rem(&self, lhs: &ApInt, rhs: &ApInt) -> Result<ApInt, LimitExceeded> {
+        self.check(lhs % rhs)
+    }
+
+    /// Computes `lhs << bits`.
+    ///
+    /// The shift is not computed if its result exceeds the limit.
+    pub fn shl(&self, lhs: &ApInt, bits: usize) -> Result<ApInt, LimitExceeded> {
+        // Shifting a non-zero value adds exactly `bits` significant bits.
+        if lhs.is_negative() || lhs.significant_bits() != 0 {
+            self.reserve(lhs.significant_bits().saturating_add(bits))?;
+        }
+        Ok(lhs << bits)
+    }
+
+    /// Computes `base` raised to the power `exp`.
+    ///
+    /// The power is not computed if it certainly exceeds the limit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `exp` is negative and `base` is not `1` or `-1`.
+    pub fn pow(&self, base: &ApInt, exp: &ApInt) -> Result<ApInt, LimitExceeded> {
+        if *exp > ApInt::ZERO && base.abs() > ApInt::ONE {
+            self.reserve(pow_bits(base, exp).unwrap_or(usize::MAX))?;
+        }
+        match base.pow_int(exp) {
+            Ok(r) => self.check(r),
+            Err(_) => Err(self.error()),
+        }
+    }
+}
@@ -1,12 +1,64 @@
 //! An arbitrary-precision arithmetic library.
+//!
+//! The library provides a single integer type, [`ApInt`], which is a signed
+//! integer of unbounded size stored in two's complement form. Small values are
+//! stored inline without allocating.
+//!
+//! Bitwise operations on an [`ApInt`] behave as if the value were infinitely
+//! sign extended. Where fixed bit width semantics are needed, such as when
+//! emulating machine integers, the width aware operations (eg.
+//! [`ApInt::trunc`], [`ApInt::sext`] and [`ApInt::uadd_ov`]) take the width as
+//! an argument and wrap their results to it.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![deny(missing_docs)]
 
 mod alloc;
 mod apint;
+pub mod consts;
+pub mod dh;
+pub mod field;
+mod guard;
 mod limb;
 mod limbs;
+mod ll;
 mod mem;
+pub mod modular;
+pub mod number_theory;
+#[cfg(feature = "alloc-observer")]
+mod observer;
+mod oom;
+pub mod padic;
+pub mod poly;
+#[cfg(feature = "pool")]
+mod pool;
+pub mod rational;
+pub mod rns;
+#[cfg(feature = "subtle")]
+mod secret;
+pub mod series;
+mod sign;
+#[cfg(feature = "stats")]
+pub mod stats;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+pub mod tuning;
 
-pub use crate::apint::ApInt;
+#[cfg(feature = "rkyv")]
+pub use crate::apint::ArchivedApInt;
+#[cfg(feature = "digest")]
+pub use crate::apint::Encoding;
+pub use crate::apint::{
+    ApInt, ApIntRange, ApIntSlice, DecimalChunks, DecodeError, FixedInt, LazyInt, PowOverflow,
+    RoundingMode, TryFromApIntError,
+};
+pub use crate::guard::{Guard, LimitExceeded};
+pub use crate::limb::LimbRepr;
+#[cfg(feature = "alloc-observer")]
+pub use crate::observer::{AllocKind, clear_alloc_observer, set_alloc_observer};
+pub use crate::oom::{AllocError, clear_oom_handler, set_oom_handler};
+#[cfg(feature = "pool")]
+pub use crate::pool::IntPool;
+#[cfg(feature = "subtle")]
+pub use crate::secret::SecretInt;
+pub use crate::sign::Sign;
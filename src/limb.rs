@@ -1,5 +1,9 @@
+/// The primitive integer type of a single limb, which is the size of a machine
+/// word.
 #[cfg(target_pointer_width = "32")]
 pub type LimbRepr = u32;
+/// The primitive integer type of a single limb, which is the size of a machine
+/// word.
 #[cfg(target_pointer_width = "64")]
 pub type LimbRepr = u64;
 
@@ -8,9 +12,15 @@ pub type LimbReprSigned = i32;
 #[cfg(target_pointer_width = "64")]
 pub type LimbReprSigned = i64;
 
+#[cfg(target_pointer_width = "32")]
+pub type DoubleLimbRepr = u64;
+#[cfg(target_pointer_width = "64")]
+pub type DoubleLimbRepr = u128;
+
 const REPR_ZERO: LimbRepr = 0x0;
 const REPR_ONE: LimbRepr = 0x1;
 const REPR_ONES: LimbRepr = !REPR_ZERO;
+const REPR_SIGN_BIT: LimbRepr = !(REPR_ONES >> 1);
 
 /// A limb is part of an `ApInt` that fits within a single machine word.
 #[repr(transparent)]
@@ -29,6 +39,8 @@ impl Limb {
     pub const ONE: Limb = Limb(REPR_ONE);
     /// A `Limb` with all bits set to `1`.
     pub const ONES: Limb = Limb(REPR_ONES);
+    /// A `Limb` with only the sign bit set.
+    pub const SIGN_BIT: Limb = Limb(REPR_SIGN_BIT);
 
     /// Returns the value of the internal representation.
     #[inline]
@@ -42,11 +54,22 @@ impl Limb {
         self.0 as LimbReprSigned
     }
 
-    /// Returns the value of the internal representation in native-endian.
+    /// Returns `true` if the sign bit of the limb is set.
+    #[inline]
+    pub fn is_negative(self) -> bool {
+        self.repr_signed() < 0
+    }
+
+    /// Returns a limb with every bit set to the sign bit of `self`.
     ///
-    /// This assumes that the limb is currently in little-endian.
-    pub fn repr_ne(self) -> LimbRepr {
-        LimbRepr::from_le(self.repr())
+    /// This is the limb used to sign extend a two's complement value.
+    #[inline]
+    pub fn sign_mask(self) -> Limb {
+        if self.is_negative() {
+            Limb::ONES
+        } else {
+            Limb::ZERO
+        }
     }
 
     /// Calculates `self` + `other`.
@@ -71,6 +94,31 @@ impl Limb {
         (Limb(val), carry)
     }
 
+    /// Calculates the full product of `self` * `other`.
+    ///
+    /// Returns a tuple of the high and low limbs of the product.
+    #[inline]
+    pub fn mul_hilo(self, other: Limb) -> (Limb, Limb) {
+        let prod = (self.repr() as DoubleLimbRepr) * (other.repr() as DoubleLimbRepr);
+        (
+            Limb((prod >> Limb::BITS) as LimbRepr),
+            Limb(prod as LimbRepr),
+        )
+    }
+
+    /// Divides the double limb value `high:low` by `self`.
+    ///
+    /// Returns a tuple of the quotient and remainder. The caller must
+    /// guarantee that `high < self`, so that the quotient fits in a limb.
+    #[inline]
+    pub fn div_rem_hilo(self, high: Limb, low: Limb) -> (Limb, Limb) {
+        debug_assert!(high < self, "quotient overflow");
+
+        let d = self.repr() as DoubleLimbRepr;
+        let n = ((high.repr() as DoubleLimbRepr) << Limb::BITS) | (low.repr() as DoubleLimbRepr);
+        (Limb((n / d) as LimbRepr), Limb((n % d) as LimbRepr))
+    }
+
     /// Returns the number of leading zeros in the binary representation of the
     /// limb.
     #[inline]
@@ -86,6 +134,36 @@ impl Limb {
     }
 }
 
+impl core::ops::Not for Limb {
+    type Output = Limb;
+
+    #[inline]
+    fn not(self) -> Limb {
+        Limb(!self.repr())
+    }
+}
+
+macro_rules! impl_bitop {
+    ($($trait:ident, $fn:ident => $op:tt;)*) => {
+        $(
+            impl core::ops::$trait for Limb {
+                type Output = Limb;
+
+                #[inline]
+                fn $fn(self, other: Limb) -> Limb {
+                    Limb(self.repr() $op other.repr())
+                }
+            }
+        )*
+    };
+}
+
+impl_bitop! {
+    BitAnd, bitand => &;
+    BitOr, bitor => |;
+    BitXor, bitxor => ^;
+}
+
 // Delegate formatting.
 macro_rules! impl_fmt {
     ($ty:ty: [$($trait:ident),* $(,)*]) => {
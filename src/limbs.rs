@@ -1,6 +1,9 @@
 // Adapted from ramp limb_ptr.
 // https://github.com/Aatch/ramp/blob/master/src/ll/limb_ptr.rs
 
+// Not every pointer operation is needed by the current algorithms.
+#![allow(dead_code)]
+
 use core::cmp::{Eq, Ord, Ordering, PartialEq, PartialOrd};
 use core::fmt;
 use core::marker::PhantomData;
@@ -39,7 +42,7 @@ macro_rules! impl_limbs {
                 debug_assert!(len.get() > 1, "invalid limbs pointer length 1");
                 $ty {
                     ptr,
-                    bounds: Bounds::new(ptr.as_ptr() as usize, len),
+                    bounds: Bounds::new(ptr.as_ptr(), len),
                     _marker: marker,
                 }
             }
@@ -51,7 +54,7 @@ macro_rules! impl_limbs {
             #[inline]
             pub unsafe fn add(self, count: usize) -> $ty<$lifetime> {
                 debug_assert!(
-                    self.bounds.is_valid_offset(self.as_ptr() as usize, count),
+                    self.bounds.is_valid_offset(self.as_ptr(), count),
                     "invalid offset `{}` from `{:?}`, should be in bounds: {:?}",
                     count, self.ptr, self.bounds,
                 );
@@ -82,7 +85,7 @@ macro_rules! impl_limbs {
         impl<$lifetime> PartialOrd for $ty<$lifetime> {
             #[inline]
             fn partial_cmp(&self, other: &$ty<$lifetime>) -> Option<Ordering> {
-                self.ptr.partial_cmp(&other.ptr)
+                Some(self.cmp(other))
             }
         }
         impl<$lifetime> Ord for $ty<$lifetime> {
@@ -98,7 +101,7 @@ macro_rules! impl_limbs {
             #[inline]
             fn deref(&self) -> &Limb {
                 debug_assert!(
-                    self.bounds.can_deref(self.as_ptr() as usize),
+                    self.bounds.can_deref(self.as_ptr()),
                     "invalid deref of `{:?}`, should be in bounds: {:?}",
                     self.ptr, self.bounds,
                 );
@@ -140,16 +143,10 @@ impl<'a> LimbsMut<'a> {
     pub unsafe fn copy_nonoverlapping(&mut self, src: Limbs, count: NonZeroUsize) {
         // Check source and destination can be dereferenced for the whole range
         // of count.
-        debug_assert!(self.bounds.can_deref(self.as_ptr() as usize));
-        debug_assert!(
-            self.bounds
-                .is_valid_offset(self.as_ptr() as usize, count.get())
-        );
-        debug_assert!(src.bounds.can_deref(src.as_ptr() as usize));
-        debug_assert!(
-            src.bounds
-                .is_valid_offset(src.as_ptr() as usize, count.get())
-        );
+        debug_assert!(self.bounds.can_deref(self.as_ptr()));
+        debug_assert!(self.bounds.is_valid_offset(self.as_ptr(), count.get()));
+        debug_assert!(src.bounds.can_deref(src.as_ptr()));
+        debug_assert!(src.bounds.is_valid_offset(src.as_ptr(), count.get()));
 
         // Check bounds don't overlap.
         debug_assert!(
@@ -166,18 +163,22 @@ impl<'a> LimbsMut<'a> {
     #[inline]
     pub unsafe fn write_bytes(&mut self, val: u8, count: usize) {
         // Check destination can be dereferenced for the whole range of count.
-        debug_assert!(self.bounds.can_deref(self.as_ptr() as usize));
-        debug_assert!(self.bounds.is_valid_offset(self.as_ptr() as usize, count));
+        debug_assert!(self.bounds.can_deref(self.as_ptr()));
+        debug_assert!(self.bounds.is_valid_offset(self.as_ptr(), count));
 
         ptr::write_bytes(self.as_ptr(), val, count);
     }
 }
 
+// Bounds are kept as pointers into the allocation rather than addresses, so
+// checks only compare pointers and never cast between pointers and integers,
+// keeping to strict provenance.
+
 #[cfg(debug_assertions)]
 #[derive(Clone, Copy)]
 struct Bounds {
-    lo: usize,
-    hi: usize,
+    lo: *const Limb,
+    hi: *const Limb,
 }
 
 #[cfg(not(debug_assertions))]
@@ -188,29 +189,29 @@ struct Bounds;
 
 #[cfg(debug_assertions)]
 impl Bounds {
-    const fn new(ptr: usize, len: NonZeroUsize) -> Bounds {
+    fn new(ptr: *const Limb, len: NonZeroUsize) -> Bounds {
         Bounds {
             lo: ptr,
-            hi: ptr + (len.get() * Limb::SIZE),
+            hi: ptr.wrapping_add(len.get()),
         }
     }
 
-    const fn can_deref(self, ptr: usize) -> bool {
+    fn can_deref(self, ptr: *const Limb) -> bool {
         // Cannot deref at the limit.
         self.lo <= ptr && ptr < self.hi
     }
 
-    const fn is_valid_offset(self, ptr: usize, count: usize) -> bool {
-        let bytes = count * Limb::SIZE;
-        // When using `add` a pointer cannot rely on wrapping.
-        match ptr.checked_add(bytes) {
-            // An offset is still valid at the limit, but cannot deref.
-            Some(offset_ptr) => self.lo <= offset_ptr && offset_ptr <= self.hi,
-            None => false,
+    fn is_valid_offset(self, ptr: *const Limb, count: usize) -> bool {
+        if !(self.lo <= ptr && ptr <= self.hi) {
+            return false;
         }
+        // SAFETY: `ptr` and `hi` are in the same allocation, with `ptr <= hi`.
+        let remaining = unsafe { self.hi.offset_from(ptr) } as usize;
+        // An offset is still valid at the limit, but cannot deref.
+        count <= remaining
     }
 
-    const fn is_nonoverlapping(self, other: Bounds) -> bool {
+    fn is_nonoverlapping(self, other: Bounds) -> bool {
         self.hi < other.lo || self.lo > other.hi
     }
 }
@@ -220,17 +221,17 @@ impl Bounds {
 #[cfg(not(debug_assertions))]
 impl Bounds {
     #[inline(always)]
-    const fn new(_ptr: usize, _len: NonZeroUsize) -> Bounds {
+    const fn new(_ptr: *const Limb, _len: NonZeroUsize) -> Bounds {
         Bounds
     }
 
     #[inline(always)]
-    const fn can_deref(self, _ptr: usize) -> bool {
+    const fn can_deref(self, _ptr: *const Limb) -> bool {
         true
     }
 
     #[inline(always)]
-    const fn is_valid_offset(self, _ptr: usize, _offset: usize) -> bool {
+    const fn is_valid_offset(self, _ptr: *const Limb, _offset: usize) -> bool {
         true
     }
 
@@ -244,8 +245,8 @@ impl fmt::Debug for Bounds {
     #[cfg(debug_assertions)]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut bounds = f.debug_struct("Bounds");
-        bounds.field("lo", &format_args!("{:#x}", self.lo));
-        bounds.field("hi", &format_args!("{:#x}", self.hi));
+        bounds.field("lo", &self.lo);
+        bounds.field("hi", &self.hi);
         bounds.finish()
     }
 
@@ -0,0 +1,249 @@
+use crate::limb::{Limb, LimbRepr};
+
+/// Adds `x` and `y` with an incoming carry, returning the sum and carry out.
+#[inline(always)]
+fn add_carry(x: Limb, y: Limb, carry: bool) -> (Limb, bool) {
+    let (sum, c1) = x.add_overflow(y);
+    let (sum, c2) = sum.add_overflow(Limb(carry as LimbRepr));
+    (sum, c1 | c2)
+}
+
+/// Subtracts `y` from `x` with an incoming borrow, returning the difference and
+/// borrow out.
+#[inline(always)]
+fn sub_borrow(x: Limb, y: Limb, borrow: bool) -> (Limb, bool) {
+    let (diff, b1) = x.sub_overflow(y);
+    let (diff, b2) = diff.sub_overflow(Limb(borrow as LimbRepr));
+    (diff, b1 | b2)
+}
+
+/// Adds the single limb `y` to `w` in place.
+///
+/// Returns the carry out of the most significant limb.
+#[inline]
+pub fn add_1_assign(w: &mut [Limb], y: Limb) -> Limb {
+    let mut carry = y;
+    for limb in w.iter_mut() {
+        if carry == Limb::ZERO {
+            break;
+        }
+        let (sum, c) = limb.add_overflow(carry);
+        *limb = sum;
+        carry = Limb(c as LimbRepr);
+    }
+    carry
+}
+
+/// Subtracts the single limb `y` from `w` in place.
+///
+/// Returns the borrow out of the most significant limb.
+#[inline]
+pub fn sub_1_assign(w: &mut [Limb], y: Limb) -> Limb {
+    let mut borrow = y;
+    for limb in w.iter_mut() {
+        if borrow == Limb::ZERO {
+            break;
+        }
+        let (diff, b) = limb.sub_overflow(borrow);
+        *limb = diff;
+        borrow = Limb(b as LimbRepr);
+    }
+    borrow
+}
+
+/// Adds `y` to `w` in place, where both slices have the same length.
+///
+/// Returns the carry out of the most significant limb.
+pub fn add_n_assign(w: &mut [Limb], y: &[Limb]) -> Limb {
+    debug_assert!(w.len() == y.len());
+
+    let mut carry = false;
+    for (w, &y) in w.iter_mut().zip(y) {
+        let (sum, c) = add_carry(*w, y, carry);
+        *w = sum;
+        carry = c;
+    }
+    Limb(carry as LimbRepr)
+}
+
+/// Subtracts `y` from `w` in place, where both slices have the same length.
+///
+/// Returns the borrow out of the most significant limb.
+pub fn sub_n_assign(w: &mut [Limb], y: &[Limb]) -> Limb {
+    debug_assert!(w.len() == y.len());
+
+    let mut borrow = false;
+    for (w, &y) in w.iter_mut().zip(y) {
+        let (diff, b) = sub_borrow(*w, y, borrow);
+        *w = diff;
+        borrow = b;
+    }
+    Limb(borrow as LimbRepr)
+}
+
+/// Writes the two's complement sum `x + y` to `w`, sign extending both
+/// operands to the length of `w`.
+///
+/// The result wraps if it does not fit in `w`.
+pub fn add_signed(w: &mut [Limb], x: &[Limb], y: &[Limb]) {
+    debug_assert!(w.len() >= x.len() && w.len() >= y.len());
+
+    let x_sign = x[x.len() - 1].sign_mask();
+    let y_sign = y[y.len() - 1].sign_mask();
+
+    let mut carry = false;
+    for (i, w) in w.iter_mut().enumerate() {
+        let x = x.get(i).copied().unwrap_or(x_sign);
+        let y = y.get(i).copied().unwrap_or(y_sign);
+        let (sum, c) = add_carry(x, y, carry);
+        *w = sum;
+        carry = c;
+    }
+}
+
+/// Adds the two's complement value `y` to `w` in place, sign extending `y` to
+/// the length of `w`.
+///
+/// The result wraps if it does not fit in `w`.
+pub fn add_signed_assign(w: &mut [Limb], y: &[Limb]) {
+    debug_assert!(w.len() >= y.len());
+
+    let y_sign = y[y.len() - 1].sign_mask();
+
+    let mut carry = false;
+    for (i, w) in w.iter_mut().enumerate() {
+        let y = y.get(i).copied().unwrap_or(y_sign);
+        let (sum, c) = add_carry(*w, y, carry);
+        *w = sum;
+        carry = c;
+    }
+}
+
+/// Subtracts the two's complement value `y` from `w` in place, sign extending
+/// `y` to the length of `w`.
+///
+/// The result wraps if it does not fit in `w`.
+pub fn sub_signed_assign(w: &mut [Limb], y: &[Limb]) {
+    debug_assert!(w.len() >= y.len());
+
+    let y_sign = y[y.len() - 1].sign_mask();
+
+    let mut borrow = false;
+    for (i, w) in w.iter_mut().enumerate() {
+        let y = y.get(i).copied().unwrap_or(y_sign);
+        let (diff, b) = sub_borrow(*w, y, borrow);
+        *w = diff;
+        borrow = b;
+    }
+}
+
+/// Writes the two's complement difference `x - y` to `w`, sign extending both
+/// operands to the length of `w`.
+///
+/// The result wraps if it does not fit in `w`.
+pub fn sub_signed(w: &mut [Limb], x: &[Limb], y: &[Limb]) {
+    debug_assert!(w.len() >= x.len() && w.len() >= y.len());
+
+    let x_sign = x[x.len() - 1].sign_mask();
+    let y_sign = y[y.len() - 1].sign_mask();
+
+    let mut borrow = false;
+    for (i, w) in w.iter_mut().enumerate() {
+        let x = x.get(i).copied().unwrap_or(x_sign);
+        let y = y.get(i).copied().unwrap_or(y_sign);
+        let (diff, b) = sub_borrow(x, y, borrow);
+        *w = diff;
+        borrow = b;
+    }
+}
+
+/// Proof harnesses for [Kani](https://github.com/model-checking/kani), run
+/// with `cargo kani`.
+///
+/// Each harness checks a routine on every value of a symbolic length of up to
+/// two limbs against the same arithmetic on `u128`, which also proves that no
+/// out of bounds access can occur.
+#[cfg(kani)]
+mod verification {
+    use super::*;
+
+    const MAX_LEN: usize = 2;
+
+    /// Returns a symbolic slice of up to `MAX_LEN` limbs.
+    fn any_limbs(buf: &mut [Limb; MAX_LEN]) -> &mut [Limb] {
+        let len: usize = kani::any();
+        kani::assume(len <= MAX_LEN);
+        for limb in buf.iter_mut() {
+            *limb = Limb(kani::any());
+        }
+        &mut buf[..len]
+    }
+
+    /// Returns the unsigned value of `x`.
+    fn value(x: &[Limb]) -> u128 {
+        x.iter()
+            .rev()
+            .fold(0, |acc, limb| (acc << Limb::BITS) | limb.0 as u128)
+    }
+
+    /// Splits `x` at `len` limbs into the low limbs and the bit above them.
+    fn split(x: u128, overflow: bool, len: usize) -> (u128, u128) {
+        let bits = len * Limb::BITS;
+        if bits == 128 {
+            (x, overflow as u128)
+        } else {
+            (x & ((1 << bits) - 1), x >> bits)
+        }
+    }
+
+    #[kani::proof]
+    #[kani::unwind(3)]
+    fn add_n_assign_matches_u128() {
+        let (mut w_buf, mut y_buf) = ([Limb::ZERO; MAX_LEN], [Limb::ZERO; MAX_LEN]);
+        let w = any_limbs(&mut w_buf);
+        let len = w.len();
+        let y = &any_limbs(&mut y_buf)[..];
+        kani::assume(y.len() == len);
+
+        let (sum, overflow) = value(w).overflowing_add(value(y));
+        let carry = add_n_assign(w, y);
+
+        let (low, high) = split(sum, overflow, len);
+        assert_eq!(value(w), low);
+        assert_eq!(carry.0 as u128, high);
+    }
+
+    #[kani::proof]
+    #[kani::unwind(3)]
+    fn sub_n_assign_matches_u128() {
+        let (mut w_buf, mut y_buf) = ([Limb::ZERO; MAX_LEN], [Limb::ZERO; MAX_LEN]);
+        let w = any_limbs(&mut w_buf);
+        let len = w.len();
+        let y = &any_limbs(&mut y_buf)[..];
+        kani::assume(y.len() == len);
+
+        let (x, y_value) = (value(w), value(y));
+        let borrow = sub_n_assign(w, y);
+
+        let (low, _) = split(x.wrapping_sub(y_value), false, len);
+        assert_eq!(value(w), low);
+        assert_eq!(borrow.0 != 0, x < y_value);
+    }
+
+    #[kani::proof]
+    #[kani::unwind(3)]
+    fn add_1_assign_matches_u128() {
+        let mut w_buf = [Limb::ZERO; MAX_LEN];
+        let w = any_limbs(&mut w_buf);
+        let len = w.len();
+        let y = Limb(kani::any());
+
+        let (sum, overflow) = value(w).overflowing_add(y.0 as u128);
+        let carry = add_1_assign(w, y);
+
+        // With no limbs to add to, the whole of `y` is carried out.
+        let (low, high) = split(sum, overflow, len);
+        assert_eq!(value(w), low);
+        assert_eq!(carry.0 as u128, high);
+    }
+}
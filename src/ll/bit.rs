@@ -0,0 +1,155 @@
+use crate::limb::{Limb, LimbRepr};
+use crate::ll::add_1_assign;
+
+cfg_if::cfg_if! {
+    if #[cfg(all(feature = "simd", target_pointer_width = "64"))] {
+        /// A vector of limbs, processed by a single instruction where the
+        /// target supports it.
+        type Vector = wide::u64x4;
+    } else if #[cfg(all(feature = "simd", target_pointer_width = "32"))] {
+        /// A vector of limbs, processed by a single instruction where the
+        /// target supports it.
+        type Vector = wide::u32x8;
+    }
+}
+
+/// The number of limbs in a vector.
+#[cfg(feature = "simd")]
+const LANES: usize = core::mem::size_of::<Vector>() / core::mem::size_of::<Limb>();
+
+/// Loads a vector from the first `LANES` limbs of `x`.
+#[cfg(feature = "simd")]
+#[inline(always)]
+fn load(x: &[Limb]) -> Vector {
+    let mut lanes = [0; LANES];
+    for (lane, limb) in lanes.iter_mut().zip(x) {
+        *lane = limb.repr();
+    }
+    Vector::new(lanes)
+}
+
+/// Stores the vector `v` to the first `LANES` limbs of `w`.
+#[cfg(feature = "simd")]
+#[inline(always)]
+fn store(w: &mut [Limb], v: Vector) {
+    for (limb, lane) in w.iter_mut().zip(v.to_array().iter()) {
+        *limb = Limb(*lane);
+    }
+}
+
+/// Inverts every bit of `w` in place.
+#[inline]
+pub fn not_assign(w: &mut [Limb]) {
+    #[cfg(feature = "simd")]
+    let w = {
+        let mut chunks = w.chunks_exact_mut(LANES);
+        for chunk in &mut chunks {
+            store(chunk, !load(chunk));
+        }
+        chunks.into_remainder()
+    };
+
+    for limb in w.iter_mut() {
+        *limb = !*limb;
+    }
+}
+
+// Defines a function writing a bitwise operation of each pair of limbs of two
+// slices to a third, vector by vector when the `simd` feature is enabled.
+macro_rules! bitop_n {
+    ($($(#[$attr:meta])* $name:ident => $op:tt;)*) => {
+        $(
+            $(#[$attr])*
+            #[inline]
+            pub fn $name(w: &mut [Limb], x: &[Limb], y: &[Limb]) {
+                debug_assert!(w.len() == x.len() && w.len() == y.len());
+
+                #[cfg(feature = "simd")]
+                let (w, x, y) = {
+                    let split = w.len() - w.len() % LANES;
+                    let (w, w_tail) = w.split_at_mut(split);
+                    let (x, x_tail) = x.split_at(split);
+                    let (y, y_tail) = y.split_at(split);
+                    let chunks = w
+                        .chunks_exact_mut(LANES)
+                        .zip(x.chunks_exact(LANES).zip(y.chunks_exact(LANES)));
+                    for (w, (x, y)) in chunks {
+                        store(w, load(x) $op load(y));
+                    }
+                    (w_tail, x_tail, y_tail)
+                };
+
+                for (w, (x, y)) in w.iter_mut().zip(x.iter().zip(y)) {
+                    *w = *x $op *y;
+                }
+            }
+        )*
+    };
+}
+
+bitop_n! {
+    /// Writes the bitwise and of `x` and `y` to `w`, where all three slices
+    /// have the same length.
+    and_n => &;
+    /// Writes the bitwise or of `x` and `y` to `w`, where all three slices
+    /// have the same length.
+    or_n => |;
+    /// Writes the bitwise xor of `x` and `y` to `w`, where all three slices
+    /// have the same length.
+    xor_n => ^;
+}
+
+/// Negates the two's complement value of `w` in place, wrapping on overflow.
+#[inline]
+pub fn neg_assign(w: &mut [Limb]) {
+    not_assign(w);
+    add_1_assign(w, Limb::ONE);
+}
+
+/// Writes `x` shifted left by `cnt` bits to `w`, where both slices have the
+/// same length and `cnt < Limb::BITS`.
+///
+/// Returns the bits shifted out of the most significant limb, in the low bits
+/// of the returned limb.
+pub fn shl(w: &mut [Limb], x: &[Limb], cnt: u32) -> Limb {
+    debug_assert!(w.len() == x.len());
+    debug_assert!((cnt as usize) < Limb::BITS);
+
+    if cnt == 0 {
+        w.copy_from_slice(x);
+        return Limb::ZERO;
+    }
+
+    let rcnt = Limb::BITS as u32 - cnt;
+
+    let mut high: LimbRepr = 0;
+    for (w, x) in w.iter_mut().zip(x) {
+        *w = Limb((x.repr() << cnt) | high);
+        high = x.repr() >> rcnt;
+    }
+    Limb(high)
+}
+
+/// Writes `x` shifted right by `cnt` bits to `w`, where both slices have the
+/// same length and `cnt < Limb::BITS`.
+///
+/// Returns the bits shifted out of the least significant limb, in the high
+/// bits of the returned limb.
+pub fn shr(w: &mut [Limb], x: &[Limb], cnt: u32) -> Limb {
+    debug_assert!(w.len() == x.len());
+    debug_assert!((cnt as usize) < Limb::BITS);
+
+    if cnt == 0 {
+        w.copy_from_slice(x);
+        return Limb::ZERO;
+    }
+
+    let lcnt = Limb::BITS as u32 - cnt;
+
+    let mut low: LimbRepr = 0;
+    for (w, x) in w.iter_mut().zip(x).rev() {
+        *w = Limb((x.repr() >> cnt) | low);
+        low = x.repr() << lcnt;
+    }
+    Limb(low)
+}
@@ -0,0 +1,318 @@
+use crate::limb::{DoubleLimbRepr, Limb, LimbRepr};
+use crate::ll::{
+    add_1_assign, add_n_assign, shl, shr, sub_1_assign, sub_n_assign, submul_1, try_mul,
+};
+use crate::mem;
+use crate::oom::AllocError;
+use crate::tuning;
+
+/// Divides `x` by the single limb `d`, writing the quotient to `q`, where `q`
+/// and `x` have the same length.
+///
+/// Returns the remainder.
+pub fn divrem_1(q: &mut [Limb], x: &[Limb], d: Limb) -> Limb {
+    debug_assert!(q.len() == x.len());
+    debug_assert!(d != Limb::ZERO, "division by zero");
+
+    let mut r = Limb::ZERO;
+    for (q, &x) in q.iter_mut().zip(x).rev() {
+        let (qi, ri) = d.div_rem_hilo(r, x);
+        *q = qi;
+        r = ri;
+    }
+    r
+}
+
+/// Divides `w` by the single limb `d` in place, leaving the quotient in `w`.
+///
+/// Returns the remainder.
+pub fn divrem_1_assign(w: &mut [Limb], d: Limb) -> Limb {
+    debug_assert!(d != Limb::ZERO, "division by zero");
+
+    let mut r = Limb::ZERO;
+    for w in w.iter_mut().rev() {
+        let (qi, ri) = d.div_rem_hilo(r, *w);
+        *w = qi;
+        r = ri;
+    }
+    r
+}
+
+/// Divides `x` by `y`, writing the quotient to `q` and the remainder to `r`.
+///
+/// The most significant limb of `y` must be non-zero, `x` must be at least as
+/// long as `y`, `q.len() == x.len() - y.len() + 1` and `r.len() == y.len()`.
+pub fn divrem(q: &mut [Limb], r: &mut [Limb], x: &[Limb], y: &[Limb]) {
+    try_divrem(q, r, x, y).unwrap_or_else(|err| err.handle())
+}
+
+/// Divides `x` by `y` like `divrem`, but returns an error if the temporary
+/// limbs cannot be allocated, leaving `q` and `r` unspecified.
+pub fn try_divrem(
+    q: &mut [Limb],
+    r: &mut [Limb],
+    x: &[Limb],
+    y: &[Limb],
+) -> Result<(), AllocError> {
+    debug_assert!(!y.is_empty() && y[y.len() - 1] != Limb::ZERO);
+    debug_assert!(x.len() >= y.len());
+    debug_assert!(q.len() == x.len() - y.len() + 1 && r.len() == y.len());
+
+    if y.len() == 1 {
+        r[0] = divrem_1(q, x, y[0]);
+        return Ok(());
+    }
+
+    let n = y.len();
+    let threshold = tuning::dc_div_threshold();
+    // Divide and conquer division halves the divisor until it is below the
+    // threshold, so it is padded with low zero limbs to a length that stays
+    // even until then. Padding both operands leaves the quotient unchanged and
+    // pads the remainder.
+    let pad = if n >= threshold {
+        let mut len = n;
+        let mut halvings = 0;
+        while len >= threshold {
+            len = (len + 1) / 2;
+            halvings += 1;
+        }
+        (len << halvings) - n
+    } else {
+        0
+    };
+
+    // Normalize so that the high bit of the divisor is set, which guarantees
+    // the estimated quotient limbs are off by at most 2.
+    let shift = y[n - 1].leading_zeros() as u32;
+
+    let mut yn = mem::try_scratch(pad + n)?;
+    shl(&mut yn[pad..], y, shift);
+
+    let mut xn = mem::try_scratch(pad + x.len() + 1)?;
+    xn[pad + x.len()] = shl(&mut xn[pad..pad + x.len()], x, shift);
+
+    if n >= threshold {
+        divrem_dc(q, &mut xn, &yn)?;
+    } else {
+        divrem_basecase(q, &mut xn, &yn);
+    }
+
+    // Unnormalize the remainder.
+    shr(r, &xn[pad..pad + n], shift);
+    Ok(())
+}
+
+/// Divides `xn` by the normalized `yn` by Knuth's Algorithm D, from The Art of
+/// Computer Programming, Vol. 2, Section 4.3.1, writing the quotient to `q` and
+/// leaving the remainder in the low limbs of `xn` with its high limbs zeroed.
+///
+/// The high bit of `yn` must be set, `yn` must be at least two limbs long,
+/// `q.len() == xn.len() - yn.len()` and the high `yn.len()` limbs of `xn` must
+/// be less than `yn`.
+fn divrem_basecase(q: &mut [Limb], xn: &mut [Limb], yn: &[Limb]) {
+    const BITS: u32 = Limb::BITS as u32;
+    const BASE: DoubleLimbRepr = 1 << BITS;
+
+    let n = yn.len();
+    let m = xn.len() - n - 1;
+    debug_assert!(n >= 2 && yn[n - 1].is_negative());
+    debug_assert!(q.len() == m + 1);
+
+    let y_hi = yn[n - 1].repr() as DoubleLimbRepr;
+    let y_lo = yn[n - 2].repr() as DoubleLimbRepr;
+
+    for j in (0..=m).rev() {
+        let x_hi =
+            ((xn[j + n].repr() as DoubleLimbRepr) << BITS) | xn[j + n - 1].repr() as DoubleLimbRepr;
+        let x_lo = xn[j + n - 2].repr() as DoubleLimbRepr;
+
+        // Estimate the quotient limb from the top limbs.
+        let mut qhat = x_hi / y_hi;
+        let mut rhat = x_hi % y_hi;
+        while qhat >= BASE || qhat * y_lo > ((rhat << BITS) | x_lo) {
+            qhat -= 1;
+            rhat += y_hi;
+            if rhat >= BASE {
+                break;
+            }
+        }
+
+        // Multiply and subtract.
+        let qhat = Limb(qhat as LimbRepr);
+        let borrow = submul_1(&mut xn[j..j + n], yn, qhat);
+        let (top, negative) = xn[j + n].sub_overflow(borrow);
+        xn[j + n] = top;
+
+        // The estimate was one too large, so add back.
+        q[j] = if negative {
+            let carry = add_n_assign(&mut xn[j..j + n], yn);
+            xn[j + n] = xn[j + n].add_overflow(carry).0;
+            Limb(qhat.repr() - 1)
+        } else {
+            qhat
+        };
+    }
+}
+
+/// Divides `xn` by the normalized `yn` like `divrem_basecase`, but by
+/// Burnikel and Ziegler's recursive division, where `yn.len()` halves evenly
+/// until it is below the threshold.
+///
+/// The dividend is divided a block of `y.len()` limbs at a time, from the most
+/// significant block, with the remainder of each block carried into the next.
+fn divrem_dc(q: &mut [Limb], xn: &mut [Limb], yn: &[Limb]) -> Result<(), AllocError> {
+    let n = yn.len();
+    let blocks = (xn.len() + n - 1) / n;
+
+    // The dividend is padded to whole blocks, with an extra zero block above
+    // them as the initial remainder, unless the high block is already less
+    // than `y` and can be the initial remainder itself.
+    let mut a = mem::try_scratch((blocks + 1) * n)?;
+    a[..xn.len()].copy_from_slice(xn);
+    let mut quot = mem::try_scratch(blocks * n)?;
+    let high = if lt(&a[(blocks - 1) * n..blocks * n], yn) {
+        blocks - 1
+    } else {
+        blocks
+    };
+    for i in (0..high).rev() {
+        div_2n_1n(
+            &mut quot[i * n..(i + 1) * n],
+            &mut a[i * n..(i + 2) * n],
+            yn,
+        )?;
+    }
+
+    debug_assert!(quot[q.len()..].iter().all(|&limb| limb == Limb::ZERO));
+    q.copy_from_slice(&quot[..q.len()]);
+    xn[..n].copy_from_slice(&a[..n]);
+    Ok(())
+}
+
+/// Divides the `2n` limbs of `a` by the `n` limbs of the normalized `y`,
+/// writing the quotient to `q` and leaving the remainder in the low `n` limbs
+/// of `a` with its high limbs zeroed, where the high half of `a` is less than
+/// `y`.
+fn div_2n_1n(q: &mut [Limb], a: &mut [Limb], y: &[Limb]) -> Result<(), AllocError> {
+    let n = y.len();
+    if n % 2 == 1 || n < tuning::dc_div_threshold() {
+        divrem_basecase(q, a, y);
+        return Ok(());
+    }
+
+    // Divide the high three halves, then the remainder with the low half.
+    let h = n / 2;
+    div_3n_2n(&mut q[h..], &mut a[h..], y)?;
+    div_3n_2n(&mut q[..h], &mut a[..3 * h], y)
+}
+
+/// Divides the `3h` limbs of `a` by the `2h` limbs of the normalized `y`,
+/// writing the quotient to `q` and leaving the remainder in the low `2h` limbs
+/// of `a` with its high limbs zeroed, where the high `2h` limbs of `a` are
+/// less than `y`.
+fn div_3n_2n(q: &mut [Limb], a: &mut [Limb], y: &[Limb]) -> Result<(), AllocError> {
+    let h = y.len() / 2;
+    let (y0, y1) = y.split_at(h);
+
+    // Estimate the quotient from the high limbs of `a` and `y`, which leaves
+    // `a` holding `r1 * B^h + a0` for the remainder `r1` of that division.
+    if lt(&a[2 * h..], y1) {
+        div_2n_1n(q, &mut a[h..], y1)?;
+    } else {
+        // The high half of `a` equals `y1`, so the estimate is `B^h - 1`, with
+        // the remainder `a1 * B^h + a2 - (B^h - 1) * y1 = a2 + y1`.
+        for limb in q.iter_mut() {
+            *limb = Limb::ONES;
+        }
+        sub_n_assign(&mut a[2 * h..], y1);
+        let carry = add_n_assign(&mut a[h..2 * h], y1);
+        a[2 * h] = carry;
+    }
+
+    // Subtract `q * y0`, correcting the estimate, which is at most 2 too large,
+    // while the remainder is negative.
+    let mut d = mem::try_scratch(2 * h)?;
+    try_mul(&mut d, q, y0)?;
+    let borrow = sub_n_assign(&mut a[..2 * h], &d);
+    let mut negative = sub_1_assign(&mut a[2 * h..], borrow) != Limb::ZERO;
+    while negative {
+        sub_1_assign(q, Limb::ONE);
+        let carry = add_n_assign(&mut a[..2 * h], y);
+        negative = add_1_assign(&mut a[2 * h..], carry) == Limb::ZERO;
+    }
+    debug_assert!(a[2 * h..].iter().all(|&limb| limb == Limb::ZERO));
+    Ok(())
+}
+
+/// Returns whether the magnitude `x` is less than `y`, where both have the
+/// same length.
+fn lt(x: &[Limb], y: &[Limb]) -> bool {
+    x.iter().rev().lt(y.iter().rev())
+}
+
+/// Returns the inverse of the odd limb `d` modulo `2^BITS`.
+pub fn inverse_1(d: Limb) -> Limb {
+    debug_assert!(d.repr() & 1 == 1);
+
+    let d = d.repr();
+    // Every odd `d` is its own inverse modulo `2^3`, and each Newton iteration
+    // doubles the number of correct low bits.
+    let mut inv = d;
+    let mut bits = 3;
+    while bits < Limb::BITS {
+        inv = inv.wrapping_mul((2 as LimbRepr).wrapping_sub(d.wrapping_mul(inv)));
+        bits *= 2;
+    }
+    Limb(inv)
+}
+
+/// Divides `x` by `y` in place, writing the quotient to `q`, where `x` is known
+/// to be an exact multiple of `y`.
+///
+/// The least significant limb of `y` must be odd, `x` must be at least as long
+/// as `y` and `q.len() == x.len() - y.len() + 1`. The contents of `x` are
+/// clobbered.
+///
+/// This is Jebelean's exact division, which finds the quotient from the least
+/// significant limb up. Each quotient limb is the product of a limb of `x` with
+/// the inverse of `y` modulo `2^BITS`, so no quotient estimation or correction
+/// is needed.
+pub fn divexact(q: &mut [Limb], x: &mut [Limb], y: &[Limb]) {
+    debug_assert!(!y.is_empty() && y[0].repr() & 1 == 1);
+    debug_assert!(x.len() >= y.len());
+    debug_assert!(q.len() == x.len() - y.len() + 1);
+
+    let inv = inverse_1(y[0]);
+    let len = x.len();
+    for i in 0..q.len() {
+        let qi = Limb(x[i].repr().wrapping_mul(inv.repr()));
+        q[i] = qi;
+
+        // Subtract `qi * y` from `x`, which clears limb `i`. The limbs past the
+        // end of `x` are not needed, since the quotient is known to fit in `q`.
+        let end = len.min(i + y.len());
+        let borrow = submul_1(&mut x[i..end], &y[..end - i], qi);
+        sub_1_assign(&mut x[end..], borrow);
+    }
+}
+
+/// Returns `true` if `x` is divisible by the odd limb `d`.
+///
+/// The limbs are reduced from the least significant limb up by multiplying
+/// with the inverse of `d`, in the manner of GMP's `mpn_modexact_1_odd`, which
+/// avoids any division instructions.
+pub fn divisible_1_odd(x: &[Limb], d: Limb) -> bool {
+    debug_assert!(d.repr() & 1 == 1);
+
+    let inv = inverse_1(d);
+    // The carry is always at most `d`, and is congruent to zero modulo `d` at
+    // the end exactly when `d` divides `x`.
+    let mut c = Limb::ZERO;
+    for &limb in x {
+        let (s, borrow) = limb.sub_overflow(c);
+        let q = Limb(s.repr().wrapping_mul(inv.repr()));
+        let (hi, _) = q.mul_hilo(d);
+        c = Limb(hi.repr() + borrow as LimbRepr);
+    }
+    c == Limb::ZERO || c == d
+}
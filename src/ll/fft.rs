@@ -0,0 +1,198 @@
+use crate::limb::{Limb, LimbRepr};
+use crate::mem;
+use crate::oom::AllocError;
+
+/// The prime `2^64 - 2^32 + 1`, whose multiplicative group has elements of
+/// every order up to `2^32`, so it has the roots of unity for transforms of up
+/// to `2^32` points.
+const P: u64 = 0xffff_ffff_0000_0001;
+
+/// `2^64 mod P`.
+const EPSILON: u64 = 0xffff_ffff;
+
+/// A generator of the multiplicative group modulo `P`.
+const GENERATOR: u64 = 7;
+
+/// The bits of each coefficient the operands are split into.
+///
+/// A coefficient of the product is a sum of at most `2^31` products of two
+/// coefficients, one from each operand, so with 16 bit coefficients it is
+/// always less than `P`.
+const DIGIT_BITS: usize = 16;
+
+const DIGITS_PER_LIMB: usize = Limb::BITS / DIGIT_BITS;
+
+/// The largest transform, limited by the roots of unity modulo `P`.
+const MAX_POINTS: u64 = 1 << 32;
+
+/// Returns whether the product of operands with `len` limbs in total can be
+/// found by `mul_fft`.
+pub fn fits(len: usize) -> bool {
+    (len as u64).saturating_mul(DIGITS_PER_LIMB as u64) <= MAX_POINTS
+}
+
+/// Writes the product `x * y` to `w` by FFT multiplication, where
+/// `w.len() == x.len() + y.len()` and `fits(w.len())`.
+///
+/// The operands are split into 16 bit coefficients, and their cyclic
+/// convolution is found by a number theoretic transform modulo the prime `P`,
+/// which is exact since no coefficient of the product reaches `P`.
+pub fn mul_fft(w: &mut [Limb], x: &[Limb], y: &[Limb]) -> Result<(), AllocError> {
+    debug_assert!(w.len() == x.len() + y.len());
+    debug_assert!(fits(w.len()));
+
+    let points = (w.len() * DIGITS_PER_LIMB).next_power_of_two();
+    let mut a = mem::try_scratch_u64(points)?;
+    let mut b = mem::try_scratch_u64(points)?;
+    split(&mut a, x);
+    split(&mut b, y);
+
+    let mut roots = mem::try_scratch_u64(points / 2)?;
+    fill_roots(&mut roots, pow(GENERATOR, (P - 1) / points as u64));
+    transform(&mut a, &roots);
+    transform(&mut b, &roots);
+    for (a, &b) in a.iter_mut().zip(&b) {
+        *a = mul(*a, b);
+    }
+    // The inverse transform is the transform by the inverse root.
+    fill_roots(
+        &mut roots,
+        pow(GENERATOR, (P - 1) - (P - 1) / points as u64),
+    );
+    transform(&mut a, &roots);
+
+    // The inverse transform leaves each coefficient multiplied by the number of
+    // points, and the coefficients overlap, so their carries are propagated.
+    let scale = pow(points as u64, P - 2);
+    let mut coefficients = a.iter().map(|&c| mul(c, scale));
+    let mut carry: u128 = 0;
+    for limb in w.iter_mut() {
+        let mut value: LimbRepr = 0;
+        for j in 0..DIGITS_PER_LIMB {
+            carry += u128::from(coefficients.next().unwrap_or(0));
+            value |= ((carry & 0xffff) as LimbRepr) << (j * DIGIT_BITS);
+            carry >>= DIGIT_BITS;
+        }
+        *limb = Limb(value);
+    }
+    debug_assert!(carry == 0);
+    Ok(())
+}
+
+/// Writes the 16 bit coefficients of `x` to `a`, least significant first.
+fn split(a: &mut [u64], x: &[Limb]) {
+    for (a, limb) in a.chunks_mut(DIGITS_PER_LIMB).zip(x) {
+        for (j, a) in a.iter_mut().enumerate() {
+            *a = u64::from((limb.repr() >> (j * DIGIT_BITS)) as u16);
+        }
+    }
+}
+
+/// Writes the powers `root^j` to `roots`.
+fn fill_roots(roots: &mut [u64], root: u64) {
+    let mut power = 1;
+    for r in roots {
+        *r = power;
+        power = mul(power, root);
+    }
+}
+
+/// Transforms `a` in place by the iterative Cooley-Tukey algorithm, where the
+/// length of `a` is a power of two and `roots` holds the first half of the
+/// powers of a primitive root of unity of that order.
+///
+/// Transforming by the inverse root leaves the values of the inverse transform
+/// multiplied by the length of `a`.
+fn transform(a: &mut [u64], roots: &[u64]) {
+    let n = a.len();
+
+    // Reorder the values by the bit reversal of their indices, so that the
+    // butterflies can work in place.
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            a.swap(i, j);
+        }
+    }
+
+    // The roots of unity of order `len` are every `n / len`th of `roots`.
+    let mut len = 2;
+    while len <= n {
+        let stride = n / len;
+        for chunk in a.chunks_mut(len) {
+            let (lo, hi) = chunk.split_at_mut(len / 2);
+            for ((u, v), &w) in lo.iter_mut().zip(hi).zip(roots.iter().step_by(stride)) {
+                let t = mul(*v, w);
+                *v = sub(*u, t);
+                *u = add(*u, t);
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// Returns `a + b` modulo `P`, where both are reduced.
+#[inline]
+fn add(a: u64, b: u64) -> u64 {
+    let (sum, overflow) = a.overflowing_add(b);
+    if overflow || sum >= P {
+        sum.wrapping_sub(P)
+    } else {
+        sum
+    }
+}
+
+/// Returns `a - b` modulo `P`, where both are reduced.
+#[inline]
+fn sub(a: u64, b: u64) -> u64 {
+    let (diff, borrow) = a.overflowing_sub(b);
+    if borrow { diff.wrapping_add(P) } else { diff }
+}
+
+/// Returns `a * b` modulo `P`, where both are reduced.
+#[inline]
+fn mul(a: u64, b: u64) -> u64 {
+    reduce(u128::from(a) * u128::from(b))
+}
+
+/// Reduces `x` modulo `P`, using `2^64 = 2^32 - 1` and `2^96 = -1`.
+#[inline]
+fn reduce(x: u128) -> u64 {
+    let (lo, hi) = (x as u64, (x >> 64) as u64);
+    let (hi_hi, hi_lo) = (hi >> 32, hi & EPSILON);
+
+    // A borrow wraps by `2^64`, which is corrected by subtracting `EPSILON`,
+    // and cannot borrow again since the difference is then at least `P`.
+    let (mut r, borrow) = lo.overflowing_sub(hi_hi);
+    if borrow {
+        r = r.wrapping_sub(EPSILON);
+    }
+    // Likewise a carry is corrected by adding `EPSILON`, and cannot carry
+    // again since the sum is then less than `hi_lo * EPSILON`.
+    let (sum, carry) = r.overflowing_add(hi_lo * EPSILON);
+    r = if carry {
+        sum.wrapping_add(EPSILON)
+    } else {
+        sum
+    };
+    if r >= P { r - P } else { r }
+}
+
+/// Returns `base^exp` modulo `P`.
+fn pow(mut base: u64, mut exp: u64) -> u64 {
+    let mut acc = 1;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc = mul(acc, base);
+        }
+        base = mul(base, base);
+        exp >>= 1;
+    }
+    acc
+}
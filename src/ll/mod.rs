@@ -0,0 +1,32 @@
+//! Low-level operations on slices of limbs.
+//!
+//! Limbs are stored least significant limb first, and unless otherwise stated
+//! the operations treat the limbs as an unsigned magnitude.
+
+use crate::limb::Limb;
+
+mod addsub;
+mod bit;
+mod div;
+mod fft;
+mod mul;
+
+pub use self::addsub::{
+    add_1_assign, add_n_assign, add_signed, add_signed_assign, sub_1_assign, sub_n_assign,
+    sub_signed, sub_signed_assign,
+};
+pub use self::bit::{and_n, neg_assign, not_assign, or_n, shl, shr, xor_n};
+pub use self::div::{divexact, divisible_1_odd, divrem, divrem_1_assign, inverse_1, try_divrem};
+pub use self::mul::{addmul_1, mul, submul_1, try_mul};
+
+/// Returns the number of limbs in the magnitude `x`, ignoring high zero limbs.
+///
+/// The length of a zero magnitude is `0`.
+#[inline]
+pub fn normalized_len(x: &[Limb]) -> usize {
+    let mut len = x.len();
+    while len > 0 && x[len - 1] == Limb::ZERO {
+        len -= 1;
+    }
+    len
+}
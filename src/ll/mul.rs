@@ -0,0 +1,375 @@
+use core::cmp::Ordering;
+
+use crate::alloc::Vec;
+use crate::limb::{Limb, LimbRepr};
+use crate::ll::fft;
+use crate::ll::{
+    add_1_assign, add_n_assign, divexact, neg_assign, normalized_len, sub_1_assign, sub_n_assign,
+};
+use crate::mem;
+use crate::oom::AllocError;
+use crate::tuning;
+
+/// Writes `x * y` to `w`, where `w` and `x` have the same length.
+///
+/// Returns the high limb of the product.
+pub fn mul_1(w: &mut [Limb], x: &[Limb], y: Limb) -> Limb {
+    debug_assert!(w.len() == x.len());
+
+    #[cfg(feature = "stats")]
+    crate::stats::record_limb_muls(x.len());
+
+    let mut carry = Limb::ZERO;
+    for (w, &x) in w.iter_mut().zip(x) {
+        let (hi, lo) = x.mul_hilo(y);
+        let (lo, c) = lo.add_overflow(carry);
+        *w = lo;
+        carry = Limb(hi.repr() + c as LimbRepr);
+    }
+    carry
+}
+
+/// Adds `x * y` to `w` in place, where `w` and `x` have the same length.
+///
+/// Returns the carry out of the most significant limb.
+pub fn addmul_1(w: &mut [Limb], x: &[Limb], y: Limb) -> Limb {
+    debug_assert!(w.len() == x.len());
+
+    #[cfg(feature = "stats")]
+    crate::stats::record_limb_muls(x.len());
+
+    let mut carry = Limb::ZERO;
+    for (w, &x) in w.iter_mut().zip(x) {
+        let (hi, lo) = x.mul_hilo(y);
+        let (lo, c1) = lo.add_overflow(carry);
+        let (lo, c2) = lo.add_overflow(*w);
+        *w = lo;
+        // The high limb of a product is at most `Limb::MAX - 1`, so adding both
+        // carries cannot overflow.
+        carry = Limb(hi.repr() + c1 as LimbRepr + c2 as LimbRepr);
+    }
+    carry
+}
+
+/// Subtracts `x * y` from `w` in place, where `w` and `x` have the same length.
+///
+/// Returns the borrow out of the most significant limb.
+pub fn submul_1(w: &mut [Limb], x: &[Limb], y: Limb) -> Limb {
+    debug_assert!(w.len() == x.len());
+
+    #[cfg(feature = "stats")]
+    crate::stats::record_limb_muls(x.len());
+
+    let mut borrow = Limb::ZERO;
+    for (w, &x) in w.iter_mut().zip(x) {
+        let (hi, lo) = x.mul_hilo(y);
+        let (lo, b1) = lo.add_overflow(borrow);
+        let (diff, b2) = w.sub_overflow(lo);
+        *w = diff;
+        borrow = Limb(hi.repr() + b1 as LimbRepr + b2 as LimbRepr);
+    }
+    borrow
+}
+
+/// Writes the product `x * y` to `w`, where `w.len() == x.len() + y.len()`.
+///
+/// `w` does not need to be zeroed beforehand.
+pub fn mul(w: &mut [Limb], x: &[Limb], y: &[Limb]) {
+    try_mul(w, x, y).unwrap_or_else(|err| err.handle())
+}
+
+/// Writes the product `x * y` to `w` like `mul`, but returns an error if the
+/// temporary limbs cannot be allocated, leaving `w` unspecified.
+pub fn try_mul(w: &mut [Limb], x: &[Limb], y: &[Limb]) -> Result<(), AllocError> {
+    debug_assert!(w.len() == x.len() + y.len());
+    debug_assert!(!x.is_empty() && !y.is_empty());
+
+    let (x, y) = if x.len() >= y.len() { (x, y) } else { (y, x) };
+
+    if y.len() < tuning::karatsuba_threshold() {
+        mul_basecase(w, x, y);
+        Ok(())
+    } else if 2 * y.len() <= x.len() {
+        mul_unbalanced(w, x, y)
+    } else if y.len() >= tuning::fft_threshold() && fft::fits(w.len()) {
+        fft::mul_fft(w, x, y)
+    } else if y.len() >= tuning::toom_threshold() {
+        mul_toom3(w, x, y)
+    } else {
+        mul_karatsuba(w, x, y)
+    }
+}
+
+/// Writes the product `x * y` to `w` by schoolbook multiplication, where
+/// `x.len() >= y.len()`.
+fn mul_basecase(w: &mut [Limb], x: &[Limb], y: &[Limb]) {
+    let n = x.len();
+    w[n] = mul_1(&mut w[..n], x, y[0]);
+    for (i, &y) in y.iter().enumerate().skip(1) {
+        w[i + n] = addmul_1(&mut w[i..i + n], x, y);
+    }
+}
+
+/// Writes the product `x * y` to `w`, where `x` is at least twice the length of
+/// `y`, by multiplying `y` by chunks of `x` the same length as `y`.
+fn mul_unbalanced(w: &mut [Limb], x: &[Limb], y: &[Limb]) -> Result<(), AllocError> {
+    let (n, m) = (x.len(), y.len());
+    try_mul(&mut w[..2 * m], &x[..m], y)?;
+
+    let mut prod = mem::try_scratch(2 * m)?;
+    let mut i = m;
+    while i < n {
+        let chunk = &x[i..n.min(i + m)];
+        let len = chunk.len();
+        let prod = &mut prod[..len + m];
+        try_mul(prod, chunk, y)?;
+
+        // The low limbs of the product overlap the high limbs of the previous
+        // product, and the rest are new.
+        w[i + m..i + m + len].copy_from_slice(&prod[m..]);
+        let carry = add_n_assign(&mut w[i..i + m], &prod[..m]);
+        add_1_assign(&mut w[i + m..i + m + len], carry);
+
+        i += m;
+    }
+    Ok(())
+}
+
+/// Writes the product `x * y` to `w` by Karatsuba multiplication, where
+/// `x.len() >= y.len() > x.len() / 2`.
+fn mul_karatsuba(w: &mut [Limb], x: &[Limb], y: &[Limb]) -> Result<(), AllocError> {
+    let (n, m) = (x.len(), y.len());
+    // Both high halves are non-empty, since `m > n / 2`.
+    let h = n / 2;
+    let (x0, x1) = x.split_at(h);
+    let (y0, y1) = y.split_at(h);
+
+    // The low and high products are written directly to their places in `w`.
+    let (z0, z2) = w.split_at_mut(2 * h);
+    try_mul(z0, x0, y0)?;
+    try_mul(z2, x1, y1)?;
+
+    // The subtractive form avoids the carries of the additive form, so the
+    // operands of every recursive product are shorter than `x`.
+    //
+    // z1 = z0 + z2 - (x0 - x1) * (y0 - y1)
+    let (dx, x_negative) = abs_diff(x0, x1)?;
+    let (dy, y_negative) = abs_diff(y0, y1)?;
+    let mut d = mem::try_scratch(dx.len() + dy.len())?;
+    try_mul(&mut d, &dx, &dy)?;
+
+    let len = (2 * h).max(n + m - 2 * h).max(d.len()) + 1;
+    let mut z1 = mem::try_scratch(len)?;
+    z1[..2 * h].copy_from_slice(&w[..2 * h]);
+    add_assign(&mut z1, &w[2 * h..]);
+    if x_negative == y_negative {
+        sub_assign(&mut z1, &d);
+    } else {
+        add_assign(&mut z1, &d);
+    }
+
+    // Add the middle product, without its high zero limbs, at an offset of `h`.
+    let z1 = &z1[..normalized_len(&z1)];
+    let carry = add_n_assign(&mut w[h..h + z1.len()], z1);
+    add_1_assign(&mut w[h + z1.len()..n + m], carry);
+    Ok(())
+}
+
+/// Writes the product `x * y` to `w` by Toom-3 multiplication, where
+/// `x.len() >= y.len() > x.len() / 2`.
+///
+/// Both operands are split into three parts of `k` limbs, the polynomials with
+/// those parts as coefficients are evaluated at `0`, `1`, `-1`, `-2` and
+/// infinity, and the five products of the values are interpolated into the
+/// coefficients of the product with Bodrato's sequence.
+fn mul_toom3(w: &mut [Limb], x: &[Limb], y: &[Limb]) -> Result<(), AllocError> {
+    let (n, m) = (x.len(), y.len());
+    let k = (n + 2) / 3;
+
+    // The values at `1`, `-1` and `-2` are less than `5 * B^k` in magnitude,
+    // so they fit in `k + 2` limbs of two's complement, and their products and
+    // every step of the interpolation fit in `2 * k + 2` limbs.
+    let (px, qx) = (toom3_eval(x, k)?, toom3_eval(y, k)?);
+    let len = 2 * k + 2;
+    let mut v = mem::try_scratch(5 * len)?;
+    {
+        let (v0, rest) = v.split_at_mut(len);
+        let (v1, rest) = rest.split_at_mut(len);
+        let (vm1, rest) = rest.split_at_mut(len);
+        let (vm2, vinf) = rest.split_at_mut(len);
+
+        mul_unsigned(v0, &x[..k], &y[..k.min(m)])?;
+        mul_signed(v1, &px[..k + 2], &qx[..k + 2])?;
+        mul_signed(vm1, &px[k + 2..2 * k + 4], &qx[k + 2..2 * k + 4])?;
+        mul_signed(vm2, &px[2 * k + 4..], &qx[2 * k + 4..])?;
+        mul_unsigned(vinf, &x[2 * k..], y.get(2 * k..).unwrap_or(&[]))?;
+
+        // r3 = (v(-2) - v(1)) / 3, left in `vm2`.
+        sub_wrapping(vm2, v1);
+        let mut quot = mem::try_scratch(len)?;
+        divexact(&mut quot, vm2, &[Limb(3)]);
+        vm2.copy_from_slice(&quot);
+        // r1 = (v(1) - v(-1)) / 2, left in `v1`.
+        sub_wrapping(v1, vm1);
+        shr1_signed(v1);
+        // r2 = v(-1) - v(0), left in `vm1`.
+        sub_wrapping(vm1, v0);
+        // r3 = (r2 - r3) / 2 + 2 * v(inf), left in `vm2`.
+        quot.copy_from_slice(vm1);
+        sub_wrapping(&mut quot, vm2);
+        shr1_signed(&mut quot);
+        add_wrapping(&mut quot, vinf);
+        add_wrapping(&mut quot, vinf);
+        vm2.copy_from_slice(&quot);
+        // r2 = r2 + r1 - r4, left in `vm1`.
+        add_wrapping(vm1, v1);
+        sub_wrapping(vm1, vinf);
+        // r1 = r1 - r3, left in `v1`.
+        sub_wrapping(v1, vm2);
+    }
+
+    // Every coefficient of the product is non-negative, so adding each at its
+    // offset gives the product.
+    for limb in w.iter_mut() {
+        *limb = Limb::ZERO;
+    }
+    for (i, r) in v.chunks(len).enumerate() {
+        let r = &r[..normalized_len(r)];
+        if r.is_empty() {
+            // The offset of a zero coefficient may be past the end of `w`.
+            continue;
+        }
+        let carry = add_n_assign(&mut w[i * k..i * k + r.len()], r);
+        add_1_assign(&mut w[i * k + r.len()..], carry);
+    }
+    Ok(())
+}
+
+/// Returns the values at `1`, `-1` and `-2` of the polynomial with the parts of
+/// `k` limbs of `x` as its coefficients, each in `k + 2` limbs of two's
+/// complement.
+fn toom3_eval(x: &[Limb], k: usize) -> Result<Vec<Limb>, AllocError> {
+    let len = k + 2;
+    let (x0, x1, x2) = (
+        &x[..k.min(x.len())],
+        x.get(k..(2 * k).min(x.len())).unwrap_or(&[]),
+        x.get(2 * k..).unwrap_or(&[]),
+    );
+
+    let mut values = mem::try_scratch(3 * len)?;
+    {
+        let (p1, rest) = values.split_at_mut(len);
+        let (pm1, pm2) = rest.split_at_mut(len);
+
+        // p(1) = x0 + x2 + x1 and p(-1) = x0 + x2 - x1.
+        add_wrapping(p1, x0);
+        add_wrapping(p1, x2);
+        pm1.copy_from_slice(p1);
+        add_wrapping(p1, x1);
+        sub_wrapping(pm1, x1);
+        // p(-2) = 2 * (p(-1) + x2) - x0.
+        pm2.copy_from_slice(pm1);
+        add_wrapping(pm2, x2);
+        shl1_assign(pm2);
+        sub_wrapping(pm2, x0);
+    }
+    Ok(values)
+}
+
+/// Writes the product of the magnitudes `x` and `y` to `w`, where the product
+/// fits in `w` and either operand may be empty.
+fn mul_unsigned(w: &mut [Limb], x: &[Limb], y: &[Limb]) -> Result<(), AllocError> {
+    let (x, y) = (&x[..normalized_len(x)], &y[..normalized_len(y)]);
+    if !x.is_empty() && !y.is_empty() {
+        try_mul(&mut w[..x.len() + y.len()], x, y)?;
+    }
+    Ok(())
+}
+
+/// Writes the product of the two's complement values `x` and `y` to `w` in
+/// two's complement, where the product fits in `w`.
+fn mul_signed(w: &mut [Limb], x: &[Limb], y: &[Limb]) -> Result<(), AllocError> {
+    let magnitude = |x: &[Limb]| -> Result<(Vec<Limb>, bool), AllocError> {
+        let mut mag = mem::try_scratch(x.len())?;
+        mag.copy_from_slice(x);
+        let negative = x[x.len() - 1].is_negative();
+        if negative {
+            neg_assign(&mut mag);
+        }
+        Ok((mag, negative))
+    };
+    let (x, x_negative) = magnitude(x)?;
+    let (y, y_negative) = magnitude(y)?;
+
+    mul_unsigned(w, &x, &y)?;
+    if x_negative != y_negative {
+        neg_assign(w);
+    }
+    Ok(())
+}
+
+/// Adds the magnitude `y` to `w` in place, wrapping modulo the length of `w`.
+fn add_wrapping(w: &mut [Limb], y: &[Limb]) {
+    let carry = add_n_assign(&mut w[..y.len()], y);
+    add_1_assign(&mut w[y.len()..], carry);
+}
+
+/// Subtracts the magnitude `y` from `w` in place, wrapping modulo the length of
+/// `w`.
+fn sub_wrapping(w: &mut [Limb], y: &[Limb]) {
+    let borrow = sub_n_assign(&mut w[..y.len()], y);
+    sub_1_assign(&mut w[y.len()..], borrow);
+}
+
+/// Doubles `w` in place, wrapping modulo the length of `w`.
+fn shl1_assign(w: &mut [Limb]) {
+    const HIGH: u32 = Limb::BITS as u32 - 1;
+
+    for i in (0..w.len()).rev() {
+        let low = if i > 0 { w[i - 1].repr() >> HIGH } else { 0 };
+        w[i] = Limb((w[i].repr() << 1) | low);
+    }
+}
+
+/// Halves the two's complement value `w` in place, which must be even.
+fn shr1_signed(w: &mut [Limb]) {
+    const HIGH: u32 = Limb::BITS as u32 - 1;
+
+    let sign = w[w.len() - 1].sign_mask();
+    for i in 0..w.len() {
+        let next = w.get(i + 1).copied().unwrap_or(sign);
+        w[i] = Limb((w[i].repr() >> 1) | (next.repr() << HIGH));
+    }
+}
+
+/// Returns `|x - y|` with the length of the longer operand, and whether
+/// `x - y` is negative.
+fn abs_diff(x: &[Limb], y: &[Limb]) -> Result<(Vec<Limb>, bool), AllocError> {
+    let (x_len, y_len) = (normalized_len(x), normalized_len(y));
+    let negative = match x_len.cmp(&y_len) {
+        Ordering::Equal => x[..x_len].iter().rev().lt(y[..y_len].iter().rev()),
+        ordering => ordering == Ordering::Less,
+    };
+    let (x, y) = if negative { (y, x) } else { (x, y) };
+
+    let mut diff = mem::try_scratch(x.len().max(y.len()))?;
+    diff[..x.len()].copy_from_slice(x);
+    sub_assign(&mut diff, y);
+    Ok((diff, negative))
+}
+
+/// Adds `y` to `w` in place, where the sum fits in `w`.
+fn add_assign(w: &mut [Limb], y: &[Limb]) {
+    let y = &y[..normalized_len(y)];
+    let carry = add_n_assign(&mut w[..y.len()], y);
+    let carry = add_1_assign(&mut w[y.len()..], carry);
+    debug_assert!(carry == Limb::ZERO);
+}
+
+/// Subtracts `y` from `w` in place, where the difference is non-negative.
+fn sub_assign(w: &mut [Limb], y: &[Limb]) {
+    let y = &y[..normalized_len(y)];
+    let borrow = sub_n_assign(&mut w[..y.len()], y);
+    let borrow = sub_1_assign(&mut w[y.len()..], borrow);
+    debug_assert!(borrow == Limb::ZERO);
+}
@@ -2,50 +2,145 @@ use core::alloc::Layout;
 use core::num::NonZeroUsize;
 use core::ptr::NonNull;
 
-use crate::alloc;
+use crate::alloc::{self, Vec};
 use crate::limb::Limb;
+use crate::oom::AllocError;
 
 // TODO: Replace with allocator_api when stabilised.
 
+cfg_if::cfg_if! {
+    if #[cfg(feature = "align-64")] {
+        /// The alignment of heap allocated limb buffers, in bytes.
+        pub const LIMB_ALIGN: usize = 64;
+    } else if #[cfg(feature = "align-32")] {
+        /// The alignment of heap allocated limb buffers, in bytes.
+        pub const LIMB_ALIGN: usize = 32;
+    } else {
+        /// The alignment of heap allocated limb buffers, in bytes.
+        pub const LIMB_ALIGN: usize = core::mem::align_of::<Limb>();
+    }
+}
+
+/// Whether heap allocated limb buffers are aligned more strictly than an array
+/// of limbs, in which case they cannot be exchanged with other allocations of
+/// limbs, such as those of a `Vec`.
+pub const OVER_ALIGNED: bool = LIMB_ALIGN > core::mem::align_of::<Limb>();
+
+// Returns the layout of a buffer of `capacity` limbs.
+#[inline]
+fn limbs_layout(capacity: NonZeroUsize) -> Layout {
+    match Layout::array::<Limb>(capacity.get()).and_then(|layout| layout.align_to(LIMB_ALIGN)) {
+        Ok(layout) => layout,
+        Err(_) => capacity_overflow(),
+    }
+}
+
+// Allocates `capacity` zeroed limbs, returning an error rather than calling
+// the OOM handler if the allocation fails.
+//
 // Whilst not inherently unsafe, this function is mark unsafe to ensure the
 // caller tracks the allocation.
 #[must_use = "the caller must track this allocation to prevent memory leaks"]
-pub unsafe fn alloc_limbs(capacity: NonZeroUsize) -> NonNull<Limb> {
-    let layout = match Layout::array::<Limb>(capacity.get()) {
-        Ok(layout) => layout,
-        Err(_) => capacity_overflow(),
-    };
+pub unsafe fn try_alloc_limbs(capacity: NonZeroUsize) -> Result<NonNull<Limb>, AllocError> {
+    let layout = limbs_layout(capacity);
     alloc_guard(layout.size());
 
     // SAFETY: This is safe since we have verified the integrity of the layout.
     let ptr = alloc::alloc_zeroed(layout);
     if ptr.is_null() {
-        alloc::handle_alloc_error(layout);
+        return Err(AllocError::new(layout));
     }
+    #[cfg(feature = "stats")]
+    crate::stats::record_alloc();
+    #[cfg(feature = "alloc-observer")]
+    crate::observer::notify(layout.size(), crate::observer::AllocKind::Alloc);
 
     // SAFETY: `ptr` is guaranteed to be non-null at this point.
-    NonNull::new_unchecked(ptr.cast())
+    Ok(NonNull::new_unchecked(ptr.cast()))
+}
+
+// Allocates `capacity` limbs like `try_alloc_limbs`, but the limbs are left
+// uninitialized, so the caller must write every limb before reading them, and
+// a failed allocation calls the OOM handler.
+#[must_use = "the caller must track this allocation to prevent memory leaks"]
+pub unsafe fn alloc_limbs_uninit(capacity: NonZeroUsize) -> NonNull<Limb> {
+    try_alloc_limbs_uninit(capacity).unwrap_or_else(|err| err.handle())
+}
+
+// Like `alloc_limbs_uninit`, but returns an error rather than calling the OOM
+// handler if the allocation fails.
+#[must_use = "the caller must track this allocation to prevent memory leaks"]
+pub unsafe fn try_alloc_limbs_uninit(capacity: NonZeroUsize) -> Result<NonNull<Limb>, AllocError> {
+    let layout = limbs_layout(capacity);
+    alloc_guard(layout.size());
+
+    // SAFETY: This is safe since we have verified the integrity of the layout.
+    let ptr = alloc::alloc(layout);
+    if ptr.is_null() {
+        return Err(AllocError::new(layout));
+    }
+    #[cfg(feature = "stats")]
+    crate::stats::record_alloc();
+    #[cfg(feature = "alloc-observer")]
+    crate::observer::notify(layout.size(), crate::observer::AllocKind::Alloc);
+
+    // SAFETY: `ptr` is guaranteed to be non-null at this point.
+    Ok(NonNull::new_unchecked(ptr.cast()))
 }
 
 pub unsafe fn dealloc_limbs(ptr: NonNull<Limb>, size: NonZeroUsize) {
-    const ALIGN: usize = core::mem::align_of::<Limb>();
     const SIZE: usize = core::mem::size_of::<Limb>();
 
     let size = SIZE * size.get();
 
+    #[cfg(feature = "alloc-observer")]
+    crate::observer::notify(size, crate::observer::AllocKind::Dealloc);
+
     // SAFETY: `ptr` is already already allocated so we can bypass checks.
-    let layout = Layout::from_size_align_unchecked(size, ALIGN);
+    let layout = Layout::from_size_align_unchecked(size, LIMB_ALIGN);
     // SAFETY: ptr is guaranteed to be non-null and layout is correct.
     alloc::dealloc(ptr.cast().as_ptr(), layout);
 }
 
+// Reports limb storage allocated elsewhere, such as by a `Vec`, that an int
+// takes ownership of, as if it had been allocated here.
+#[inline]
+pub fn adopt_limbs(_size: NonZeroUsize) {
+    #[cfg(feature = "alloc-observer")]
+    crate::observer::notify(
+        core::mem::size_of::<Limb>() * _size.get(),
+        crate::observer::AllocKind::Alloc,
+    );
+}
+
+// Reports limb storage that an int gives up ownership of, as if it had been
+// freed here.
+#[inline]
+pub fn release_limbs(_size: NonZeroUsize) {
+    #[cfg(feature = "alloc-observer")]
+    crate::observer::notify(
+        core::mem::size_of::<Limb>() * _size.get(),
+        crate::observer::AllocKind::Dealloc,
+    );
+}
+
 #[must_use = "the caller must track this reallocation to prevent memory leaks"]
 pub unsafe fn realloc_limbs(
     ptr: NonNull<Limb>,
     old_size: NonZeroUsize,
     new_size: NonZeroUsize,
 ) -> NonNull<Limb> {
-    const ALIGN: usize = core::mem::align_of::<Limb>();
+    try_realloc_limbs(ptr, old_size, new_size).unwrap_or_else(|err| err.handle())
+}
+
+// Like `realloc_limbs`, but returns an error rather than calling the OOM
+// handler if the reallocation fails, in which case `ptr` is left as is.
+#[must_use = "the caller must track this reallocation to prevent memory leaks"]
+pub unsafe fn try_realloc_limbs(
+    ptr: NonNull<Limb>,
+    old_size: NonZeroUsize,
+    new_size: NonZeroUsize,
+) -> Result<NonNull<Limb>, AllocError> {
     const SIZE: usize = core::mem::size_of::<Limb>();
 
     let old_size = SIZE * old_size.get();
@@ -53,16 +148,69 @@ pub unsafe fn realloc_limbs(
     alloc_guard(new_size);
 
     // SAFETY: `ptr` is already already allocated so we can bypass checks.
-    let layout = Layout::from_size_align_unchecked(old_size, ALIGN);
+    let layout = Layout::from_size_align_unchecked(old_size, LIMB_ALIGN);
 
     // SAFETY: This is safe since we have verified the integrity of the layout.
     let ptr = alloc::realloc(ptr.cast().as_ptr(), layout, new_size);
     if ptr.is_null() {
-        alloc::handle_alloc_error(layout);
+        // SAFETY: `new_size` was checked by `alloc_guard`.
+        return Err(AllocError::new(Layout::from_size_align_unchecked(
+            new_size, LIMB_ALIGN,
+        )));
     }
+    #[cfg(feature = "stats")]
+    crate::stats::record_realloc();
+    #[cfg(feature = "alloc-observer")]
+    crate::observer::notify(
+        new_size,
+        crate::observer::AllocKind::Realloc {
+            old_bytes: old_size,
+        },
+    );
 
     // SAFETY: ptr is guaranteed to be non-null at this point.
-    NonNull::new_unchecked(ptr.cast())
+    Ok(NonNull::new_unchecked(ptr.cast()))
+}
+
+// Returns a buffer of `len` zeroed limbs for temporary use inside algorithms,
+// or an error if it cannot be allocated.
+//
+// The buffer is allocated directly rather than through `Vec::try_reserve`,
+// which needs a newer compiler than the minimum supported version. It is not
+// counted as limb storage by the stats or the allocation observer.
+pub fn try_scratch(len: usize) -> Result<Vec<Limb>, AllocError> {
+    // SAFETY: A limb of all zero bits is zero.
+    unsafe { try_zeroed_vec(len) }
+}
+
+// Like `try_scratch`, but for the `u64` coefficients of FFT multiplication.
+pub fn try_scratch_u64(len: usize) -> Result<Vec<u64>, AllocError> {
+    // SAFETY: A `u64` of all zero bits is zero.
+    unsafe { try_zeroed_vec(len) }
+}
+
+// Returns a `Vec` of `len` zeroed values, or an error if it cannot be
+// allocated.
+//
+// The caller must ensure that all zero bits is a valid `T`.
+unsafe fn try_zeroed_vec<T>(len: usize) -> Result<Vec<T>, AllocError> {
+    if len == 0 {
+        return Ok(Vec::new());
+    }
+    let layout = match Layout::array::<T>(len) {
+        Ok(layout) => layout,
+        Err(_) => capacity_overflow(),
+    };
+    alloc_guard(layout.size());
+
+    // SAFETY: The layout has a non-zero size.
+    let ptr = alloc::alloc_zeroed(layout);
+    if ptr.is_null() {
+        return Err(AllocError::new(layout));
+    }
+    // SAFETY: `ptr` was allocated by the global allocator with the layout of
+    //         an array of `len` values, all of which are zeroed.
+    Ok(Vec::from_raw_parts(ptr.cast(), len, len))
 }
 
 // We need to guarantee the following:
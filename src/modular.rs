@@ -0,0 +1,410 @@
+//! Modular arithmetic types and helpers.
+//!
+//! [`Mod2k`] is an element of the ring of integers modulo a power of two, with
+//! the wrapping semantics of a `k` bit machine integer, and [`MontgomeryCtx`]
+//! holds the constants for Montgomery multiplication modulo an odd modulus.
+//!
+//! An RSA private key operation `c^d mod pq` is usually done with the Chinese
+//! remainder theorem: exponentiating modulo each of the primes separately with
+//! reduced exponents, and combining the two results with Garner's formula. The
+//! helpers here compute the key components for that, and perform the combined
+//! exponentiation, so no hand written CRT code is needed.
+
+use core::ops::{Add, Mul, Neg, Sub};
+
+use crate::apint::ApInt;
+use crate::number_theory::{pow_mod, rem_euclid};
+
+/// The components of an RSA private key used for exponentiation with the
+/// Chinese remainder theorem, as in the `RSAPrivateKey` structure of RFC 8017.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RsaCrtComponents {
+    /// The exponent `d mod (p - 1)`.
+    pub d_p: ApInt,
+    /// The exponent `d mod (q - 1)`.
+    pub d_q: ApInt,
+    /// The inverse of `q` modulo `p`.
+    pub q_inv: ApInt,
+}
+
+/// Returns the CRT components of the private exponent `d` for the distinct
+/// primes `p` and `q`, or `None` if `q` has no inverse modulo `p`.
+///
+/// # Panics
+///
+/// Panics if `p` or `q` is not greater than one, or if `d` is negative.
+///
+/// # Examples
+///
+/// ```
+/// use apa::modular::rsa_crt_components;
+/// use apa::ApInt;
+///
+/// let (p, q, d) = (ApInt::from(61), ApInt::from(53), ApInt::from(2753));
+/// let c = rsa_crt_components(&p, &q, &d).unwrap();
+/// assert_eq!((c.d_p, c.d_q, c.q_inv), (ApInt::from(53), ApInt::from(49), ApInt::from(38)));
+/// ```
+pub fn rsa_crt_components(p: &ApInt, q: &ApInt, d: &ApInt) -> Option<RsaCrtComponents> {
+    assert_prime_modulus(p);
+    assert_prime_modulus(q);
+    assert!(!d.is_negative(), "exponent must be non-negative");
+
+    let (g, q_inv, _) = q.extended_gcd(p);
+    if g != ApInt::ONE {
+        return None;
+    }
+    Some(RsaCrtComponents {
+        d_p: d % &(p - ApInt::ONE),
+        d_q: d % &(q - ApInt::ONE),
+        q_inv: rem_euclid(&q_inv, p),
+    })
+}
+
+/// Returns `c^d mod pq`, computed from the CRT components of `d` for the
+/// distinct primes `p` and `q`.
+///
+/// `c` is exponentiated modulo `p` and `q` separately, with the exponents
+/// `d_p` and `d_q`, and the results `m_p` and `m_q` are combined with Garner's
+/// formula `m_q + q * (q_inv * (m_p - m_q) mod p)`. Each exponentiation works
+/// on numbers half the size of the modulus, making this several times faster
+/// than exponentiating modulo `pq` directly. The result is in the range
+/// `0..pq`, and `c` may be any int.
+///
+/// # Panics
+///
+/// Panics if `p` or `q` is not greater than one, or if `d_p` or `d_q` is
+/// negative.
+///
+/// # Examples
+///
+/// ```
+/// use apa::modular::rsa_crt_decrypt;
+/// use apa::ApInt;
+///
+/// // The textbook key with `n = 3233`, `e = 17` and `d = 2753`.
+/// let (p, q) = (ApInt::from(61), ApInt::from(53));
+/// let (d_p, d_q, q_inv) = (ApInt::from(53), ApInt::from(49), ApInt::from(38));
+/// let m = rsa_crt_decrypt(&ApInt::from(2790), &d_p, &d_q, &q_inv, &p, &q);
+/// assert_eq!(m, ApInt::from(65));
+/// ```
+pub fn rsa_crt_decrypt(
+    c: &ApInt,
+    d_p: &ApInt,
+    d_q: &ApInt,
+    q_inv: &ApInt,
+    p: &ApInt,
+    q: &ApInt,
+) -> ApInt {
+    assert_prime_modulus(p);
+    assert_prime_modulus(q);
+    assert!(
+        !d_p.is_negative() && !d_q.is_negative(),
+        "exponent must be non-negative"
+    );
+
+    let m_p = pow_mod(&rem_euclid(c, p), d_p, p);
+    let m_q = pow_mod(&rem_euclid(c, q), d_q, q);
+    let h = rem_euclid(&(q_inv * (m_p - &m_q)), p);
+    m_q + q * h
+}
+
+fn assert_prime_modulus(p: &ApInt) {
+    if *p <= ApInt::ONE {
+        panic!("prime must be greater than one");
+    }
+}
+
+/// An integer modulo `2^k`.
+///
+/// Arithmetic wraps modulo `2^k` like a `k` bit unsigned machine integer, with
+/// the reduction done by masking the low `k` bits. The value is kept in the
+/// range `0..2^k`, and [`to_signed`](Mod2k::to_signed) gives its two's
+/// complement interpretation.
+///
+/// # Panics
+///
+/// Operations on two elements panic if their moduli differ.
+///
+/// # Examples
+///
+/// ```
+/// use apa::modular::Mod2k;
+/// use apa::ApInt;
+///
+/// let a = Mod2k::new(&ApInt::from(u64::MAX), 64);
+/// let b = Mod2k::new(&ApInt::from(2), 64);
+/// assert_eq!((&a + &b).value(), &ApInt::ONE);
+/// assert_eq!(a.to_signed(), ApInt::from(-1));
+///
+/// let inv = b.inverse();
+/// assert!(inv.is_none());
+/// let inv = a.inverse().unwrap();
+/// assert_eq!((&a * &inv).value(), &ApInt::ONE);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Mod2k {
+    value: ApInt,
+    k: usize,
+}
+
+impl Mod2k {
+    /// Creates the element congruent to `value` modulo `2^k`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is zero.
+    pub fn new(value: &ApInt, k: usize) -> Mod2k {
+        assert!(k > 0, "modulus exponent must be positive");
+        Mod2k {
+            value: value.mod_pow2(k),
+            k,
+        }
+    }
+
+    /// Returns the exponent `k` of the modulus `2^k`.
+    #[inline]
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Returns the value of the element, in the range `0..2^k`.
+    #[inline]
+    pub fn value(&self) -> &ApInt {
+        &self.value
+    }
+
+    /// Returns the value of the element, consuming it.
+    #[inline]
+    pub fn into_value(self) -> ApInt {
+        self.value
+    }
+
+    /// Returns the value of the element read as a `k` bit two's complement
+    /// integer, in the range `-2^(k - 1)..2^(k - 1)`.
+    pub fn to_signed(&self) -> ApInt {
+        self.value.sext(self.k)
+    }
+
+    /// Returns the multiplicative inverse of the element, or `None` if it is
+    /// even and so has no inverse.
+    pub fn inverse(&self) -> Option<Mod2k> {
+        let value = self.value.inv_mod_pow2(self.k)?;
+        Some(Mod2k { value, k: self.k })
+    }
+
+    /// Returns the element raised to the power `exp`.
+    ///
+    /// A negative `exp` raises the inverse of the element, so the element
+    /// must then be odd.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `exp` is negative and the element is even.
+    pub fn pow(&self, exp: &ApInt) -> Mod2k {
+        let base = if exp.is_negative() {
+            self.inverse()
+                .expect("attempt to raise an even element to a negative power")
+        } else {
+            self.clone()
+        };
+        let exp = exp.abs();
+
+        let mut r = Mod2k::new(&ApInt::ONE, self.k);
+        for i in (0..exp.bits()).rev() {
+            r = &r * &r;
+            if exp.bit(i) {
+                r = &r * &base;
+            }
+        }
+        r
+    }
+
+    fn wrap(&self, other: &Mod2k, value: ApInt) -> Mod2k {
+        assert_eq!(self.k, other.k, "moduli must match");
+        Mod2k {
+            value: value.mod_pow2(self.k),
+            k: self.k,
+        }
+    }
+}
+
+macro_rules! impl_mod2k_binop {
+    ($($trait:ident, $fn:ident;)*) => {
+        $(
+            impl $trait<&Mod2k> for &Mod2k {
+                type Output = Mod2k;
+
+                fn $fn(self, other: &Mod2k) -> Mod2k {
+                    self.wrap(other, (&self.value).$fn(&other.value))
+                }
+            }
+
+            impl $trait<Mod2k> for &Mod2k {
+                type Output = Mod2k;
+
+                #[inline]
+                fn $fn(self, other: Mod2k) -> Mod2k {
+                    self.$fn(&other)
+                }
+            }
+
+            impl $trait<&Mod2k> for Mod2k {
+                type Output = Mod2k;
+
+                #[inline]
+                fn $fn(self, other: &Mod2k) -> Mod2k {
+                    (&self).$fn(other)
+                }
+            }
+
+            impl $trait<Mod2k> for Mod2k {
+                type Output = Mod2k;
+
+                #[inline]
+                fn $fn(self, other: Mod2k) -> Mod2k {
+                    (&self).$fn(&other)
+                }
+            }
+        )*
+    };
+}
+
+impl_mod2k_binop! {
+    Add, add;
+    Sub, sub;
+    Mul, mul;
+}
+
+impl Neg for &Mod2k {
+    type Output = Mod2k;
+
+    fn neg(self) -> Mod2k {
+        self.wrap(self, -&self.value)
+    }
+}
+
+impl Neg for Mod2k {
+    type Output = Mod2k;
+
+    #[inline]
+    fn neg(self) -> Mod2k {
+        -&self
+    }
+}
+
+/// The precomputed constants for Montgomery multiplication modulo an odd
+/// modulus `n`.
+///
+/// A value `a` is represented in Montgomery form as `a * R mod n`, where `R`
+/// is the least power of two greater than `n`. Products of values in this form
+/// are reduced with shifts and masks by `R` instead of divisions by `n`, which
+/// makes long chains of multiplications, such as in exponentiation, cheaper.
+///
+/// # Examples
+///
+/// ```
+/// use apa::modular::MontgomeryCtx;
+/// use apa::ApInt;
+///
+/// let ctx = MontgomeryCtx::new(&ApInt::from(101)).unwrap();
+/// let (a, b) = (ctx.to_montgomery(&ApInt::from(20)), ctx.to_montgomery(&ApInt::from(30)));
+/// assert_eq!(ctx.from_montgomery(&ctx.mul(&a, &b)), ApInt::from(600 % 101));
+///
+/// let r = ctx.pow(&a, &ApInt::from(100));
+/// assert_eq!(ctx.from_montgomery(&r), ApInt::ONE);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MontgomeryCtx {
+    modulus: ApInt,
+    /// The exponent `k` of `R = 2^k`.
+    k: usize,
+    /// `-n^-1 mod R`.
+    n_inv: ApInt,
+    /// `R^2 mod n`, to convert values into Montgomery form.
+    r2: ApInt,
+}
+
+impl MontgomeryCtx {
+    /// Creates the context for the modulus `n`, or returns `None` if `n` is
+    /// not an odd int greater than one.
+    pub fn new(n: &ApInt) -> Option<MontgomeryCtx> {
+        if n.is_even() || *n <= ApInt::ONE {
+            return None;
+        }
+
+        let k = n.bits();
+        let n_inv = (-n).inv_mod_pow2(k)?;
+        let r2 = (ApInt::ONE << (2 * k)) % n;
+        Some(MontgomeryCtx {
+            modulus: n.clone(),
+            k,
+            n_inv,
+            r2,
+        })
+    }
+
+    /// Returns the modulus `n`.
+    #[inline]
+    pub fn modulus(&self) -> &ApInt {
+        &self.modulus
+    }
+
+    /// Returns `a * R mod n`, the Montgomery form of `a`, which may be any
+    /// int.
+    pub fn to_montgomery(&self, a: &ApInt) -> ApInt {
+        self.reduce(&(rem_euclid(a, &self.modulus) * &self.r2))
+    }
+
+    /// Returns `a * R^-1 mod n`, the value of `a` in Montgomery form.
+    ///
+    /// `a` must be in the range `0..n`.
+    pub fn from_montgomery(&self, a: &ApInt) -> ApInt {
+        self.reduce(a)
+    }
+
+    /// Returns the Montgomery form of `1`, which is `R mod n`.
+    pub fn one(&self) -> ApInt {
+        self.to_montgomery(&ApInt::ONE)
+    }
+
+    /// Returns the Montgomery product `a * b * R^-1 mod n`, which is the
+    /// Montgomery form of the product of the values of `a` and `b`.
+    ///
+    /// `a` and `b` must be in the range `0..n`.
+    pub fn mul(&self, a: &ApInt, b: &ApInt) -> ApInt {
+        self.reduce(&(a * b))
+    }
+
+    /// Returns the Montgomery form of the value of `a` raised to the power
+    /// `exp`.
+    ///
+    /// `a` must be in the range `0..n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `exp` is negative.
+    pub fn pow(&self, a: &ApInt, exp: &ApInt) -> ApInt {
+        assert!(!exp.is_negative(), "exponent must be non-negative");
+        let mut r = self.one();
+        for i in (0..exp.bits()).rev() {
+            r = self.mul(&r, &r);
+            if exp.bit(i) {
+                r = self.mul(&r, a);
+            }
+        }
+        r
+    }
+
+    /// Returns `t * R^-1 mod n` by Montgomery reduction, for `t` in the range
+    /// `0..n * R`.
+    fn reduce(&self, t: &ApInt) -> ApInt {
+        // `m * n == -t (mod R)`, so `t + m * n` is divisible by `R`.
+        let m = (t.mod_pow2(self.k) * &self.n_inv).mod_pow2(self.k);
+        let u = (t + m * &self.modulus) >> self.k;
+        if u >= self.modulus {
+            u - &self.modulus
+        } else {
+            u
+        }
+    }
+}
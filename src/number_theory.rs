@@ -0,0 +1,1252 @@
+//! Number theoretic functions on [`ApInt`].
+//!
+//! Functions that need to factor an integer use trial division followed by
+//! Pollard's rho algorithm, and identify primes with a Miller-Rabin test. The
+//! test is deterministic below `3.3 * 10^24`, and above that the chance of a
+//! composite being mistaken for a prime is negligible.
+
+use num_traits::ToPrimitive;
+
+use crate::alloc::{Vec, vec};
+use crate::apint::ApInt;
+use crate::limb::Limb;
+
+/// The primes below 100, used for trial division and as Miller-Rabin bases.
+const SMALL_PRIMES: [u32; 25] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+];
+
+/// The number of Miller-Rabin bases from [`SMALL_PRIMES`] that are enough to
+/// correctly identify every prime below `3.3 * 10^24`.
+const MILLER_RABIN_BASES: usize = 13;
+
+/// The prime factorization of a positive integer.
+///
+/// A factorization is either computed with [`factorize`], or built from
+/// already known prime factors with [`Factorization::from_prime_powers`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Factorization {
+    /// Pairs of a prime and its exponent, in ascending order of the primes.
+    prime_powers: Vec<(ApInt, u32)>,
+}
+
+impl Factorization {
+    /// Creates a factorization from pairs of a prime and its exponent.
+    ///
+    /// The pairs may be given in any order. Repeated primes have their
+    /// exponents summed and zero exponents are ignored. The primes are not
+    /// checked to be prime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a prime is not greater than one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use apa::number_theory::Factorization;
+    /// use apa::ApInt;
+    ///
+    /// let f = Factorization::from_prime_powers(vec![(ApInt::from(3), 1), (ApInt::from(2), 2)]);
+    /// assert_eq!(f.value(), ApInt::from(12));
+    /// ```
+    pub fn from_prime_powers<I>(prime_powers: I) -> Factorization
+    where
+        I: IntoIterator<Item = (ApInt, u32)>,
+    {
+        let mut f = Factorization::default();
+        for (p, e) in prime_powers {
+            if p <= ApInt::ONE {
+                panic!("prime factor must be greater than one");
+            }
+            f.push(p, e);
+        }
+        f.prime_powers.sort_by(|(p, _), (q, _)| p.cmp(q));
+        f
+    }
+
+    /// Returns the pairs of a prime and its exponent, in ascending order of
+    /// the primes.
+    #[inline]
+    pub fn prime_powers(&self) -> &[(ApInt, u32)] {
+        &self.prime_powers
+    }
+
+    /// Returns the integer with this factorization.
+    pub fn value(&self) -> ApInt {
+        let mut r = ApInt::ONE;
+        for (p, e) in &self.prime_powers {
+            r *= p.pow(*e);
+        }
+        r
+    }
+
+    /// Multiplies the factorization by `p^e`, without keeping the primes
+    /// sorted.
+    fn push(&mut self, p: ApInt, e: u32) {
+        if e == 0 {
+            return;
+        }
+        match self.prime_powers.iter_mut().find(|(q, _)| *q == p) {
+            Some((_, f)) => *f += e,
+            None => self.prime_powers.push((p, e)),
+        }
+    }
+}
+
+/// Returns the smallest of the first `k` primes that divides `n`, or `None` if
+/// none of them divide `n`.
+///
+/// The primes are grouped so that the product of each group fits in a `u64`,
+/// and `n` is reduced modulo the product of every group in a single pass over
+/// its limbs. Only the small remainders are then tested against each prime.
+///
+/// # Examples
+///
+/// ```
+/// use apa::number_theory::small_prime_filter;
+/// use apa::ApInt;
+///
+/// let n = ApInt::from(1_000_003u64 * 1009);
+/// assert_eq!(small_prime_filter(&n, 168), None);
+/// assert_eq!(small_prime_filter(&n, 169), Some(1009));
+/// ```
+pub fn small_prime_filter(n: &ApInt, k: usize) -> Option<u32> {
+    let primes = first_primes(k);
+
+    // Group the primes, along with the product of each group.
+    let mut groups: Vec<(u64, &[u32])> = Vec::new();
+    let mut start = 0;
+    while start < primes.len() {
+        let mut product = 1u64;
+        let mut end = start;
+        while let Some(next) = primes
+            .get(end)
+            .and_then(|&p| product.checked_mul(u64::from(p)))
+        {
+            product = next;
+            end += 1;
+        }
+        groups.push((product, &primes[start..end]));
+        start = end;
+    }
+
+    // A negative `n` is reduced as its limbs with an extra limb of `-1` above
+    // them, which leaves a remainder of `product - 1`.
+    let mut rems: Vec<u64> = groups
+        .iter()
+        .map(|&(product, _)| if n.is_negative() { product - 1 } else { 0 })
+        .collect();
+    for limb in n.as_limbs().iter().rev() {
+        for (r, &(product, _)) in rems.iter_mut().zip(&groups) {
+            *r = (((*r as u128) << Limb::BITS | limb.repr() as u128) % product as u128) as u64;
+        }
+    }
+
+    groups
+        .iter()
+        .zip(&rems)
+        .flat_map(|(&(_, primes), &r)| {
+            primes
+                .iter()
+                .copied()
+                .filter(move |&p| r % u64::from(p) == 0)
+        })
+        .next()
+}
+
+/// Returns the first `k` primes.
+fn first_primes(k: usize) -> Vec<u32> {
+    PrimeIter::new().take(k).map(|p| p as u32).collect()
+}
+
+/// Returns the primes below `n`, in ascending order.
+///
+/// The primes are found with a sieve of Eratosthenes over the odd numbers
+/// below `n`, packed one bit per number, so the sieve takes `n / 16` bytes.
+///
+/// # Examples
+///
+/// ```
+/// use apa::number_theory::primes_below;
+///
+/// assert_eq!(primes_below(20), [2, 3, 5, 7, 11, 13, 17, 19]);
+/// assert_eq!(primes_below(1_000_000).len(), 78_498);
+/// ```
+pub fn primes_below(n: u64) -> Vec<u64> {
+    if n <= 2 {
+        return Vec::new();
+    }
+
+    // Bit `i` is set if `3 + 2 * i` is prime, and the odd numbers from `3` are
+    // crossed out by the primes up to the square root of `n - 1`.
+    let len = (n - 2) / 2;
+    let mut sieve = full_sieve(len as usize);
+    cross_out(&mut sieve, 3, &primes_below(isqrt(n - 1) + 1));
+
+    let mut primes = vec![2];
+    primes.extend(SieveBits::new(&sieve).map(|i| 3 + 2 * i as u64));
+    primes
+}
+
+/// An iterator over every prime, in ascending order.
+///
+/// The primes are found with a segmented sieve of Eratosthenes, so only a
+/// small block of odd numbers is sieved at a time, and the iterator uses
+/// memory proportional to the square root of the largest prime it yields.
+///
+/// # Examples
+///
+/// ```
+/// use apa::number_theory::PrimeIter;
+///
+/// let primes: Vec<u64> = PrimeIter::new().skip(10).take(5).collect();
+/// assert_eq!(primes, [31, 37, 41, 43, 47]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct PrimeIter {
+    /// The primes up to the square root of the end of the segment.
+    base: Vec<u64>,
+    /// The bound that `base` holds every prime below.
+    base_limit: u64,
+    /// The odd number represented by the first bit of the segment, or zero
+    /// before `2` is yielded.
+    lo: u64,
+    /// Bit `i` is set if `lo + 2 * i` is prime.
+    segment: Vec<u64>,
+    /// The next bit of the segment to check.
+    pos: usize,
+}
+
+impl PrimeIter {
+    /// The number of odd numbers in each segment.
+    const SEGMENT: usize = 1 << 15;
+
+    /// Creates an iterator over every prime, starting from `2`.
+    pub fn new() -> PrimeIter {
+        PrimeIter {
+            base: Vec::new(),
+            base_limit: 0,
+            lo: 0,
+            segment: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Sieves the segment of odd numbers starting from `lo`, or returns
+    /// `false` if the segment would reach beyond `u64::MAX`.
+    fn sieve(&mut self, lo: u64) -> bool {
+        let hi = match lo.checked_add(2 * PrimeIter::SEGMENT as u64) {
+            Some(hi) => hi,
+            None => return false,
+        };
+
+        // Only primes up to the square root of the last odd number are needed,
+        // and the limit is doubled to rarely recompute them.
+        let needed = isqrt(hi - 2) + 1;
+        if self.base_limit < needed {
+            self.base_limit = needed.saturating_mul(2);
+            self.base = primes_below(self.base_limit);
+        }
+
+        self.segment = full_sieve(PrimeIter::SEGMENT);
+        cross_out(&mut self.segment, lo, &self.base);
+        self.lo = lo;
+        self.pos = 0;
+        true
+    }
+}
+
+impl Default for PrimeIter {
+    #[inline]
+    fn default() -> PrimeIter {
+        PrimeIter::new()
+    }
+}
+
+impl Iterator for PrimeIter {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        if self.lo == 0 {
+            // The first segment starts at `3`, leaving `1` out of the sieve.
+            self.sieve(3);
+            return Some(2);
+        }
+
+        loop {
+            if let Some(i) = SieveBits::new(&self.segment).from(self.pos).next() {
+                self.pos = i + 1;
+                return Some(self.lo + 2 * i as u64);
+            }
+            let next = self.lo + 2 * PrimeIter::SEGMENT as u64;
+            if !self.sieve(next) {
+                return None;
+            }
+        }
+    }
+}
+
+/// Returns a sieve of `len` set bits.
+fn full_sieve(len: usize) -> Vec<u64> {
+    let mut sieve = vec![u64::MAX; (len + 63) / 64];
+    if len % 64 != 0 {
+        sieve[len / 64] = (1 << (len % 64)) - 1;
+    }
+    sieve
+}
+
+/// Clears the bits of `sieve` that represent odd multiples of `primes` other
+/// than the primes themselves, where bit `i` represents the odd `lo + 2 * i`.
+fn cross_out(sieve: &mut [u64], lo: u64, primes: &[u64]) {
+    let len = sieve.len() as u64 * 64;
+    for &p in primes.iter().filter(|&&p| p != 2) {
+        // Smaller multiples have a smaller prime factor, so start from `p^2`.
+        let mut m = match p.checked_mul(p) {
+            Some(sq) if sq >= lo => sq,
+            Some(_) => {
+                let m = (lo + p - 1) / p * p;
+                if m % 2 == 0 { m + p } else { m }
+            }
+            None => break,
+        };
+        while m >= lo && (m - lo) / 2 < len {
+            let i = (m - lo) / 2;
+            sieve[(i / 64) as usize] &= !(1 << (i % 64));
+            m = match m.checked_add(2 * p) {
+                Some(m) => m,
+                None => break,
+            };
+        }
+    }
+}
+
+/// An iterator over the indices of the set bits of a sieve.
+struct SieveBits<'a> {
+    sieve: &'a [u64],
+    /// The index of the current word.
+    word: usize,
+    /// The bits of the current word that have not yet been yielded.
+    bits: u64,
+}
+
+impl<'a> SieveBits<'a> {
+    fn new(sieve: &'a [u64]) -> SieveBits<'a> {
+        SieveBits {
+            sieve,
+            word: 0,
+            bits: sieve.first().copied().unwrap_or(0),
+        }
+    }
+
+    /// Skips the bits before index `pos`.
+    fn from(mut self, pos: usize) -> SieveBits<'a> {
+        self.word = pos / 64;
+        self.bits = self
+            .sieve
+            .get(self.word)
+            .map_or(0, |&w| w & (u64::MAX << (pos % 64)));
+        self
+    }
+}
+
+impl Iterator for SieveBits<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.bits == 0 {
+            self.word += 1;
+            self.bits = *self.sieve.get(self.word)?;
+        }
+        let i = self.bits.trailing_zeros() as usize;
+        self.bits &= self.bits - 1;
+        Some(self.word * 64 + i)
+    }
+}
+
+/// Returns the floor of the square root of `n`.
+fn isqrt(mut n: u64) -> u64 {
+    // Find the root one bit at a time, starting from the highest power of four
+    // not above `n`.
+    let mut bit = 1 << 62;
+    while bit > n {
+        bit >>= 2;
+    }
+
+    let mut r = 0;
+    while bit != 0 {
+        if n >= r + bit {
+            n -= r + bit;
+            r = (r >> 1) + bit;
+        } else {
+            r >>= 1;
+        }
+        bit >>= 2;
+    }
+    r
+}
+
+/// Returns the prime factorization of `n`.
+///
+/// # Panics
+///
+/// Panics if `n` is not positive.
+///
+/// # Examples
+///
+/// ```
+/// use apa::number_theory::factorize;
+/// use apa::ApInt;
+///
+/// let f = factorize(&ApInt::from(360));
+/// let expected = [(ApInt::from(2), 3), (ApInt::from(3), 2), (ApInt::from(5), 1)];
+/// assert_eq!(f.prime_powers(), &expected[..]);
+/// ```
+pub fn factorize(n: &ApInt) -> Factorization {
+    if *n <= ApInt::ZERO {
+        panic!("cannot factorize a non-positive integer");
+    }
+
+    let mut f = Factorization::default();
+    let mut n = n.clone();
+
+    for &p in SMALL_PRIMES.iter() {
+        let mut e = 0;
+        while n.divisible_by_u32(p) {
+            n = n.div_exact(&ApInt::from(p));
+            e += 1;
+        }
+        f.push(ApInt::from(p), e);
+    }
+
+    let mut composites = vec![n];
+    while let Some(n) = composites.pop() {
+        if n == ApInt::ONE {
+            continue;
+        }
+        if is_prime(&n) {
+            f.push(n, 1);
+        } else if let Some((root, k)) = n.is_perfect_power() {
+            // Pollard's rho algorithm is very slow to split prime powers.
+            for _ in 0..k {
+                composites.push(root.clone());
+            }
+        } else {
+            let d = pollard_rho(&n);
+            composites.push(&n / &d);
+            composites.push(d);
+        }
+    }
+
+    f.prime_powers.sort_by(|(p, _), (q, _)| p.cmp(q));
+    f
+}
+
+/// Returns Euler's totient `φ(n)`, the number of integers in `1..=n` coprime
+/// to `n`, given the factorization of `n`.
+///
+/// # Examples
+///
+/// ```
+/// use apa::number_theory::{euler_phi, factorize};
+/// use apa::ApInt;
+///
+/// assert_eq!(euler_phi(&factorize(&ApInt::from(36))), ApInt::from(12));
+/// ```
+pub fn euler_phi(n: &Factorization) -> ApInt {
+    let mut phi = ApInt::ONE;
+    // `φ(p^e) = p^(e - 1) * (p - 1)`.
+    for (p, e) in n.prime_powers() {
+        phi *= p.pow(e - 1) * (p - ApInt::ONE);
+    }
+    phi
+}
+
+/// Returns the Carmichael function `λ(n)`, the smallest positive `m` such
+/// that `a^m ≡ 1 (mod n)` for every `a` coprime to `n`, given the
+/// factorization of `n`.
+///
+/// # Examples
+///
+/// ```
+/// use apa::number_theory::{carmichael_lambda, factorize};
+/// use apa::ApInt;
+///
+/// assert_eq!(carmichael_lambda(&factorize(&ApInt::from(36))), ApInt::from(6));
+/// ```
+pub fn carmichael_lambda(n: &Factorization) -> ApInt {
+    let mut lambda = ApInt::ONE;
+    for (p, e) in n.prime_powers() {
+        let lambda_pe = if *p == ApInt::TWO && *e >= 3 {
+            // The group of units modulo `2^e` is not cyclic for `e >= 3`.
+            ApInt::ONE << (*e as usize - 2)
+        } else {
+            p.pow(e - 1) * (p - ApInt::ONE)
+        };
+        lambda = lambda.lcm(&lambda_pe);
+    }
+    lambda
+}
+
+/// Returns the multiplicative order of `a` modulo `modulus`, which is the
+/// smallest positive `k` such that `a^k ≡ 1 (mod modulus)`.
+///
+/// Returns `None` if `a` and `modulus` are not coprime, in which case no such
+/// `k` exists.
+///
+/// # Panics
+///
+/// Panics if `modulus` is not positive.
+///
+/// # Examples
+///
+/// ```
+/// use apa::number_theory::multiplicative_order;
+/// use apa::ApInt;
+///
+/// let order = multiplicative_order(&ApInt::from(2), &ApInt::from(7));
+/// assert_eq!(order, Some(ApInt::from(3)));
+/// ```
+pub fn multiplicative_order(a: &ApInt, modulus: &ApInt) -> Option<ApInt> {
+    assert_positive(modulus);
+
+    let a = rem_euclid(a, modulus);
+    if a.gcd(modulus) != ApInt::ONE {
+        return None;
+    }
+
+    // The order divides `φ(modulus)`, so remove each prime factor of `φ` for as
+    // long as the power stays at one.
+    let phi = totient_factors(&factorize(modulus));
+    let mut order = phi.value();
+    for (q, e) in phi.prime_powers() {
+        for _ in 0..*e {
+            let t = &order / q;
+            if pow_mod(&a, &t, modulus) != ApInt::ONE {
+                break;
+            }
+            order = t;
+        }
+    }
+    Some(order)
+}
+
+/// Returns the smallest non-negative primitive root modulo `modulus`, which is
+/// a generator of the multiplicative group of integers modulo `modulus`.
+///
+/// Returns `None` if there are no primitive roots modulo `modulus`. Primitive
+/// roots only exist modulo `1`, `2`, `4`, `p^k` and `2 * p^k`, where `p` is an
+/// odd prime.
+///
+/// # Panics
+///
+/// Panics if `modulus` is not positive.
+///
+/// # Examples
+///
+/// ```
+/// use apa::number_theory::primitive_root;
+/// use apa::ApInt;
+///
+/// assert_eq!(primitive_root(&ApInt::from(998_244_353)), Some(ApInt::from(3)));
+/// assert_eq!(primitive_root(&ApInt::from(8)), None);
+/// ```
+pub fn primitive_root(modulus: &ApInt) -> Option<ApInt> {
+    assert_positive(modulus);
+
+    if *modulus <= ApInt::TWO {
+        return Some(modulus - ApInt::ONE);
+    }
+    if *modulus == ApInt::from(4) {
+        return Some(ApInt::from(3));
+    }
+
+    let factors = factorize(modulus);
+    let odd_factors = match factors.prime_powers().split_first() {
+        Some(((p, e), rest)) if *p == ApInt::TWO => match (e, rest) {
+            (1, rest) => rest,
+            _ => return None,
+        },
+        _ => factors.prime_powers(),
+    };
+    if odd_factors.len() != 1 {
+        return None;
+    }
+
+    let phi_factors = totient_factors(&factors);
+    let phi = phi_factors.value();
+    let exponents: Vec<ApInt> = phi_factors
+        .prime_powers()
+        .iter()
+        .map(|(q, _)| &phi / q)
+        .collect();
+
+    let mut g = ApInt::TWO;
+    loop {
+        let is_root = g.gcd(modulus) == ApInt::ONE
+            && exponents
+                .iter()
+                .all(|e| pow_mod(&g, e, modulus) != ApInt::ONE);
+        if is_root {
+            return Some(g);
+        }
+        g += ApInt::ONE;
+    }
+}
+
+/// Returns the smallest non-negative `x` below `order_bound` such that
+/// `base^x ≡ target (mod modulus)`, or `None` if there is none.
+///
+/// The logarithm is found with the baby-step giant-step algorithm, which takes
+/// time and memory proportional to the square root of `order_bound`, so it is
+/// only feasible for bounds up to around `2^64`. The order of `base`, or any
+/// multiple of it such as `φ(modulus)`, is a bound that finds every logarithm
+/// that exists.
+///
+/// With the `std` feature the baby steps are kept in a hash table, and
+/// otherwise in an ordered tree, which costs an extra logarithmic factor per
+/// lookup.
+///
+/// # Panics
+///
+/// Panics if `modulus` is not positive, if `base` and `modulus` are not
+/// coprime, or if the number of baby steps, the square root of `order_bound`,
+/// does not fit in a `usize`.
+///
+/// # Examples
+///
+/// ```
+/// use apa::number_theory::discrete_log;
+/// use apa::ApInt;
+///
+/// let (base, p) = (ApInt::from(5), ApInt::from(1_000_003));
+/// let target = ApInt::from(5).pow_int(&ApInt::from(123_456)).unwrap() % &p;
+/// let bound = &p - ApInt::ONE;
+/// assert_eq!(discrete_log(&base, &target, &p, &bound), Some(ApInt::from(123_456)));
+///
+/// assert_eq!(discrete_log(&ApInt::from(2), &ApInt::from(3), &ApInt::from(7), &bound), None);
+/// ```
+pub fn discrete_log(
+    base: &ApInt,
+    target: &ApInt,
+    modulus: &ApInt,
+    order_bound: &ApInt,
+) -> Option<ApInt> {
+    assert_positive(modulus);
+    let (g, base_inv, _) = base.extended_gcd(modulus);
+    if g != ApInt::ONE {
+        panic!("base must be coprime to the modulus");
+    }
+    if *order_bound <= ApInt::ZERO {
+        return None;
+    }
+
+    let base = rem_euclid(base, modulus);
+    let target = rem_euclid(target, modulus);
+
+    // Write `x = i * m + j` with `0 <= i, j < m`, where `m^2 >= order_bound`.
+    let m = (order_bound - ApInt::ONE).sqrt_floor() + ApInt::ONE;
+    let steps = m.to_usize().expect("order bound is too large");
+
+    // The baby steps `base^j -> j`, keeping the smallest `j` for each power.
+    let mut table = StepTable::new();
+    let mut power = ApInt::ONE % modulus;
+    for j in 0..steps {
+        table.entry(power.clone()).or_insert(j);
+        power = mul_mod(&power, &base, modulus);
+    }
+
+    // The giant steps `target * base^(-i * m)`, which match a baby step
+    // `base^j` exactly when `x = i * m + j`. The first match is the smallest.
+    let giant = pow_mod(&rem_euclid(&base_inv, modulus), &m, modulus);
+    let mut gamma = target;
+    for i in 0..steps {
+        if let Some(&j) = table.get(&gamma) {
+            let x = ApInt::from(i) * &m + ApInt::from(j);
+            return if x < *order_bound { Some(x) } else { None };
+        }
+        gamma = mul_mod(&gamma, &giant, modulus);
+    }
+    None
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "std")] {
+        type StepTable = crate::alloc::HashMap<ApInt, usize>;
+    } else {
+        type StepTable = crate::alloc::BTreeMap<ApInt, usize>;
+    }
+}
+
+/// Returns the terms `U_n` and `V_n` of the Lucas sequences with parameters
+/// `p` and `q`, modulo `modulus`.
+///
+/// The sequences are defined by `U_0 = 0`, `U_1 = 1`, `V_0 = 2`, `V_1 = p` and
+/// `X_k = p * X_(k - 1) - q * X_(k - 2)`. They are evaluated with a binary
+/// chain over the bits of `n`, which never divides by two and so works for
+/// any modulus.
+///
+/// # Panics
+///
+/// Panics if `n` is negative, or if `modulus` is not positive.
+///
+/// # Examples
+///
+/// ```
+/// use apa::number_theory::lucas_uv;
+/// use apa::ApInt;
+///
+/// // The Fibonacci and Lucas numbers have `p = 1` and `q = -1`.
+/// let (u, v) = lucas_uv(&ApInt::ONE, &-ApInt::ONE, &ApInt::from(10), &ApInt::from(1000));
+/// assert_eq!((u, v), (ApInt::from(55), ApInt::from(123)));
+/// ```
+pub fn lucas_uv(p: &ApInt, q: &ApInt, n: &ApInt, modulus: &ApInt) -> (ApInt, ApInt) {
+    assert_positive(modulus);
+    if n.is_negative() {
+        panic!("index must be non-negative");
+    }
+
+    let m = modulus;
+    let (p, q) = (rem_euclid(p, m), rem_euclid(q, m));
+
+    // The chain holds `(U_k, U_(k + 1))`, starting from `k = 0`, and uses:
+    //
+    //   U_(2k)     = U_k * (2 * U_(k + 1) - p * U_k)
+    //   U_(2k + 1) = U_(k + 1)^2 - q * U_k^2
+    //   U_(2k + 2) = U_(k + 1) * (p * U_(k + 1) - 2 * q * U_k)
+    let (mut a, mut b) = (ApInt::ZERO, ApInt::ONE % m);
+    for i in (0..n.significant_bits()).rev() {
+        let odd = rem_euclid(&(&b * &b - &q * &a * &a), m);
+        if n.bit(i) {
+            b = rem_euclid(&(&b * (&p * &b - ((&q * &a) << 1))), m);
+            a = odd;
+        } else {
+            a = rem_euclid(&(&a * ((&b << 1) - &p * &a)), m);
+            b = odd;
+        }
+    }
+
+    // `V_n = 2 * U_(n + 1) - p * U_n`.
+    let v = rem_euclid(&((&b << 1) - &p * &a), m);
+    (a, v)
+}
+
+/// Returns `true` if `n` is a strong probable prime to `base`, which is the
+/// single base Miller-Rabin test.
+///
+/// Writing `n - 1 = d * 2^s` with `d` odd, `n` passes if `base^d ≡ 1` or
+/// `base^(d * 2^r) ≡ -1 (mod n)` for some `0 <= r < s`. Every odd prime passes
+/// for every base, while an odd composite passes for at most a quarter of the
+/// bases. The base is reduced modulo `n`, and a base that is a multiple of `n`
+/// tests nothing, so `n` passes. Even `n` only pass if they are `2`, and `n`
+/// below `2` never pass.
+///
+/// This is the test repeated by the primality checks of the crate, with the
+/// primes below `43` as bases.
+///
+/// # Examples
+///
+/// ```
+/// use apa::number_theory::is_strong_probable_prime;
+/// use apa::ApInt;
+///
+/// // 2047 = 23 * 89 is the smallest strong pseudoprime to base 2.
+/// let n = ApInt::from(2047);
+/// assert!(is_strong_probable_prime(&n, &ApInt::from(2)));
+/// assert!(!is_strong_probable_prime(&n, &ApInt::from(3)));
+/// ```
+pub fn is_strong_probable_prime(n: &ApInt, base: &ApInt) -> bool {
+    if *n <= ApInt::TWO || n.is_even() {
+        return *n == ApInt::TWO;
+    }
+
+    let a = rem_euclid(base, n);
+    if a == ApInt::ZERO {
+        return true;
+    }
+
+    // `n` is odd, so `n - 1 = d * 2^s` with `s > 0`.
+    let n_minus_one = n - ApInt::ONE;
+    let s = n_minus_one.trailing_zeros().unwrap();
+    let d = &n_minus_one >> s;
+
+    let mut x = pow_mod(&a, &d, n);
+    if x == ApInt::ONE || x == n_minus_one {
+        return true;
+    }
+    for _ in 1..s {
+        x = mul_mod(&x, &x, n);
+        if x == n_minus_one {
+            return true;
+        }
+    }
+    false
+}
+
+/// Returns `true` if `n` is a strong Lucas probable prime, with the parameters
+/// chosen by Selfridge's method A.
+///
+/// The parameters are `p = 1` and `q = (1 - d) / 4`, where `d` is the first of
+/// `5, -7, 9, -11, ...` with a Jacobi symbol `(d / n)` of `-1`. Writing
+/// `n + 1 = k * 2^s` with `k` odd, `n` passes if `U_k ≡ 0` or
+/// `V_(k * 2^r) ≡ 0 (mod n)` for some `0 <= r < s`, using the sequences of
+/// [`lucas_uv`]. Every odd prime passes, and no composite is known to pass both
+/// this test and [`is_strong_probable_prime`] to base `2`, which together are
+/// the Baillie-PSW test. Even `n` only pass if they are `2`, and `n` below `2`
+/// never pass.
+///
+/// # Examples
+///
+/// ```
+/// use apa::number_theory::is_lucas_probable_prime;
+/// use apa::ApInt;
+///
+/// assert!(is_lucas_probable_prime(&ApInt::from(1_000_003)));
+/// // 5459 = 53 * 103 is the smallest strong Lucas pseudoprime.
+/// assert!(is_lucas_probable_prime(&ApInt::from(5459)));
+/// assert!(!is_lucas_probable_prime(&ApInt::from(5461)));
+/// ```
+pub fn is_lucas_probable_prime(n: &ApInt) -> bool {
+    if *n <= ApInt::TWO || n.is_even() {
+        return *n == ApInt::TWO;
+    }
+    // No `d` has a symbol of `-1` for a perfect square.
+    if n.is_perfect_square() {
+        return false;
+    }
+
+    let mut d = ApInt::from(5);
+    loop {
+        match jacobi(&d, n) {
+            -1 => break,
+            // `n` shares a factor with `d`, so is composite unless it is `|d|`.
+            0 if d.abs() != *n => return false,
+            _ => {}
+        }
+        d = if d.is_negative() {
+            ApInt::TWO - d
+        } else {
+            -(d + ApInt::TWO)
+        };
+    }
+    let q = (ApInt::ONE - &d) >> 2;
+
+    let n_plus_one = n + ApInt::ONE;
+    let s = n_plus_one.trailing_zeros().unwrap();
+    let k = &n_plus_one >> s;
+
+    let (u, mut v) = lucas_uv(&ApInt::ONE, &q, &k, n);
+    if u == ApInt::ZERO || v == ApInt::ZERO {
+        return true;
+    }
+    // `V_(2j) = V_j^2 - 2 * q^j`.
+    let mut q_pow = pow_mod(&rem_euclid(&q, n), &k, n);
+    for _ in 1..s {
+        v = rem_euclid(&(&v * &v - (&q_pow << 1)), n);
+        if v == ApInt::ZERO {
+            return true;
+        }
+        q_pow = mul_mod(&q_pow, &q_pow, n);
+    }
+    false
+}
+
+/// Returns the partial quotients of the continued fraction of `numer / denom`.
+///
+/// The first partial quotient is the floor of the fraction and may be zero
+/// or negative, while the rest are positive. The expansion is finite and the
+/// last convergent is the fraction in lowest terms.
+///
+/// # Panics
+///
+/// Panics if `denom` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use apa::number_theory::continued_fraction;
+/// use apa::ApInt;
+///
+/// let cf = continued_fraction(&ApInt::from(415), &ApInt::from(93));
+/// assert_eq!(cf, [4, 2, 6, 7].iter().map(|&a| ApInt::from(a)).collect::<Vec<_>>());
+/// ```
+pub fn continued_fraction(numer: &ApInt, denom: &ApInt) -> Vec<ApInt> {
+    if *denom == ApInt::ZERO {
+        panic!("attempt to divide by zero");
+    }
+
+    let (mut n, mut d) = if denom.is_negative() {
+        (-numer, -denom)
+    } else {
+        (numer.clone(), denom.clone())
+    };
+
+    let mut quotients = Vec::new();
+    while d != ApInt::ZERO {
+        let (mut q, mut r) = n.div_rem(&d);
+        // Round the quotient towards negative infinity, so that the remainder
+        // is non-negative.
+        if r.is_negative() {
+            q -= ApInt::ONE;
+            r += &d;
+        }
+        quotients.push(q);
+        n = d;
+        d = r;
+    }
+    quotients
+}
+
+/// Returns the continued fraction of the square root of `n`, as its first
+/// partial quotient and the period of the remaining partial quotients.
+///
+/// The square root of a non-square `n` has a periodic continued fraction
+/// `[a0; a1, a2, ..., a2, a1, 2 * a0]`, where the period ends with `2 * a0`.
+/// The period is empty if `n` is a perfect square.
+///
+/// # Panics
+///
+/// Panics if `n` is negative.
+///
+/// # Examples
+///
+/// ```
+/// use apa::number_theory::sqrt_continued_fraction;
+/// use apa::ApInt;
+///
+/// let (a0, period) = sqrt_continued_fraction(&ApInt::from(7));
+/// assert_eq!(a0, ApInt::from(2));
+/// assert_eq!(period, [1, 1, 1, 4].iter().map(|&a| ApInt::from(a)).collect::<Vec<_>>());
+/// ```
+pub fn sqrt_continued_fraction(n: &ApInt) -> (ApInt, Vec<ApInt>) {
+    if n.is_negative() {
+        panic!("attempt to take the square root of a negative number");
+    }
+    if *n == ApInt::ZERO {
+        return (ApInt::ZERO, Vec::new());
+    }
+
+    let a0 = n.sqrt_floor();
+    let mut period = Vec::new();
+    if &a0 * &a0 == *n {
+        return (a0, period);
+    }
+
+    // Each complete quotient is `(sqrt(n) + m) / d` for integers `m` and `d`.
+    let two_a0 = &a0 << 1;
+    let (mut m, mut d, mut a) = (ApInt::ZERO, ApInt::ONE, a0.clone());
+    while a != two_a0 {
+        m = &d * &a - &m;
+        d = (n - &m * &m) / &d;
+        a = (&a0 + &m) / &d;
+        period.push(a.clone());
+    }
+    (a0, period)
+}
+
+/// Returns an iterator over the convergents of the continued fraction with
+/// the partial quotients `quotients`.
+///
+/// Each convergent is yielded as a pair of a numerator and a positive
+/// denominator, in lowest terms. Periodic continued fractions can be passed
+/// as an infinite iterator of partial quotients.
+///
+/// # Examples
+///
+/// ```
+/// use apa::number_theory::{convergents, sqrt_continued_fraction};
+/// use apa::ApInt;
+///
+/// let (a0, period) = sqrt_continued_fraction(&ApInt::from(2));
+/// let quotients = std::iter::once(a0).chain(period.into_iter().cycle());
+///
+/// let (p, q) = convergents(quotients).nth(3).unwrap();
+/// assert_eq!((p, q), (ApInt::from(17), ApInt::from(12)));
+/// ```
+pub fn convergents<I>(quotients: I) -> Convergents<I::IntoIter>
+where
+    I: IntoIterator<Item = ApInt>,
+{
+    Convergents {
+        quotients: quotients.into_iter(),
+        prev: (ApInt::ZERO, ApInt::ONE),
+        curr: (ApInt::ONE, ApInt::ZERO),
+    }
+}
+
+/// An iterator over the convergents of a continued fraction.
+///
+/// This `struct` is created by [`convergents`].
+#[derive(Clone, Debug)]
+pub struct Convergents<I> {
+    quotients: I,
+    /// The convergent before the current convergent.
+    prev: (ApInt, ApInt),
+    /// The current convergent.
+    curr: (ApInt, ApInt),
+}
+
+impl<I> Iterator for Convergents<I>
+where
+    I: Iterator<Item = ApInt>,
+{
+    type Item = (ApInt, ApInt);
+
+    fn next(&mut self) -> Option<(ApInt, ApInt)> {
+        let a = self.quotients.next()?;
+
+        // `h[n] = a[n] * h[n - 1] + h[n - 2]`, and likewise for `k[n]`.
+        let next = (
+            &a * &self.curr.0 + &self.prev.0,
+            &a * &self.curr.1 + &self.prev.1,
+        );
+        self.prev = core::mem::replace(&mut self.curr, next);
+        Some(self.curr.clone())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.quotients.size_hint()
+    }
+}
+
+/// Returns the fundamental solution `(x, y)` of the Pell equation
+/// `x^2 - d * y^2 = 1`.
+///
+/// This is the solution in positive integers with the smallest `x`, and every
+/// other positive solution is a power of `x + y * sqrt(d)`. It is read off the
+/// convergent ending the first period of the continued fraction of `sqrt(d)`,
+/// or the second period if the first has odd length.
+///
+/// # Panics
+///
+/// Panics if `d` is not positive or is a perfect square, in which case the
+/// equation has no positive solutions.
+///
+/// # Examples
+///
+/// ```
+/// use apa::number_theory::pell;
+/// use apa::ApInt;
+///
+/// assert_eq!(pell(&ApInt::from(2)), (ApInt::from(3), ApInt::from(2)));
+///
+/// let (x, y) = pell(&ApInt::from(61));
+/// assert_eq!(x, ApInt::from(1_766_319_049));
+/// assert_eq!(y, ApInt::from(226_153_980));
+/// ```
+pub fn pell(d: &ApInt) -> (ApInt, ApInt) {
+    if *d <= ApInt::ZERO {
+        panic!("Pell equation with a non-positive `d` has no positive solutions");
+    }
+    let (a0, period) = sqrt_continued_fraction(d);
+    if period.is_empty() {
+        panic!("Pell equation with a square `d` has no positive solutions");
+    }
+
+    // With an odd period the convergent ending it solves `x^2 - d * y^2 = -1`
+    // instead, and the solution comes a period later.
+    let len = if period.len() % 2 == 0 {
+        period.len()
+    } else {
+        2 * period.len()
+    };
+    let quotients = core::iter::once(a0).chain(period.into_iter().cycle());
+    convergents(quotients)
+        .nth(len - 1)
+        .expect("periodic continued fractions are infinite")
+}
+
+/// Returns, for each of `moduli`, its gcd with the product of all the others.
+///
+/// This finds the moduli that share a factor with any other modulus, such as
+/// RSA moduli generated with a weak random number generator, far faster than
+/// taking the gcd of every pair. The product of the moduli is computed with a
+/// product tree and reduced back down it with a remainder tree, modulo the
+/// square of each node, after which `gcd(n, (P mod n^2) / n)` is the gcd of
+/// `n` and `P / n`.
+///
+/// # Panics
+///
+/// Panics if any of `moduli` is not positive.
+///
+/// # Examples
+///
+/// ```
+/// use apa::number_theory::batch_gcd;
+/// use apa::ApInt;
+///
+/// let moduli = [ApInt::from(7 * 11), ApInt::from(13 * 17), ApInt::from(11 * 19)];
+/// let gcds = batch_gcd(&moduli);
+/// assert_eq!(gcds, [ApInt::from(11), ApInt::from(1), ApInt::from(11)]);
+/// ```
+pub fn batch_gcd(moduli: &[ApInt]) -> Vec<ApInt> {
+    moduli.iter().for_each(assert_positive);
+
+    let tree = ApInt::product_tree(moduli);
+    let mut rems = match tree.last() {
+        Some(root) => root.clone(),
+        None => return Vec::new(),
+    };
+    // Each node of a level is the product of the nodes `2 * i` and `2 * i + 1`
+    // of the level below it, or a copy of node `2 * i` if it is the last.
+    for level in tree.iter().rev().skip(1) {
+        rems = level
+            .iter()
+            .enumerate()
+            .map(|(i, n)| &rems[i / 2] % &(n * n))
+            .collect();
+    }
+
+    moduli
+        .iter()
+        .zip(&rems)
+        .map(|(n, r)| n.gcd(&(r / n)))
+        .collect()
+}
+
+fn assert_positive(modulus: &ApInt) {
+    if *modulus <= ApInt::ZERO {
+        panic!("modulus must be positive");
+    }
+}
+
+/// Returns `a mod m` in the range `0..m`, for a positive `m`.
+pub(crate) fn rem_euclid(a: &ApInt, m: &ApInt) -> ApInt {
+    let r = a % m;
+    if r.is_negative() { r + m } else { r }
+}
+
+/// Returns the inverse of `a` modulo `m`, in the range `0..m`, or `None` if
+/// `a` and `m` are not coprime.
+pub(crate) fn inv_mod(a: &ApInt, m: &ApInt) -> Option<ApInt> {
+    let (g, x, _) = a.extended_gcd(m);
+    if g == ApInt::ONE {
+        Some(rem_euclid(&x, m))
+    } else {
+        None
+    }
+}
+
+/// Returns `a * b mod m`, for `a` and `b` in the range `0..m`.
+fn mul_mod(a: &ApInt, b: &ApInt, m: &ApInt) -> ApInt {
+    a * b % m
+}
+
+/// Returns `base^exp mod m`, for `base` in the range `0..m` and a non-negative
+/// `exp`.
+pub(crate) fn pow_mod(base: &ApInt, exp: &ApInt, m: &ApInt) -> ApInt {
+    let mut r = ApInt::ONE % m;
+    for i in (0..exp.significant_bits()).rev() {
+        r = mul_mod(&r, &r, m);
+        if exp.bit(i) {
+            r = mul_mod(&r, base, m);
+        }
+    }
+    r
+}
+
+/// Returns the Jacobi symbol `(a / n)`, for an odd positive `n`.
+fn jacobi(a: &ApInt, n: &ApInt) -> i32 {
+    let (mut a, mut n) = (rem_euclid(a, n), n.clone());
+    let mut result = 1;
+    while a != ApInt::ZERO {
+        // `(2 / n)` is `-1` when `n ≡ 3` or `5 (mod 8)`.
+        let twos = a.trailing_zeros().unwrap();
+        a >>= twos;
+        let n_mod_8 = u8::from(&n) & 7;
+        if twos % 2 == 1 && (n_mod_8 == 3 || n_mod_8 == 5) {
+            result = -result;
+        }
+        // Quadratic reciprocity flips the sign when both are `3 (mod 4)`.
+        if u8::from(&a) & 3 == 3 && n_mod_8 & 3 == 3 {
+            result = -result;
+        }
+        let r = &n % &a;
+        n = a;
+        a = r;
+    }
+    if n == ApInt::ONE { result } else { 0 }
+}
+
+/// Returns `true` if `n` is a prime.
+pub(crate) fn is_prime(n: &ApInt) -> bool {
+    if *n < ApInt::TWO {
+        return false;
+    }
+    // A multiple of a small prime is only prime if it is that prime.
+    if let Some(p) = small_prime_filter(n, SMALL_PRIMES.len()) {
+        return *n == ApInt::from(p);
+    }
+
+    SMALL_PRIMES[..MILLER_RABIN_BASES]
+        .iter()
+        .all(|&a| is_strong_probable_prime(n, &ApInt::from(a)))
+}
+
+/// Returns the prime factorization of `φ(n)`, given the factorization of `n`.
+fn totient_factors(n: &Factorization) -> Factorization {
+    let mut phi = Factorization::default();
+    // `φ(p^e) = p^(e - 1) * (p - 1)`.
+    for (p, e) in n.prime_powers() {
+        phi.push(p.clone(), e - 1);
+        for (q, f) in factorize(&(p - ApInt::ONE)).prime_powers {
+            phi.push(q, f);
+        }
+    }
+    phi.prime_powers.sort_by(|(p, _), (q, _)| p.cmp(q));
+    phi
+}
+
+/// Returns a non-trivial factor of the odd composite `n`, using Brent's
+/// variant of Pollard's rho algorithm.
+fn pollard_rho(n: &ApInt) -> ApInt {
+    // The number of steps between each gcd.
+    const BATCH: usize = 128;
+
+    let mut c = ApInt::ONE;
+    loop {
+        let f = |x: &ApInt| (x * x + &c) % n;
+        let diff = |x: &ApInt, y: &ApInt| (x - y).abs();
+
+        let mut y = ApInt::TWO;
+        let (mut x, mut ys) = (y.clone(), y.clone());
+        let (mut q, mut g) = (ApInt::ONE, ApInt::ONE);
+        let mut r = 1;
+
+        while g == ApInt::ONE {
+            x = y.clone();
+            for _ in 0..r {
+                y = f(&y);
+            }
+
+            let mut k = 0;
+            while k < r && g == ApInt::ONE {
+                ys = y.clone();
+                for _ in 0..BATCH.min(r - k) {
+                    y = f(&y);
+                    q = mul_mod(&q, &diff(&x, &y), n);
+                }
+                g = q.gcd(n);
+                k += BATCH;
+            }
+            r *= 2;
+        }
+
+        // The batched product hit zero, so retrace the last batch step by step.
+        if g == *n {
+            loop {
+                ys = f(&ys);
+                g = diff(&x, &ys).gcd(n);
+                if g != ApInt::ONE {
+                    break;
+                }
+            }
+        }
+
+        if g != *n {
+            return g;
+        }
+        c += ApInt::ONE;
+    }
+}
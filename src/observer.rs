@@ -0,0 +1,86 @@
+//! A process-wide hook observing the limb storage allocations of ints.
+
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+/// The kind of limb storage event reported to an allocation observer.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum AllocKind {
+    /// New limb storage was allocated.
+    Alloc,
+    /// Limb storage was resized from `old_bytes` bytes.
+    Realloc {
+        /// The size of the storage before it was resized.
+        old_bytes: usize,
+    },
+    /// Limb storage was freed.
+    Dealloc,
+}
+
+/// The observer, as a type-erased `fn(usize, AllocKind)`, or null if there is
+/// none.
+static OBSERVER: AtomicPtr<()> = AtomicPtr::new(core::ptr::null_mut());
+
+/// Sets the function called on every allocation, reallocation and
+/// deallocation of the limb storage of an [`ApInt`](crate::ApInt), replacing
+/// any previous observer.
+///
+/// The observer is passed the size in bytes of the allocated or freed storage,
+/// or the new size of reallocated storage, so that summing the allocated bytes
+/// and subtracting the freed bytes gives the memory held by ints. Memory an int
+/// takes ownership of, such as the buffer passed to
+/// [`ApInt::from_raw_parts`](crate::ApInt::from_raw_parts) or
+/// [`ApInt::from_limbs_le`](crate::ApInt::from_limbs_le), is reported as
+/// allocated, and memory an int gives up, such as by
+/// [`ApInt::into_raw_parts`](crate::ApInt::into_raw_parts), as freed.
+///
+/// The observer applies to the whole process and is called on the thread
+/// making the allocation. Temporary buffers used inside algorithms are not
+/// reported.
+///
+/// This function is only available with the `alloc-observer` feature.
+///
+/// # Examples
+///
+/// ```
+/// use core::sync::atomic::{AtomicIsize, Ordering};
+///
+/// use apa::{AllocKind, ApInt};
+///
+/// static LIVE: AtomicIsize = AtomicIsize::new(0);
+///
+/// fn observe(bytes: usize, kind: AllocKind) {
+///     let delta = match kind {
+///         AllocKind::Alloc => bytes as isize,
+///         AllocKind::Realloc { old_bytes } => bytes as isize - old_bytes as isize,
+///         AllocKind::Dealloc => -(bytes as isize),
+///     };
+///     LIVE.fetch_add(delta, Ordering::Relaxed);
+/// }
+///
+/// apa::set_alloc_observer(observe);
+/// let int = ApInt::from(u128::MAX) << 1000;
+/// assert!(LIVE.load(Ordering::Relaxed) >= int.memory_usage() as isize);
+/// drop(int);
+/// apa::clear_alloc_observer();
+/// ```
+pub fn set_alloc_observer(observer: fn(usize, AllocKind)) {
+    OBSERVER.store(observer as *mut (), Ordering::Release);
+}
+
+/// Removes the allocation observer set by [`set_alloc_observer`], if any.
+///
+/// This function is only available with the `alloc-observer` feature.
+pub fn clear_alloc_observer() {
+    OBSERVER.store(core::ptr::null_mut(), Ordering::Release);
+}
+
+/// Reports a limb storage event of `bytes` bytes to the observer, if any.
+#[inline]
+pub(crate) fn notify(bytes: usize, kind: AllocKind) {
+    let observer = OBSERVER.load(Ordering::Acquire);
+    if !observer.is_null() {
+        // SAFETY: A non-null observer was stored from a `fn(usize, AllocKind)`.
+        let observer = unsafe { core::mem::transmute::<*mut (), fn(usize, AllocKind)>(observer) };
+        observer(bytes, kind);
+    }
+}
@@ -0,0 +1,103 @@
+//! A process-wide hook called when the limb storage of an int cannot be
+//! allocated, and the error returned by the fallible operations instead.
+
+use core::alloc::Layout;
+use core::fmt;
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::alloc;
+
+/// The handler, as a type-erased `fn(Layout) -> !`, or null if there is none.
+static HANDLER: AtomicPtr<()> = AtomicPtr::new(core::ptr::null_mut());
+
+/// Sets the function called when the limb storage of an
+/// [`ApInt`](crate::ApInt) cannot be allocated, replacing any previous
+/// handler.
+///
+/// Without a handler, a failed allocation calls
+/// [`handle_alloc_error`](alloc::handle_alloc_error), which aborts the
+/// process on most targets. A handler can instead panic, so that a build with
+/// unwinding can catch the panic and degrade gracefully, or free memory held
+/// elsewhere and reset the system. The handler is passed the layout of the
+/// failed allocation and must not return.
+///
+/// The handler applies to the whole process, and also covers the temporary
+/// limbs used by multiplication and division. Other temporary buffers, such as
+/// those used by parsing and formatting, are allocated through the standard
+/// collections, so their failures still go to `handle_alloc_error`.
+///
+/// Operations that must not fail at all have `try_` variants, such as
+/// [`try_mul`](crate::ApInt::try_mul), which return an [`AllocError`] instead
+/// of calling the handler.
+///
+/// # Examples
+///
+/// ```
+/// use core::alloc::Layout;
+///
+/// fn out_of_memory(layout: Layout) -> ! {
+///     panic!("failed to allocate {} bytes of limbs", layout.size());
+/// }
+///
+/// apa::set_oom_handler(out_of_memory);
+/// ```
+pub fn set_oom_handler(handler: fn(Layout) -> !) {
+    HANDLER.store(handler as *mut (), Ordering::Release);
+}
+
+/// Removes the handler set by [`set_oom_handler`], if any, so that failed
+/// allocations call [`handle_alloc_error`](alloc::handle_alloc_error) again.
+pub fn clear_oom_handler() {
+    HANDLER.store(core::ptr::null_mut(), Ordering::Release);
+}
+
+/// The error returned by the `try_` operations of [`ApInt`](crate::ApInt) when
+/// the limbs of a result or of a temporary buffer cannot be allocated.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct AllocError {
+    layout: Layout,
+}
+
+impl AllocError {
+    #[inline]
+    pub(crate) const fn new(layout: Layout) -> AllocError {
+        AllocError { layout }
+    }
+
+    /// Returns the layout of the allocation that failed.
+    #[inline]
+    pub const fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// Reports the failure to the OOM handler, for the infallible operations.
+    #[cold]
+    pub(crate) fn handle(self) -> ! {
+        alloc_failed(self.layout)
+    }
+}
+
+impl fmt::Display for AllocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to allocate {} bytes of limbs",
+            self.layout.size()
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AllocError {}
+
+/// Reports the failure to allocate limb storage with `layout`.
+#[cold]
+pub(crate) fn alloc_failed(layout: Layout) -> ! {
+    let handler = HANDLER.load(Ordering::Acquire);
+    if !handler.is_null() {
+        // SAFETY: A non-null handler was stored from a `fn(Layout) -> !`.
+        let handler = unsafe { core::mem::transmute::<*mut (), fn(Layout) -> !>(handler) };
+        handler(layout);
+    }
+    alloc::handle_alloc_error(layout)
+}
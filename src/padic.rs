@@ -0,0 +1,114 @@
+//! p-adic utilities on [`ApInt`].
+//!
+//! Polynomials are given as slices of coefficients, with the constant term
+//! first, so `[c0, c1, c2]` is the polynomial `c0 + c1 * x + c2 * x^2`.
+
+use crate::alloc::Vec;
+use crate::apint::ApInt;
+use crate::number_theory::{inv_mod, rem_euclid};
+
+/// Returns the p-adic valuation of `n`, which is the exponent of the largest
+/// power of `p` dividing `n`, or `None` if `n` is zero.
+///
+/// # Panics
+///
+/// Panics if `p` is not greater than one.
+///
+/// # Examples
+///
+/// ```
+/// use apa::padic::valuation;
+/// use apa::ApInt;
+///
+/// assert_eq!(valuation(&ApInt::from(-72), &ApInt::from(3)), Some(2));
+/// assert_eq!(valuation(&ApInt::ZERO, &ApInt::from(3)), None);
+/// ```
+pub fn valuation(n: &ApInt, p: &ApInt) -> Option<u32> {
+    assert_base(p);
+    if *n == ApInt::ZERO {
+        return None;
+    }
+
+    let mut n = n.clone();
+    let mut v = 0;
+    loop {
+        let (q, r) = n.div_rem(p);
+        if r != ApInt::ZERO {
+            return Some(v);
+        }
+        n = q;
+        v += 1;
+    }
+}
+
+/// Lifts the root `root` of the polynomial `coeffs` modulo the prime `p` to the
+/// unique root modulo `p^k` congruent to it, by Hensel's lemma.
+///
+/// The root is lifted with Newton iteration, doubling the exponent of the
+/// modulus at each step. The result is in the range `0..p^k`.
+///
+/// Returns `None` if `root` is not a root modulo `p`, or if it is a multiple
+/// root modulo `p`, where the derivative of the polynomial vanishes and the
+/// lift need not exist or be unique.
+///
+/// # Panics
+///
+/// Panics if `p` is not greater than one, or if `k` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use apa::padic::lift_root;
+/// use apa::ApInt;
+///
+/// // A square root of 2 modulo 7^10, from the root 3 of x^2 - 2 modulo 7.
+/// let coeffs = [ApInt::from(-2), ApInt::ZERO, ApInt::ONE];
+/// let (p, m) = (ApInt::from(7), ApInt::from(7u64.pow(10)));
+/// let r = lift_root(&coeffs, &ApInt::from(3), &p, 10).unwrap();
+/// assert_eq!((&r * &r) % &m, ApInt::TWO);
+/// ```
+pub fn lift_root(coeffs: &[ApInt], root: &ApInt, p: &ApInt, k: u32) -> Option<ApInt> {
+    assert_base(p);
+    assert!(k > 0, "precision must be positive");
+
+    let derivative: Vec<ApInt> = coeffs
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, c)| c * ApInt::from(i))
+        .collect();
+
+    let mut r = rem_euclid(root, p);
+    if eval_mod(coeffs, &r, p) != ApInt::ZERO {
+        return None;
+    }
+    // The derivative is only checked modulo `p`, since if it is invertible
+    // modulo `p` it is invertible modulo every power of `p`.
+    inv_mod(&eval_mod(&derivative, &r, p), p)?;
+
+    // `r` is a root modulo `p^e`.
+    let mut e = 1;
+    while e < k {
+        e = e.saturating_mul(2).min(k);
+        let m = p.pow(e);
+
+        let f = eval_mod(coeffs, &r, &m);
+        let df = eval_mod(&derivative, &r, &m);
+        let inv = inv_mod(&df, &m).unwrap();
+        r = rem_euclid(&(r - f * inv), &m);
+    }
+    Some(r)
+}
+
+fn assert_base(p: &ApInt) {
+    assert!(*p > ApInt::ONE, "p must be greater than one");
+}
+
+/// Returns the value of the polynomial `coeffs` at `x` modulo `m`, in the
+/// range `0..m`.
+fn eval_mod(coeffs: &[ApInt], x: &ApInt, m: &ApInt) -> ApInt {
+    coeffs
+        .iter()
+        .rev()
+        .fold(ApInt::ZERO, |y, c| rem_euclid(&(y * x + c), m))
+}
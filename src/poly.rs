@@ -0,0 +1,105 @@
+//! Polynomial arithmetic over [`ApInt`] coefficients.
+//!
+//! Polynomials are given as slices of coefficients, with the constant term
+//! first, so `[c0, c1, c2]` is the polynomial `c0 + c1 * x + c2 * x^2`.
+
+use crate::alloc::Vec;
+use crate::apint::ApInt;
+use crate::limb::Limb;
+
+/// Returns the product of the polynomials `a` and `b`.
+///
+/// The product has `a.len() + b.len() - 1` coefficients, or none if either
+/// polynomial has none. Coefficients may be negative.
+///
+/// The product is computed by Kronecker substitution: each polynomial is
+/// packed into a single int by evaluating it at a power of two large enough
+/// to keep the coefficients of the product apart, the two ints are multiplied,
+/// and the coefficients are read back out of the bits of the result. This
+/// turns a large polynomial product into a single large multiplication.
+///
+/// # Examples
+///
+/// ```
+/// use apa::ApInt;
+/// use apa::poly;
+///
+/// // (1 + 2x)(3 - x) = 3 + 5x - 2x^2
+/// let a = [ApInt::from(1), ApInt::from(2)];
+/// let b = [ApInt::from(3), ApInt::from(-1)];
+/// assert_eq!(poly::mul(&a, &b), [ApInt::from(3), ApInt::from(5), ApInt::from(-2)]);
+/// ```
+pub fn mul(a: &[ApInt], b: &[ApInt]) -> Vec<ApInt> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+
+    // Each coefficient of the product is a sum of at most `min(a.len(),
+    // b.len())` products, so its magnitude is below `2^(s - 1)`, leaving a
+    // bit for the sign.
+    let terms = a.len().min(b.len());
+    let term_bits = 8 * core::mem::size_of::<usize>() - terms.leading_zeros() as usize;
+    let s = max_bits(a) + max_bits(b) + term_bits + 1;
+
+    let prod = pack(a, s) * pack(b, s);
+
+    let n = a.len() + b.len() - 1;
+    let mut fields = Vec::with_capacity(n);
+    unpack(prod, s, n, &mut fields);
+
+    // The fields are the unsigned digits of the product in base `2^s`, so
+    // convert them to balanced digits, which are the coefficients.
+    let half = ApInt::ONE << (s - 1);
+    let mut borrow = false;
+    for field in fields.iter_mut() {
+        if borrow {
+            *field += ApInt::ONE;
+        }
+        borrow = *field >= half;
+        if borrow {
+            *field -= &half << 1;
+        }
+    }
+    fields
+}
+
+/// Returns the number of bits in the largest magnitude of the coefficients.
+fn max_bits(coeffs: &[ApInt]) -> usize {
+    coeffs
+        .iter()
+        .map(|c| {
+            let mag = c.magnitude();
+            mag.last().map_or(0, |high| {
+                mag.len() * Limb::BITS - high.leading_zeros() as usize
+            })
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Returns the value of the polynomial `coeffs` at `2^s`.
+///
+/// The halves are packed separately, so that the total work is proportional
+/// to the size of the result times the log of the number of coefficients.
+fn pack(coeffs: &[ApInt], s: usize) -> ApInt {
+    match coeffs {
+        [c] => c.clone(),
+        _ => {
+            let h = coeffs.len() / 2;
+            pack(&coeffs[..h], s) + (pack(&coeffs[h..], s) << (h * s))
+        }
+    }
+}
+
+/// Pushes the low `n` digits of `x` in base `2^s`, each in the range `0..2^s`,
+/// to `out`, least significant digit first.
+fn unpack(x: ApInt, s: usize, n: usize, out: &mut Vec<ApInt>) {
+    if n == 1 {
+        out.push(x.low_bits(s));
+        return;
+    }
+    let h = n / 2;
+    let (low, high) = x.split_at_bit(h * s);
+    unpack(low, s, h, out);
+    unpack(high, s, n - h, out);
+}
@@ -0,0 +1,121 @@
+//! Interning of frequently recurring values.
+
+use crate::alloc::{Arc, BTreeSet};
+use crate::apint::{ApInt, pow10};
+
+/// A pool of interned ints, handing out shared handles so that each distinct
+/// value is stored once.
+///
+/// Interning a value returns an [`Arc`] to the pooled copy of it, so programs
+/// holding many copies of the same constants, such as the small ints and
+/// powers of ten of parsed expressions, keep a single allocation of each and
+/// can compare handles with [`Arc::ptr_eq`] instead of comparing limbs.
+///
+/// The pool holds a handle to every value it has interned until the value is
+/// [`purge`](IntPool::purge)d.
+///
+/// This is only available with the `pool` feature, which needs a target with
+/// pointer-width atomics for [`Arc`].
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use apa::{ApInt, IntPool};
+///
+/// let mut pool = IntPool::new();
+/// let a = pool.intern(ApInt::from(1000));
+/// let b = pool.intern_ref(&ApInt::from(1000));
+/// assert!(Arc::ptr_eq(&a, &b));
+/// assert!(Arc::ptr_eq(&a, &pool.pow10(3)));
+/// assert_eq!(pool.len(), 1);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct IntPool {
+    ints: BTreeSet<Arc<ApInt>>,
+}
+
+impl IntPool {
+    /// Creates an empty pool.
+    #[inline]
+    pub fn new() -> IntPool {
+        IntPool::default()
+    }
+
+    /// Returns the number of distinct values in the pool.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.ints.len()
+    }
+
+    /// Returns `true` if the pool holds no values.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.ints.is_empty()
+    }
+
+    /// Returns a handle to the pooled copy of `int`, adding it to the pool if
+    /// it is not already there.
+    pub fn intern(&mut self, int: ApInt) -> Arc<ApInt> {
+        if let Some(pooled) = self.ints.get(&int) {
+            return Arc::clone(pooled);
+        }
+        let pooled = Arc::new(int);
+        self.ints.insert(Arc::clone(&pooled));
+        pooled
+    }
+
+    /// Returns a handle to the pooled copy of `int`, adding a copy of it to
+    /// the pool if it is not already there.
+    ///
+    /// Unlike [`intern`](IntPool::intern), this only copies `int` when it is
+    /// new to the pool.
+    pub fn intern_ref(&mut self, int: &ApInt) -> Arc<ApInt> {
+        match self.ints.get(int) {
+            Some(pooled) => Arc::clone(pooled),
+            None => self.intern(int.clone()),
+        }
+    }
+
+    /// Returns a handle to the pooled copy of `int`, if there is one.
+    pub fn get(&self, int: &ApInt) -> Option<Arc<ApInt>> {
+        self.ints.get(int).cloned()
+    }
+
+    /// Returns a handle to the pooled `10^exp`, adding it to the pool if it is
+    /// not already there.
+    ///
+    /// The power is computed to look it up, so callers using it repeatedly
+    /// should keep the handle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `exp` does not fit in a `u32`.
+    pub fn pow10(&mut self, exp: usize) -> Arc<ApInt> {
+        self.intern(pow10(exp))
+    }
+
+    /// Removes the values that are only held by the pool, returning the
+    /// number removed.
+    ///
+    /// Values with handles outside the pool stay pooled, so handles to equal
+    /// values stay shared.
+    pub fn purge(&mut self) -> usize {
+        let len = self.ints.len();
+        self.ints = core::mem::take(&mut self.ints)
+            .into_iter()
+            .filter(|int| Arc::strong_count(int) > 1)
+            .collect();
+        len - self.ints.len()
+    }
+
+    /// Removes every value from the pool.
+    ///
+    /// Handles already handed out stay valid, but are no longer shared with
+    /// values interned afterwards.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.ints.clear();
+    }
+}
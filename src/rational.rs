@@ -0,0 +1,319 @@
+//! Exact rational numbers.
+//!
+//! A [`Rational`] is a fraction of two [`ApInt`]s, always kept in lowest terms
+//! with a positive denominator, so equal values have equal representations.
+//!
+//! # Examples
+//!
+//! ```
+//! use apa::rational::{harmonic, Rational};
+//! use apa::ApInt;
+//!
+//! let a = Rational::new(ApInt::from(1), ApInt::from(6));
+//! let b = Rational::new(ApInt::from(-3), ApInt::from(4));
+//! assert_eq!(&a + &b, Rational::new(ApInt::from(-7), ApInt::from(12)));
+//!
+//! assert_eq!(harmonic(4), Rational::new(ApInt::from(25), ApInt::from(12)));
+//! ```
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::alloc::Vec;
+use crate::apint::ApInt;
+
+/// A rational number of unbounded size.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Rational {
+    /// The numerator, which shares no factor with the denominator.
+    numer: ApInt,
+    /// The denominator, which is positive.
+    denom: ApInt,
+}
+
+impl Rational {
+    /// Creates the rational `numer / denom`, reduced to lowest terms.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `denom` is zero.
+    pub fn new(numer: ApInt, denom: ApInt) -> Rational {
+        if denom == ApInt::ZERO {
+            panic!("attempt to create a rational with a zero denominator");
+        }
+
+        let g = numer.gcd(&denom);
+        let (mut numer, mut denom) = if g == ApInt::ONE {
+            (numer, denom)
+        } else {
+            (numer / &g, denom / &g)
+        };
+        if denom.is_negative() {
+            numer.negate_to_fit();
+            denom.negate_to_fit();
+        }
+        Rational { numer, denom }
+    }
+
+    /// Returns the numerator, which has the sign of the rational.
+    #[inline]
+    pub fn numer(&self) -> &ApInt {
+        &self.numer
+    }
+
+    /// Returns the denominator, which is always positive.
+    #[inline]
+    pub fn denom(&self) -> &ApInt {
+        &self.denom
+    }
+
+    /// Returns the numerator and the denominator.
+    #[inline]
+    pub fn into_parts(self) -> (ApInt, ApInt) {
+        (self.numer, self.denom)
+    }
+
+    /// Returns `true` if the rational is an integer.
+    #[inline]
+    pub fn is_integer(&self) -> bool {
+        self.denom == ApInt::ONE
+    }
+
+    /// Returns the reciprocal of the rational.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rational is zero.
+    pub fn recip(&self) -> Rational {
+        if self.numer == ApInt::ZERO {
+            panic!("attempt to take the reciprocal of zero");
+        }
+        let (mut numer, mut denom) = (self.denom.clone(), self.numer.clone());
+        if denom.is_negative() {
+            numer.negate_to_fit();
+            denom.negate_to_fit();
+        }
+        Rational { numer, denom }
+    }
+}
+
+impl From<ApInt> for Rational {
+    fn from(int: ApInt) -> Rational {
+        Rational {
+            numer: int,
+            denom: ApInt::ONE,
+        }
+    }
+}
+
+impl PartialOrd for Rational {
+    #[inline]
+    fn partial_cmp(&self, other: &Rational) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Rational) -> Ordering {
+        // The denominators are positive, so cross multiplying keeps the order.
+        (&self.numer * &other.denom).cmp(&(&other.numer * &self.denom))
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+impl Add<&Rational> for &Rational {
+    type Output = Rational;
+
+    fn add(self, other: &Rational) -> Rational {
+        Rational::new(
+            &self.numer * &other.denom + &other.numer * &self.denom,
+            &self.denom * &other.denom,
+        )
+    }
+}
+
+impl Sub<&Rational> for &Rational {
+    type Output = Rational;
+
+    fn sub(self, other: &Rational) -> Rational {
+        Rational::new(
+            &self.numer * &other.denom - &other.numer * &self.denom,
+            &self.denom * &other.denom,
+        )
+    }
+}
+
+impl Mul<&Rational> for &Rational {
+    type Output = Rational;
+
+    fn mul(self, other: &Rational) -> Rational {
+        Rational::new(&self.numer * &other.numer, &self.denom * &other.denom)
+    }
+}
+
+impl Div<&Rational> for &Rational {
+    type Output = Rational;
+
+    fn div(self, other: &Rational) -> Rational {
+        if other.numer == ApInt::ZERO {
+            panic!("attempt to divide by zero");
+        }
+        Rational::new(&self.numer * &other.denom, &self.denom * &other.numer)
+    }
+}
+
+impl Neg for &Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        Rational {
+            numer: -&self.numer,
+            denom: self.denom.clone(),
+        }
+    }
+}
+
+macro_rules! forward_rational_binop {
+    ($($trait:ident, $fn:ident;)*) => {
+        $(
+            impl $trait<Rational> for &Rational {
+                type Output = Rational;
+
+                #[inline]
+                fn $fn(self, other: Rational) -> Rational {
+                    self.$fn(&other)
+                }
+            }
+
+            impl $trait<&Rational> for Rational {
+                type Output = Rational;
+
+                #[inline]
+                fn $fn(self, other: &Rational) -> Rational {
+                    (&self).$fn(other)
+                }
+            }
+
+            impl $trait<Rational> for Rational {
+                type Output = Rational;
+
+                #[inline]
+                fn $fn(self, other: Rational) -> Rational {
+                    (&self).$fn(&other)
+                }
+            }
+        )*
+    };
+}
+
+forward_rational_binop! {
+    Add, add;
+    Sub, sub;
+    Mul, mul;
+    Div, div;
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    #[inline]
+    fn neg(mut self) -> Rational {
+        self.numer.negate_to_fit();
+        self
+    }
+}
+
+/// Returns the `n`th harmonic number `1 + 1/2 + ... + 1/n`.
+///
+/// The sum is evaluated by binary splitting, which adds the fractions in a
+/// balanced tree and reduces only the final result, so the operands of each
+/// multiplication stay of similar size.
+///
+/// # Examples
+///
+/// ```
+/// use apa::rational::{harmonic, Rational};
+/// use apa::ApInt;
+///
+/// assert_eq!(harmonic(0), Rational::from(ApInt::ZERO));
+/// assert_eq!(harmonic(3), Rational::new(ApInt::from(11), ApInt::from(6)));
+/// ```
+pub fn harmonic(n: u64) -> Rational {
+    if n == 0 {
+        return Rational::from(ApInt::ZERO);
+    }
+    let (p, q) = harmonic_split(1, n + 1);
+    Rational::new(p, q)
+}
+
+/// Returns `(p, q)` with `p / q` the sum of `1/k` for `k` in `a..b`, where `q`
+/// is the product of the `k`.
+fn harmonic_split(a: u64, b: u64) -> (ApInt, ApInt) {
+    if b - a == 1 {
+        return (ApInt::ONE, ApInt::from(a));
+    }
+    let mid = a + (b - a) / 2;
+    let (p1, q1) = harmonic_split(a, mid);
+    let (p2, q2) = harmonic_split(mid, b);
+    (&p1 * &q2 + &p2 * &q1, &q1 * &q2)
+}
+
+/// Returns the `n`th Bernoulli number `B(n)`, with `B(1) = -1/2`.
+///
+/// The Bernoulli numbers of even index are computed exactly from the tangent
+/// numbers, which are integers found by a recurrence with `O(n^2)` small
+/// multiplications. The odd ones past `B(1)` are zero.
+///
+/// # Examples
+///
+/// ```
+/// use apa::rational::{bernoulli, Rational};
+/// use apa::ApInt;
+///
+/// assert_eq!(bernoulli(1), Rational::new(ApInt::from(-1), ApInt::from(2)));
+/// assert_eq!(bernoulli(12), Rational::new(ApInt::from(-691), ApInt::from(2730)));
+/// assert_eq!(bernoulli(13), Rational::from(ApInt::ZERO));
+/// ```
+pub fn bernoulli(n: u32) -> Rational {
+    match n {
+        0 => return Rational::from(ApInt::ONE),
+        1 => return Rational::new(ApInt::from(-1), ApInt::TWO),
+        _ if n % 2 == 1 => return Rational::from(ApInt::ZERO),
+        _ => {}
+    }
+
+    // The tangent numbers `T(1), ..., T(k)`, by the algorithm of Brent and
+    // Harvey, "Fast computation of Bernoulli, Tangent and Secant numbers".
+    let k = (n / 2) as usize;
+    let mut tangent: Vec<ApInt> = Vec::with_capacity(k);
+    tangent.push(ApInt::ONE);
+    for i in 1..k {
+        let next = &tangent[i - 1] * ApInt::from(i as u64);
+        tangent.push(next);
+    }
+    for i in 1..k {
+        for j in i..k {
+            tangent[j] = &tangent[j - 1] * ApInt::from((j - i) as u64)
+                + &tangent[j] * ApInt::from((j - i + 2) as u64);
+        }
+    }
+
+    // `B(2k) = (-1)^(k - 1) * 2k * T(k) / (4^k * (4^k - 1))`.
+    let four_k = ApInt::ONE << n as usize;
+    let mut numer = &tangent[k - 1] * ApInt::from(n);
+    if k % 2 == 0 {
+        numer.negate_to_fit();
+    }
+    let denom = &four_k * &(&four_k - ApInt::ONE);
+    Rational::new(numer, denom)
+}
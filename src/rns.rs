@@ -0,0 +1,144 @@
+//! Conversion between [`ApInt`] and residue number systems.
+//!
+//! A residue number system represents an int by its residues modulo a fixed
+//! set of pairwise coprime moduli, which here each fit in a `u64`. Addition,
+//! subtraction and multiplication then act on each residue independently, so
+//! a long computation can be split into independent single word pipelines and
+//! converted back to an int once at the end, as long as the result stays below
+//! the product of the moduli.
+
+use crate::alloc::Vec;
+use crate::apint::ApInt;
+use crate::number_theory::inv_mod;
+
+/// A set of pairwise coprime moduli, along with the constants needed to
+/// reconstruct an int from its residues by the Chinese remainder theorem.
+///
+/// # Examples
+///
+/// ```
+/// use apa::rns::Basis;
+/// use apa::ApInt;
+///
+/// let basis = Basis::new(vec![1_000_000_007, 998_244_353, 754_974_721]);
+///
+/// // Multiply residue-wise, then reconstruct the product.
+/// let (a, b) = (ApInt::from(123_456_789_012u64), ApInt::from(-987_654_321));
+/// let product: Vec<u64> = basis
+///     .to_residues(&a)
+///     .iter()
+///     .zip(basis.to_residues(&b))
+///     .zip(basis.moduli())
+///     .map(|((&x, y), &m)| (u128::from(x) * u128::from(y) % u128::from(m)) as u64)
+///     .collect();
+/// assert_eq!(basis.from_residues_signed(&product), &a * &b);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Basis {
+    moduli: Vec<u64>,
+    /// The product `M` of the moduli.
+    product: ApInt,
+    /// The cofactor `M / m` of each modulus `m`.
+    cofactors: Vec<ApInt>,
+    /// The inverse of each cofactor modulo its modulus.
+    inverses: Vec<u64>,
+}
+
+impl Basis {
+    /// Creates a basis of the given moduli, precomputing the constants used to
+    /// reconstruct ints.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `moduli` is empty, if any modulus is less than `2`, or if the
+    /// moduli are not pairwise coprime.
+    pub fn new(moduli: Vec<u64>) -> Basis {
+        assert!(!moduli.is_empty(), "basis must have a modulus");
+        assert!(moduli.iter().all(|&m| m >= 2), "moduli must be at least 2");
+
+        let ints: Vec<ApInt> = moduli.iter().map(|&m| ApInt::from(m)).collect();
+        let product = ApInt::product(ints.iter().cloned());
+        let cofactors: Vec<ApInt> = ints.iter().map(|m| &product / m).collect();
+
+        let inverses = cofactors
+            .iter()
+            .zip(&moduli)
+            .map(|(c, &m)| {
+                let inv = inv_mod(c, &ApInt::from(m)).expect("moduli must be pairwise coprime");
+                u64::from(&inv)
+            })
+            .collect();
+
+        Basis {
+            moduli,
+            product,
+            cofactors,
+            inverses,
+        }
+    }
+
+    /// Returns the moduli of the basis.
+    #[inline]
+    pub fn moduli(&self) -> &[u64] {
+        &self.moduli
+    }
+
+    /// Returns the product of the moduli, which bounds the range of ints that
+    /// can be represented.
+    #[inline]
+    pub fn product(&self) -> &ApInt {
+        &self.product
+    }
+
+    /// Returns the residues of `n` modulo each modulus of the basis, each in
+    /// the range `0..m`.
+    pub fn to_residues(&self, n: &ApInt) -> Vec<u64> {
+        // `rem_u64` gives the Euclidean remainder, so negative ints need no
+        // adjustment.
+        self.moduli.iter().map(|&m| n.rem_u64(m)).collect()
+    }
+
+    /// Returns the int in the range `0..M` with the given residues, where `M`
+    /// is the [`product`] of the moduli.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of residues differs from the number of moduli.
+    ///
+    /// [`product`]: Basis::product
+    pub fn from_residues(&self, residues: &[u64]) -> ApInt {
+        assert_eq!(
+            residues.len(),
+            self.moduli.len(),
+            "expected one residue per modulus"
+        );
+
+        // `sum((r * inv mod m) * M / m)` is congruent to each residue, and is
+        // less than `M` times the number of moduli.
+        let scaled: Vec<ApInt> = residues
+            .iter()
+            .zip(&self.inverses)
+            .zip(&self.moduli)
+            .map(|((&r, &inv), &m)| ApInt::from((r as u128 * inv as u128 % m as u128) as u64))
+            .collect();
+        ApInt::dot(&scaled, &self.cofactors) % &self.product
+    }
+
+    /// Returns the int in the range `-M/2..M/2` with the given residues, where
+    /// `M` is the [`product`] of the moduli, for computations whose results
+    /// may be negative.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of residues differs from the number of moduli.
+    ///
+    /// [`product`]: Basis::product
+    pub fn from_residues_signed(&self, residues: &[u64]) -> ApInt {
+        let n = self.from_residues(residues);
+        if &n + &n >= self.product {
+            n - &self.product
+        } else {
+            n
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! Secret ints with a fixed memory layout.
+
+use core::fmt;
+use core::num::NonZeroUsize;
+use core::ptr;
+
+use subtle::{Choice, ConstantTimeEq, ConstantTimeGreater, ConstantTimeLess};
+
+use crate::alloc::{Box, vec};
+use crate::apint::ApInt;
+use crate::limb::{Limb, LimbRepr};
+
+/// A secret integer stored in a fixed number of limbs.
+///
+/// An [`ApInt`] grows and shrinks its storage to fit its value, so the size
+/// of a secret can leak through the timing of its allocations, or through
+/// which allocations are reused. A `SecretInt` instead allocates a number of
+/// limbs chosen by the caller when it is created, and never reallocates. Its
+/// value is held in two's complement, and arithmetic wraps at the width of its
+/// limbs, as with machine integers.
+///
+/// The operations of a `SecretInt` run in time independent of its value, and
+/// its limbs are zeroed when it is dropped. Its [`Debug`](fmt::Debug) output
+/// shows only its width.
+///
+/// # Examples
+///
+/// ```
+/// use apa::{ApInt, SecretInt};
+/// use subtle::ConstantTimeGreater;
+///
+/// let m = SecretInt::from_int(&ApInt::from(13), 2).unwrap();
+/// let mut x = SecretInt::from_int(&ApInt::from(9), 2).unwrap();
+///
+/// // `(9 + 9) mod 13`, without branching on the sum.
+/// x.add_assign(&x.clone());
+/// x.ct_sub_assign_if(&m, !m.ct_gt(&x));
+/// assert_eq!(x.to_int(), ApInt::from(5));
+/// ```
+pub struct SecretInt {
+    limbs: Box<[LimbRepr]>,
+}
+
+impl SecretInt {
+    /// Creates a secret int with the value zero, stored in `limbs` limbs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limbs` is zero.
+    pub fn new(limbs: usize) -> SecretInt {
+        assert!(limbs > 0, "secret int must have a limb");
+        SecretInt {
+            limbs: vec![0; limbs].into_boxed_slice(),
+        }
+    }
+
+    /// Creates a secret int with the value of `value`, stored in `limbs` limbs,
+    /// or returns `None` if the value does not fit in that many limbs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limbs` is zero.
+    pub fn from_int(value: &ApInt, limbs: usize) -> Option<SecretInt> {
+        let mut secret = SecretInt::new(limbs);
+        let src = value.as_limbs();
+        if src.len() > limbs {
+            return None;
+        }
+
+        let ext = value.high_limb().sign_mask().repr();
+        for (i, dst) in secret.limbs.iter_mut().enumerate() {
+            *dst = src.get(i).map_or(ext, |limb| limb.repr());
+        }
+        Some(secret)
+    }
+
+    /// Returns the value of `self` as an [`ApInt`].
+    ///
+    /// The int is normalized, so its storage depends on the value, which is
+    /// then no longer protected.
+    pub fn to_int(&self) -> ApInt {
+        // SAFETY: A secret int always has at least one limb.
+        let len = unsafe { NonZeroUsize::new_unchecked(self.limbs.len()) };
+        let mut int = ApInt::zeroed(len);
+        for (dst, &src) in int.as_limbs_mut().iter_mut().zip(self.limbs.iter()) {
+            *dst = Limb(src);
+        }
+        int.normalize();
+        int
+    }
+
+    /// Returns the number of limbs `self` is stored in.
+    #[inline]
+    pub fn limbs(&self) -> usize {
+        self.limbs.len()
+    }
+
+    /// Adds `other` to `self`, wrapping at the width of `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` and `other` have different numbers of limbs.
+    pub fn add_assign(&mut self, other: &SecretInt) {
+        self.ct_add_assign_if(other, Choice::from(1));
+    }
+
+    /// Subtracts `other` from `self`, wrapping at the width of `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` and `other` have different numbers of limbs.
+    pub fn sub_assign(&mut self, other: &SecretInt) {
+        self.ct_sub_assign_if(other, Choice::from(1));
+    }
+
+    /// Adds `other` to `self` if `choice` is set, wrapping at the width of
+    /// `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` and `other` have different numbers of limbs.
+    pub fn ct_add_assign_if(&mut self, other: &SecretInt, choice: Choice) {
+        let mask = mask(choice);
+        let mut carry = 0;
+        let other = same_width(other, self.limbs.len());
+        for (a, &b) in self.limbs.iter_mut().zip(other) {
+            let (r, c1) = a.overflowing_add(b & mask);
+            let (r, c2) = r.overflowing_add(carry);
+            *a = r;
+            carry = (c1 | c2) as LimbRepr;
+        }
+    }
+
+    /// Subtracts `other` from `self` if `choice` is set, wrapping at the width
+    /// of `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` and `other` have different numbers of limbs.
+    pub fn ct_sub_assign_if(&mut self, other: &SecretInt, choice: Choice) {
+        let mask = mask(choice);
+        let mut borrow = 0;
+        let other = same_width(other, self.limbs.len());
+        for (a, &b) in self.limbs.iter_mut().zip(other) {
+            let (r, c1) = a.overflowing_sub(b & mask);
+            let (r, c2) = r.overflowing_sub(borrow);
+            *a = r;
+            borrow = (c1 | c2) as LimbRepr;
+        }
+    }
+
+    /// Replaces the value of `self` with that of `other` if `choice` is set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` and `other` have different numbers of limbs.
+    pub fn ct_assign_if(&mut self, other: &SecretInt, choice: Choice) {
+        let mask = mask(choice);
+        let other = same_width(other, self.limbs.len());
+        for (a, &b) in self.limbs.iter_mut().zip(other) {
+            *a ^= (*a ^ b) & mask;
+        }
+    }
+}
+
+impl Clone for SecretInt {
+    fn clone(&self) -> SecretInt {
+        SecretInt {
+            limbs: self.limbs.clone(),
+        }
+    }
+}
+
+impl Drop for SecretInt {
+    fn drop(&mut self) {
+        for limb in self.limbs.iter_mut() {
+            // SAFETY: `limb` is a valid reference. The write is volatile so
+            // that it is not removed as a dead store.
+            unsafe { ptr::write_volatile(limb, 0) };
+        }
+    }
+}
+
+impl fmt::Debug for SecretInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecretInt")
+            .field("limbs", &self.limbs.len())
+            .finish()
+    }
+}
+
+impl ConstantTimeEq for SecretInt {
+    /// Compares the values of `self` and `other`, which must have the same
+    /// number of limbs.
+    fn ct_eq(&self, other: &SecretInt) -> Choice {
+        let diff = self
+            .limbs
+            .iter()
+            .zip(same_width(other, self.limbs.len()))
+            .fold(0, |diff, (a, b)| diff | (a ^ b));
+        diff.ct_eq(&0)
+    }
+}
+
+impl ConstantTimeGreater for SecretInt {
+    /// Compares the values of `self` and `other`, which must have the same
+    /// number of limbs.
+    fn ct_gt(&self, other: &SecretInt) -> Choice {
+        // `self > other` exactly when `other - self` borrows, after flipping
+        // the sign bits to compare the two's complement values as unsigned.
+        let sign = !(LimbRepr::MAX >> 1);
+        let high = self.limbs.len() - 1;
+
+        let mut borrow = 0;
+        for (i, (&a, &b)) in self
+            .limbs
+            .iter()
+            .zip(same_width(other, self.limbs.len()))
+            .enumerate()
+        {
+            let flip = if i == high { sign } else { 0 };
+            let (r, c1) = (b ^ flip).overflowing_sub(a ^ flip);
+            let (_, c2) = r.overflowing_sub(borrow);
+            borrow = (c1 | c2) as LimbRepr;
+        }
+        Choice::from(borrow as u8)
+    }
+}
+
+impl ConstantTimeLess for SecretInt {}
+
+/// Returns the limbs of `other`, which must have `len` limbs.
+fn same_width(other: &SecretInt, len: usize) -> &[LimbRepr] {
+    assert_eq!(
+        other.limbs.len(),
+        len,
+        "secret ints must have the same number of limbs"
+    );
+    &other.limbs
+}
+
+/// Returns a limb of all ones if `choice` is set, and of all zeros otherwise.
+#[inline]
+fn mask(choice: Choice) -> LimbRepr {
+    (choice.unwrap_u8() as LimbRepr).wrapping_neg()
+}
@@ -0,0 +1,148 @@
+//! Evaluation of hypergeometric-like series by binary splitting.
+//!
+//! A series of the form
+//!
+//! ```text
+//! S = sum(a(n) / b(n) * (p(0) * ... * p(n)) / (q(0) * ... * q(n)) for n in 0..N)
+//! ```
+//!
+//! with small integer terms can be summed exactly far faster than term by
+//! term, by splitting the range of terms in half recursively and combining
+//! the halves with a few multiplications of balanced size. Most of the
+//! classical series for constants such as `e`, `pi` and `ln(2)` have this
+//! form.
+//!
+//! # Examples
+//!
+//! Summing `1/n!` for `e`:
+//!
+//! ```
+//! use apa::series::binary_split;
+//! use apa::ApInt;
+//!
+//! let one = |_| ApInt::ONE;
+//! let q = |n: u64| if n == 0 { ApInt::ONE } else { ApInt::from(n) };
+//! let sum = binary_split(30, one, one, one, q);
+//!
+//! // The first 30 terms of the series agree with `e` to 30 digits.
+//! let e = sum.scaled(&ApInt::from(10u128.pow(30)));
+//! assert_eq!(e.to_string(), "2718281828459045235360287471352");
+//! ```
+
+use crate::apint::ApInt;
+use crate::rational::Rational;
+
+/// The products accumulated by [`binary_split`] over a range of terms.
+///
+/// For the terms `n1..n2` these are the products `P` of `p(n)`, `Q` of `q(n)`
+/// and `B` of `b(n)`, and `T = B * Q * S` for the partial sum `S` of the
+/// range, where the `p(n)` and `q(n)` of a partial sum start from `n1`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BinarySplit {
+    /// The product of the `p(n)`.
+    pub p: ApInt,
+    /// The product of the `q(n)`.
+    pub q: ApInt,
+    /// The product of the `b(n)`.
+    pub b: ApInt,
+    /// The partial sum scaled by `b * q`.
+    pub t: ApInt,
+}
+
+impl BinarySplit {
+    /// Returns the sum exactly.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any `b(n)` or `q(n)` is zero.
+    pub fn to_rational(&self) -> Rational {
+        Rational::new(self.t.clone(), &self.b * &self.q)
+    }
+
+    /// Returns the sum multiplied by `scale`, rounded toward negative
+    /// infinity.
+    ///
+    /// With a power of ten or two as `scale`, this is the sum as a fixed
+    /// point number.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any `b(n)` or `q(n)` is zero.
+    pub fn scaled(&self, scale: &ApInt) -> ApInt {
+        (&self.t * scale).div_floor(&(&self.b * &self.q))
+    }
+}
+
+/// Sums the first `n` terms of the series with the term functions `a`, `b`,
+/// `p` and `q`, as described in the [module documentation](self).
+///
+/// Each term function is called once for each index in `0..n`. An empty sum
+/// is zero.
+///
+/// # Examples
+///
+/// ```
+/// use apa::rational::Rational;
+/// use apa::series::binary_split;
+/// use apa::ApInt;
+///
+/// // sum(1 / (n + 1) / 2^(n + 1)) converges to ln(2).
+/// let sum = binary_split(
+///     3,
+///     |_| ApInt::ONE,
+///     |n| ApInt::from(n + 1),
+///     |_| ApInt::ONE,
+///     |_| ApInt::TWO,
+/// );
+/// // 1/2 + 1/8 + 1/24
+/// assert_eq!(sum.to_rational(), Rational::new(ApInt::from(2), ApInt::from(3)));
+/// ```
+pub fn binary_split<A, B, P, Q>(n: u64, a: A, b: B, p: P, q: Q) -> BinarySplit
+where
+    A: Fn(u64) -> ApInt,
+    B: Fn(u64) -> ApInt,
+    P: Fn(u64) -> ApInt,
+    Q: Fn(u64) -> ApInt,
+{
+    if n == 0 {
+        return BinarySplit {
+            p: ApInt::ONE,
+            q: ApInt::ONE,
+            b: ApInt::ONE,
+            t: ApInt::ZERO,
+        };
+    }
+    split(0, n, &a, &b, &p, &q)
+}
+
+/// Returns the products over the terms `n1..n2`, which is not empty.
+fn split<A, B, P, Q>(n1: u64, n2: u64, a: &A, b: &B, p: &P, q: &Q) -> BinarySplit
+where
+    A: Fn(u64) -> ApInt,
+    B: Fn(u64) -> ApInt,
+    P: Fn(u64) -> ApInt,
+    Q: Fn(u64) -> ApInt,
+{
+    if n2 - n1 == 1 {
+        let p = p(n1);
+        return BinarySplit {
+            t: a(n1) * &p,
+            p,
+            q: q(n1),
+            b: b(n1),
+        };
+    }
+
+    let mid = n1 + (n2 - n1) / 2;
+    let left = split(n1, mid, a, b, p, q);
+    let right = split(mid, n2, a, b, p, q);
+
+    // The right half continues the products of `p` and `q` of the left half.
+    let t = &right.b * &right.q * &left.t + &left.b * &left.p * &right.t;
+    BinarySplit {
+        p: left.p * right.p,
+        q: left.q * right.q,
+        b: left.b * right.b,
+        t,
+    }
+}
@@ -0,0 +1,121 @@
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::{Mul, MulAssign, Neg};
+
+/// The sign of an integer.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Sign {
+    /// Less than zero.
+    Negative,
+    /// Equal to zero.
+    Zero,
+    /// Greater than zero.
+    Positive,
+}
+
+impl Sign {
+    /// Returns the sign of `n`.
+    #[inline]
+    pub const fn from_i8(n: i8) -> Sign {
+        if n < 0 {
+            Sign::Negative
+        } else if n == 0 {
+            Sign::Zero
+        } else {
+            Sign::Positive
+        }
+    }
+
+    /// Returns `-1`, `0` or `1` for a negative, zero or positive sign.
+    #[inline]
+    pub const fn to_i8(self) -> i8 {
+        match self {
+            Sign::Negative => -1,
+            Sign::Zero => 0,
+            Sign::Positive => 1,
+        }
+    }
+
+    /// Returns `true` if the sign is negative.
+    #[inline]
+    pub const fn is_negative(self) -> bool {
+        matches!(self, Sign::Negative)
+    }
+
+    /// Returns `true` if the sign is positive.
+    #[inline]
+    pub const fn is_positive(self) -> bool {
+        matches!(self, Sign::Positive)
+    }
+}
+
+impl Neg for Sign {
+    type Output = Sign;
+
+    #[inline]
+    fn neg(self) -> Sign {
+        match self {
+            Sign::Negative => Sign::Positive,
+            Sign::Zero => Sign::Zero,
+            Sign::Positive => Sign::Negative,
+        }
+    }
+}
+
+impl Mul for Sign {
+    type Output = Sign;
+
+    /// Returns the sign of the product of two values with signs `self` and
+    /// `other`.
+    #[inline]
+    fn mul(self, other: Sign) -> Sign {
+        match (self, other) {
+            (Sign::Zero, _) | (_, Sign::Zero) => Sign::Zero,
+            (a, b) if a == b => Sign::Positive,
+            _ => Sign::Negative,
+        }
+    }
+}
+
+impl MulAssign for Sign {
+    #[inline]
+    fn mul_assign(&mut self, other: Sign) {
+        *self = *self * other;
+    }
+}
+
+impl From<Ordering> for Sign {
+    /// Converts an ordering relative to zero into a sign.
+    #[inline]
+    fn from(ordering: Ordering) -> Sign {
+        match ordering {
+            Ordering::Less => Sign::Negative,
+            Ordering::Equal => Sign::Zero,
+            Ordering::Greater => Sign::Positive,
+        }
+    }
+}
+
+impl From<Sign> for Ordering {
+    /// Converts a sign into its ordering relative to zero.
+    #[inline]
+    fn from(sign: Sign) -> Ordering {
+        match sign {
+            Sign::Negative => Ordering::Less,
+            Sign::Zero => Ordering::Equal,
+            Sign::Positive => Ordering::Greater,
+        }
+    }
+}
+
+impl fmt::Display for Sign {
+    /// Writes the sign as `-`, `0` or `+`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Sign::Negative => "-",
+            Sign::Zero => "0",
+            Sign::Positive => "+",
+        };
+        f.pad(s)
+    }
+}
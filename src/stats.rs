@@ -0,0 +1,76 @@
+//! Per-thread counters of the work done by the library, for profiling.
+//!
+//! This module is only available with the `stats` feature. Each thread counts
+//! its own work, so a [`snapshot`] only reflects the operations made on the
+//! calling thread.
+//!
+//! Limb multiplications are counted in the multiplication kernels, which are
+//! also used by division. Allocations and reallocations count the limb
+//! storage of [`ApInt`](crate::ApInt) values, not temporary buffers used
+//! inside algorithms.
+
+use std::cell::Cell;
+
+/// The counters of a thread at a point in time.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Snapshot {
+    /// The number of limb by limb multiplications.
+    pub limb_muls: u64,
+    /// The number of limb storage allocations.
+    pub allocs: u64,
+    /// The number of limb storage reallocations.
+    pub reallocs: u64,
+}
+
+impl Snapshot {
+    /// Returns the counts of the work done between `earlier` and `self`.
+    ///
+    /// Each count saturates at zero rather than overflowing, so if the
+    /// counters were [`reset`] between the two snapshots, the counts may be
+    /// lower than the work actually done.
+    pub fn since(&self, earlier: &Snapshot) -> Snapshot {
+        Snapshot {
+            limb_muls: self.limb_muls.saturating_sub(earlier.limb_muls),
+            allocs: self.allocs.saturating_sub(earlier.allocs),
+            reallocs: self.reallocs.saturating_sub(earlier.reallocs),
+        }
+    }
+}
+
+thread_local! {
+    static STATS: Cell<Snapshot> = Cell::new(Snapshot::default());
+}
+
+/// Returns the counters of the current thread.
+pub fn snapshot() -> Snapshot {
+    STATS.with(Cell::get)
+}
+
+/// Resets the counters of the current thread to zero.
+pub fn reset() {
+    STATS.with(|stats| stats.set(Snapshot::default()));
+}
+
+#[inline]
+fn update(f: impl FnOnce(&mut Snapshot)) {
+    STATS.with(|stats| {
+        let mut snapshot = stats.get();
+        f(&mut snapshot);
+        stats.set(snapshot);
+    });
+}
+
+#[inline]
+pub(crate) fn record_limb_muls(n: usize) {
+    update(|stats| stats.limb_muls += n as u64);
+}
+
+#[inline]
+pub(crate) fn record_alloc() {
+    update(|stats| stats.allocs += 1);
+}
+
+#[inline]
+pub(crate) fn record_realloc() {
+    update(|stats| stats.reallocs += 1);
+}
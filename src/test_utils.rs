@@ -0,0 +1,176 @@
+//! Generators and property suites for testing code built on [`ApInt`].
+//!
+//! The properties are plain functions returning whether they hold, so they can
+//! be passed to a property testing library such as `quickcheck`, or driven by
+//! the seeded [`Gen`] with [`check_laws`].
+//!
+//! # Examples
+//!
+//! ```
+//! use apa::test_utils::{self, Gen};
+//!
+//! let mut gen = Gen::new(1);
+//! let (a, b, c) = (gen.int(4), gen.int(4), gen.int(4));
+//! assert!(test_utils::mul_distributes_over_add(&a, &b, &c));
+//!
+//! test_utils::check_laws(7, 100);
+//! ```
+
+use crate::alloc::Vec;
+use crate::{ApInt, LimbRepr};
+
+/// A seeded pseudorandom generator of ints.
+///
+/// The generator is deterministic, so a failing case can be reproduced from
+/// its seed. Generated ints favour the limb patterns most likely to expose
+/// carry and sign bugs, such as all zero and all one limbs.
+#[derive(Clone, Debug)]
+pub struct Gen {
+    state: u64,
+}
+
+impl Gen {
+    /// Creates a generator from a seed.
+    pub fn new(seed: u64) -> Gen {
+        // The xorshift state must be non-zero, so mix the seed first.
+        let state = seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1;
+        Gen { state }
+    }
+
+    /// Returns the next pseudorandom `u64`.
+    pub fn next_u64(&mut self) -> u64 {
+        // xorshift64*
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Returns a pseudorandom limb, biased towards edge patterns.
+    pub fn limb(&mut self) -> LimbRepr {
+        let bits = self.next_u64();
+        match bits % 8 {
+            0 => 0,
+            1 => LimbRepr::MAX,
+            2 => 1,
+            3 => LimbRepr::MAX >> 1,
+            _ => self.next_u64() as LimbRepr,
+        }
+    }
+
+    /// Returns a pseudorandom int of at most `max_limbs` limbs, read as two's
+    /// complement.
+    ///
+    /// A `max_limbs` of zero always gives zero.
+    pub fn int(&mut self, max_limbs: usize) -> ApInt {
+        if max_limbs == 0 {
+            return ApInt::ZERO;
+        }
+        let len = 1 + (self.next_u64() % max_limbs as u64) as usize;
+        let limbs: Vec<LimbRepr> = (0..len).map(|_| self.limb()).collect();
+        ApInt::from_limbs(&limbs)
+    }
+
+    /// Returns a pseudorandom non-zero int of at most `max_limbs` limbs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_limbs` is zero.
+    pub fn nonzero_int(&mut self, max_limbs: usize) -> ApInt {
+        assert!(max_limbs > 0, "a non-zero int needs at least one limb");
+        loop {
+            let int = self.int(max_limbs);
+            if int != ApInt::ZERO {
+                return int;
+            }
+        }
+    }
+}
+
+/// Returns whether `(a + b) + c == a + (b + c)`.
+pub fn add_associative(a: &ApInt, b: &ApInt, c: &ApInt) -> bool {
+    &(a + b) + c == a + &(b + c)
+}
+
+/// Returns whether `a + b == b + a`.
+pub fn add_commutative(a: &ApInt, b: &ApInt) -> bool {
+    a + b == b + a
+}
+
+/// Returns whether `(a * b) * c == a * (b * c)`.
+pub fn mul_associative(a: &ApInt, b: &ApInt, c: &ApInt) -> bool {
+    &(a * b) * c == a * &(b * c)
+}
+
+/// Returns whether `a * b == b * a`.
+pub fn mul_commutative(a: &ApInt, b: &ApInt) -> bool {
+    a * b == b * a
+}
+
+/// Returns whether `a * (b + c) == a * b + a * c`.
+pub fn mul_distributes_over_add(a: &ApInt, b: &ApInt, c: &ApInt) -> bool {
+    a * &(b + c) == &(a * b) + &(a * c)
+}
+
+/// Returns whether `(a - b) + b == a` and `(a + b) - b == a`.
+pub fn sub_inverts_add(a: &ApInt, b: &ApInt) -> bool {
+    &(a - b) + b == *a && &(a + b) - b == *a
+}
+
+/// Returns whether `(a / b) * b + a % b == a`, with the remainder having the
+/// sign of `a` and a smaller magnitude than `b`.
+///
+/// Holds trivially if `b` is zero.
+pub fn div_rem_identity(a: &ApInt, b: &ApInt) -> bool {
+    if *b == ApInt::ZERO {
+        return true;
+    }
+    let (q, r) = a.div_rem(b);
+    &(&q * b) + &r == *a
+        && r.abs() < b.abs()
+        && (r == ApInt::ZERO || r.is_negative() == a.is_negative())
+}
+
+/// Returns whether `a` survives a round trip through each of its text and
+/// byte encodings.
+pub fn round_trips(a: &ApInt) -> bool {
+    ApInt::from_hex(&a.to_hex()).as_ref() == Ok(a)
+        && ApInt::from_signed_bytes_le(&a.to_signed_bytes_le()) == *a
+        && ApInt::from_signed_bytes_be(&a.to_signed_bytes_be()) == *a
+        && ApInt::from_canonical_bytes(&a.to_canonical_bytes()).as_ref() == Ok(a)
+}
+
+/// Checks every property in this module on `cases` sets of ints generated from
+/// `seed`.
+///
+/// # Panics
+///
+/// Panics naming the property and the inputs for which it fails.
+#[track_caller]
+pub fn check_laws(seed: u64, cases: usize) {
+    const MAX_LIMBS: usize = 6;
+
+    let mut gen = Gen::new(seed);
+    for _ in 0..cases {
+        let (a, b, c) = (gen.int(MAX_LIMBS), gen.int(MAX_LIMBS), gen.int(MAX_LIMBS));
+
+        let check = |name: &str, holds: bool| {
+            assert!(
+                holds,
+                "`{}` failed for a = {}, b = {}, c = {}",
+                name, a, b, c
+            );
+        };
+        check("add_associative", add_associative(&a, &b, &c));
+        check("add_commutative", add_commutative(&a, &b));
+        check("mul_associative", mul_associative(&a, &b, &c));
+        check("mul_commutative", mul_commutative(&a, &b));
+        check(
+            "mul_distributes_over_add",
+            mul_distributes_over_add(&a, &b, &c),
+        );
+        check("sub_inverts_add", sub_inverts_add(&a, &b));
+        check("div_rem_identity", div_rem_identity(&a, &b));
+        check("round_trips", round_trips(&a));
+    }
+}
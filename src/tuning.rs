@@ -0,0 +1,287 @@
+//! Thresholds at which faster algorithms take over from simpler ones.
+//!
+//! Thresholds are measured in limbs, and apply to the whole process. The best
+//! values depend on the machine, so the defaults are only a starting point;
+//! [`tune`] measures the crossovers on the running machine.
+//!
+//! Multiplication of operands below the [`karatsuba_threshold`] uses the
+//! schoolbook method. Above it, balanced multiplications use Karatsuba
+//! multiplication, then Toom-3 multiplication from the [`toom_threshold`] and
+//! FFT multiplication from the [`fft_threshold`]. Each threshold applies to
+//! the shorter operand, and a much longer operand is multiplied in chunks the
+//! length of the shorter one.
+//!
+//! Division by a divisor of at least [`dc_div_threshold`] limbs uses
+//! Burnikel and Ziegler's divide and conquer division, and conversion of a
+//! value of at least [`dc_radix_threshold`] limbs to digits in a radix that is
+//! not a power of two splits it by powers of the radix. Parsing always
+//! combines its digits pairwise, so it has no threshold.
+//!
+//! Every threshold only affects performance, never results.
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// The default Karatsuba multiplication threshold.
+///
+/// The defaults were chosen for x86_64, and are used for every target since no
+/// other target has been measured. Other machines, such as POWER9 or aarch64,
+/// are likely to have different crossovers, which [`tune`] finds at startup.
+pub const DEFAULT_KARATSUBA_THRESHOLD: usize = 32;
+
+/// The default Toom-3 multiplication threshold.
+pub const DEFAULT_TOOM_THRESHOLD: usize = 192;
+
+/// The default FFT multiplication threshold.
+pub const DEFAULT_FFT_THRESHOLD: usize = 8192;
+
+/// The default divide and conquer division threshold.
+pub const DEFAULT_DC_DIV_THRESHOLD: usize = 128;
+
+/// The default divide and conquer radix conversion threshold.
+pub const DEFAULT_DC_RADIX_THRESHOLD: usize = 48;
+
+/// The smallest threshold at which Karatsuba multiplication can split its
+/// operands.
+const MIN_KARATSUBA_THRESHOLD: usize = 2;
+
+/// The smallest threshold at which Toom-3 multiplication splits its operands
+/// into three non-empty parts.
+const MIN_TOOM_THRESHOLD: usize = 3;
+
+/// The smallest threshold at which the halves of a divisor split by divide and
+/// conquer division are at least two limbs long.
+const MIN_DC_DIV_THRESHOLD: usize = 4;
+
+/// The smallest threshold at which radix conversion has a value to split.
+const MIN_DC_RADIX_THRESHOLD: usize = 2;
+
+static KARATSUBA_THRESHOLD: AtomicUsize = AtomicUsize::new(DEFAULT_KARATSUBA_THRESHOLD);
+static TOOM_THRESHOLD: AtomicUsize = AtomicUsize::new(DEFAULT_TOOM_THRESHOLD);
+static FFT_THRESHOLD: AtomicUsize = AtomicUsize::new(DEFAULT_FFT_THRESHOLD);
+static DC_DIV_THRESHOLD: AtomicUsize = AtomicUsize::new(DEFAULT_DC_DIV_THRESHOLD);
+static DC_RADIX_THRESHOLD: AtomicUsize = AtomicUsize::new(DEFAULT_DC_RADIX_THRESHOLD);
+
+/// Returns the number of limbs both operands of a multiplication must have
+/// for Karatsuba multiplication to be used.
+#[inline]
+pub fn karatsuba_threshold() -> usize {
+    KARATSUBA_THRESHOLD.load(Ordering::Relaxed)
+}
+
+/// Sets the number of limbs both operands of a multiplication must have for
+/// Karatsuba multiplication to be used.
+///
+/// Thresholds below 2 are treated as 2, and `usize::MAX` disables Karatsuba
+/// multiplication, along with Toom-3 and FFT multiplication.
+#[inline]
+pub fn set_karatsuba_threshold(limbs: usize) {
+    KARATSUBA_THRESHOLD.store(limbs.max(MIN_KARATSUBA_THRESHOLD), Ordering::Relaxed);
+}
+
+/// Returns the number of limbs both operands of a multiplication must have
+/// for Toom-3 multiplication to be used.
+#[inline]
+pub fn toom_threshold() -> usize {
+    TOOM_THRESHOLD.load(Ordering::Relaxed)
+}
+
+/// Sets the number of limbs both operands of a multiplication must have for
+/// Toom-3 multiplication to be used.
+///
+/// Thresholds below 3 are treated as 3, and `usize::MAX` disables Toom-3
+/// multiplication. Operands below the Karatsuba threshold always use the
+/// schoolbook method, whatever this threshold.
+#[inline]
+pub fn set_toom_threshold(limbs: usize) {
+    TOOM_THRESHOLD.store(limbs.max(MIN_TOOM_THRESHOLD), Ordering::Relaxed);
+}
+
+/// Returns the number of limbs both operands of a multiplication must have
+/// for FFT multiplication to be used.
+#[inline]
+pub fn fft_threshold() -> usize {
+    FFT_THRESHOLD.load(Ordering::Relaxed)
+}
+
+/// Sets the number of limbs both operands of a multiplication must have for
+/// FFT multiplication to be used.
+///
+/// `usize::MAX` disables FFT multiplication. Operands below the Karatsuba
+/// threshold always use the schoolbook method, whatever this threshold.
+#[inline]
+pub fn set_fft_threshold(limbs: usize) {
+    FFT_THRESHOLD.store(limbs, Ordering::Relaxed);
+}
+
+/// Returns the number of limbs a divisor must have for divide and conquer
+/// division to be used.
+#[inline]
+pub fn dc_div_threshold() -> usize {
+    DC_DIV_THRESHOLD.load(Ordering::Relaxed)
+}
+
+/// Sets the number of limbs a divisor must have for divide and conquer
+/// division to be used.
+///
+/// Thresholds below 4 are treated as 4, and `usize::MAX` disables divide and
+/// conquer division.
+#[inline]
+pub fn set_dc_div_threshold(limbs: usize) {
+    DC_DIV_THRESHOLD.store(limbs.max(MIN_DC_DIV_THRESHOLD), Ordering::Relaxed);
+}
+
+/// Returns the number of limbs a value must have for its conversion to digits
+/// to be split by powers of the radix.
+#[inline]
+pub fn dc_radix_threshold() -> usize {
+    DC_RADIX_THRESHOLD.load(Ordering::Relaxed)
+}
+
+/// Sets the number of limbs a value must have for its conversion to digits to
+/// be split by powers of the radix.
+///
+/// Thresholds below 2 are treated as 2, and `usize::MAX` disables divide and
+/// conquer radix conversion.
+#[inline]
+pub fn set_dc_radix_threshold(limbs: usize) {
+    DC_RADIX_THRESHOLD.store(limbs.max(MIN_DC_RADIX_THRESHOLD), Ordering::Relaxed);
+}
+
+/// The thresholds measured by [`tune`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Thresholds {
+    /// The Karatsuba multiplication threshold.
+    pub karatsuba: usize,
+    /// The Toom-3 multiplication threshold.
+    pub toom: usize,
+    /// The FFT multiplication threshold.
+    pub fft: usize,
+    /// The divide and conquer division threshold.
+    pub dc_div: usize,
+    /// The divide and conquer radix conversion threshold.
+    pub dc_radix: usize,
+}
+
+/// Measures the crossover of every algorithm on the running machine, sets
+/// the thresholds to them and returns them.
+///
+/// A crossover beyond the sizes measured disables the algorithm. The divide
+/// and conquer division threshold is instead the one that divides a large
+/// dividend fastest, since that algorithm only gains through its recursion.
+///
+/// This takes a fraction of a second, and is meant to be called once at
+/// startup. Other threads using the thresholds while it runs get correct
+/// results, but disturb the measurements.
+#[cfg(feature = "std")]
+pub fn tune() -> Thresholds {
+    use std::time::{Duration, Instant};
+
+    use crate::alloc::{Vec, vec};
+    use crate::apint::radix::to_radix_digits_le;
+    use crate::limb::Limb;
+    use crate::ll;
+
+    /// Returns the best time of several runs of `f`.
+    fn time(runs: usize, mut f: impl FnMut()) -> Duration {
+        (0..runs)
+            .map(|_| {
+                let start = Instant::now();
+                f();
+                start.elapsed()
+            })
+            .min()
+            .unwrap()
+    }
+
+    /// Returns the first of `sizes` at which setting the threshold to the size
+    /// with `set` makes `run` faster than with the threshold disabled, or
+    /// `usize::MAX` if there is none.
+    fn crossover(sizes: &[usize], set: fn(usize), mut run: impl FnMut(usize)) -> usize {
+        sizes
+            .iter()
+            .copied()
+            .find(|&n| {
+                // Larger sizes take longer, so need fewer runs to settle.
+                let runs = (4096 / n).max(2).min(16);
+                set(n);
+                let with = time(runs, || run(n));
+                set(usize::MAX);
+                with < time(runs, || run(n))
+            })
+            .unwrap_or(usize::MAX)
+    }
+
+    const MAX: usize = 8192;
+
+    // Operands filled with xorshift noise, as a stand in for typical values.
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let limbs: Vec<Limb> = (0..2 * MAX)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            Limb(state as _)
+        })
+        .collect();
+    let mut w = vec![Limb::ZERO; 2 * MAX];
+    let mut mul = |n: usize| {
+        let (x, y) = (&limbs[..n], &limbs[MAX..MAX + n]);
+        ll::mul(&mut w[..2 * n], x, y);
+    };
+
+    // Each multiplication threshold is measured with the thresholds below it
+    // already set, so that it is the first size at which a single level of the
+    // algorithm beats the one below.
+    set_toom_threshold(usize::MAX);
+    set_fft_threshold(usize::MAX);
+    let karatsuba = crossover(
+        &[8, 12, 16, 24, 32, 48, 64, 96, 128],
+        set_karatsuba_threshold,
+        &mut mul,
+    );
+    set_karatsuba_threshold(karatsuba);
+    let toom = crossover(
+        &[48, 64, 96, 128, 192, 256, 384, 512],
+        set_toom_threshold,
+        &mut mul,
+    );
+    set_toom_threshold(toom);
+    let fft = crossover(
+        &[1024, 1536, 2048, 3072, 4096, 6144, 8192],
+        set_fft_threshold,
+        &mut mul,
+    );
+    set_fft_threshold(fft);
+
+    // Divide and conquer division only pays off through its recursion, since a
+    // single level does as much schoolbook division as it saves, so rather
+    // than a crossover its threshold is the one that divides a large dividend
+    // fastest.
+    let n = 2048;
+    let (mut q, mut r) = (vec![Limb::ZERO; n + 1], vec![Limb::ZERO; n]);
+    let mut divide = || ll::divrem(&mut q, &mut r, &limbs[..2 * n], &limbs[MAX..MAX + n]);
+    set_dc_div_threshold(usize::MAX);
+    let mut best = (time(2, &mut divide), usize::MAX);
+    for &threshold in &[32, 64, 128, 256, 512, 1024] {
+        set_dc_div_threshold(threshold);
+        best = best.min((time(2, &mut divide), threshold));
+    }
+    set_dc_div_threshold(best.1);
+
+    let dc_radix = crossover(
+        &[8, 12, 16, 24, 32, 48, 64, 96, 128],
+        set_dc_radix_threshold,
+        |n| {
+            to_radix_digits_le(&limbs[..n], 10);
+        },
+    );
+    set_dc_radix_threshold(dc_radix);
+
+    Thresholds {
+        karatsuba: karatsuba_threshold(),
+        toom: toom_threshold(),
+        fft: fft_threshold(),
+        dc_div: dc_div_threshold(),
+        dc_radix: dc_radix_threshold(),
+    }
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "alloc-observer")]
+
+use std::sync::atomic::{AtomicIsize, AtomicUsize, Ordering};
+
+use apa::{AllocKind, ApInt, Sign};
+
+static LIVE: AtomicIsize = AtomicIsize::new(0);
+static REALLOCS: AtomicUsize = AtomicUsize::new(0);
+
+fn observe(bytes: usize, kind: AllocKind) {
+    let delta = match kind {
+        AllocKind::Alloc => bytes as isize,
+        AllocKind::Realloc { old_bytes } => {
+            REALLOCS.fetch_add(1, Ordering::Relaxed);
+            bytes as isize - old_bytes as isize
+        }
+        AllocKind::Dealloc => -(bytes as isize),
+    };
+    LIVE.fetch_add(delta, Ordering::Relaxed);
+}
+
+// The observer is global, so every check is made in a single test.
+#[test]
+fn tracks_live_bytes() {
+    apa::set_alloc_observer(observe);
+
+    let mut ints: Vec<ApInt> = (1..50usize).map(|i| ApInt::from(-7) << (i * 37)).collect();
+    let held: usize = ints.iter().map(ApInt::memory_usage).sum();
+    assert_eq!(LIVE.load(Ordering::Relaxed), held as isize);
+
+    // Shrinking in place reallocates.
+    for int in &mut ints {
+        *int >>= 100;
+    }
+    let held: usize = ints.iter().map(ApInt::memory_usage).sum();
+    assert_eq!(LIVE.load(Ordering::Relaxed), held as isize);
+
+    let mut acc = ApInt::from(u128::MAX) << 500;
+    let before = REALLOCS.load(Ordering::Relaxed);
+    acc.add_mul_assign(&ints[40], &ints[48]);
+    assert!(REALLOCS.load(Ordering::Relaxed) > before);
+
+    drop(ints);
+    drop(acc);
+    assert_eq!(LIVE.load(Ordering::Relaxed), 0);
+
+    // Buffers moving between vectors and ints.
+    let int = ApInt::from_limbs_le(vec![1, 2, 3], Sign::Negative);
+    assert_eq!(LIVE.load(Ordering::Relaxed), int.memory_usage() as isize);
+    drop(int);
+    assert_eq!(LIVE.load(Ordering::Relaxed), 0);
+
+    drop(ApInt::from_u32_limbs_le(
+        vec![1, 2, 3, 4, 5],
+        Sign::Positive,
+    ));
+    drop(ApInt::from_limbs_le(vec![7], Sign::Positive));
+    assert_eq!(LIVE.load(Ordering::Relaxed), 0);
+
+    let (limbs, _) = (ApInt::from(-3) << 300).into_limbs_le();
+    assert_eq!(LIVE.load(Ordering::Relaxed), 0);
+    drop(limbs);
+    drop((ApInt::from(u128::MAX) << 100).into_u32_limbs_le());
+    drop(ApInt::from(5).into_limbs_le());
+    assert_eq!(LIVE.load(Ordering::Relaxed), 0);
+
+    let ints = [
+        ApInt::from(-9),
+        ApInt::from(u128::MAX),
+        ApInt::from(1) << 400,
+    ];
+    let base = LIVE.load(Ordering::Relaxed);
+    for int in &ints {
+        let held = int.memory_usage() as isize;
+        let (ptr, len) = int.clone().into_raw_parts();
+        assert_eq!(LIVE.load(Ordering::Relaxed), base);
+        // SAFETY: The parts came from `into_raw_parts` and are used once.
+        let back = unsafe { ApInt::from_raw_parts(ptr, len) };
+        assert_eq!(LIVE.load(Ordering::Relaxed), base + held);
+        assert_eq!(back, *int);
+        drop(back);
+        assert_eq!(LIVE.load(Ordering::Relaxed), base);
+    }
+    drop(ints);
+    assert_eq!(LIVE.load(Ordering::Relaxed), 0);
+
+    apa::clear_alloc_observer();
+    let _ = ApInt::from(u128::MAX) << 1000;
+    assert_eq!(LIVE.load(Ordering::Relaxed), 0);
+}
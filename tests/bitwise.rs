@@ -0,0 +1,465 @@
+use apa::ApInt;
+
+mod qc;
+
+macro_rules! quickcheck_not {
+    ($($ty:ident as $wide:ident),* $(,)*) => {
+        $(
+            paste::item! {
+               #[test]
+               fn [< prop_not_ $ty >] () {
+                    fn prop(n: $ty) -> bool {
+                        let expected = ApInt::from(!(n as $wide));
+
+                        let mut int = ApInt::from(n);
+                        int.bitnot_assign();
+
+                        !ApInt::from(n) == expected && int == expected
+                    }
+                    qc::quickcheck(prop as fn($ty) -> bool)
+               }
+            }
+        )*
+    };
+}
+
+#[rustfmt::skip]
+quickcheck_not!(
+    u8 as i16, u16 as i32, u32 as i64, u64 as i128, usize as i128,
+    i8 as i8, i16 as i16, i32 as i32, i64 as i64, i128 as i128, isize as isize,
+);
+
+#[test]
+fn not_not() {
+    let int = ApInt::from(u128::MAX);
+    assert_eq!(!!int.clone(), int);
+}
+
+macro_rules! quickcheck_bitops {
+    ($($ty:ident),* $(,)*) => {
+        $(
+            paste::item! {
+               #[test]
+               fn [< prop_bitops_ $ty >] () {
+                    fn prop(l: $ty, r: $ty) -> bool {
+                        let (li, ri) = (ApInt::from(l), ApInt::from(r));
+
+                        &li & &ri == ApInt::from(l & r)
+                            && &li | &ri == ApInt::from(l | r)
+                            && li ^ ri == ApInt::from(l ^ r)
+                    }
+                    qc::quickcheck(prop as fn($ty, $ty) -> bool)
+               }
+
+               #[test]
+               fn [< prop_bitops_mixed_ $ty >] () {
+                    fn prop(l: $ty, r: i8) -> bool {
+                        // Mismatched lengths sign extend the shorter operand.
+                        let (li, ri) = (ApInt::from(l), ApInt::from(r));
+                        let (lw, rw) = (l as i128, r as i128);
+
+                        let mut and = li.clone();
+                        and &= &ri;
+                        let mut or = li.clone();
+                        or |= &ri;
+                        let mut xor = li;
+                        xor ^= ri;
+
+                        and == ApInt::from(lw & rw) && or == ApInt::from(lw | rw) && xor == ApInt::from(lw ^ rw)
+                    }
+                    qc::quickcheck(prop as fn($ty, i8) -> bool)
+               }
+            }
+        )*
+    };
+}
+
+#[rustfmt::skip]
+quickcheck_bitops!(
+    u8, u16, u32, u64, usize,
+    i8, i16, i32, i64, i128, isize,
+);
+
+macro_rules! quickcheck_shifts {
+    ($($ty:ident),* $(,)*) => {
+        $(
+            paste::item! {
+               #[test]
+               fn [< prop_shifts_ $ty >] () {
+                    fn prop(n: $ty, bits: u8) -> bool {
+                        let int = ApInt::from(n);
+                        let wide = n as i128;
+
+                        let shl = bits as usize % 63;
+                        let shr = bits as usize;
+
+                        let mut assign = int.clone();
+                        assign <<= shl;
+                        assign >>= shl;
+
+                        &int << shl == ApInt::from(wide << shl)
+                            && &int >> shr == ApInt::from(wide >> shr.min(127))
+                            && assign == int
+                    }
+                    qc::quickcheck(prop as fn($ty, u8) -> bool)
+               }
+            }
+        )*
+    };
+}
+
+#[rustfmt::skip]
+quickcheck_shifts!(
+    u8, u16, u32, u64,
+    i8, i16, i32, i64,
+);
+
+#[test]
+fn prop_shift_round_trip() {
+    fn prop(n: i128, bits: u16) -> bool {
+        let int = ApInt::from(n);
+        let bits = bits as usize;
+        (&int << bits) >> bits == int
+    }
+    qc::quickcheck(prop as fn(i128, u16) -> bool)
+}
+
+#[test]
+fn shr_negative() {
+    assert_eq!(ApInt::from(-1) >> 1000, ApInt::from(-1));
+    assert_eq!(ApInt::from(i128::MIN) >> 127, ApInt::from(-1));
+    assert_eq!(ApInt::from(-5) >> 1, ApInt::from(-3));
+}
+
+#[test]
+fn checked_shifts() {
+    let int = ApInt::from(-3);
+    // The budget limits the bits of the result, not the shift amount.
+    assert_eq!(int.checked_shl(62, 64), Some(ApInt::from(-3i128 << 62)));
+    assert_eq!(int.checked_shl(63, 64), None);
+    assert_eq!(int.checked_shl(usize::MAX, usize::MAX), None);
+    assert_eq!(ApInt::ZERO.checked_shl(usize::MAX, 0), Some(ApInt::ZERO));
+    assert_eq!(int.checked_shr(1, 64), Some(ApInt::from(-2)));
+    assert_eq!(int.checked_shr(65, 64), None);
+}
+
+#[test]
+fn checked_shl_by() {
+    let int = ApInt::from(-3);
+    assert_eq!(
+        int.checked_shl_by(&ApInt::from(62), 64),
+        Some(ApInt::from(-3i128 << 62))
+    );
+    assert_eq!(int.checked_shl_by(&ApInt::from(63), 64), None);
+    assert_eq!(int.checked_shl_by(&ApInt::NEG_ONE, 64), None);
+    assert_eq!(int.checked_shl_by(&(ApInt::ONE << 200), usize::MAX), None);
+    assert_eq!(
+        ApInt::ZERO.checked_shl_by(&(ApInt::ONE << 200), 0),
+        Some(ApInt::ZERO)
+    );
+}
+
+#[test]
+fn prop_shift_by_apint() {
+    fn prop(n: i128, bits: u8) -> bool {
+        let int = ApInt::from(n);
+        let amount = ApInt::from(bits);
+        let bits = bits as usize;
+
+        let mut assigned = int.clone();
+        assigned <<= &amount;
+
+        &int << &amount == &int << bits
+            && &int >> &amount == &int >> bits
+            && assigned == &int << bits
+    }
+    qc::quickcheck(prop as fn(i128, u8) -> bool)
+}
+
+#[test]
+fn shr_by_huge_apint() {
+    let huge = ApInt::from(u128::MAX);
+    assert_eq!(ApInt::from(-7) >> &huge, ApInt::from(-1));
+    assert_eq!(ApInt::from(7) >> &huge, ApInt::from(0));
+}
+
+#[test]
+#[should_panic(expected = "attempt to shift left with overflow")]
+fn shl_by_huge_apint() {
+    let _ = ApInt::from(1) << ApInt::from(u128::MAX);
+}
+
+#[test]
+#[should_panic(expected = "attempt to shift by a negative amount")]
+fn shl_by_negative_apint() {
+    let _ = ApInt::from(1) << ApInt::from(-1);
+}
+
+#[test]
+fn prop_wnaf() {
+    fn prop(a: i128, b: i64, width: u8) -> bool {
+        let width = u32::from(width % 7 + 2);
+        let int = ApInt::from(a) * ApInt::from(b);
+        let digits = int.to_wnaf(width);
+
+        let mut value = ApInt::ZERO;
+        for &d in digits.iter().rev() {
+            value = (value << 1) + ApInt::from(d);
+        }
+
+        let bound = 1i32 << (width - 1);
+        let nonzero: Vec<usize> = (0..digits.len()).filter(|&i| digits[i] != 0).collect();
+        value == int
+            && digits.last() != Some(&0)
+            && nonzero
+                .iter()
+                .all(|&i| digits[i] % 2 != 0 && i32::from(digits[i]).abs() < bound)
+            && nonzero.windows(2).all(|w| w[1] - w[0] >= width as usize)
+    }
+    qc::quickcheck(prop as fn(i128, i64, u8) -> bool)
+}
+
+#[test]
+fn naf() {
+    assert_eq!(ApInt::ZERO.to_naf(), []);
+    assert_eq!(ApInt::from(-7).to_naf(), [1, 0, 0, -1]);
+    assert_eq!(ApInt::from(255).to_wnaf(4), [-1, 0, 0, 0, 0, 0, 0, 0, 1]);
+    assert_eq!(ApInt::from(i128::MIN).to_naf().len(), 128);
+}
+
+#[test]
+#[should_panic(expected = "width must be within the range 2..=8")]
+fn wnaf_invalid_width() {
+    let _ = ApInt::ONE.to_wnaf(9);
+}
+
+#[test]
+fn prop_bit_counts() {
+    fn prop(n: i128) -> bool {
+        let int = ApInt::from(n);
+        let ones = if n < 0 {
+            None
+        } else {
+            Some(n.count_ones() as usize)
+        };
+        let zeros = if n < 0 {
+            Some(n.count_zeros() as usize)
+        } else {
+            None
+        };
+        let trailing_zeros = if n == 0 {
+            None
+        } else {
+            Some(n.trailing_zeros() as usize)
+        };
+        let trailing_ones = if n == -1 {
+            None
+        } else {
+            Some(n.trailing_ones() as usize)
+        };
+
+        int.count_ones() == ones
+            && int.count_zeros() == zeros
+            && int.trailing_zeros() == trailing_zeros
+            && int.trailing_ones() == trailing_ones
+            && (0..200).all(|i| int.bit(i) == (n >> i.min(127) & 1 == 1))
+    }
+    qc::quickcheck(prop as fn(i128) -> bool)
+}
+
+#[test]
+fn prop_split_concat_bits() {
+    fn prop(n: i128, bits: u8) -> bool {
+        let bits = bits as usize % 160;
+        let int = ApInt::from(n);
+        let (low, high) = int.split_at_bit(bits);
+
+        let expected_high = n >> bits.min(127);
+        let expected_low = ApInt::from(n) - (ApInt::from(expected_high) << bits);
+
+        low == expected_low
+            && high == ApInt::from(expected_high)
+            && low >= ApInt::ZERO
+            && ApInt::concat_bits(&high, &low, bits) == int
+    }
+    qc::quickcheck(prop as fn(i128, u8) -> bool)
+}
+
+#[test]
+fn concat_bits_masks_low() {
+    let r = ApInt::concat_bits(&ApInt::from(1), &ApInt::from(-1), 8);
+    assert_eq!(r, ApInt::from(0x1ff));
+}
+
+#[test]
+fn prop_low_high_bits() {
+    fn prop(n: i128, bits: u8) -> bool {
+        let bits = bits as usize % 160;
+        let int = ApInt::from(n);
+        let (low, high) = int.split_at_bit(bits);
+
+        let mut masked = int.clone();
+        masked.mask_assign(bits);
+
+        int.low_bits(bits) == low && int.high_bits(bits) == high && masked == low
+    }
+    qc::quickcheck(prop as fn(i128, u8) -> bool)
+}
+
+#[test]
+fn low_bits_of_negative() {
+    assert_eq!(ApInt::from(-1).low_bits(0), ApInt::ZERO);
+    assert_eq!(ApInt::from(-1).low_bits(128), ApInt::from(u128::MAX));
+
+    let mut int = ApInt::from(i128::MIN);
+    int.mask_assign(127);
+    assert_eq!(int, ApInt::ZERO);
+}
+
+#[test]
+fn prop_pow2_ops() {
+    fn prop(n: i64, k: u8) -> bool {
+        let k = k as usize % 64;
+        let (int, p) = (ApInt::from(n), 1i128 << k);
+        // The divisor is positive, so Euclidean division rounds down.
+        let (q, r) = ((n as i128).div_euclid(p), (n as i128).rem_euclid(p));
+        let ceil = if r == 0 { q } else { q + 1 };
+
+        int.mul_pow2(k) == ApInt::from(n as i128 * p)
+            && int.div_pow2_floor(k) == ApInt::from(q)
+            && int.div_pow2_ceil(k) == ApInt::from(ceil)
+            && int.mod_pow2(k) == ApInt::from(r)
+    }
+    qc::quickcheck(prop as fn(i64, u8) -> bool)
+}
+
+#[test]
+fn prop_inv_mod_pow2() {
+    fn prop(n: i128, shift: u8, k: u16) -> bool {
+        let int = (ApInt::from(n) << shift as usize) | ApInt::ONE;
+        let k = k as usize % 600;
+        let inv = int.inv_mod_pow2(k).unwrap();
+
+        inv >= ApInt::ZERO
+            && inv < ApInt::ONE << k
+            && (&int * &inv).mod_pow2(k) == ApInt::ONE.mod_pow2(k)
+            && (int + ApInt::ONE).inv_mod_pow2(k).is_none()
+    }
+    qc::quickcheck(prop as fn(i128, u8, u16) -> bool)
+}
+
+#[test]
+fn prop_twos_complement_identities() {
+    fn prop(a: i128, b: i128, sa: u8, sb: u8) -> bool {
+        // Shift the operands so that they span different numbers of limbs.
+        let x = ApInt::from(a) << (sa as usize % 200);
+        let y = ApInt::from(b) << (sb as usize % 200);
+        let n = sa as usize;
+
+        (ApInt::NEG_ONE & &x) == x
+            && (ApInt::ZERO | &x) == x
+            && (&x ^ ApInt::NEG_ONE) == !&x
+            && !&x == -&x - ApInt::ONE
+            && (&x & &y) + (&x | &y) == &x + &y
+            && (&x ^ &y) == (&x | &y) - (&x & &y)
+            && (&x & &y) == !(!&x | !&y)
+            && (ApInt::NEG_ONE >> n) == ApInt::NEG_ONE
+            && ((&x << n) >> n) == x
+            && (&x >> n) == x.div_pow2_floor(n)
+    }
+    qc::quickcheck(prop as fn(i128, i128, u8, u8) -> bool)
+}
+
+#[test]
+fn prop_gray_code() {
+    fn prop(n: u128, shift: u8) -> bool {
+        let narrow = ApInt::from(n).to_gray() == ApInt::from(n ^ (n >> 1));
+
+        // Shift to cover values wider than a primitive.
+        let int = ApInt::from(n) << usize::from(shift);
+        let next = &int + ApInt::ONE;
+        let gray = int.to_gray();
+        let back = gray.from_gray();
+        back.assert_valid();
+
+        narrow && back == int && (&gray ^ &next.to_gray()).count_ones() == Some(1)
+    }
+    qc::quickcheck(prop as fn(u128, u8) -> bool)
+}
+
+#[test]
+fn gray_code_small() {
+    let codes = [0, 1, 3, 2, 6, 7, 5, 4, 12];
+    for (i, &code) in codes.iter().enumerate() {
+        assert_eq!(ApInt::from(i).to_gray(), ApInt::from(code));
+        assert_eq!(ApInt::from(code).from_gray(), ApInt::from(i));
+    }
+}
+
+#[test]
+#[should_panic(expected = "Gray codes of negative values are undefined")]
+fn gray_code_negative() {
+    ApInt::from(-1).to_gray();
+}
+
+#[test]
+fn prop_hamming_distance() {
+    fn prop(a: i128, b: i128, shift: u8) -> bool {
+        let (a, b) = (ApInt::from(a) << usize::from(shift), ApInt::from(b));
+        let xor = &a ^ &b;
+        a.hamming_distance(&b) == xor.count_ones() && b.hamming_distance(&a) == xor.count_ones()
+    }
+    qc::quickcheck(prop as fn(i128, i128, u8) -> bool)
+}
+
+#[test]
+fn prop_size_estimates() {
+    fn prop(a: i128, b: i64, shift: u8, exp: u8) -> bool {
+        let (a, b) = (ApInt::from(a) << usize::from(shift), ApInt::from(b));
+        let exp = u64::from(exp % 16);
+
+        let product = (&a * &b).bits();
+        let product_ok = match a.estimate_product_bits(&b) {
+            0 => product == 0,
+            bound => bound == product || bound == product + 1,
+        };
+
+        let pow = a.pow_int(&ApInt::from(exp)).unwrap().bits() as u64;
+        let pow_ok = match a.estimate_pow_bits(exp) {
+            Some(bound) => pow <= bound as u64 && bound as u64 <= pow + exp.saturating_sub(1),
+            None => false,
+        };
+        product_ok && pow_ok
+    }
+    qc::quickcheck(prop as fn(i128, i64, u8, u8) -> bool)
+}
+
+#[test]
+fn prop_bitops_long() {
+    // Applies `op` to each byte of `x` and `y` sign extended to a common
+    // length.
+    fn bytewise(x: &ApInt, y: &ApInt, op: impl Fn(u8, u8) -> u8) -> ApInt {
+        let (mut xb, mut yb) = (x.to_signed_bytes_le(), y.to_signed_bytes_le());
+        let len = xb.len().max(yb.len());
+        xb.resize(len, if x < &ApInt::ZERO { 0xff } else { 0 });
+        yb.resize(len, if y < &ApInt::ZERO { 0xff } else { 0 });
+        let bytes: Vec<u8> = xb.iter().zip(&yb).map(|(&x, &y)| op(x, y)).collect();
+        ApInt::from_signed_bytes_le(&bytes)
+    }
+
+    fn prop(x: (i128, i128, i128, i128, i128), y: (i128, i128), shift: u16) -> bool {
+        // Ints long enough to fill several vectors of limbs.
+        let x = (ApInt::from(x.0) << 512)
+            + (ApInt::from(x.1) << 384)
+            + (ApInt::from(x.2) << 256)
+            + (ApInt::from(x.3) << 128)
+            + ApInt::from(x.4);
+        let y = (ApInt::from(y.0) << (usize::from(shift) % 700)) + ApInt::from(y.1);
+
+        &x & &y == bytewise(&x, &y, |x, y| x & y)
+            && &x | &y == bytewise(&x, &y, |x, y| x | y)
+            && &x ^ &y == bytewise(&x, &y, |x, y| x ^ y)
+            && !&x == bytewise(&x, &ApInt::ZERO, |x, _| !x)
+    }
+    qc::quickcheck(prop as fn((i128, i128, i128, i128, i128), (i128, i128), u16) -> bool);
+}
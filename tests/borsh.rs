@@ -0,0 +1,56 @@
+#![cfg(feature = "borsh")]
+
+use apa::ApInt;
+
+mod qc;
+
+#[test]
+fn prop_borsh_round_trip() {
+    fn prop(n: i128, shift: u8) -> bool {
+        let int = ApInt::from(n) << shift as usize;
+        let bytes = borsh::to_vec(&int).unwrap();
+        borsh::from_slice::<ApInt>(&bytes).unwrap() == int
+    }
+    qc::quickcheck(prop as fn(i128, u8) -> bool)
+}
+
+#[test]
+fn borsh_encoding() {
+    let cases: &[(ApInt, &[u8])] = &[
+        (ApInt::ZERO, &[0, 0, 0, 0, 0]),
+        (ApInt::from(1), &[1, 1, 0, 0, 0, 1]),
+        (ApInt::from(-256), &[0xff, 2, 0, 0, 0, 0, 1]),
+        (
+            ApInt::from(i64::MIN),
+            &[0xff, 8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x80],
+        ),
+        (
+            ApInt::ONE << 64,
+            &[1, 9, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+        ),
+    ];
+    for (int, bytes) in cases {
+        assert_eq!(borsh::to_vec(int).unwrap(), *bytes, "{}", int);
+        assert_eq!(borsh::from_slice::<ApInt>(bytes).unwrap(), *int);
+    }
+}
+
+#[test]
+fn borsh_rejects_non_canonical() {
+    let cases: &[&[u8]] = &[
+        // Sign out of range.
+        &[2, 1, 0, 0, 0, 1],
+        // High zero byte.
+        &[1, 2, 0, 0, 0, 1, 0],
+        // Zero with a sign.
+        &[1, 0, 0, 0, 0],
+        &[0xff, 0, 0, 0, 0],
+        // Non-zero with a zero sign.
+        &[0, 1, 0, 0, 0, 1],
+        // Truncated.
+        &[1, 2, 0, 0, 0, 1],
+    ];
+    for bytes in cases {
+        assert!(borsh::from_slice::<ApInt>(bytes).is_err(), "{:?}", bytes);
+    }
+}
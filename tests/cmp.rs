@@ -1,3 +1,6 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
 use apa::ApInt;
 
 mod qc;
@@ -26,3 +29,84 @@ quickcheck_prims!(
     u8, u16, u32, u64, u128, usize,
     i8, i16, i32, i64, i128, isize,
 );
+
+#[test]
+fn prop_min_max_clamp() {
+    fn prop(x: i128, a: i128, b: i128) -> bool {
+        let (lo, hi) = (a.min(b), a.max(b));
+        let (xi, ai) = (ApInt::from(x), ApInt::from(a));
+        let (loi, hii) = (ApInt::from(lo), ApInt::from(hi));
+
+        *xi.min_ref(&ai) == ApInt::from(x.min(a))
+            && *xi.max_ref(&ai) == ApInt::from(x.max(a))
+            && *xi.clamp_ref(&loi, &hii) == ApInt::from(x.max(lo).min(hi))
+    }
+    qc::quickcheck(prop as fn(i128, i128, i128) -> bool)
+}
+
+#[test]
+fn min_max_ties() {
+    let (l, r) = (ApInt::ONE, ApInt::ONE);
+    assert!(core::ptr::eq(l.min_ref(&r), &l));
+    assert!(core::ptr::eq(l.max_ref(&r), &r));
+}
+
+#[test]
+#[should_panic(expected = "lower bound must not exceed the upper bound")]
+fn clamp_inverted() {
+    let _ = ApInt::ZERO.clamp_ref(&ApInt::ONE, &ApInt::ZERO);
+}
+
+#[test]
+fn prop_cmp_f64() {
+    fn prop(x: i128, m: i32, e: i8) -> bool {
+        let f = m as f64 * 2f64.powi(e as i32 % 96);
+        let int = ApInt::from(x);
+
+        // The floor of `f` fits in an `i128`, so the comparison can be made
+        // exactly against it.
+        let floor = f.floor();
+        let expected = x.cmp(&(floor as i128)).then(if f == floor {
+            core::cmp::Ordering::Equal
+        } else {
+            core::cmp::Ordering::Less
+        });
+
+        int.partial_cmp(&f) == Some(expected)
+            && f.partial_cmp(&int) == Some(expected.reverse())
+            && (int == f) == (expected == core::cmp::Ordering::Equal)
+    }
+    qc::quickcheck(prop as fn(i128, i32, i8) -> bool)
+}
+
+#[test]
+fn cmp_f64_beyond_precision() {
+    // `2^64 + 1` rounds to `2^64` as a float, but compares exactly.
+    let int = ApInt::from(u64::MAX) + ApInt::from(2);
+    assert!(int > 18446744073709551616.0);
+    assert!(int != 18446744073709551616.0);
+    assert!(ApInt::from(u64::MAX) + ApInt::ONE == 18446744073709551616.0);
+
+    assert!(ApInt::from(u128::MAX) < 1e300);
+    assert!(ApInt::from(i128::MIN) > -1e300);
+    assert!(ApInt::ZERO < f64::INFINITY && ApInt::ZERO > f64::NEG_INFINITY);
+    assert!(ApInt::ZERO == -0.0 && ApInt::ZERO > -5e-324);
+    assert_eq!(ApInt::ZERO.partial_cmp(&f64::NAN), None);
+}
+
+#[test]
+fn prop_hash_eq() {
+    fn hash(int: &ApInt) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        int.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn prop(x: i128, shift: u8) -> bool {
+        // Shifting out and back builds the same value through a wider int.
+        let shift = usize::from(shift);
+        let roundtrip = (ApInt::from(x) << shift) >> shift;
+        hash(&roundtrip) == hash(&ApInt::from(x))
+    }
+    qc::quickcheck(prop as fn(i128, u8) -> bool)
+}
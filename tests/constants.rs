@@ -19,3 +19,27 @@ macro_rules! test_prims {
 
 test_prims!(zero: 0, ApInt::ZERO, [u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize]);
 test_prims!(one: 1, ApInt::ONE, [u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize]);
+
+test_prims!(two: 2, ApInt::TWO, [u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize]);
+test_prims!(neg_one: -1, ApInt::NEG_ONE, [i8, i16, i32, i64, i128, isize]);
+
+#[test]
+fn default() {
+    assert_eq!(ApInt::default(), ApInt::ZERO);
+}
+
+#[test]
+fn from_bool() {
+    assert_eq!(ApInt::from(false), ApInt::ZERO);
+    assert_eq!(ApInt::from(true), ApInt::ONE);
+}
+
+#[test]
+fn small() {
+    const SMALL: ApInt = ApInt::small(-100);
+
+    assert_eq!(SMALL, ApInt::from(-100));
+    for n in i8::MIN..=i8::MAX {
+        assert_eq!(ApInt::small(n), ApInt::from(n));
+    }
+}
@@ -0,0 +1,46 @@
+use apa::consts;
+
+/// Each constant truncated to 400 digits after the decimal point, with the
+/// point removed.
+const PI: &str = "3141592653589793238462643383279502884197169399375105820974944592307816\
+     4062862089986280348253421170679821480865132823066470938446095505822317\
+     2535940812848111745028410270193852110555964462294895493038196442881097\
+     5665933446128475648233786783165271201909145648566923460348610454326648\
+     2133936072602491412737245870066063155881748815209209628292540917153643\
+     678925903600113305305488204665213841469519415116094";
+const E: &str = "2718281828459045235360287471352662497757247093699959574966967627724076\
+     6303535475945713821785251664274274663919320030599218174135966290435729\
+     0033429526059563073813232862794349076323382988075319525101901157383418\
+     7930702154089149934884167509244761460668082264800168477411853742345442\
+     4371075390777449920695517027618386062613313845830007520449338265602976\
+     067371132007093287091274437470472306969772093101416";
+const LN2: &str = "6931471805599453094172321214581765680755001343602552541206800094933936\
+     2196969471560586332699641868754200148102057068573368552023575813055703\
+     2670751635075961930727570828371435190307038623891673471123350115364497\
+     9552391204751726815749320651555247341395258829504530070953263666426541\
+     0423915781495204374043038550080194417064167151864471283996817178454695\
+     70262716310645461502572074024816377733896385506952";
+
+/// Checks that `f(digits)` truncates `expected` for a range of digits.
+fn check(f: fn(usize) -> apa::ApInt, expected: &str, int_digits: usize) {
+    for digits in (0..=40).chain((41..400).step_by(37)).chain(Some(400)) {
+        let want = expected[..int_digits + digits].trim_start_matches('0');
+        let want = if want.is_empty() { "0" } else { want };
+        assert_eq!(f(digits).to_string(), want, "digits = {}", digits);
+    }
+}
+
+#[test]
+fn pi() {
+    check(consts::pi, PI, 1);
+}
+
+#[test]
+fn e() {
+    check(consts::e, E, 1);
+}
+
+#[test]
+fn ln2() {
+    check(consts::ln2, LN2, 0);
+}
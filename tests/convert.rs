@@ -1,4 +1,7 @@
-use apa::ApInt;
+use std::convert::TryFrom;
+
+use apa::{ApInt, LimbRepr, Sign};
+use num_traits::ToPrimitive;
 
 mod qc;
 
@@ -38,3 +41,187 @@ test_prims!(
     u8, u16, u32, u64, u128, usize,
     i8, i16, i32, i64, i128, isize,
 );
+
+#[test]
+fn prop_raw_parts() {
+    fn prop(n: i128) -> bool {
+        let (ptr, len) = ApInt::from(n).into_raw_parts();
+        // SAFETY: The parts came from `into_raw_parts` and are used once.
+        unsafe { ApInt::from_raw_parts(ptr, len) == ApInt::from(n) }
+    }
+    qc::quickcheck(prop as fn(i128) -> bool)
+}
+
+#[test]
+fn from_raw_parts_boxed() {
+    // Redundant sign limbs are trimmed, and a single limb is moved inline.
+    for (limbs, expected) in vec![
+        (
+            vec![LimbRepr::MAX, LimbRepr::MAX, LimbRepr::MAX],
+            ApInt::from(-1),
+        ),
+        (vec![7, 0], ApInt::from(7)),
+        (
+            vec![0, 1, 0],
+            ApInt::ONE << (8 * core::mem::size_of::<LimbRepr>()),
+        ),
+    ] {
+        let len = limbs.len();
+        let ptr = Box::into_raw(limbs.into_boxed_slice()).cast::<LimbRepr>();
+        // SAFETY: A boxed slice has the layout of an array of its length.
+        let int = unsafe { ApInt::from_raw_parts(ptr, len) };
+        int.assert_valid();
+        assert_eq!(int, expected);
+        assert_eq!(int.clone(), expected);
+    }
+}
+
+#[test]
+fn prop_limbs_le() {
+    fn prop(n: i128) -> bool {
+        let sign = ApInt::from(n).sign();
+        let mag = n.wrapping_abs() as u128;
+
+        let mut limbs = vec![mag as u64, (mag >> 64) as u64];
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        let mut words: Vec<u32> = (0..4).map(|i| (mag >> (32 * i)) as u32).collect();
+        while words.last() == Some(&0) {
+            words.pop();
+        }
+
+        ApInt::from_limbs_le(limbs.clone(), sign) == ApInt::from(n)
+            && ApInt::from_u32_limbs_le(words.clone(), sign) == ApInt::from(n)
+            && ApInt::from(n).into_limbs_le() == (limbs, sign)
+            && ApInt::from(n).into_u32_limbs_le() == (words, sign)
+    }
+    qc::quickcheck(prop as fn(i128) -> bool)
+}
+
+#[test]
+fn from_limbs_le_trims_zeros() {
+    let int = ApInt::from_limbs_le(vec![u64::MAX, 0, 0], Sign::Positive);
+    assert_eq!(int, ApInt::from(u64::MAX));
+    assert_eq!(ApInt::from_limbs_le(vec![0, 0], Sign::Zero), ApInt::ZERO);
+    assert_eq!(
+        ApInt::from_u32_limbs_le(vec![], Sign::Negative),
+        ApInt::ZERO
+    );
+}
+
+#[test]
+#[should_panic(expected = "non-zero magnitude with a zero sign")]
+fn from_limbs_le_zero_sign() {
+    let _ = ApInt::from_limbs_le(vec![1], Sign::Zero);
+}
+
+macro_rules! quickcheck_nonzero {
+    ($($nz:ident => $ty:ident),* $(,)*) => {
+        $(
+            paste::item! {
+               #[test]
+               fn [< prop_nonzero_ $ty >] () {
+                    fn prop(n: $ty) -> bool {
+                        let int = ApInt::from(n);
+                        let converted = core::num::$nz::try_from(&int).ok();
+                        let round_trip = converted.map_or(true, |nz| ApInt::from(nz) == int);
+
+                        converted == core::num::$nz::new(n) && round_trip
+                    }
+                    qc::quickcheck(prop as fn($ty) -> bool)
+               }
+            }
+        )*
+    };
+}
+
+quickcheck_nonzero!(
+    NonZeroU8 => u8, NonZeroU16 => u16, NonZeroU32 => u32, NonZeroU64 => u64,
+    NonZeroU128 => u128, NonZeroUsize => usize,
+    NonZeroI8 => i8, NonZeroI16 => i16, NonZeroI32 => i32, NonZeroI64 => i64,
+    NonZeroI128 => i128, NonZeroIsize => isize,
+);
+
+#[test]
+fn nonzero_out_of_range() {
+    use core::num::{NonZeroI8, NonZeroU64};
+
+    assert!(NonZeroU64::try_from(ApInt::from(-1)).is_err());
+    assert!(NonZeroU64::try_from(ApInt::from(u128::MAX)).is_err());
+    assert!(NonZeroI8::try_from(ApInt::from(128)).is_err());
+    assert!(NonZeroI8::try_from(ApInt::ZERO).is_err());
+    assert_eq!(
+        NonZeroI8::try_from(ApInt::from(-128)),
+        Ok(NonZeroI8::new(-128).unwrap())
+    );
+
+    let err = NonZeroU64::try_from(ApInt::ZERO).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "out of range integral type conversion attempted"
+    );
+}
+
+#[test]
+fn prop_bits() {
+    fn prop(n: i128) -> bool {
+        let expected = 128 - (n.wrapping_abs() as u128).leading_zeros() as usize;
+        ApInt::from(n).bits() == expected
+    }
+    qc::quickcheck(prop as fn(i128) -> bool)
+}
+
+#[test]
+fn prop_to_f64_exp() {
+    fn prop(n: i64, shift: u16) -> bool {
+        let int = ApInt::from(n) << shift as usize;
+        let (d, exp) = int.to_f64_exp();
+        if n == 0 {
+            return (d, exp) == (0.0, 0);
+        }
+
+        // `d` holds the leading bits of the value, truncated.
+        let bits = int.bits();
+        let top = int.abs() >> bits.saturating_sub(53);
+        // Scale by an exact power of two, since `powi` is imprecise under Miri.
+        let expected = top.to_f64().unwrap() / (1u64 << bits.min(53)) as f64;
+        exp == bits as i64 && d.abs() == expected && (d < 0.0) == (n < 0) && d.abs() >= 0.5
+    }
+    qc::quickcheck(prop as fn(i64, u16) -> bool)
+}
+
+#[test]
+fn to_f64_exp_truncates() {
+    // `2^54 - 1` rounds up to `2^54` as an `f64`, but is truncated here.
+    let (d, exp) = ApInt::from((1u64 << 54) - 1).to_f64_exp();
+    assert_eq!(exp, 54);
+    assert_eq!(d, 1.0 - 2f64.powi(-53));
+}
+
+#[test]
+fn prop_from_limbs() {
+    fn prop(val: i128, extra: u8) -> bool {
+        let int = ApInt::from(val);
+        let fill = if val < 0 { LimbRepr::MAX } else { 0 };
+
+        let limb_bits = 8 * core::mem::size_of::<LimbRepr>();
+        let mut limbs = Vec::new();
+        let mut rest = val;
+        for _ in 0..(128 / limb_bits) {
+            limbs.push(rest as LimbRepr);
+            rest >>= limb_bits;
+        }
+        limbs.extend(std::iter::repeat(fill).take(extra as usize % 4));
+
+        let built = ApInt::from_limbs(&limbs);
+        built.assert_valid();
+        built == int
+    }
+    qc::quickcheck(prop as fn(i128, u8) -> bool)
+}
+
+#[test]
+fn from_limbs_empty() {
+    assert_eq!(ApInt::from_limbs(&[]), ApInt::ZERO);
+}
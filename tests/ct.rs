@@ -0,0 +1,75 @@
+#![cfg(feature = "subtle")]
+
+use apa::ApInt;
+use subtle::{Choice, ConstantTimeEq, ConstantTimeGreater, ConstantTimeLess};
+
+mod qc;
+
+#[test]
+fn prop_ct_add_sub_if() {
+    fn prop(x: i128, y: i128, shift: u8, choice: bool) -> bool {
+        let x = ApInt::from(x) << (shift % 130) as usize;
+        let y = ApInt::from(y);
+        let c = Choice::from(choice as u8);
+
+        let mut sum = x.clone();
+        sum.ct_add_assign_if(&y, c);
+        let mut diff = x.clone();
+        diff.ct_sub_assign_if(&y, c);
+
+        if choice {
+            sum == &x + &y && diff == &x - &y
+        } else {
+            sum == x && diff == x
+        }
+    }
+    qc::quickcheck(prop as fn(i128, i128, u8, bool) -> bool)
+}
+
+#[test]
+fn prop_ct_assign_swap() {
+    fn prop(x: i128, y: i64, shift: u8, choice: bool) -> bool {
+        let x = ApInt::from(x) << (shift % 130) as usize;
+        let y = ApInt::from(y);
+        let c = Choice::from(choice as u8);
+
+        let mut assigned = x.clone();
+        assigned.ct_assign_if(&y, c);
+        let (mut a, mut b) = (x.clone(), y.clone());
+        ApInt::ct_swap_if(&mut a, &mut b, c);
+
+        if choice {
+            assigned == y && a == y && b == x
+        } else {
+            assigned == x && a == x && b == y
+        }
+    }
+    qc::quickcheck(prop as fn(i128, i64, u8, bool) -> bool)
+}
+
+#[test]
+fn prop_ct_cmp() {
+    fn prop(x: i128, y: i128, shift: u8) -> bool {
+        let x = ApInt::from(x) << (shift % 130) as usize;
+        let y = ApInt::from(y);
+
+        bool::from(x.ct_eq(&y)) == (x == y)
+            && bool::from(x.ct_gt(&y)) == (x > y)
+            && bool::from(x.ct_lt(&y)) == (x < y)
+            && bool::from(x.ct_eq(&x.clone()))
+    }
+    qc::quickcheck(prop as fn(i128, i128, u8) -> bool)
+}
+
+#[test]
+fn ct_reduce() {
+    // A conditional subtraction leaving `x mod m` for every `x < 2m`.
+    let m = (ApInt::ONE << 130) - ApInt::from(5);
+    for k in [0u64, 1, 4, 5, 6, u64::MAX].iter() {
+        for x in [ApInt::from(*k), &m - ApInt::ONE + ApInt::from(*k)].iter() {
+            let mut r = x.clone();
+            r.ct_sub_assign_if(&m, !m.ct_gt(x));
+            assert_eq!(r, x % &m);
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use apa::ApInt;
+use apa::dh::Params;
+
+#[test]
+fn params_new() {
+    let params = Params::new(ApInt::from(23), ApInt::from(4)).unwrap();
+    assert_eq!(*params.prime(), ApInt::from(23));
+    assert_eq!(*params.generator(), ApInt::from(4));
+    assert_eq!(params.order(), ApInt::from(11));
+
+    // 29 is prime, but 14 is not.
+    assert!(Params::new(ApInt::from(29), ApInt::from(4)).is_none());
+    // 1 and `p - 1` generate subgroups of order 1 and 2.
+    assert!(Params::new(ApInt::from(23), ApInt::ONE).is_none());
+    assert!(Params::new(ApInt::from(23), ApInt::from(22)).is_none());
+    assert!(Params::new(ApInt::from(23), ApInt::from(27)).is_none());
+}
+
+#[test]
+fn params_rfc_3526() {
+    // The 1536-bit MODP group of RFC 3526.
+    let p = ApInt::from_hex(
+        "FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD1\
+         29024E088A67CC74020BBEA63B139B22514A08798E3404DD\
+         EF9519B3CD3A431B302B0A6DF25F14374FE1356D6D51C245\
+         E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7ED\
+         EE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3D\
+         C2007CB8A163BF0598DA48361C55D39A69163FA8FD24CF5F\
+         83655D23DCA3AD961C62F356208552BB9ED529077096966D\
+         670C354E4ABC9804F1746C08CA237327FFFFFFFFFFFFFFFF",
+    )
+    .unwrap();
+    assert!(Params::new(p, ApInt::TWO).is_some());
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn generate_params() {
+    use apa::dh;
+
+    let mut rng = rand::rngs::mock::StepRng::new(7, 0x9e37_79b9_7f4a_7c15);
+    for &bits in &[3, 4, 16, 64, 128] {
+        let params = dh::generate_params(&mut rng, bits);
+        let p = params.prime();
+        assert!(*p >= ApInt::ONE << (bits - 1) && *p < ApInt::ONE << bits);
+        assert!(Params::new(p.clone(), params.generator().clone()).as_ref() == Some(&params));
+    }
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "digest")]
+
+use apa::{ApInt, Encoding};
+
+mod qc;
+
+/// A digest that records the bytes fed to it.
+#[derive(Default)]
+struct Bytes(Vec<u8>);
+
+impl digest::Update for Bytes {
+    fn update(&mut self, data: &[u8]) {
+        self.0.extend_from_slice(data);
+    }
+}
+
+fn hash(int: &ApInt, encoding: Encoding) -> Vec<u8> {
+    let mut d = Bytes::default();
+    int.hash_into(&mut d, encoding);
+    d.0
+}
+
+#[test]
+fn prop_signed_bytes() {
+    fn prop(n: i128, shift: u8) -> bool {
+        let int = ApInt::from(n) << shift as usize;
+        let bytes = int.to_signed_bytes_be();
+
+        let mut prefixed = (bytes.len() as u64).to_be_bytes().to_vec();
+        prefixed.extend_from_slice(&bytes);
+
+        hash(&int, Encoding::SignedBytes) == bytes
+            && hash(&int, Encoding::LengthPrefixed) == prefixed
+    }
+    qc::quickcheck(prop as fn(i128, u8) -> bool)
+}
+
+#[test]
+fn prop_unsigned() {
+    fn prop(n: u128, extra: u8) -> bool {
+        let int = ApInt::from(n);
+        let width = 16 + extra as usize;
+
+        let mut bytes = vec![0; extra as usize];
+        bytes.extend_from_slice(&n.to_be_bytes());
+        hash(&int, Encoding::Unsigned(width)) == bytes
+    }
+    qc::quickcheck(prop as fn(u128, u8) -> bool)
+}
+
+#[test]
+fn hash_into_vectors() {
+    assert_eq!(hash(&ApInt::ZERO, Encoding::SignedBytes), [0]);
+    assert_eq!(hash(&ApInt::ZERO, Encoding::Unsigned(0)), []);
+    assert_eq!(hash(&ApInt::from(-1), Encoding::SignedBytes), [0xff]);
+    assert_eq!(
+        hash(&(ApInt::ONE << 63), Encoding::SignedBytes),
+        [0, 0x80, 0, 0, 0, 0, 0, 0, 0]
+    );
+    assert_eq!(
+        hash(&ApInt::from(0x80), Encoding::LengthPrefixed),
+        [0, 0, 0, 0, 0, 0, 0, 2, 0, 0x80]
+    );
+    assert_eq!(
+        hash(&ApInt::from(0xffff), Encoding::Unsigned(2)),
+        [0xff, 0xff]
+    );
+    assert_eq!(hash(&ApInt::ZERO, Encoding::Unsigned(100)), vec![0; 100]);
+}
+
+#[test]
+#[should_panic(expected = "value does not fit in the given width")]
+fn hash_into_unsigned_too_wide() {
+    hash(&ApInt::from(0x1_0000), Encoding::Unsigned(2));
+}
+
+#[test]
+#[should_panic(expected = "cannot encode a negative value")]
+fn hash_into_unsigned_negative() {
+    hash(&ApInt::from(-1), Encoding::Unsigned(16));
+}
@@ -0,0 +1,178 @@
+use apa::ApInt;
+
+mod qc;
+
+fn from_be_bytes(bytes: &[u8]) -> ApInt {
+    bytes
+        .iter()
+        .fold(ApInt::ZERO, |int, &b| (int << 8) | ApInt::from(b))
+}
+
+#[test]
+fn base58_vectors() {
+    let hello = from_be_bytes(b"Hello World!");
+    assert_eq!(hello.to_base58(), "2NEpo7TZRRrLZSi2U");
+    assert_eq!(ApInt::from_base58("2NEpo7TZRRrLZSi2U"), Ok(hello));
+
+    assert_eq!(ApInt::ZERO.to_base58(), "");
+    assert_eq!(ApInt::from_base58(""), Ok(ApInt::ZERO));
+    assert_eq!(ApInt::from_base58("111"), Ok(ApInt::ZERO));
+    assert_eq!(ApInt::from_base58("11z"), Ok(ApInt::from(57)));
+    assert_eq!(ApInt::from(58).to_base58(), "21");
+
+    assert!(ApInt::from_base58("0").is_err());
+    assert!(ApInt::from_base58("l").is_err());
+    assert!(ApInt::from_base58("2NEpo 7TZ").is_err());
+}
+
+#[test]
+fn base64_vectors() {
+    let cases = [
+        ("", ""),
+        ("f", "Zg=="),
+        ("fo", "Zm8="),
+        ("foo", "Zm9v"),
+        ("foob", "Zm9vYg=="),
+        ("fooba", "Zm9vYmE="),
+        ("foobar", "Zm9vYmFy"),
+    ];
+    for &(bytes, encoded) in &cases {
+        let int = from_be_bytes(bytes.as_bytes());
+        assert_eq!(int.to_base64(), encoded);
+        assert_eq!(ApInt::from_base64(encoded), Ok(int));
+    }
+
+    assert_eq!(ApInt::from_base64("AAAB"), Ok(ApInt::from(1)));
+    assert_eq!(ApInt::from(0xfbff).to_base64(), "+/8=");
+
+    for s in &["Zg", "Zg=", "Zh==", "Z===", "Zm8=Zm8=", "====", "Zm9v===="] {
+        assert!(ApInt::from_base64(s).is_err(), "{}", s);
+    }
+}
+
+#[test]
+fn base32_vectors() {
+    let cases = [
+        ("", ""),
+        ("f", "MY======"),
+        ("fo", "MZXQ===="),
+        ("foo", "MZXW6==="),
+        ("foob", "MZXW6YQ="),
+        ("fooba", "MZXW6YTB"),
+        ("foobar", "MZXW6YTBOI======"),
+    ];
+    for &(bytes, encoded) in &cases {
+        let int = from_be_bytes(bytes.as_bytes());
+        assert_eq!(int.to_base32(), encoded);
+        assert_eq!(ApInt::from_base32(encoded), Ok(int));
+    }
+
+    for s in &[
+        "MY", "MZ======", "M=======", "MZX=====", "my======", "========",
+    ] {
+        assert!(ApInt::from_base32(s).is_err(), "{}", s);
+    }
+}
+
+#[test]
+#[should_panic(expected = "cannot encode a negative value")]
+fn base58_negative() {
+    ApInt::from(-1).to_base58();
+}
+
+#[test]
+fn prop_base_round_trip() {
+    fn prop(n: u128, shift: u8) -> bool {
+        let int = ApInt::from(n) << shift as usize;
+        ApInt::from_base58(&int.to_base58()) == Ok(int.clone())
+            && ApInt::from_base64(&int.to_base64()) == Ok(int.clone())
+            && ApInt::from_base32(&int.to_base32()) == Ok(int)
+    }
+    qc::quickcheck(prop as fn(u128, u8) -> bool)
+}
+
+#[test]
+fn hex_vectors() {
+    assert_eq!(ApInt::from(0xabc).to_hex(), "abc");
+    assert_eq!(ApInt::from(-0xabc).to_hex(), "-abc");
+    assert_eq!(ApInt::ZERO.to_hex_padded(4), "0000");
+    assert_eq!(ApInt::ZERO.to_hex_padded(0), "0");
+    assert_eq!((ApInt::ONE << 64).to_hex_padded(20), "00010000000000000000");
+
+    let cases = [
+        ("0", 0i128),
+        ("-0", 0),
+        ("+0x0", 0),
+        ("0XaBcD", 0xabcd),
+        ("-0x_ff_ff", -0xffff),
+        ("7fff_ffff_ffff_ffff_ffff", 0x7fff_ffff_ffff_ffff_ffff),
+    ];
+    for &(s, n) in &cases {
+        assert_eq!(ApInt::from_hex(s), Ok(ApInt::from(n)), "{}", s);
+    }
+
+    for s in &["", "-", "0x", "_", "0x_", "0xg", "0x-1", "--1", " 1", "x1"] {
+        assert!(ApInt::from_hex(s).is_err(), "{}", s);
+    }
+}
+
+#[test]
+fn prop_hex_round_trip() {
+    fn prop(n: i128, shift: u8, width: u8) -> bool {
+        let int = ApInt::from(n) << shift as usize;
+        let padded = int.to_hex_padded(width as usize);
+        let digits = padded.trim_start_matches('-').len();
+
+        ApInt::from_hex(&int.to_hex()) == Ok(int.clone())
+            && ApInt::from_hex(&padded) == Ok(int.clone())
+            && ApInt::from_hex(&format!("{:#X}", int)) == Ok(int.clone())
+            && digits == (width as usize).max(int.abs().to_hex().len())
+    }
+    qc::quickcheck(prop as fn(i128, u8, u8) -> bool)
+}
+
+#[test]
+fn prop_canonical_bytes() {
+    fn prop(n: i128, shift: u8) -> bool {
+        let int = ApInt::from(n) << shift as usize;
+        let bytes = int.to_canonical_bytes();
+
+        bytes[0] == 1
+            && bytes[1..] == int.to_signed_bytes_be()[..]
+            && ApInt::from_canonical_bytes(&bytes) == Ok(int)
+    }
+    qc::quickcheck(prop as fn(i128, u8) -> bool)
+}
+
+#[test]
+fn canonical_bytes_vectors() {
+    // These encodings are stable, and must never change.
+    let cases: &[(i128, &[u8])] = &[
+        (0, &[1, 0x00]),
+        (1, &[1, 0x01]),
+        (-1, &[1, 0xff]),
+        (127, &[1, 0x7f]),
+        (128, &[1, 0x00, 0x80]),
+        (-128, &[1, 0x80]),
+        (-129, &[1, 0xff, 0x7f]),
+        (1 << 64, &[1, 0x01, 0, 0, 0, 0, 0, 0, 0, 0]),
+    ];
+    for &(n, bytes) in cases {
+        assert_eq!(ApInt::from(n).to_canonical_bytes(), bytes);
+        assert_eq!(ApInt::from_canonical_bytes(bytes), Ok(ApInt::from(n)));
+    }
+
+    let invalid: &[&[u8]] = &[
+        &[],
+        &[1],
+        &[0, 0x00],
+        &[2, 0x00],
+        &[1, 0x00, 0x00],
+        &[1, 0x00, 0x7f],
+        &[1, 0xff, 0xff],
+        &[1, 0xff, 0x80],
+    ];
+    for bytes in invalid {
+        assert!(ApInt::from_canonical_bytes(bytes).is_err(), "{:?}", bytes);
+    }
+}
@@ -0,0 +1,36 @@
+use apa::ApInt;
+
+// Conversions between types of different widths only agree with `as` casts
+// when the limbs hold native-endian values, so these catch a byte order
+// mismatch on big-endian targets.
+
+#[test]
+fn narrow_conversions_truncate() {
+    let int = ApInt::from(0x0123_4567_89ab_cdef_fedc_ba98_7654_3210u128);
+
+    assert_eq!(u8::from(&int), 0x10);
+    assert_eq!(u16::from(&int), 0x3210);
+    assert_eq!(u32::from(&int), 0x7654_3210);
+    assert_eq!(u64::from(&int), 0xfedc_ba98_7654_3210);
+    assert_eq!(i64::from(&int), 0xfedc_ba98_7654_3210u64 as i64);
+}
+
+#[test]
+fn wide_conversions_sign_extend() {
+    assert_eq!(i128::from(&ApInt::from(-2i8)), -2);
+    assert_eq!(i128::from(&ApInt::from(i64::MIN)), i64::MIN as i128);
+    assert_eq!(u128::from(&ApInt::from(-1i32)), u128::MAX);
+    assert_eq!(u128::from(&ApInt::from(u64::MAX)), u64::MAX as u128);
+}
+
+#[test]
+fn cmp_across_limbs() {
+    let small = ApInt::from(u64::MAX as u128);
+    let large = ApInt::from(1u128 << 64);
+    let neg = ApInt::from(-(1i128 << 64));
+
+    assert!(small < large);
+    assert!(neg < small);
+    assert!(ApInt::from(0x0100u16) > ApInt::from(0x00ffu16));
+    assert!(ApInt::from(i128::MIN) < ApInt::from(i128::MIN + 1));
+}
@@ -0,0 +1,122 @@
+use core::cell::Cell;
+use std::alloc::{GlobalAlloc, Layout, System};
+
+use apa::ApInt;
+
+thread_local! {
+    // The number of allocations the current thread may still make, or `None`
+    // for no limit.
+    static BUDGET: Cell<Option<usize>> = Cell::new(None);
+}
+
+// Fails allocations once the budget of the current thread runs out.
+struct Limited;
+
+impl Limited {
+    fn take() -> bool {
+        BUDGET.with(|budget| match budget.get() {
+            None => true,
+            Some(0) => false,
+            Some(n) => {
+                budget.set(Some(n - 1));
+                true
+            }
+        })
+    }
+}
+
+unsafe impl GlobalAlloc for Limited {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if Limited::take() {
+            System.alloc(layout)
+        } else {
+            core::ptr::null_mut()
+        }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        if Limited::take() {
+            System.alloc_zeroed(layout)
+        } else {
+            core::ptr::null_mut()
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if Limited::take() {
+            System.realloc(ptr, layout, new_size)
+        } else {
+            core::ptr::null_mut()
+        }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOC: Limited = Limited;
+
+// Runs `f` with increasing allocation budgets until it succeeds, checking that
+// it fails cleanly until then.
+fn with_budgets<T, F>(expected: T, f: F)
+where
+    T: PartialEq + core::fmt::Debug,
+    F: Fn() -> Result<T, apa::AllocError>,
+{
+    let mut n = 0;
+    loop {
+        BUDGET.with(|budget| budget.set(Some(n)));
+        let result = f();
+        BUDGET.with(|budget| budget.set(None));
+        match result {
+            Ok(value) => return assert_eq!(value, expected),
+            Err(err) => assert!(err.layout().size() > 0),
+        }
+        n += 1;
+    }
+}
+
+fn big(limbs: usize, seed: u64) -> ApInt {
+    let limbs: Vec<_> = (0..limbs as u64)
+        .map(|i| (i ^ seed).wrapping_mul(0x9e37_79b9_7f4a_7c15) as _)
+        .collect();
+    ApInt::from_limbs(&limbs)
+}
+
+#[test]
+fn try_ops_fail_cleanly() {
+    // Large enough to use the scratch buffers of Karatsuba multiplication.
+    let x = big(200, 1);
+    let y = -big(150, 2);
+
+    with_budgets(&x + &y, || x.try_add(&y));
+    with_budgets(&x - &y, || x.try_sub(&y));
+    with_budgets(&x * &y, || x.try_mul(&y));
+    with_budgets(&x * &x, || x.try_mul(&x));
+    with_budgets(x.div_rem(&y), || x.try_div_rem(&y));
+    with_budgets(y.div_rem(&x), || y.try_div_rem(&x));
+    with_budgets(x.clone(), || x.try_clone());
+}
+
+#[test]
+fn try_ops_on_inline_ints_do_not_allocate() {
+    let (x, y) = (ApInt::from(-7), ApInt::from(3));
+
+    BUDGET.with(|budget| budget.set(Some(0)));
+    let results = (
+        x.try_add(&y),
+        x.try_sub(&y),
+        x.try_mul(&y),
+        x.try_div_rem(&y),
+        x.try_clone(),
+    );
+    BUDGET.with(|budget| budget.set(None));
+
+    assert_eq!(results.0, Ok(ApInt::from(-4)));
+    assert_eq!(results.1, Ok(ApInt::from(-10)));
+    assert_eq!(results.2, Ok(ApInt::from(-21)));
+    assert_eq!(results.3, Ok((ApInt::from(-2), ApInt::from(-1))));
+    assert_eq!(results.4, Ok(ApInt::from(-7)));
+}
@@ -0,0 +1,126 @@
+use apa::ApInt;
+use apa::field::Fp;
+use apa::modular::MontgomeryCtx;
+
+mod qc;
+
+/// Primes of various sizes, including `p = 1 (mod 8)` and a Mersenne prime,
+/// which exercise the general case of Tonelli-Shanks.
+const PRIMES: &[u128] = &[
+    3,
+    5,
+    17,
+    113,
+    65537,
+    1_000_000_007,
+    (1 << 61) - 1,
+    (1 << 127) - 1,
+];
+
+fn ctx(i: u8) -> MontgomeryCtx {
+    MontgomeryCtx::new(&ApInt::from(PRIMES[usize::from(i) % PRIMES.len()])).unwrap()
+}
+
+#[test]
+fn prop_montgomery_mul() {
+    fn prop(i: u8, a: i128, b: i128) -> bool {
+        let ctx = ctx(i);
+        let p = ctx.modulus().clone();
+        let (am, bm) = (
+            ctx.to_montgomery(&ApInt::from(a)),
+            ctx.to_montgomery(&ApInt::from(b)),
+        );
+        let expected = (ApInt::from(a) * ApInt::from(b)).mod_floor(&p);
+        ctx.from_montgomery(&ctx.mul(&am, &bm)) == expected
+            && ctx.from_montgomery(&am) == ApInt::from(a).mod_floor(&p)
+    }
+    qc::quickcheck(prop as fn(u8, i128, i128) -> bool)
+}
+
+#[test]
+fn prop_field_ops() {
+    fn prop(i: u8, a: i128, b: i128, e: i8) -> bool {
+        let ctx = ctx(i);
+        let p = ctx.modulus().clone();
+        let reduce = |v: ApInt| v.mod_floor(&p);
+        let (x, y) = (
+            Fp::new(&ctx, &ApInt::from(a)),
+            Fp::new(&ctx, &ApInt::from(b)),
+        );
+        let (a, b) = (ApInt::from(a), ApInt::from(b));
+
+        let ring = (&x + &y).value() == reduce(&a + &b)
+            && (&x - &y).value() == reduce(&a - &b)
+            && (&x * &y).value() == reduce(&a * &b)
+            && (-&x).value() == reduce(-&a);
+
+        let field = match x.inv() {
+            Some(inv) => {
+                (&x * &inv).value() == ApInt::ONE
+                    && x.pow(&ApInt::from(e)) * x.pow(&ApInt::from(-e)) == Fp::one(&ctx)
+            }
+            None => x.is_zero(),
+        };
+        ring && field
+    }
+    qc::quickcheck(prop as fn(u8, i128, i128, i8) -> bool)
+}
+
+#[test]
+fn prop_sqrt() {
+    fn prop(i: u8, a: i128) -> bool {
+        let ctx = ctx(i);
+        let x = Fp::new(&ctx, &ApInt::from(a));
+        let square = &x * &x;
+        match square.sqrt() {
+            Some(r) => &r * &r == square && (r == x || r == -&x),
+            None => false,
+        }
+    }
+    qc::quickcheck(prop as fn(u8, i128) -> bool)
+}
+
+#[test]
+fn sqrt_of_non_squares() {
+    let ctx = ctx(3);
+    // The squares modulo 113 are exactly half of the non-zero elements.
+    let squares = (0..113)
+        .filter(|&a| Fp::new(&ctx, &ApInt::from(a)).sqrt().is_some())
+        .count();
+    assert_eq!(squares, 57);
+}
+
+#[test]
+fn prop_batch_inv() {
+    fn prop(i: u8, seed: u64) -> bool {
+        let ctx = ctx(i);
+        let mut x = seed | 1;
+        let elems: Vec<Fp> = (0..(seed % 9))
+            .map(|_| {
+                x ^= x << 13;
+                x ^= x >> 7;
+                x ^= x << 17;
+                Fp::new(&ctx, &ApInt::from(x))
+            })
+            .collect();
+
+        let invs = Fp::batch_inv(&elems);
+        if elems.iter().any(Fp::is_zero) {
+            return invs.is_none();
+        }
+        let invs = invs.unwrap();
+        invs.len() == elems.len()
+            && elems
+                .iter()
+                .zip(&invs)
+                .all(|(e, inv)| Some(inv.clone()) == e.inv())
+    }
+    qc::quickcheck(prop as fn(u8, u64) -> bool)
+}
+
+#[test]
+fn montgomery_ctx_rejects_even_moduli() {
+    assert!(MontgomeryCtx::new(&ApInt::from(10)).is_none());
+    assert!(MontgomeryCtx::new(&ApInt::ONE).is_none());
+    assert!(MontgomeryCtx::new(&ApInt::from(-7)).is_none());
+}
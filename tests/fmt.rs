@@ -0,0 +1,334 @@
+use core::mem::size_of;
+
+use apa::{ApInt, LimbRepr};
+
+mod qc;
+
+macro_rules! quickcheck_unsigned {
+    ($($ty:ident),* $(,)*) => {
+        $(
+            paste::item! {
+               #[test]
+               fn [< prop_fmt_ $ty >] () {
+                    fn prop(n: $ty) -> bool {
+                        let int = ApInt::from(n);
+
+                        format!("{}", int) == format!("{}", n)
+                            && format!("{:b}", int) == format!("{:b}", n)
+                            && format!("{:o}", int) == format!("{:o}", n)
+                            && format!("{:x}", int) == format!("{:x}", n)
+                            && format!("{:X}", int) == format!("{:X}", n)
+                            && format!("{:#020x}", int) == format!("{:#020x}", n)
+                            && format!("{:+}", int) == format!("{:+}", n)
+                    }
+                    qc::quickcheck(prop as fn($ty) -> bool)
+               }
+
+               #[test]
+               fn [< prop_to_str_radix_ $ty >] () {
+                    fn prop(n: $ty, radix: u8) -> bool {
+                        let radix = 2 + (radix % 35) as u32;
+                        let s = ApInt::from(n).to_str_radix(radix);
+                        $ty::from_str_radix(&s, radix) == Ok(n)
+                    }
+                    qc::quickcheck(prop as fn($ty, u8) -> bool)
+               }
+            }
+        )*
+    };
+}
+
+#[rustfmt::skip]
+quickcheck_unsigned!(u8, u16, u32, u64, u128, usize);
+
+macro_rules! quickcheck_signed {
+    ($($ty:ident),* $(,)*) => {
+        $(
+            paste::item! {
+               #[test]
+               fn [< prop_fmt_ $ty >] () {
+                    fn prop(n: $ty) -> bool {
+                        let int = ApInt::from(n);
+
+                        // Negative values are formatted as a sign and magnitude.
+                        let mag = (n as i128).wrapping_neg() as u128;
+                        let hex = if n < 0 { format!("-{:x}", mag) } else { format!("{:x}", n) };
+
+                        format!("{}", int) == format!("{}", n)
+                            && format!("{:>50}", int) == format!("{:>50}", n)
+                            && format!("{:x}", int) == hex
+                    }
+                    qc::quickcheck(prop as fn($ty) -> bool)
+               }
+
+               #[test]
+               fn [< prop_to_str_radix_ $ty >] () {
+                    fn prop(n: $ty, radix: u8) -> bool {
+                        let radix = 2 + (radix % 35) as u32;
+                        let s = ApInt::from(n).to_str_radix(radix);
+                        $ty::from_str_radix(&s, radix) == Ok(n)
+                    }
+                    qc::quickcheck(prop as fn($ty, u8) -> bool)
+               }
+            }
+        )*
+    };
+}
+
+#[rustfmt::skip]
+quickcheck_signed!(i8, i16, i32, i64, i128, isize);
+
+#[test]
+fn fmt_multi_limb() {
+    let max = ApInt::from(u128::MAX);
+    let int = &max * &max;
+
+    assert_eq!(
+        format!("{}", int),
+        "115792089237316195423570985008687907852589419931798687112530834793049593217025"
+    );
+    assert_eq!(
+        format!("{:x}", int),
+        "fffffffffffffffffffffffffffffffe00000000000000000000000000000001"
+    );
+    assert_eq!(
+        format!("{:#o}", int),
+        "0o17777777777777777777777777777777777777777770000000000000000000000000000000000000000001"
+    );
+    assert_eq!(
+        int.to_str_radix(36),
+        "6dp5qcb22im238nr3wvp0ic7peyo491jomn7inkv66wd3xyfi9"
+    );
+    assert_eq!(
+        int.to_str_radix(32),
+        "1vvvvvvvvvvvvvvvvvvvvvvvvvg0000000000000000000000001"
+    );
+    assert_eq!(
+        (-int).to_str_radix(7),
+        "-13053363422555522616006053000312142162322155050143541422566402200156420552055246145414154412"
+    );
+}
+
+#[test]
+fn fmt_zero() {
+    assert_eq!(format!("{}", ApInt::ZERO), "0");
+    assert_eq!(format!("{:#b}", ApInt::ZERO), "0b0");
+    assert_eq!(ApInt::ZERO.to_str_radix(36), "0");
+}
+
+#[test]
+#[should_panic(expected = "radix must be within the range 2..=36")]
+fn to_str_radix_invalid() {
+    ApInt::ONE.to_str_radix(37);
+}
+
+fn chunks_to_string(int: &ApInt) -> String {
+    let mut s = String::new();
+    for (i, chunk) in int.decimal_chunks().enumerate() {
+        assert!(chunk < 10_000_000_000_000_000_000);
+        if i == 0 {
+            s += &chunk.to_string();
+        } else {
+            s += &format!("{:019}", chunk);
+        }
+    }
+    s
+}
+
+#[test]
+fn prop_digits() {
+    fn prop(a: i128, b: i128, radix: u8) -> bool {
+        let radix = radix as u32 % 35 + 2;
+        let int = ApInt::from(a) * ApInt::from(b);
+        let digits = int.to_str_radix(radix);
+        let digits = digits.trim_start_matches('-');
+
+        let sum: u64 = digits.chars().map(|c| c.to_digit(36).unwrap() as u64).sum();
+        int.digit_count(radix) == digits.len() && int.digit_sum(radix) == sum
+    }
+    qc::quickcheck(prop as fn(i128, i128, u8) -> bool)
+}
+
+#[test]
+fn prop_decimal_chunks() {
+    fn prop(a: i128, b: i128, c: i64) -> bool {
+        let int = ApInt::from(a) * ApInt::from(b) * ApInt::from(c);
+        chunks_to_string(&int) == int.abs().to_string()
+    }
+    qc::quickcheck(prop as fn(i128, i128, i64) -> bool)
+}
+
+#[test]
+fn decimal_chunks_large() {
+    let mut int = ApInt::ONE;
+    for _ in 0..3000 {
+        int *= ApInt::from(3);
+    }
+    assert_eq!(chunks_to_string(&int), int.to_string());
+
+    // Zero chunks within the value must be kept.
+    let int = ApInt::from(10u64.pow(19)) * ApInt::from(10u64.pow(19)) * ApInt::from(10u64.pow(19));
+    assert_eq!(int.decimal_chunks().collect::<Vec<_>>(), [1, 0, 0, 0]);
+    assert_eq!(ApInt::ZERO.decimal_chunks().collect::<Vec<_>>(), [0]);
+}
+
+#[test]
+fn digit_count_zero() {
+    assert_eq!(ApInt::ZERO.digit_count(10), 1);
+    assert_eq!(ApInt::ZERO.digit_sum(10), 0);
+    assert_eq!(ApInt::from(-255).digit_count(16), 2);
+}
+
+#[test]
+fn prop_to_string_grouped() {
+    fn prop(n: i128, group: u8) -> bool {
+        let group = group as usize % 5 + 1;
+        let s = ApInt::from(n).to_string_grouped(',', group);
+
+        let digits = s.trim_start_matches('-');
+        let parts: Vec<&str> = digits.split(',').collect();
+        parts.concat() == (n.wrapping_abs() as u128).to_string()
+            && parts[1..].iter().all(|p| p.len() == group)
+            && (1..=group).contains(&parts[0].len())
+            && s.starts_with('-') == (n < 0)
+    }
+    qc::quickcheck(prop as fn(i128, u8) -> bool)
+}
+
+#[test]
+fn prop_fmt_exp() {
+    fn prop(n: i64, precision: u8) -> bool {
+        let precision = precision as usize % 22;
+        let int = ApInt::from(n);
+
+        format!("{:e}", int) == format!("{:e}", n)
+            && format!("{:E}", int) == format!("{:E}", n)
+            && format!("{:+.*e}", precision, int) == format!("{:+.*e}", precision, n)
+    }
+    qc::quickcheck(prop as fn(i64, u8) -> bool)
+}
+
+#[test]
+fn fmt_exp() {
+    let mut int = ApInt::from(1234567);
+    for _ in 0..1234 {
+        int *= ApInt::from(10);
+    }
+    assert_eq!(format!("{:e}", int), "1.234567e1240");
+    assert_eq!(format!("{:.2E}", -int), "-1.23E1240");
+
+    assert_eq!(format!("{:e}", ApInt::ZERO), "0e0");
+    assert_eq!(format!("{:.1e}", ApInt::from(999)), "1.0e3");
+    assert_eq!(format!("{:>8e}", ApInt::from(1500)), "   1.5e3");
+}
+
+#[test]
+fn prop_twos_complement() {
+    fn prop(a: i32, b: i64, c: i128) -> bool {
+        // Primitive integers format negative values in two's complement.
+        ApInt::from(a).to_twos_complement_hex(32) == format!("{:08x}", a)
+            && ApInt::from(b).to_twos_complement_hex(64) == format!("{:016x}", b)
+            && ApInt::from(c).to_twos_complement_hex(128) == format!("{:032x}", c)
+            && ApInt::from(a).to_twos_complement_binary(32) == format!("{:032b}", a)
+            && ApInt::from(b).to_twos_complement_binary(64) == format!("{:064b}", b)
+    }
+    qc::quickcheck(prop as fn(i32, i64, i128) -> bool)
+}
+
+#[test]
+fn twos_complement_odd_width() {
+    assert_eq!(ApInt::from(-1).to_twos_complement_hex(5), "1f");
+    assert_eq!(ApInt::from(0x1234).to_twos_complement_hex(8), "34");
+    assert_eq!(ApInt::ZERO.to_twos_complement_hex(1), "0");
+    assert_eq!(
+        (-(ApInt::ONE << 100)).to_twos_complement_hex(104),
+        "f0000000000000000000000000"
+    );
+    assert_eq!(ApInt::from(-2).to_twos_complement_binary(3), "110");
+}
+
+/// Converts the scientific notation of `{:e}` to engineering notation.
+fn sci_to_engineering(sci: &str) -> String {
+    let (neg, sci) = match sci.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, sci),
+    };
+    let (mantissa, exp) = sci.split_at(sci.find('e').unwrap());
+    let exp: usize = exp[1..].parse().unwrap();
+    let mut digits: String = mantissa.chars().filter(|&c| c != '.').collect();
+
+    let int_len = exp % 3 + 1;
+    while digits.len() < int_len {
+        digits.push('0');
+    }
+    let (int, frac) = digits.split_at(int_len);
+    let sign = if neg { "-" } else { "" };
+    let point = if frac.is_empty() { "" } else { "." };
+    format!("{}{}{}{}e+{}", sign, int, point, frac, exp - exp % 3)
+}
+
+#[test]
+fn prop_engineering_string() {
+    fn prop(n: i128, shift: u8, sig: u8) -> bool {
+        let sig = sig as usize % 30 + 1;
+        // Multiplying by powers of ten makes long runs of zeros and ties.
+        let int = ApInt::from(n) * ApInt::from(10).pow_int(&ApInt::from(shift % 60)).unwrap();
+
+        let expected = sci_to_engineering(&format!("{:.*e}", sig - 1, int));
+        int.to_engineering_string(sig) == expected
+    }
+    qc::quickcheck(prop as fn(i128, u8, u8) -> bool)
+}
+
+#[test]
+fn engineering_string() {
+    assert_eq!(ApInt::ZERO.to_engineering_string(3), "0.00e+0");
+    assert_eq!(ApInt::from(12345).to_engineering_string(1), "10e+3");
+    assert_eq!(ApInt::from(125).to_engineering_string(2), "120e+0");
+    assert_eq!(ApInt::from(135).to_engineering_string(2), "140e+0");
+    assert_eq!(ApInt::from(999).to_engineering_string(2), "1.0e+3");
+
+    let big = ApInt::from(12345) * ApInt::from(10).pow_int(&ApInt::from(6017)).unwrap();
+    assert_eq!(big.to_engineering_string(5), "1.2345e+6021");
+    assert_eq!(
+        (-(&big + ApInt::ONE)).to_engineering_string(3),
+        "-1.23e+6021"
+    );
+}
+
+#[test]
+fn debug_limbs() {
+    let hex = |limb: LimbRepr| format!("{:#01$x}", limb, 2 + 2 * size_of::<LimbRepr>());
+
+    assert_eq!(
+        format!("{:?}", ApInt::ZERO),
+        format!("ApInt {{ sign: Zero, bits: 0, limbs: [{}] }}", hex(0)),
+    );
+
+    let int = -(ApInt::from(5) << (8 * size_of::<LimbRepr>()));
+    assert_eq!(
+        format!("{:?}", int),
+        format!(
+            "ApInt {{ sign: Negative, bits: {}, limbs: [{}, {}] }}",
+            int.bits(),
+            hex(LimbRepr::MAX - 4),
+            hex(0),
+        ),
+    );
+}
+
+#[test]
+fn debug_alternate_elides() {
+    let int = (ApInt::ONE << (3000 * 8 * size_of::<LimbRepr>())) - ApInt::from(2);
+    let full = format!("{:?}", int);
+    let pretty = format!("{:#?}", int);
+
+    assert_eq!(full.matches("0x").count(), 3001);
+    assert_eq!(pretty.matches("0x").count(), 8);
+    assert!(pretty.contains("... 2993 limbs ..."));
+    assert!(pretty.lines().count() < 20);
+
+    // Small ints are never elided.
+    let small = format!("{:#?}", ApInt::from(u128::MAX));
+    assert!(!small.contains("..."));
+}
@@ -0,0 +1,92 @@
+use apa::ApInt;
+
+mod qc;
+
+fn gcd_i128(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a.abs()
+}
+
+#[test]
+fn prop_gcd_lcm() {
+    fn prop(a: i64, b: i64) -> bool {
+        let (x, y) = (ApInt::from(a), ApInt::from(b));
+        let gcd = gcd_i128(a as i128, b as i128);
+        let lcm = if gcd == 0 {
+            0
+        } else {
+            (a as i128 / gcd * b as i128).abs()
+        };
+
+        x.gcd(&y) == ApInt::from(gcd)
+            && x.lcm(&y) == ApInt::from(lcm)
+            && x.gcd_lcm(&y) == (ApInt::from(gcd), ApInt::from(lcm))
+    }
+    qc::quickcheck(prop as fn(i64, i64) -> bool)
+}
+
+#[test]
+fn prop_extended_gcd() {
+    fn prop(a: i64, b: i64, k: u32) -> bool {
+        // Scale both operands to exercise shared factors of two.
+        let (x, y) = (
+            ApInt::from(a) << (k as usize % 70),
+            ApInt::from(b) << (k as usize % 70),
+        );
+        let (g, s, t) = x.extended_gcd(&y);
+        g == x.gcd(&y) && &x * &s + &y * &t == g
+    }
+    qc::quickcheck(prop as fn(i64, i64, u32) -> bool)
+}
+
+#[test]
+fn prop_extended_gcd_multi_limb() {
+    fn prop(a: i128, b: i128, c: i64) -> bool {
+        let c = ApInt::from(c);
+        let (x, y) = (ApInt::from(a) * &c, ApInt::from(b) * &c);
+        let (g, s, t) = x.extended_gcd(&y);
+        &x * &s + &y * &t == g && (&g % &c) == ApInt::ZERO
+    }
+    qc::quickcheck(prop as fn(i128, i128, i64) -> bool)
+}
+
+#[test]
+fn zero() {
+    let zero = ApInt::ZERO;
+    let n = ApInt::from(-12);
+
+    assert_eq!(zero.gcd(&zero), zero);
+    assert_eq!(zero.gcd_lcm(&n), (ApInt::from(12), zero.clone()));
+    assert_eq!(
+        zero.extended_gcd(&zero),
+        (zero.clone(), zero.clone(), zero.clone())
+    );
+    assert_eq!(
+        zero.extended_gcd(&n),
+        (ApInt::from(12), zero.clone(), ApInt::from(-1))
+    );
+    assert_eq!(
+        n.extended_gcd(&zero),
+        (ApInt::from(12), ApInt::from(-1), zero)
+    );
+}
+
+#[test]
+fn prop_is_coprime() {
+    fn prop(a: i64, b: i64) -> bool {
+        let (x, y) = (ApInt::from(a), ApInt::from(b));
+        let mut g = (a as i128).abs();
+        let mut h = (b as i128).abs();
+        while h != 0 {
+            let r = g % h;
+            g = h;
+            h = r;
+        }
+        x.is_coprime(&y) == (g == 1)
+    }
+    qc::quickcheck(prop as fn(i64, i64) -> bool)
+}
@@ -0,0 +1,68 @@
+use apa::{ApInt, Guard};
+
+mod qc;
+
+#[test]
+fn check() {
+    let guard = Guard::new(8);
+    assert_eq!(guard.check(ApInt::from(255)), Ok(ApInt::from(255)));
+    assert_eq!(guard.check(ApInt::from(-256)), Ok(ApInt::from(-256)));
+    assert!(guard.check(ApInt::from(256)).is_err());
+    assert!(guard.check(ApInt::from(-257)).is_err());
+
+    let err = guard.check(ApInt::from(256)).unwrap_err();
+    assert_eq!(err.max_bits(), 8);
+    assert_eq!(err.to_string(), "result exceeds the limit of 8 bits");
+}
+
+#[test]
+fn prop_guarded_ops() {
+    fn prop(a: i64, b: i64, max_bits: u8) -> bool {
+        let max_bits = max_bits % 127;
+        let guard = Guard::new(max_bits as usize);
+        let (x, y) = (ApInt::from(a), ApInt::from(b));
+        let fits = |v: i128| v >= -(1i128 << max_bits) && v < (1i128 << max_bits);
+        let expect = |v: i128| if fits(v) { Ok(ApInt::from(v)) } else { Err(()) };
+
+        let (a, b) = (a as i128, b as i128);
+        let ok = guard.add(&x, &y).map_err(drop) == expect(a + b)
+            && guard.sub(&x, &y).map_err(drop) == expect(a - b)
+            && guard.mul(&x, &y).map_err(drop) == expect(a * b);
+
+        if b == 0 {
+            ok
+        } else {
+            ok && guard.div(&x, &y).map_err(drop) == expect(a / b)
+                && guard.rem(&x, &y).map_err(drop) == expect(a % b)
+        }
+    }
+    qc::quickcheck(prop as fn(i64, i64, u8) -> bool)
+}
+
+#[test]
+fn prop_guarded_shl() {
+    fn prop(a: i32, bits: u8, max_bits: u8) -> bool {
+        let guard = Guard::new(max_bits as usize % 96);
+        let x = ApInt::from(a);
+        let bits = bits as usize % 64;
+
+        let expected = guard.check(&x << bits);
+        guard.shl(&x, bits) == expected
+    }
+    qc::quickcheck(prop as fn(i32, u8, u8) -> bool)
+}
+
+#[test]
+fn memory_bomb() {
+    let guard = Guard::new(1 << 20);
+    let mut x = ApInt::from(10);
+    let err = loop {
+        match guard.mul(&x, &x) {
+            Ok(y) => x = y,
+            Err(err) => break err,
+        }
+    };
+    assert_eq!(err.max_bits(), 1 << 20);
+    assert!(guard.shl(&ApInt::from(-1), usize::MAX).is_err());
+    assert_eq!(guard.shl(&ApInt::ZERO, usize::MAX), Ok(ApInt::ZERO));
+}
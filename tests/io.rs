@@ -0,0 +1,65 @@
+#![cfg(feature = "std")]
+
+use std::io::{self, Read};
+
+use apa::ApInt;
+
+mod qc;
+
+/// A reader that yields its input a few bytes at a time.
+struct Trickle<'a>(&'a [u8]);
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = buf.len().min(self.0.len()).min(3);
+        buf[..n].copy_from_slice(&self.0[..n]);
+        self.0 = &self.0[n..];
+        Ok(n)
+    }
+}
+
+#[test]
+fn prop_round_trip() {
+    fn prop(a: i128, b: i128, c: i64) -> bool {
+        let int = ApInt::from(a) * ApInt::from(b) * ApInt::from(c);
+
+        let mut out = Vec::new();
+        int.write_decimal(&mut out).unwrap();
+
+        out == int.to_string().into_bytes()
+            && ApInt::read_decimal(&out[..]).unwrap() == int
+            && ApInt::read_decimal(Trickle(&out)).unwrap() == int
+    }
+    qc::quickcheck(prop as fn(i128, i128, i64) -> bool)
+}
+
+#[test]
+fn round_trip_large() {
+    let mut int = -ApInt::from(7);
+    for _ in 0..5000 {
+        int *= ApInt::from(7);
+    }
+
+    let mut out = Vec::new();
+    int.write_decimal(&mut out).unwrap();
+    assert_eq!(out, int.to_string().into_bytes());
+    assert_eq!(ApInt::read_decimal(&out[..]).unwrap(), int);
+}
+
+#[test]
+fn read_decimal_syntax() {
+    let read = |s: &str| ApInt::read_decimal(s.as_bytes()).ok();
+
+    assert_eq!(read("0"), Some(ApInt::ZERO));
+    assert_eq!(read("-0"), Some(ApInt::ZERO));
+    assert_eq!(read("+42"), Some(ApInt::from(42)));
+    assert_eq!(
+        read("  \n-0000000000000000000000012\r\n"),
+        Some(ApInt::from(-12))
+    );
+
+    for s in &["", " ", "-", "+-1", "1 2", "12a", "0x10", "1_000"] {
+        let err = ApInt::read_decimal(s.as_bytes()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{:?}", s);
+    }
+}
@@ -0,0 +1,41 @@
+#![cfg(feature = "test-utils")]
+
+use apa::ApInt;
+use apa::test_utils::{self, Gen};
+
+mod qc;
+
+#[test]
+fn laws_hold() {
+    test_utils::check_laws(0x5eed, 2_000);
+}
+
+#[test]
+fn prop_laws_from_seeds() {
+    fn prop(seed: u64) -> bool {
+        let mut gen = Gen::new(seed);
+        let (a, b, c) = (gen.int(4), gen.int(4), gen.int(4));
+        test_utils::add_associative(&a, &b, &c)
+            && test_utils::mul_distributes_over_add(&a, &b, &c)
+            && test_utils::div_rem_identity(&a, &b)
+            && test_utils::round_trips(&c)
+    }
+    qc::quickcheck(prop as fn(u64) -> bool)
+}
+
+#[test]
+fn gen_is_deterministic() {
+    let ints = |seed| {
+        let mut gen = Gen::new(seed);
+        (0..16).map(|_| gen.int(3)).collect::<Vec<_>>()
+    };
+    assert_eq!(ints(3), ints(3));
+    assert_ne!(ints(3), ints(4));
+}
+
+#[test]
+fn gen_nonzero() {
+    let mut gen = Gen::new(0);
+    assert!((0..1_000).all(|_| gen.nonzero_int(1) != ApInt::ZERO));
+    assert_eq!(gen.int(0), ApInt::ZERO);
+}
@@ -0,0 +1,94 @@
+use apa::{ApInt, LazyInt, LimbRepr};
+
+mod qc;
+
+#[test]
+fn prop_matches_eager() {
+    fn prop(start: i128, seed: u64) -> bool {
+        let mut state = seed | 1;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        let mut lazy = LazyInt::from(ApInt::from(start));
+        let mut eager = ApInt::from(start);
+        for _ in 0..16 {
+            let op = next();
+            // Widen the operands past a single limb now and then.
+            let shift = (op as usize / 4 % 4) * 50;
+            let x = ApInt::from(next() as i64) << shift;
+            let y = ApInt::from(next() as i64);
+            match op % 4 {
+                0 => {
+                    lazy.add_assign(&x);
+                    eager += &x;
+                }
+                1 => {
+                    lazy.sub_assign(&x);
+                    eager -= &x;
+                }
+                2 => {
+                    lazy.add_mul(&x, &y);
+                    eager += &x * &y;
+                }
+                _ => {
+                    lazy.sub_mul(&x, &y);
+                    eager -= &x * &y;
+                }
+            }
+        }
+        let result = lazy.normalize();
+        result.assert_valid();
+        result == eager
+    }
+    qc::quickcheck(prop as fn(i128, u64) -> bool);
+}
+
+#[test]
+fn cancellation_normalizes() {
+    let big = ApInt::from(u128::MAX) << 300;
+    let mut lazy = LazyInt::new();
+    lazy.add_mul(&big, &big);
+    lazy.sub_mul(&big, &big);
+    lazy.add_assign(&ApInt::NEG_ONE);
+    let result = lazy.normalize();
+    result.assert_valid();
+    assert_eq!(result, ApInt::NEG_ONE);
+}
+
+#[test]
+fn capacity_is_reused() {
+    let mut lazy = LazyInt::with_capacity(4);
+    let capacity = lazy.capacity();
+    for i in 0..1000 {
+        lazy.add_assign(&ApInt::from(i));
+    }
+    assert_eq!(lazy.capacity(), capacity);
+    assert_eq!(lazy.normalize(), ApInt::from(999 * 1000 / 2));
+}
+
+#[test]
+fn shrink_releases_memory() {
+    let big = ApInt::from(u128::MAX) << 1000;
+    let mut lazy = LazyInt::new();
+    lazy.add_mul(&big, &big);
+    lazy.sub_mul(&big, &big);
+    lazy.add_assign(&ApInt::from(-3));
+    assert!(lazy.memory_usage() >= 2 * big.memory_usage());
+
+    lazy.shrink_to_fit();
+    assert_eq!(lazy.capacity(), 2);
+    let expected = if ApInt::INLINE_LIMBS >= 2 {
+        0
+    } else {
+        2 * core::mem::size_of::<LimbRepr>()
+    };
+    assert_eq!(lazy.memory_usage(), expected);
+
+    lazy.add_mul(&big, &ApInt::ONE);
+    lazy.sub_assign(&big);
+    assert_eq!(lazy.normalize(), ApInt::from(-3));
+}
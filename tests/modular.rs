@@ -0,0 +1,109 @@
+use apa::ApInt;
+use apa::modular::{Mod2k, rsa_crt_components, rsa_crt_decrypt};
+
+mod qc;
+
+fn pow_mod(base: &ApInt, exp: &ApInt, m: &ApInt) -> ApInt {
+    let mut r = ApInt::ONE;
+    let mut base = base % m;
+    let mut exp = exp.clone();
+    while exp > ApInt::ZERO {
+        if exp.bit(0) {
+            r = r * &base % m;
+        }
+        base = &base * &base % m;
+        exp >>= 1;
+    }
+    r
+}
+
+#[test]
+fn prop_rsa_crt_decrypt() {
+    fn prop(m: u64, d: u64) -> bool {
+        // Two primes below `2^64`, so the modulus spans three limbs.
+        let p = ApInt::from(18446744073709551557u64);
+        let q = ApInt::from(18446744073709551533u64);
+        let n = &p * &q;
+        let (m, d) = (ApInt::from(m), ApInt::from(d));
+
+        let c = rsa_crt_components(&p, &q, &d).unwrap();
+        rsa_crt_decrypt(&m, &c.d_p, &c.d_q, &c.q_inv, &p, &q) == pow_mod(&m, &d, &n)
+    }
+    qc::quickcheck(prop as fn(u64, u64) -> bool)
+}
+
+#[test]
+fn rsa_round_trip() {
+    let p = (ApInt::ONE << 127) - ApInt::ONE;
+    let q = (ApInt::ONE << 89) - ApInt::ONE;
+    let n = &p * &q;
+    let e = ApInt::from(65537);
+    let phi = (&p - ApInt::ONE) * (&q - ApInt::ONE);
+    let (_, d, _) = e.extended_gcd(&phi);
+    let d = ((d % &phi) + &phi) % &phi;
+
+    let c = rsa_crt_components(&p, &q, &d).unwrap();
+    let m = ApInt::from(0x1234_5678_9abc_def0u64) << 100;
+    let ciphertext = pow_mod(&m, &e, &n);
+    assert_eq!(
+        rsa_crt_decrypt(&ciphertext, &c.d_p, &c.d_q, &c.q_inv, &p, &q),
+        m
+    );
+
+    // A negative input is reduced into range first.
+    let neg = rsa_crt_decrypt(&(&ciphertext - &n), &c.d_p, &c.d_q, &c.q_inv, &p, &q);
+    assert_eq!(neg, m);
+}
+
+#[test]
+fn rsa_crt_components_not_coprime() {
+    assert!(rsa_crt_components(&ApInt::from(7), &ApInt::from(14), &ApInt::from(5)).is_none());
+}
+
+#[test]
+fn prop_mod2k_matches_wrapping_u64() {
+    fn prop(a: u64, b: u64, e: u8) -> bool {
+        let m = |v: u64| Mod2k::new(&ApInt::from(v), 64);
+        let (x, y) = (m(a), m(b));
+        (&x + &y) == m(a.wrapping_add(b))
+            && (&x - &y) == m(a.wrapping_sub(b))
+            && (&x * &y) == m(a.wrapping_mul(b))
+            && -&x == m(a.wrapping_neg())
+            && x.pow(&ApInt::from(e)) == m(a.wrapping_pow(u32::from(e)))
+            && x.to_signed() == ApInt::from(a as i64)
+    }
+    qc::quickcheck(prop as fn(u64, u64, u8) -> bool)
+}
+
+#[test]
+fn prop_mod2k_inverse() {
+    fn prop(a: i128, k: u16) -> bool {
+        let k = usize::from(k % 300) + 1;
+        let x = Mod2k::new(&ApInt::from(a), k);
+        match x.inverse() {
+            Some(inv) => {
+                let one = Mod2k::new(&ApInt::ONE, k);
+                a % 2 != 0
+                    && &x * &inv == one
+                    && x.pow(&ApInt::from(-3)) == inv.pow(&ApInt::from(3))
+            }
+            None => a % 2 == 0,
+        }
+    }
+    qc::quickcheck(prop as fn(i128, u16) -> bool)
+}
+
+#[test]
+fn mod2k_wide() {
+    let k = 200;
+    let x = Mod2k::new(&ApInt::from(-1), k);
+    assert_eq!(x.value(), &((ApInt::ONE << k) - ApInt::ONE));
+    assert_eq!((&x + &x).to_signed(), ApInt::from(-2));
+    assert_eq!(x.clone().into_value().bits(), k);
+}
+
+#[test]
+#[should_panic(expected = "moduli must match")]
+fn mod2k_mismatched() {
+    let _ = Mod2k::new(&ApInt::ONE, 8) + Mod2k::new(&ApInt::ONE, 16);
+}
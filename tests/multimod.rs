@@ -0,0 +1,81 @@
+use apa::ApInt;
+
+mod qc;
+
+const PRIMES: [i64; 8] = [3, 5, 7, 11, 13, 17, 19, 23];
+
+#[test]
+fn prop_rem_multi() {
+    fn prop(n: i128, seed: u64, len: u8) -> bool {
+        let mut state = seed | 1;
+        let moduli: Vec<i128> = (0..len % 20)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state >> (state % 64)) as i128 + 1
+            })
+            .collect();
+
+        let expected: Vec<ApInt> = moduli
+            .iter()
+            .map(|&m| ApInt::from(n.rem_euclid(m)))
+            .collect();
+        let moduli: Vec<ApInt> = moduli.into_iter().map(ApInt::from).collect();
+        ApInt::from(n).rem_multi(&moduli) == expected
+    }
+    qc::quickcheck(prop as fn(i128, u64, u8) -> bool)
+}
+
+#[test]
+fn prop_crt() {
+    fn prop(n: u64, len: u8) -> bool {
+        let moduli: Vec<ApInt> = PRIMES[..len as usize % 9]
+            .iter()
+            .map(|&p| ApInt::from(p))
+            .collect();
+        let product: ApInt = moduli.iter().product();
+        let n = ApInt::from(n) % &product;
+
+        let residues = n.rem_multi(&moduli);
+        ApInt::crt(&residues, &moduli) == Some(n)
+    }
+    qc::quickcheck(prop as fn(u64, u8) -> bool)
+}
+
+#[test]
+fn crt_large_moduli() {
+    let moduli = [
+        (ApInt::ONE << 127) - ApInt::ONE,
+        (ApInt::ONE << 89) - ApInt::ONE,
+        ApInt::from(u64::MAX),
+        ApInt::from(1_000_000_007),
+    ];
+    let n = (ApInt::ONE << 250) + ApInt::from(12345);
+    let residues = n.rem_multi(&moduli);
+    assert_eq!(ApInt::crt(&residues, &moduli), Some(n));
+
+    // Negative residues are reduced into range.
+    let residues = [
+        ApInt::from(-1),
+        ApInt::from(-1),
+        ApInt::from(-1),
+        ApInt::from(-1),
+    ];
+    let product: ApInt = moduli.iter().product();
+    assert_eq!(ApInt::crt(&residues, &moduli), Some(product - ApInt::ONE));
+}
+
+#[test]
+fn crt_not_coprime() {
+    let moduli = [ApInt::from(6), ApInt::from(35), ApInt::from(10)];
+    let residues = [ApInt::ZERO, ApInt::ZERO, ApInt::ZERO];
+    assert_eq!(ApInt::crt(&residues, &moduli), None);
+    assert_eq!(ApInt::crt(&[], &[]), Some(ApInt::ZERO));
+}
+
+#[test]
+#[should_panic(expected = "modulus must be positive")]
+fn rem_multi_zero_modulus() {
+    ApInt::from(5).rem_multi(&[ApInt::from(3), ApInt::ZERO]);
+}
@@ -0,0 +1,111 @@
+use apa::{ApInt, RoundingMode};
+
+mod qc;
+
+macro_rules! quickcheck_neg {
+    ($($ty:ident),* $(,)*) => {
+        $(
+            paste::item! {
+               #[test]
+               fn [< prop_neg_ $ty >] () {
+                    fn prop(n: $ty) -> bool {
+                        let expected = ApInt::from(-(n as i128));
+
+                        let mut int = ApInt::from(n);
+                        int.negate();
+
+                        -ApInt::from(n) == expected && int == expected
+                    }
+                    qc::quickcheck(prop as fn($ty) -> bool)
+               }
+
+               #[test]
+               fn [< prop_abs_ $ty >] () {
+                    fn prop(n: $ty) -> bool {
+                        let expected = ApInt::from((n as i128).abs());
+
+                        let mut int = ApInt::from(n);
+                        int.make_abs();
+
+                        ApInt::from(n).abs() == expected && int == expected
+                    }
+                    qc::quickcheck(prop as fn($ty) -> bool)
+               }
+            }
+        )*
+    };
+}
+
+#[rustfmt::skip]
+quickcheck_neg!(
+    u8, u16, u32, u64,
+    i8, i16, i32, i64,
+);
+
+macro_rules! quickcheck_neg_neg {
+    ($($ty:ident),* $(,)*) => {
+        $(
+            paste::item! {
+               #[test]
+               fn [< prop_neg_neg_ $ty >] () {
+                    fn prop(n: $ty) -> bool {
+                        let int = ApInt::from(n);
+                        -(-&int) == int
+                    }
+                    qc::quickcheck(prop as fn($ty) -> bool)
+               }
+            }
+        )*
+    };
+}
+
+quickcheck_neg_neg!(u128, i128);
+
+#[test]
+fn neg_min() {
+    let min = ApInt::from(i128::MIN);
+    let expected = ApInt::from(1u128 << 127);
+    assert_eq!(-&min, expected);
+    assert_eq!(-expected, min);
+}
+
+#[test]
+fn neg_limb_min() {
+    let min = ApInt::from(isize::MIN);
+    let expected = ApInt::from(isize::MIN as usize);
+    assert_eq!(min.abs(), expected);
+    assert_eq!(-expected, min);
+}
+
+#[test]
+fn neg_zero() {
+    assert_eq!(-ApInt::ZERO, ApInt::ZERO);
+}
+
+#[test]
+fn neg_keeps_storage() {
+    // Negating `2^127` needs one limb fewer, which must not shrink the int.
+    let mut int = ApInt::from(1u128 << 127);
+    let usage = int.memory_usage();
+    int.negate();
+    assert_eq!(int, ApInt::from(i128::MIN));
+    assert_eq!(int.memory_usage(), usage);
+
+    // Growing back fits in the same allocation.
+    int.negate();
+    assert_eq!(int, ApInt::from(1u128 << 127));
+    assert_eq!(int.memory_usage(), usage);
+}
+
+#[test]
+fn neg_op_fits_storage() {
+    // Unlike `negate`, the operator reallocates the result to fit.
+    let int = -ApInt::from(1u128 << 127);
+    assert_eq!(int, ApInt::from(i128::MIN));
+    assert_eq!(int.memory_usage(), ApInt::from(i128::MIN).memory_usage());
+
+    let s = "-170141183460469231731687303715884105728";
+    let parsed = ApInt::parse_rounded(s, RoundingMode::Floor).unwrap();
+    assert_eq!(parsed, ApInt::from(i128::MIN));
+    assert_eq!(parsed.memory_usage(), ApInt::from(i128::MIN).memory_usage());
+}
@@ -1,5 +1,5 @@
 use apa::ApInt;
-use num_traits::{One, Zero};
+use num_traits::{FromBytes, FromPrimitive, MulAdd, MulAddAssign, One, ToBytes, ToPrimitive, Zero};
 
 #[test]
 fn zero() {
@@ -10,3 +10,155 @@ fn zero() {
 fn one() {
     assert!(ApInt::ONE.is_one());
 }
+
+mod qc;
+
+#[test]
+fn prop_to_bytes() {
+    fn prop(n: i128) -> bool {
+        let int = ApInt::from(n);
+        let le = ToBytes::to_le_bytes(&int);
+        let be = ToBytes::to_be_bytes(&int);
+
+        // The bytes are the minimal sign extended form of the i128 bytes.
+        let full = n.to_le_bytes();
+        let sign = if n < 0 { 0xff } else { 0x00 };
+        let extended = le.iter().copied().chain(core::iter::repeat(sign)).take(16);
+
+        le.len() <= 16
+            && extended.eq(full.iter().copied())
+            && (le.len() == 1
+                || le[le.len() - 1] != sign
+                || (le[le.len() - 2] & 0x80 == 0) != (sign == 0))
+            && be.iter().rev().eq(le.iter())
+    }
+    qc::quickcheck(prop as fn(i128) -> bool)
+}
+
+#[test]
+fn prop_from_bytes() {
+    fn prop(low: u128, high: u128, len: u8) -> bool {
+        let mut bytes = low.to_le_bytes().to_vec();
+        bytes.extend_from_slice(&high.to_le_bytes());
+        bytes.truncate(len as usize % 33);
+
+        let int = <ApInt as FromBytes>::from_le_bytes(&bytes);
+        let mut reversed = bytes.clone();
+        reversed.reverse();
+
+        // Reading and writing the bytes again gives the same value.
+        <ApInt as FromBytes>::from_be_bytes(&reversed) == int
+            && ApInt::from_signed_bytes_le(&int.to_signed_bytes_le()) == int
+    }
+    qc::quickcheck(prop as fn(u128, u128, u8) -> bool)
+}
+
+#[test]
+fn bytes() {
+    assert_eq!(ApInt::ZERO.to_signed_bytes_le(), [0x00]);
+    assert_eq!(ApInt::from(-1).to_signed_bytes_le(), [0xff]);
+    assert_eq!(ApInt::from(0x8000).to_signed_bytes_be(), [0x00, 0x80, 0x00]);
+    assert_eq!(ApInt::from_signed_bytes_le(&[]), ApInt::ZERO);
+    assert_eq!(
+        ApInt::from_signed_bytes_be(&[0xff, 0x00]),
+        ApInt::from(-256)
+    );
+    assert_eq!(ApInt::from_signed_bytes_le(&[0xff; 20]), ApInt::from(-1));
+}
+
+#[test]
+fn prop_mul_add() {
+    fn prop(x: i128, y: i128, z: i128) -> bool {
+        let (x, y, z) = (ApInt::from(x), ApInt::from(y), ApInt::from(z));
+        let expected = &x * &y + &z;
+
+        let mut assign = x.clone();
+        assign.mul_add_assign(&y, &z);
+
+        MulAdd::mul_add(&x, &y, &z) == expected
+            && MulAdd::mul_add(x, y, z) == expected
+            && assign == expected
+    }
+    qc::quickcheck(prop as fn(i128, i128, i128) -> bool)
+}
+
+#[test]
+fn prop_mul_add_small() {
+    fn prop(x: i64, y: i64, z: i64) -> bool {
+        let expected = x as i128 * y as i128 + z as i128;
+        ApInt::from(x).mul_add(&ApInt::from(y), &ApInt::from(z)) == ApInt::from(expected)
+    }
+    qc::quickcheck(prop as fn(i64, i64, i64) -> bool)
+}
+
+#[test]
+fn prop_from_f64() {
+    fn prop(m: i64, e: u16) -> bool {
+        // Integral floats well beyond `i128`, which convert exactly.
+        let e = e % 960;
+        let f = (m >> 11) as f64 * 2f64.powi(e as i32);
+        let exact = ApInt::from_f64(f) == Some(ApInt::from(m >> 11) << e as usize);
+
+        // Fractional floats, which truncate toward zero.
+        let g = m as f64 / 1024.0;
+        let truncated = ApInt::from_f64(g) == Some(ApInt::from(g as i64));
+
+        exact && truncated
+    }
+    qc::quickcheck(prop as fn(i64, u16) -> bool)
+}
+
+#[test]
+fn from_f64() {
+    assert_eq!(
+        ApInt::from_f64(f64::MAX),
+        Some(ApInt::from((1u64 << 53) - 1) << 971)
+    );
+    assert_eq!(ApInt::from_f64(-2.5), Some(ApInt::from(-2)));
+    assert_eq!(ApInt::from_f64(-0.5), Some(ApInt::ZERO));
+    assert_eq!(ApInt::from_f64(f64::MIN_POSITIVE), Some(ApInt::ZERO));
+    assert_eq!(ApInt::from_f64(f64::NAN), None);
+    assert_eq!(ApInt::from_f64(f64::NEG_INFINITY), None);
+    assert_eq!(
+        ApInt::from_f32(f32::MAX),
+        Some(ApInt::from((1u64 << 24) - 1) << 104)
+    );
+}
+
+#[test]
+fn prop_to_primitive() {
+    fn prop(n: i128, shift: u8) -> bool {
+        // Spread values across every width, including multi-limb ints.
+        let n = n >> (shift % 128);
+        let int = ApInt::from(n);
+
+        int.to_i8() == n.to_i8()
+            && int.to_i16() == n.to_i16()
+            && int.to_i32() == n.to_i32()
+            && int.to_i64() == n.to_i64()
+            && int.to_i128() == Some(n)
+            && int.to_isize() == n.to_isize()
+            && int.to_u8() == n.to_u8()
+            && int.to_u16() == n.to_u16()
+            && int.to_u32() == n.to_u32()
+            && int.to_u64() == n.to_u64()
+            && int.to_u128() == n.to_u128()
+            && int.to_usize() == n.to_usize()
+    }
+    qc::quickcheck(prop as fn(i128, u8) -> bool)
+}
+
+#[test]
+fn to_primitive_multi_limb() {
+    let big = ApInt::ONE << 200;
+    assert_eq!(big.to_u8(), None);
+    assert_eq!(big.to_i128(), None);
+    assert_eq!((-&big).to_i64(), None);
+
+    assert_eq!(ApInt::from(u64::MAX).to_u64(), Some(u64::MAX));
+    assert_eq!(ApInt::from(u64::MAX).to_i64(), None);
+    assert_eq!(ApInt::from(u128::MAX).to_u128(), Some(u128::MAX));
+    assert_eq!(ApInt::from(i128::MIN).to_i128(), Some(i128::MIN));
+    assert_eq!(ApInt::from(i128::MIN).to_u128(), None);
+    assert_eq!((ApInt::from(i128::MIN) - ApInt::ONE).to_i128(), None);
+}
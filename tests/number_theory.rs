@@ -0,0 +1,518 @@
+use apa::ApInt;
+use apa::number_theory::{
+    Factorization, PrimeIter, batch_gcd, carmichael_lambda, continued_fraction, convergents,
+    discrete_log, euler_phi, factorize, is_lucas_probable_prime, is_strong_probable_prime,
+    lucas_uv, multiplicative_order, pell, primes_below, primitive_root, small_prime_filter,
+    sqrt_continued_fraction,
+};
+
+mod qc;
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+fn order_naive(a: u64, m: u64) -> Option<u64> {
+    if gcd(a, m) != 1 {
+        return None;
+    }
+    let mut x = a % m;
+    let mut k = 1;
+    while x != 1 % m {
+        x = x * a % m;
+        k += 1;
+    }
+    Some(k)
+}
+
+#[test]
+fn prop_multiplicative_order() {
+    fn prop(a: i16, m: u16) -> bool {
+        let m = m as u64 % 5000 + 1;
+        let a_mod = (a as i64).rem_euclid(m as i64) as u64;
+        let expected = order_naive(a_mod, m).map(ApInt::from);
+        multiplicative_order(&ApInt::from(a), &ApInt::from(m)) == expected
+    }
+    qc::quickcheck(prop as fn(i16, u16) -> bool)
+}
+
+#[test]
+fn primitive_roots_small() {
+    for m in 1..2000u64 {
+        let phi = (1..=m).filter(|&a| gcd(a, m) == 1).count() as u64;
+        let expected = (0..m).find(|&g| order_naive(g, m) == Some(phi));
+        assert_eq!(
+            primitive_root(&ApInt::from(m)),
+            expected.map(ApInt::from),
+            "m = {}",
+            m
+        );
+    }
+}
+
+#[test]
+fn ntt_primes() {
+    // (prime, smallest primitive root)
+    let primes = [
+        (998_244_353u64, 3),
+        (469_762_049, 3),
+        (167_772_161, 3),
+        (2_305_843_009_213_693_951, 37),
+    ];
+    for &(p, g) in primes.iter() {
+        assert_eq!(primitive_root(&ApInt::from(p)), Some(ApInt::from(g)));
+        assert_eq!(
+            multiplicative_order(&ApInt::from(g), &ApInt::from(p)),
+            Some(ApInt::from(p - 1))
+        );
+    }
+}
+
+#[test]
+fn multi_limb_modulus() {
+    // The Mersenne primes `2^61 - 1` and `2^31 - 1`.
+    let p = ApInt::from((1u64 << 61) - 1);
+    let q = ApInt::from((1u64 << 31) - 1);
+    let m = &p * &q;
+
+    // The order of 2 modulo a Mersenne prime `2^k - 1` is `k`.
+    assert_eq!(
+        multiplicative_order(&ApInt::from(2), &m),
+        Some(ApInt::from(61 * 31))
+    );
+    assert_eq!(multiplicative_order(&p, &m), None);
+    assert_eq!(primitive_root(&m), None);
+    assert_eq!(primitive_root(&(&p * &p)), Some(ApInt::from(37)));
+}
+
+#[test]
+#[should_panic(expected = "modulus must be positive")]
+fn zero_modulus() {
+    let _ = primitive_root(&ApInt::ZERO);
+}
+
+fn is_prime_naive(n: u64) -> bool {
+    n >= 2 && (2..).take_while(|d| d * d <= n).all(|d| n % d != 0)
+}
+
+#[test]
+fn prop_factorize() {
+    fn prop(n: u64) -> bool {
+        // Keep the factors small enough to split quickly in debug builds.
+        let n = (n >> 32).max(1);
+        let f = factorize(&ApInt::from(n));
+        let primes = f.prime_powers();
+
+        f.value() == ApInt::from(n)
+            && primes.windows(2).all(|w| w[0].0 < w[1].0)
+            && primes
+                .iter()
+                .all(|(p, e)| *e > 0 && is_prime_naive(u64::from(p)))
+    }
+    qc::quickcheck(prop as fn(u64) -> bool)
+}
+
+#[test]
+fn totient_and_carmichael_small() {
+    for n in 1..1000u64 {
+        let f = factorize(&ApInt::from(n));
+        let units: Vec<u64> = (1..=n).filter(|&a| gcd(a, n) == 1).collect();
+        let lambda = units
+            .iter()
+            .map(|&a| order_naive(a, n).unwrap())
+            .max()
+            .unwrap();
+
+        assert_eq!(euler_phi(&f), ApInt::from(units.len()), "n = {}", n);
+        assert_eq!(carmichael_lambda(&f), ApInt::from(lambda), "n = {}", n);
+    }
+}
+
+#[test]
+fn rsa_parameters() {
+    let p = ApInt::from((1u64 << 61) - 1);
+    let q = ApInt::from(1_000_000_007);
+    let n = Factorization::from_prime_powers(vec![(q.clone(), 1), (p.clone(), 1)]);
+
+    assert_eq!(n.value(), &p * &q);
+    assert_eq!(factorize(&n.value()), n);
+    assert_eq!(euler_phi(&n), (&p - ApInt::ONE) * (&q - ApInt::ONE));
+    assert_eq!(
+        carmichael_lambda(&n),
+        (&p - ApInt::ONE).lcm(&(&q - ApInt::ONE))
+    );
+}
+
+#[test]
+fn from_prime_powers_merges() {
+    let f = Factorization::from_prime_powers(vec![
+        (ApInt::from(5), 1),
+        (ApInt::from(2), 0),
+        (ApInt::from(5), 2),
+    ]);
+    assert_eq!(f.prime_powers(), &[(ApInt::from(5), 3)][..]);
+    assert_eq!(Factorization::from_prime_powers(vec![]).value(), ApInt::ONE);
+}
+
+#[test]
+#[should_panic(expected = "cannot factorize a non-positive integer")]
+fn factorize_zero() {
+    let _ = factorize(&ApInt::ZERO);
+}
+
+fn ints(values: &[i64]) -> Vec<ApInt> {
+    values.iter().map(|&v| ApInt::from(v)).collect()
+}
+
+#[test]
+fn prop_continued_fraction() {
+    fn prop(n: i64, d: i64) -> bool {
+        if d == 0 {
+            return true;
+        }
+        let (numer, denom) = (ApInt::from(n), ApInt::from(d));
+        let cf = continued_fraction(&numer, &denom);
+
+        let g = numer.gcd(&denom);
+        let (mut p, mut q) = (&numer / &g, &denom / &g);
+        if q < ApInt::ZERO {
+            p = -p;
+            q = -q;
+        }
+
+        cf[1..].iter().all(|a| *a > ApInt::ZERO) && convergents(cf).last() == Some((p, q))
+    }
+    qc::quickcheck(prop as fn(i64, i64) -> bool)
+}
+
+#[test]
+fn continued_fraction_negative() {
+    let cf = continued_fraction(&ApInt::from(-415), &ApInt::from(93));
+    assert_eq!(cf, ints(&[-5, 1, 1, 6, 7]));
+    let cf = continued_fraction(&ApInt::from(415), &ApInt::from(-93));
+    assert_eq!(cf, ints(&[-5, 1, 1, 6, 7]));
+    assert_eq!(
+        continued_fraction(&ApInt::ZERO, &ApInt::from(3)),
+        ints(&[0])
+    );
+}
+
+#[test]
+fn sqrt_continued_fractions() {
+    assert_eq!(sqrt_continued_fraction(&ApInt::ZERO), (ApInt::ZERO, vec![]));
+    assert_eq!(
+        sqrt_continued_fraction(&ApInt::from(1)),
+        (ApInt::from(1), vec![])
+    );
+    assert_eq!(
+        sqrt_continued_fraction(&ApInt::from(2)),
+        (ApInt::from(1), ints(&[2]))
+    );
+    assert_eq!(
+        sqrt_continued_fraction(&ApInt::from(49)),
+        (ApInt::from(7), vec![])
+    );
+    assert_eq!(
+        sqrt_continued_fraction(&ApInt::from(61)),
+        (ApInt::from(7), ints(&[1, 4, 3, 1, 2, 2, 1, 3, 4, 1, 14]))
+    );
+}
+
+#[test]
+fn prop_sqrt_convergents() {
+    fn prop(n: u16) -> bool {
+        let n = ApInt::from(n);
+        let (a0, period) = sqrt_continued_fraction(&n);
+        if period.is_empty() {
+            return &a0 * &a0 == n;
+        }
+
+        // The convergent before the end of the first period solves Pell's
+        // equation `p^2 - n * q^2 = ±1`.
+        let quotients = core::iter::once(a0).chain(period.iter().cloned());
+        let (p, q) = convergents(quotients).nth(period.len() - 1).unwrap();
+        let norm = &p * &p - &n * &q * &q;
+        norm == ApInt::ONE || norm == -ApInt::ONE
+    }
+    qc::quickcheck(prop as fn(u16) -> bool)
+}
+
+#[test]
+fn sqrt_convergents_multi_limb() {
+    // The convergents of `sqrt(2)` satisfy `p^2 - 2 * q^2 = ±1`.
+    let quotients = core::iter::once(ApInt::ONE).chain(core::iter::repeat(ApInt::TWO));
+    let (p, q) = convergents(quotients).nth(200).unwrap();
+    assert!(q > ApInt::from(u128::MAX));
+    assert_eq!(&p * &p - ApInt::TWO * &q * &q, -ApInt::ONE);
+}
+
+#[test]
+fn prop_small_prime_filter() {
+    fn prop(a: i64, b: i64, k: u8) -> bool {
+        let k = k as usize;
+        let n = ApInt::from(a) * ApInt::from(b);
+
+        let primes: Vec<u64> = (2..).filter(|&p| is_prime_naive(p)).take(k).collect();
+        let expected = primes
+            .into_iter()
+            .find(|&p| n.rem_u64(p) == 0)
+            .map(|p| p as u32);
+        small_prime_filter(&n, k) == expected
+    }
+    qc::quickcheck(prop as fn(i64, i64, u8) -> bool)
+}
+
+fn lucas_naive(p: i64, q: i64, n: u32, m: i64) -> (i64, i64) {
+    let (p, q, m) = (p as i128, q as i128, m as i128);
+    let (mut u0, mut u1) = (0i128, 1i128);
+    let (mut v0, mut v1) = (2i128, p);
+    for _ in 0..n {
+        let u2 = (p * u1 - q * u0).rem_euclid(m);
+        let v2 = (p * v1 - q * v0).rem_euclid(m);
+        u0 = u1;
+        u1 = u2;
+        v0 = v1;
+        v1 = v2;
+    }
+    (u0.rem_euclid(m) as i64, v0.rem_euclid(m) as i64)
+}
+
+#[test]
+fn prop_lucas_uv() {
+    fn prop(p: i32, q: i32, n: u8, m: u32) -> bool {
+        let m = i64::from(m.max(1));
+        let (u, v) = lucas_naive(i64::from(p), i64::from(q), u32::from(n), m);
+
+        let m = ApInt::from(m);
+        let (p, q, n) = (ApInt::from(p), ApInt::from(q), ApInt::from(n));
+        lucas_uv(&p, &q, &n, &m) == (ApInt::from(u), ApInt::from(v))
+    }
+    qc::quickcheck(prop as fn(i32, i32, u8, u32) -> bool)
+}
+
+#[test]
+fn lucas_uv_large_index() {
+    // `F_(p - (5 / p)) ≡ 0 (mod p)` for a prime `p`, and `L_p ≡ 1 (mod p)`.
+    let p = ApInt::from((1u128 << 127) - 1);
+    let (one, neg_one) = (ApInt::ONE, -ApInt::ONE);
+
+    let (u, _) = lucas_uv(&one, &neg_one, &(&p + ApInt::ONE), &p);
+    assert_eq!(u, ApInt::ZERO);
+    let (_, v) = lucas_uv(&one, &neg_one, &p, &p);
+    assert_eq!(v, ApInt::ONE);
+}
+
+#[test]
+fn prop_batch_gcd() {
+    fn prop(seed: u64, len: u8) -> bool {
+        let mut state = seed | 1;
+        let moduli: Vec<u64> = (0..len % 24)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                // Small factors, so that moduli often share one.
+                (state % 60 + 1) * (state >> 32) % 1000 + 1
+            })
+            .collect();
+
+        let expected: Vec<ApInt> = moduli
+            .iter()
+            .enumerate()
+            .map(|(i, &n)| {
+                // The product of the other moduli, reduced modulo `n`.
+                let p = moduli
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, _)| j != i)
+                    .fold(1 % n, |p, (_, &m)| p * m % n);
+                ApInt::from(gcd(n, p))
+            })
+            .collect();
+
+        let moduli: Vec<ApInt> = moduli.into_iter().map(ApInt::from).collect();
+        batch_gcd(&moduli) == expected
+    }
+    qc::quickcheck(prop as fn(u64, u8) -> bool)
+}
+
+#[test]
+fn prop_primes_below() {
+    fn prop(n: u16) -> bool {
+        let n = u64::from(n % 5000);
+        let expected: Vec<u64> = (0..n).filter(|&k| is_prime_naive(k)).collect();
+        primes_below(n) == expected
+    }
+    qc::quickcheck(prop as fn(u16) -> bool)
+}
+
+#[test]
+fn prime_iter() {
+    // The segments of the iterator are 2^16 numbers wide, so this crosses
+    // several of them.
+    let primes = primes_below(1_000_000);
+    assert!(
+        PrimeIter::new()
+            .take(primes.len())
+            .eq(primes.iter().copied())
+    );
+    assert_eq!(PrimeIter::new().nth(primes.len()), Some(1_000_003));
+    assert_eq!(primes.len(), 78_498);
+}
+
+#[test]
+fn strong_probable_prime_base_2() {
+    // The strong pseudoprimes to base 2 below 100000.
+    const PSEUDOPRIMES: [u64; 16] = [
+        2047, 3277, 4033, 4681, 8321, 15841, 29341, 42799, 49141, 52633, 65281, 74665, 80581,
+        85489, 88357, 90751,
+    ];
+
+    let two = ApInt::TWO;
+    for n in 0..100_000 {
+        let expected = is_prime_naive(n) || PSEUDOPRIMES.contains(&n);
+        assert_eq!(
+            is_strong_probable_prime(&ApInt::from(n), &two),
+            expected,
+            "{}",
+            n
+        );
+    }
+}
+
+#[test]
+fn prop_strong_probable_prime() {
+    fn prop(n: u32, base: i64) -> bool {
+        let n = u64::from(n);
+        // Primes pass for every base, and most composites fail for most bases.
+        !is_prime_naive(n) || is_strong_probable_prime(&ApInt::from(n), &ApInt::from(base))
+    }
+    qc::quickcheck(prop as fn(u32, i64) -> bool)
+}
+
+#[test]
+fn lucas_probable_prime() {
+    // The strong Lucas pseudoprimes below 100000.
+    const PSEUDOPRIMES: [u64; 12] = [
+        5459, 5777, 10877, 16109, 18971, 22499, 24569, 25199, 40309, 58519, 75077, 97439,
+    ];
+
+    for n in 0..100_000 {
+        let expected = is_prime_naive(n) || PSEUDOPRIMES.contains(&n);
+        assert_eq!(is_lucas_probable_prime(&ApInt::from(n)), expected, "{}", n);
+    }
+
+    let m127 = (ApInt::ONE << 127) - ApInt::ONE;
+    assert!(is_lucas_probable_prime(&m127));
+    assert!(!is_lucas_probable_prime(&(&m127 * &m127)));
+    assert!(!is_lucas_probable_prime(&(&m127 * ApInt::from(1_000_003))));
+}
+
+#[test]
+fn prop_discrete_log() {
+    fn prop(m: u16, a: u16, x: u16) -> bool {
+        let m = u64::from(m) % 2000 + 2;
+        let a = u64::from(a) % m;
+        if gcd(a, m) != 1 {
+            return true;
+        }
+
+        let x = u64::from(x) % m;
+        let pow = |e: u64| (0..e).fold(1 % m, |acc, _| acc * a % m);
+        let target = pow(x);
+        // The smallest logarithm, found by brute force.
+        let smallest = (0..m).find(|&e| pow(e) == target).unwrap();
+
+        let (ai, ti, mi) = (ApInt::from(a), ApInt::from(target), ApInt::from(m));
+        discrete_log(&ai, &ti, &mi, &mi) == Some(ApInt::from(smallest))
+            && discrete_log(&ai, &ti, &mi, &ApInt::from(smallest)).is_none()
+    }
+    qc::quickcheck(prop as fn(u16, u16, u16) -> bool);
+}
+
+#[test]
+fn discrete_log_large_prime() {
+    let p = 1_000_000_007u64;
+    let g = primitive_root(&ApInt::from(p)).unwrap();
+    let g64 = g.to_string().parse::<u64>().unwrap();
+
+    let x = 987_654_321u64;
+    let (mut target, mut base, mut e) = (1u128, u128::from(g64), x);
+    while e > 0 {
+        if e & 1 == 1 {
+            target = target * base % u128::from(p);
+        }
+        base = base * base % u128::from(p);
+        e >>= 1;
+    }
+
+    let bound = ApInt::from(p - 1);
+    assert_eq!(
+        discrete_log(&g, &ApInt::from(target), &ApInt::from(p), &bound),
+        Some(ApInt::from(x))
+    );
+}
+
+#[test]
+fn discrete_log_no_solution() {
+    // The powers of 2 modulo 7 are 1, 2 and 4.
+    let seven = ApInt::from(7);
+    assert_eq!(
+        discrete_log(&ApInt::TWO, &ApInt::from(3), &seven, &seven),
+        None
+    );
+    assert_eq!(
+        discrete_log(&ApInt::TWO, &ApInt::from(4), &seven, &ApInt::ZERO),
+        None
+    );
+}
+
+#[test]
+#[should_panic(expected = "base must be coprime to the modulus")]
+fn discrete_log_non_coprime_base() {
+    discrete_log(
+        &ApInt::from(6),
+        &ApInt::ONE,
+        &ApInt::from(9),
+        &ApInt::from(9),
+    );
+}
+
+#[test]
+fn prop_pell() {
+    fn prop(d: u16) -> bool {
+        let d = ApInt::from(u64::from(d) % 1000 + 2);
+        if d.is_perfect_square() {
+            return true;
+        }
+        let (x, y) = pell(&d);
+        // Check that no smaller `y` gives a solution, where that is cheap.
+        let minimal = y > ApInt::from(10_000)
+            || (1..10_000)
+                .map(ApInt::from)
+                .take_while(|k| *k < y)
+                .all(|k| !(&d * &k * &k + ApInt::ONE).is_perfect_square());
+        &x * &x - &d * &y * &y == ApInt::ONE && y > ApInt::ZERO && minimal
+    }
+    qc::quickcheck(prop as fn(u16) -> bool);
+}
+
+#[test]
+fn pell_fundamental_solutions() {
+    let cases: &[(i64, i64, i64)] = &[
+        (2, 3, 2),
+        (3, 2, 1),
+        (5, 9, 4),
+        (13, 649, 180),
+        (61, 1_766_319_049, 226_153_980),
+    ];
+    for &(d, x, y) in cases {
+        assert_eq!(pell(&ApInt::from(d)), (ApInt::from(x), ApInt::from(y)));
+    }
+}
+
+#[test]
+#[should_panic(expected = "Pell equation with a square `d` has no positive solutions")]
+fn pell_square() {
+    pell(&ApInt::from(49));
+}
@@ -0,0 +1,29 @@
+// Only a 64-bit address space is certain to be too small for the allocation.
+#![cfg(target_pointer_width = "64")]
+
+use core::alloc::Layout;
+use std::panic;
+
+use apa::ApInt;
+
+fn out_of_memory(layout: Layout) -> ! {
+    panic!("out of memory: {} bytes", layout.size());
+}
+
+// The handler is global, so every check is made in a single test.
+#[test]
+fn handler_unwinds() {
+    apa::set_oom_handler(out_of_memory);
+
+    // Far more limbs than any machine can hold, but a valid layout.
+    let bits = 1usize << 59;
+    let err = panic::catch_unwind(|| ApInt::ONE << bits).unwrap_err();
+    let msg = err.downcast_ref::<String>().unwrap();
+    assert!(msg.starts_with("out of memory"), "{}", msg);
+
+    // Other allocations still succeed afterwards.
+    let x = ApInt::from(u128::MAX) << 1000;
+    assert_eq!(x >> 1000, ApInt::from(u128::MAX));
+
+    apa::clear_oom_handler();
+}
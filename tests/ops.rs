@@ -0,0 +1,536 @@
+use apa::ApInt;
+
+mod qc;
+
+macro_rules! quickcheck_ops {
+    ($($ty:ident),* $(,)*) => {
+        $(
+            paste::item! {
+               #[test]
+               fn [< prop_add_sub_mul_ $ty >] () {
+                    fn prop(l: $ty, r: $ty) -> bool {
+                        let (lw, rw) = (l as i128, r as i128);
+                        let (li, ri) = (ApInt::from(l), ApInt::from(r));
+
+                        &li + &ri == ApInt::from(lw + rw)
+                            && &li - &ri == ApInt::from(lw - rw)
+                            && lw.checked_mul(rw).map_or(true, |prod| li * ri == ApInt::from(prod))
+                    }
+                    qc::quickcheck(prop as fn($ty, $ty) -> bool)
+               }
+
+               #[test]
+               fn [< prop_div_rem_ $ty >] () {
+                    fn prop(l: $ty, r: $ty) -> bool {
+                        if r == 0 {
+                            return true;
+                        }
+
+                        let (lw, rw) = (l as i128, r as i128);
+                        let (li, ri) = (ApInt::from(l), ApInt::from(r));
+
+                        &li / &ri == ApInt::from(lw / rw) && li % ri == ApInt::from(lw % rw)
+                    }
+                    qc::quickcheck(prop as fn($ty, $ty) -> bool)
+               }
+            }
+        )*
+    };
+}
+
+#[rustfmt::skip]
+quickcheck_ops!(
+    u8, u16, u32, u64,
+    i8, i16, i32, i64,
+);
+
+macro_rules! quickcheck_checked_ops {
+    ($($ty:ident),* $(,)*) => {
+        $(
+            paste::item! {
+               #[test]
+               fn [< prop_checked_ops_ $ty >] () {
+                    fn prop(l: $ty, r: $ty) -> bool {
+                        let (li, ri) = (ApInt::from(l), ApInt::from(r));
+
+                        let check = |op: Option<$ty>, int: ApInt| op.map_or(true, |op| int == ApInt::from(op));
+
+                        check(l.checked_add(r), &li + &ri)
+                            && check(l.checked_sub(r), &li - &ri)
+                            && check(l.checked_mul(r), &li * &ri)
+                            && (r == 0 || (check(l.checked_div(r), &li / &ri) && check(l.checked_rem(r), &li % &ri)))
+                    }
+                    qc::quickcheck(prop as fn($ty, $ty) -> bool)
+               }
+            }
+        )*
+    };
+}
+
+quickcheck_checked_ops!(u128, i128);
+
+#[test]
+fn prop_div_rem_identity() {
+    fn prop(a: i128, b: u128, c: i64, d: i128, e: u64) -> bool {
+        // Build multi-limb dividends and divisors.
+        let x = ApInt::from(a) * ApInt::from(b) + ApInt::from(c);
+        let y = ApInt::from(d) * ApInt::from(e) + ApInt::from(1);
+
+        let (q, r) = x.div_rem(&y);
+        q * &y + &r == x && r.abs() < y.abs()
+    }
+    qc::quickcheck(prop as fn(i128, u128, i64, i128, u64) -> bool)
+}
+
+#[test]
+fn prop_div_exact() {
+    fn prop(a: i128, b: u128, c: i128, d: u64, shift: u8) -> bool {
+        // Build multi-limb quotients and divisors, with factors of two.
+        let x = (ApInt::from(a) * ApInt::from(b)) << (shift as usize % 80);
+        let y = (ApInt::from(c) * ApInt::from(d) + ApInt::from(1)) << (shift as usize % 70);
+        if y == ApInt::ZERO {
+            return true;
+        }
+
+        let product = &x * &y;
+        product.div_exact(&y) == x && (x == ApInt::ZERO || product.div_exact(&x) == y)
+    }
+    qc::quickcheck(prop as fn(i128, u128, i128, u64, u8) -> bool)
+}
+
+#[test]
+fn div_exact_edge_cases() {
+    let min = ApInt::from(i64::MIN);
+    assert_eq!(min.div_exact(&ApInt::from(-1)), ApInt::from(1u128 << 63));
+    assert_eq!(ApInt::ZERO.div_exact(&ApInt::from(u128::MAX)), ApInt::ZERO);
+    assert_eq!(
+        ApInt::from(i128::MIN).div_exact(&ApInt::from(i128::MIN)),
+        ApInt::ONE
+    );
+}
+
+#[test]
+#[should_panic(expected = "attempt to divide by zero")]
+fn div_exact_zero() {
+    let _ = ApInt::ONE.div_exact(&ApInt::ZERO);
+}
+
+#[test]
+fn prop_rem_u64() {
+    fn prop(a: i128, b: i128, d: u64) -> bool {
+        let d = d.max(1);
+        let x = ApInt::from(a) * ApInt::from(b);
+
+        let expected_rem = {
+            let r = &x % ApInt::from(d);
+            if r < ApInt::ZERO {
+                r + ApInt::from(d)
+            } else {
+                r
+            }
+        };
+        let expected_quot = (&x - &expected_rem) / ApInt::from(d);
+
+        let (q, r) = x.div_rem_u64(d);
+        ApInt::from(x.rem_u64(d)) == expected_rem
+            && ApInt::from(r) == expected_rem
+            && q == expected_quot
+    }
+    qc::quickcheck(prop as fn(i128, i128, u64) -> bool)
+}
+
+#[test]
+fn rem_u64_edge_cases() {
+    assert_eq!(ApInt::from(-1).rem_u64(u64::MAX), u64::MAX - 1);
+    assert_eq!(ApInt::from(-1).div_rem_u64(1), (ApInt::from(-1), 0));
+    assert_eq!(
+        ApInt::from(i128::MIN).div_rem_u64(1 << 63),
+        (ApInt::from(-(1i128 << 64)), 0)
+    );
+    assert_eq!(ApInt::ZERO.div_rem_u64(7), (ApInt::ZERO, 0));
+}
+
+#[test]
+#[should_panic(expected = "attempt to divide by zero")]
+fn rem_u64_zero() {
+    let _ = ApInt::ONE.rem_u64(0);
+}
+
+#[test]
+fn prop_divisible_by_u32() {
+    fn prop(a: i128, b: i128, c: u32, d: u32) -> bool {
+        let x = ApInt::from(a) * ApInt::from(b);
+        let multiple = &x * ApInt::from(d);
+
+        let divisible =
+            |x: &ApInt| d == 0 && *x == ApInt::ZERO || d != 0 && x.rem_u64(u64::from(d)) == 0;
+        let y = &multiple + ApInt::from(c);
+        multiple.divisible_by_u32(d)
+            && y.divisible_by_u32(d) == divisible(&y)
+            && x.divisible_by_u32(d) == divisible(&x)
+    }
+    qc::quickcheck(prop as fn(i128, i128, u32, u32) -> bool)
+}
+
+#[test]
+fn divisible_by_u32_edge_cases() {
+    assert!(ApInt::ZERO.divisible_by_u32(0));
+    assert!(!ApInt::ONE.divisible_by_u32(0));
+    assert!(ApInt::from(i128::MIN).divisible_by_u32(1 << 31));
+    assert!(!ApInt::from(i128::MIN).divisible_by_u32(3));
+    assert!(ApInt::from(-(u32::MAX as i64)).divisible_by_u32(u32::MAX));
+}
+
+#[test]
+fn add_carry_limb() {
+    let max = ApInt::from(u64::MAX);
+    assert_eq!(&max + &max, ApInt::from(2 * u64::MAX as u128));
+    assert_eq!(
+        ApInt::from(i128::MIN) - ApInt::from(1),
+        -ApInt::from(1u128 << 127) - ApInt::from(1)
+    );
+}
+
+#[test]
+fn mul_max() {
+    let max = ApInt::from(u64::MAX);
+    assert_eq!(
+        &max * &max,
+        ApInt::from(u64::MAX as u128 * u64::MAX as u128)
+    );
+}
+
+#[test]
+fn mul_min() {
+    let min = ApInt::from(i64::MIN);
+    assert_eq!(&min * &min, ApInt::from(1u128 << 126));
+    assert_eq!(&min / ApInt::from(-1), ApInt::from(1u128 << 63));
+}
+
+#[test]
+#[should_panic(expected = "attempt to divide by zero")]
+fn div_zero() {
+    let _ = ApInt::ONE / ApInt::ZERO;
+}
+
+#[test]
+fn prop_aliased_operands() {
+    fn prop(n: i128, shift: u8) -> bool {
+        let x = ApInt::from(n) << shift as usize;
+        let y = x.clone();
+
+        &x + &x == &x + &y
+            && &x - &x == ApInt::ZERO
+            && &x * &x == &x * &y
+            && (n == 0 || (&x / &x == ApInt::ONE && &x % &x == ApInt::ZERO))
+    }
+    qc::quickcheck(prop as fn(i128, u8) -> bool)
+}
+
+#[test]
+fn prop_assign_trivial_rhs() {
+    fn prop(n: i128, shift: u8) -> bool {
+        let x = ApInt::from(n) << shift as usize;
+        let rhs = [ApInt::ZERO, ApInt::ONE, ApInt::NEG_ONE, x.clone()];
+
+        rhs.iter().all(|y| {
+            let mut ops = vec![&x + y, &x - y, &x * y];
+            let mut assigned = vec![x.clone(), x.clone(), x.clone()];
+            assigned[0] += y;
+            assigned[1] -= y;
+            assigned[2] *= y;
+            if *y != ApInt::ZERO {
+                ops.extend(vec![&x / y, &x % y]);
+                let (mut quot, mut rem) = (x.clone(), x.clone());
+                quot /= y;
+                rem %= y.clone();
+                assigned.extend(vec![quot, rem]);
+            }
+            ops == assigned
+        }) && {
+            let (mut zero_plus, mut zero_minus) = (ApInt::ZERO, ApInt::ZERO);
+            zero_plus += &x;
+            zero_minus -= &x;
+            zero_plus == x && zero_minus == -&x
+        }
+    }
+    qc::quickcheck(prop as fn(i128, u8) -> bool)
+}
+
+#[test]
+#[should_panic(expected = "attempt to divide by zero")]
+fn div_assign_zero() {
+    let mut x = ApInt::ONE;
+    x /= ApInt::ZERO;
+}
+
+#[test]
+fn prop_results_valid() {
+    fn prop(l: i128, r: i128, shift: u8) -> bool {
+        let (x, y) = (ApInt::from(l) << shift as usize, ApInt::from(r));
+        let mut results = vec![&x + &y, &x - &y, &x * &y, -&x, &x - &x];
+        if r != 0 {
+            results.extend(vec![&x / &y, &x % &y]);
+        }
+        results.iter().for_each(ApInt::assert_valid);
+        true
+    }
+    qc::quickcheck(prop as fn(i128, i128, u8) -> bool)
+}
+
+#[test]
+fn prop_dot() {
+    fn prop(seed: u64, len: u8, shift: u8) -> bool {
+        // Operands of one to a few limbs, generated from `seed`.
+        let mut state = seed | 1;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            ApInt::from(state as i64) << (state as usize % 3 * 64)
+        };
+        let len = len as usize % 40;
+        let xs: Vec<ApInt> = (0..len).map(|_| next() << shift as usize).collect();
+        let ys: Vec<ApInt> = (0..len).map(|_| next()).collect();
+
+        let expected = xs
+            .iter()
+            .zip(&ys)
+            .fold(ApInt::ZERO, |acc, (x, y)| acc + x * y);
+        let dot = ApInt::dot(&xs, &ys);
+        dot.assert_valid();
+        dot == expected
+    }
+    qc::quickcheck(prop as fn(u64, u8, u8) -> bool)
+}
+
+#[test]
+fn dot_edge_cases() {
+    assert_eq!(ApInt::dot(&[], &[]), ApInt::ZERO);
+
+    // Products that cancel back down to a single limb.
+    let big = ApInt::ONE << 300;
+    let xs = [big.clone(), -&big, ApInt::from(i64::MIN)];
+    let ys = [big.clone(), big.clone(), ApInt::from(i64::MIN)];
+    assert_eq!(ApInt::dot(&xs, &ys), ApInt::ONE << 126);
+}
+
+#[test]
+#[should_panic(expected = "slices must have the same length")]
+fn dot_length_mismatch() {
+    ApInt::dot(&[ApInt::ONE], &[]);
+}
+
+#[test]
+fn prop_add_sub_mul_assign() {
+    fn prop(w: i128, a: i128, b: i64, sw: u8, sa: u8, sb: u8) -> bool {
+        let w = ApInt::from(w) << (sw as usize % 200);
+        let a = ApInt::from(a) << (sa as usize % 200);
+        let b = ApInt::from(b) << (sb as usize % 200);
+
+        let (mut added, mut subbed) = (w.clone(), w.clone());
+        added.add_mul_assign(&a, &b);
+        subbed.sub_mul_assign(&a, &b);
+        added.assert_valid();
+        subbed.assert_valid();
+
+        added == &w + &a * &b && subbed == &w - &a * &b
+    }
+    qc::quickcheck(prop as fn(i128, i128, i64, u8, u8, u8) -> bool)
+}
+
+#[test]
+fn prop_reciprocal() {
+    fn prop(d: i128, shift: u8, bits: u16) -> bool {
+        if d == 0 {
+            return true;
+        }
+        let d = ApInt::from(d) << (shift as usize % 300);
+        let bits = bits as usize % 1500;
+
+        let n = ApInt::ONE << bits;
+        // Floor division, rounding towards negative infinity.
+        let (q, r) = n.div_rem(&d);
+        let floor = if r != ApInt::ZERO && d < ApInt::ZERO {
+            q - ApInt::ONE
+        } else {
+            q
+        };
+
+        d.reciprocal(bits) == floor
+    }
+    qc::quickcheck(prop as fn(i128, u8, u16) -> bool)
+}
+
+#[test]
+#[should_panic(expected = "attempt to divide by zero")]
+fn reciprocal_zero() {
+    ApInt::ZERO.reciprocal(10);
+}
+
+#[test]
+fn reciprocal_large() {
+    let d = (ApInt::from(0x1234_5678_9abc_def1u64) << 5000) + ApInt::from(12345);
+    let bits = 40_000;
+    let r = d.reciprocal(bits);
+    let e = (ApInt::ONE << bits) - &d * &r;
+    assert!(e >= ApInt::ZERO && e < d);
+}
+
+#[test]
+fn prop_divmod() {
+    fn prop(l: i64, r: i64, shift: u8) -> bool {
+        if r == 0 {
+            return true;
+        }
+        let (lw, rw) = (l as i128, r as i128);
+        // Floor division of the primitives, rounding towards negative infinity.
+        let (mut q, mut m) = (lw / rw, lw % rw);
+        if m != 0 && (m < 0) != (rw < 0) {
+            q -= 1;
+            m += rw;
+        }
+
+        let shift = shift as usize % 100;
+        let (x, y) = (ApInt::from(l) << shift, ApInt::from(r) << shift);
+        let (xq, xm) = x.divmod(&y);
+
+        xq == ApInt::from(q)
+            && xm == ApInt::from(m) << shift
+            && x.div_floor(&y) == xq
+            && x.mod_floor(&y) == xm
+    }
+    qc::quickcheck(prop as fn(i64, i64, u8) -> bool)
+}
+
+macro_rules! quickcheck_prim_lhs {
+    ($($ty:ident),* $(,)*) => {
+        $(
+            paste::item! {
+                #[test]
+                fn [< prop_prim_lhs_div_rem_ $ty >] () {
+                    fn prop(l: $ty, r: i128) -> bool {
+                        if r == 0 {
+                            return true;
+                        }
+                        let (li, ri) = (ApInt::from(l), ApInt::from(r));
+
+                        l / &ri == &li / &ri
+                            && l % &ri == &li % &ri
+                            && l / ri.clone() == &li / &ri
+                            && l % ri.clone() == li % ri
+                    }
+                    qc::quickcheck(prop as fn($ty, i128) -> bool)
+                }
+            }
+        )*
+    };
+}
+
+#[rustfmt::skip]
+quickcheck_prim_lhs!(
+    u8, u16, u32, u64, u128, usize,
+    i8, i16, i32, i64, i128, isize,
+);
+
+#[test]
+fn prim_lhs_min_div_neg_one() {
+    assert_eq!(i64::MIN / &ApInt::NEG_ONE, ApInt::from(1u64 << 63));
+    assert_eq!(i64::MIN % ApInt::NEG_ONE, ApInt::ZERO);
+}
+
+#[test]
+fn prop_product() {
+    fn prop(seed: u64, len: u8) -> bool {
+        let mut state = seed | 1;
+        let factors: Vec<ApInt> = (0..len % 40)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                ApInt::from(state as i64) << (state % 100) as usize
+            })
+            .collect();
+
+        let folded = factors.iter().fold(ApInt::ONE, |acc, x| acc * x);
+        let tree = ApInt::product_tree(&factors);
+
+        ApInt::product(factors.iter().cloned()) == folded
+            && factors.iter().product::<ApInt>() == folded
+            && tree.first().map_or(true, |leaves| *leaves == factors)
+            && tree
+                .last()
+                .map_or(factors.is_empty(), |root| root[..] == [folded])
+    }
+    qc::quickcheck(prop as fn(u64, u8) -> bool)
+}
+
+#[test]
+fn product_tree_levels() {
+    let factors: Vec<ApInt> = (1..=5).map(ApInt::from).collect();
+    let tree = ApInt::product_tree(&factors);
+
+    let levels: Vec<Vec<i64>> = tree
+        .iter()
+        .map(|level| level.iter().map(i64::from).collect())
+        .collect();
+    assert_eq!(
+        levels,
+        [vec![1, 2, 3, 4, 5], vec![2, 12, 5], vec![24, 5], vec![120]]
+    );
+
+    assert!(ApInt::product_tree(&[]).is_empty());
+    assert_eq!(ApInt::product(Vec::new()), ApInt::ONE);
+}
+
+/// Returns the number of limb allocations and reallocations made by the
+/// current thread, or zero without the `stats` feature to count them.
+fn allocator_events() -> u64 {
+    #[cfg(feature = "stats")]
+    {
+        let work = apa::stats::snapshot();
+        work.allocs + work.reallocs
+    }
+    #[cfg(not(feature = "stats"))]
+    {
+        0
+    }
+}
+
+#[test]
+fn into_reuses_destination() {
+    let mut operands = vec![ApInt::ZERO, ApInt::ONE, ApInt::NEG_ONE];
+    for &n in &[i128::MIN, i128::MAX, -3, 1 << 64, (-1) << 63] {
+        for &shift in &[0, 1, 63, 64, 130, 255] {
+            operands.push(ApInt::from(n) << shift);
+        }
+    }
+
+    // The first operation grows the destination to fit every result.
+    let big = ApInt::NEG_ONE << 1000;
+    let mut dst = ApInt::ZERO;
+    ApInt::mul_into(&mut dst, &big, &big);
+    assert_eq!(dst, &big * &big);
+
+    for x in &operands {
+        for y in &operands {
+            let (sum, diff, prod, square) = (x + y, x - y, x * y, x * x);
+            let events = allocator_events();
+
+            ApInt::add_into(&mut dst, x, y);
+            dst.assert_valid();
+            assert_eq!(dst, sum);
+            ApInt::sub_into(&mut dst, x, y);
+            dst.assert_valid();
+            assert_eq!(dst, diff);
+            ApInt::mul_into(&mut dst, x, y);
+            dst.assert_valid();
+            assert_eq!(dst, prod);
+            ApInt::mul_into(&mut dst, x, x);
+            dst.assert_valid();
+            assert_eq!(dst, square);
+
+            assert_eq!(allocator_events(), events, "{:?} and {:?}", x, y);
+        }
+    }
+}
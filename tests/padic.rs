@@ -0,0 +1,63 @@
+use apa::ApInt;
+use apa::padic::{lift_root, valuation};
+
+mod qc;
+
+const PRIMES: [u32; 6] = [2, 3, 5, 7, 13, 65_537];
+
+#[test]
+fn prop_lift_root() {
+    fn prop(x0: i64, c1: i32, p: u8, k: u8) -> bool {
+        let p = ApInt::from(PRIMES[p as usize % PRIMES.len()]);
+        let k = k as u32 % 20 + 1;
+        let (x0, c1) = (ApInt::from(x0), ApInt::from(c1));
+
+        // `x^3 + c1 * x + c0` with the integer root `x0`.
+        let c0 = -(&x0 * &x0 * &x0 + &c1 * &x0);
+        let coeffs = [c0, c1.clone(), ApInt::ZERO, ApInt::ONE];
+        let root = &x0 % &p;
+
+        let m = (0..k).fold(ApInt::ONE, |m, _| m * &p);
+        let expected = ((&x0 % &m) + &m) % &m;
+        let df = ApInt::from(3) * &x0 * &x0 + &c1;
+
+        match lift_root(&coeffs, &root, &p, k) {
+            Some(r) => r == expected,
+            None => &df % &p == ApInt::ZERO,
+        }
+    }
+    qc::quickcheck(prop as fn(i64, i32, u8, u8) -> bool)
+}
+
+#[test]
+fn lift_root_not_a_root() {
+    let coeffs = [ApInt::from(-2), ApInt::ZERO, ApInt::ONE];
+    assert_eq!(
+        lift_root(&coeffs, &ApInt::from(2), &ApInt::from(7), 5),
+        None
+    );
+    // 3 is a root of x^2 - 2 modulo 7, and the result is reduced.
+    assert_eq!(
+        lift_root(&coeffs, &ApInt::from(-4), &ApInt::from(7), 1),
+        Some(ApInt::from(3))
+    );
+}
+
+#[test]
+fn prop_valuation() {
+    fn prop(n: i64, e: u8, p: u8) -> bool {
+        let p = PRIMES[p as usize % PRIMES.len()];
+        let e = e as u32 % 20;
+        let n = n as i128 / p as i128 * p as i128 + 1;
+
+        let int = ApInt::from(n) * (0..e).fold(ApInt::ONE, |m, _| m * ApInt::from(p));
+        valuation(&int, &ApInt::from(p)) == Some(e)
+    }
+    qc::quickcheck(prop as fn(i64, u8, u8) -> bool)
+}
+
+#[test]
+#[should_panic(expected = "p must be greater than one")]
+fn valuation_base_one() {
+    valuation(&ApInt::ONE, &ApInt::ONE);
+}
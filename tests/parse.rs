@@ -0,0 +1,209 @@
+use std::cmp::Ordering;
+
+use apa::{ApInt, RoundingMode};
+
+mod qc;
+
+#[test]
+fn prop_lenient_matches_display() {
+    fn prop(a: i128, frac_zeros: u8, exp: u8) -> bool {
+        let (frac_zeros, exp) = (frac_zeros as usize % 4, exp as i32 % 40);
+        let expected = ApInt::from(a) * ApInt::from(10).pow_int(&ApInt::from(exp)).unwrap();
+
+        // Write `a * 10^exp` as `a.000e{exp}`, and as the digits of `a` with
+        // the decimal point moved left by `exp` against a larger exponent.
+        let s = format!("{}.{}e{}", a, "0".repeat(frac_zeros), exp);
+        let shifted = format!("{}0e{}", a, exp - 1);
+
+        ApInt::from_decimal_str_lenient(&s) == Ok(expected.clone())
+            && ApInt::from_decimal_str_lenient(&shifted) == Ok(expected)
+    }
+    qc::quickcheck(prop as fn(i128, u8, u8) -> bool)
+}
+
+#[test]
+fn lenient_syntax() {
+    let ok = |s: &str, v: i64| {
+        assert_eq!(
+            ApInt::from_decimal_str_lenient(s),
+            Ok(ApInt::from(v)),
+            "{:?}",
+            s
+        );
+    };
+    ok("0", 0);
+    ok("-0.0e-7", 0);
+    ok("1e2", 100);
+    ok("1E+2", 100);
+    ok("+2.5e3", 2500);
+    ok(".5e1", 5);
+    ok("5.", 5);
+    ok("007", 7);
+    ok("-1200e-2", -12);
+    ok("\t12.340e2\n", 1234);
+
+    for s in &[
+        "",
+        " ",
+        "-",
+        ".",
+        "e5",
+        ".e5",
+        "1e",
+        "1e+",
+        "1.5",
+        "1e-1",
+        "12e-2",
+        "1_000",
+        "1.2.3",
+        "1e2.5",
+        "0x10",
+        "1 2",
+        "1e99999999999999999999",
+    ] {
+        assert!(ApInt::from_decimal_str_lenient(s).is_err(), "{:?}", s);
+    }
+}
+
+#[test]
+fn lenient_large() {
+    let int = ApInt::from_decimal_str_lenient("1.2345678901234567890123456789e100").unwrap();
+    let expected = format!("12345678901234567890123456789{}", "0".repeat(72));
+    assert_eq!(int.to_string(), expected);
+}
+
+#[test]
+fn lenient_exponent_limit() {
+    let int = ApInt::from_decimal_str_lenient("1e1000000").unwrap();
+    assert_eq!(int.bits(), 3_321_929);
+    assert!(ApInt::from_decimal_str_lenient("1e1000001").is_err());
+    assert!(ApInt::from_decimal_str_lenient("1e4000000000").is_err());
+    assert_eq!(
+        ApInt::from_decimal_str_lenient("0e4000000000"),
+        Ok(ApInt::ZERO)
+    );
+}
+
+const MODES: [RoundingMode; 7] = [
+    RoundingMode::TowardZero,
+    RoundingMode::AwayFromZero,
+    RoundingMode::Floor,
+    RoundingMode::Ceiling,
+    RoundingMode::HalfAwayFromZero,
+    RoundingMode::HalfTowardZero,
+    RoundingMode::HalfEven,
+];
+
+/// Rounds `n / d` for a positive `d` in the given mode.
+fn round_div(n: i128, d: i128, mode: RoundingMode) -> i128 {
+    let (q, r) = (n / d, n % d);
+    if r == 0 {
+        return q;
+    }
+    let away = if n < 0 { q - 1 } else { q + 1 };
+    let half = (2 * r.abs()).cmp(&d);
+    let up = match mode {
+        RoundingMode::TowardZero => false,
+        RoundingMode::AwayFromZero => true,
+        RoundingMode::Floor => n < 0,
+        RoundingMode::Ceiling => n > 0,
+        RoundingMode::HalfAwayFromZero => half != Ordering::Less,
+        RoundingMode::HalfTowardZero => half == Ordering::Greater,
+        RoundingMode::HalfEven => {
+            half == Ordering::Greater || (half == Ordering::Equal && q % 2 != 0)
+        }
+    };
+    if up { away } else { q }
+}
+
+#[test]
+fn prop_parse_rounded() {
+    fn prop(n: i64, places: u8, exp: i8) -> bool {
+        let (places, exp) = (u32::from(places % 6), i32::from(exp % 4));
+
+        // Write `n / 10^places` with its digits, then shift the point by `exp`.
+        let digits = format!("{:0width$}", n.abs(), width = places as usize + 1);
+        let (int, frac) = digits.split_at(digits.len() - places as usize);
+        let sign = if n < 0 { "-" } else { "" };
+        let s = format!("{}{}.{}e{}", sign, int, frac, exp);
+
+        let (mut n, mut d) = (i128::from(n), 10i128.pow(places));
+        if exp >= 0 {
+            n *= 10i128.pow(exp as u32);
+        } else {
+            d *= 10i128.pow(-exp as u32);
+        }
+        MODES
+            .iter()
+            .all(|&mode| ApInt::parse_rounded(&s, mode) == Ok(ApInt::from(round_div(n, d, mode))))
+    }
+    qc::quickcheck(prop as fn(i64, u8, i8) -> bool)
+}
+
+#[test]
+fn parse_rounded_halves() {
+    let round = |s: &str, mode| ApInt::parse_rounded(s, mode).unwrap();
+    assert_eq!(round("2.5", RoundingMode::HalfEven), ApInt::from(2));
+    assert_eq!(round("3.5", RoundingMode::HalfEven), ApInt::from(4));
+    assert_eq!(
+        round("2.50000000000000000000001", RoundingMode::HalfEven),
+        ApInt::from(3)
+    );
+    assert_eq!(round("-2.5", RoundingMode::HalfTowardZero), ApInt::from(-2));
+    assert_eq!(
+        round("-0.5", RoundingMode::HalfAwayFromZero),
+        ApInt::from(-1)
+    );
+    assert_eq!(round("0.05", RoundingMode::HalfAwayFromZero), ApInt::ZERO);
+    assert_eq!(round("-1e-1000000", RoundingMode::Floor), ApInt::from(-1));
+    assert_eq!(round("1e-99999999999", RoundingMode::Ceiling), ApInt::ONE);
+    assert_eq!(round("-0.0", RoundingMode::Floor), ApInt::ZERO);
+    assert!(ApInt::parse_rounded("1.5x", RoundingMode::Floor).is_err());
+}
+
+#[test]
+fn parse_rounded_exponent_limit() {
+    for &mode in &MODES {
+        assert!(ApInt::parse_rounded("1e1000001", mode).is_err());
+        assert!(ApInt::parse_rounded("-2.5e4000000000", mode).is_err());
+        assert_eq!(ApInt::parse_rounded("0e4000000000", mode), Ok(ApInt::ZERO));
+    }
+}
+
+#[test]
+fn prop_from_f64_round() {
+    fn prop(n: i64, k: u8) -> bool {
+        // Keep `n` exact as an `f64`, so `n / 2^k` is too.
+        let (n, k) = (n >> 11, u32::from(k % 70));
+        let x = n as f64 / 2f64.powi(k as i32);
+        MODES.iter().all(|&mode| {
+            let expected = ApInt::from(round_div(i128::from(n), 1i128 << k, mode));
+            ApInt::from_f64_round(x, mode) == Some(expected)
+        })
+    }
+    qc::quickcheck(prop as fn(i64, u8) -> bool)
+}
+
+#[test]
+fn from_f64_round_special() {
+    for &mode in &MODES {
+        assert_eq!(ApInt::from_f64_round(f64::INFINITY, mode), None);
+        assert_eq!(ApInt::from_f64_round(f64::NAN, mode), None);
+        assert_eq!(ApInt::from_f64_round(-0.0, mode), Some(ApInt::ZERO));
+        let big = Some(ApInt::from(1) << 152);
+        assert_eq!(ApInt::from_f64_round(2f64.powi(152), mode), big);
+    }
+    let tiny = f64::from_bits(1);
+    assert_eq!(
+        ApInt::from_f64_round(tiny, RoundingMode::Ceiling),
+        Some(ApInt::ONE)
+    );
+    assert_eq!(
+        ApInt::from_f64_round(-tiny, RoundingMode::Floor),
+        Some(ApInt::from(-1))
+    );
+    assert_eq!(
+        ApInt::from_f64_round(-tiny, RoundingMode::HalfEven),
+        Some(ApInt::ZERO)
+    );
+}
@@ -0,0 +1,62 @@
+use apa::ApInt;
+use apa::poly;
+
+mod qc;
+
+/// Returns `len` coefficients of up to `bits` bits, generated from `seed`.
+fn coeffs(seed: u64, len: usize, bits: usize) -> Vec<ApInt> {
+    let mut state = seed | 1;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let c = ApInt::from(state as i64) << (state as usize % (bits + 1));
+            c >> 64
+        })
+        .collect()
+}
+
+fn mul_naive(a: &[ApInt], b: &[ApInt]) -> Vec<ApInt> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut c = vec![ApInt::ZERO; a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            c[i + j] += x * y;
+        }
+    }
+    c
+}
+
+#[test]
+fn prop_mul() {
+    fn prop(sa: u64, sb: u64, la: u8, lb: u8, bits: u8) -> bool {
+        let a = coeffs(sa, la as usize % 16, bits as usize);
+        let b = coeffs(sb, lb as usize % 16, bits as usize / 2);
+        poly::mul(&a, &b) == mul_naive(&a, &b)
+    }
+    qc::quickcheck(prop as fn(u64, u64, u8, u8, u8) -> bool)
+}
+
+#[test]
+fn mul_edge_cases() {
+    let one = [ApInt::ONE];
+    let zeros = [ApInt::ZERO, ApInt::ZERO];
+    assert!(poly::mul(&[], &one).is_empty());
+    assert_eq!(poly::mul(&zeros, &zeros), vec![ApInt::ZERO; 3]);
+    assert_eq!(poly::mul(&one, &[ApInt::NEG_ONE]), [ApInt::NEG_ONE]);
+
+    // (x - 1)(x^2 + x + 1) = x^3 - 1
+    let a = [ApInt::NEG_ONE, ApInt::ONE];
+    let b = [ApInt::ONE, ApInt::ONE, ApInt::ONE];
+    let expected = [ApInt::NEG_ONE, ApInt::ZERO, ApInt::ZERO, ApInt::ONE];
+    assert_eq!(poly::mul(&a, &b), expected);
+
+    // Coefficients at the extremes of their width.
+    let big = ApInt::ONE << 200;
+    let a = [-&big, big.clone(), -&big];
+    let b = [big.clone(), -&big];
+    assert_eq!(poly::mul(&a, &b), mul_naive(&a, &b));
+}
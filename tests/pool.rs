@@ -0,0 +1,76 @@
+#![cfg(feature = "pool")]
+
+use std::sync::Arc;
+
+use apa::{ApInt, IntPool};
+
+mod qc;
+
+#[test]
+fn prop_intern_dedupes() {
+    fn prop(values: (i16, i16, i16, i16), shift: u8) -> bool {
+        let (a, b, c, d) = values;
+        let ints: Vec<ApInt> = [a, b, c, d, a, c]
+            .iter()
+            .map(|&n| ApInt::from(n % 8) << (shift as usize % 200))
+            .collect();
+
+        let mut pool = IntPool::new();
+        let handles: Vec<Arc<ApInt>> = ints.iter().map(|int| pool.intern_ref(int)).collect();
+
+        let mut distinct = ints.clone();
+        distinct.sort();
+        distinct.dedup();
+
+        pool.len() == distinct.len()
+            && handles
+                .iter()
+                .zip(&ints)
+                .all(|(handle, int)| **handle == *int)
+            && handles
+                .iter()
+                .all(|x| handles.iter().all(|y| (**x == **y) == Arc::ptr_eq(x, y)))
+    }
+    qc::quickcheck(prop as fn((i16, i16, i16, i16), u8) -> bool)
+}
+
+#[test]
+fn intern_and_get() {
+    let mut pool = IntPool::new();
+    assert!(pool.is_empty());
+    assert_eq!(pool.get(&ApInt::ONE), None);
+
+    let big = ApInt::from(u128::MAX) << 300;
+    let a = pool.intern(big.clone());
+    let b = pool.intern(big.clone());
+    assert!(Arc::ptr_eq(&a, &b));
+    assert!(Arc::ptr_eq(&a, &pool.get(&big).unwrap()));
+
+    let thousand = pool.pow10(3);
+    assert_eq!(*thousand, ApInt::from(1000));
+    assert!(Arc::ptr_eq(&thousand, &pool.intern(ApInt::from(1000))));
+    assert_eq!(pool.len(), 2);
+}
+
+#[test]
+fn purge_keeps_shared_values() {
+    let mut pool = IntPool::new();
+    let kept = pool.intern(ApInt::from(7));
+    pool.intern(ApInt::from(8));
+    pool.intern(ApInt::from(9));
+
+    assert_eq!(pool.purge(), 2);
+    assert_eq!(pool.len(), 1);
+    assert!(Arc::ptr_eq(&kept, &pool.intern(ApInt::from(7))));
+
+    pool.clear();
+    assert!(pool.is_empty());
+    assert!(!Arc::ptr_eq(&kept, &pool.intern(ApInt::from(7))));
+}
+
+#[test]
+#[cfg(target_pointer_width = "64")]
+#[should_panic(expected = "power of ten exponent 4294967296 is too large")]
+fn pow10_exp_overflow() {
+    let _ = IntPool::new().pow10(1 << 32);
+}
@@ -0,0 +1,88 @@
+use apa::{ApInt, Guard, PowOverflow};
+
+mod qc;
+
+#[test]
+fn prop_pow_int() {
+    fn prop(base: i8, exp: u8) -> bool {
+        let expected = (0..exp).fold(ApInt::ONE, |r, _| r * ApInt::from(base));
+        ApInt::from(base).pow_int(&ApInt::from(exp)) == Ok(expected)
+    }
+    qc::quickcheck(prop as fn(i8, u8) -> bool)
+}
+
+#[test]
+fn prop_pow_int_large_exp() {
+    fn prop(exp: u16) -> bool {
+        // Powers of two are easy to check, and exercise every window size.
+        let r = ApInt::from(-2).pow_int(&ApInt::from(exp)).unwrap();
+        let expected = ApInt::ONE << exp as usize;
+        r == if exp % 2 == 0 { expected } else { -expected }
+    }
+    qc::quickcheck(prop as fn(u16) -> bool)
+}
+
+#[test]
+fn pow_int_small_bases() {
+    let huge = ApInt::from(u128::MAX) << 1000;
+    assert_eq!(ApInt::ZERO.pow_int(&ApInt::ZERO), Ok(ApInt::ONE));
+    assert_eq!(ApInt::ZERO.pow_int(&huge), Ok(ApInt::ZERO));
+    assert_eq!(ApInt::ONE.pow_int(&huge), Ok(ApInt::ONE));
+    assert_eq!(
+        ApInt::NEG_ONE.pow_int(&(&huge + ApInt::ONE)),
+        Ok(ApInt::NEG_ONE)
+    );
+    assert_eq!(ApInt::NEG_ONE.pow_int(&ApInt::from(-3)), Ok(ApInt::NEG_ONE));
+    assert_eq!(ApInt::ONE.pow_int(&-huge), Ok(ApInt::ONE));
+}
+
+#[test]
+fn pow_int_overflow() {
+    assert_eq!(
+        ApInt::from(2).pow_int(&(ApInt::ONE << 64)),
+        Err(PowOverflow)
+    );
+    assert_eq!(
+        ApInt::from(10).pow_int(&ApInt::from(u64::MAX)),
+        Err(PowOverflow)
+    );
+}
+
+#[test]
+#[should_panic(expected = "attempt to raise to a negative power")]
+fn pow_int_negative_exp() {
+    let _ = ApInt::from(2).pow_int(&ApInt::NEG_ONE);
+}
+
+#[test]
+fn guard_pow() {
+    let guard = Guard::new(64);
+    assert_eq!(
+        guard.pow(&ApInt::from(2), &ApInt::from(63)),
+        Ok(ApInt::ONE << 63)
+    );
+    assert!(guard.pow(&ApInt::from(2), &ApInt::from(64)).is_err());
+    assert!(guard.pow(&ApInt::from(10), &ApInt::from(u64::MAX)).is_err());
+    assert_eq!(
+        guard
+            .pow(&ApInt::from(-2), &ApInt::from(64))
+            .unwrap_err()
+            .max_bits(),
+        64
+    );
+}
+
+#[test]
+fn pow_int_beyond_memory() {
+    // The exponent fits in a `usize`, but the result could never be allocated.
+    let exp = ApInt::from(usize::MAX / 2);
+    assert_eq!(ApInt::from(5).pow_int(&exp), Err(PowOverflow));
+}
+
+#[test]
+fn pow_int_beyond_address_space() {
+    // `10^(10^15)` needs over `3 * 10^15` bits, more than any 64-bit address
+    // space can hold, so this must fail before any work is done.
+    let exp = ApInt::from(10u64.pow(15));
+    assert_eq!(ApInt::from(10).pow_int(&exp), Err(PowOverflow));
+}
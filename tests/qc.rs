@@ -1,7 +1,8 @@
 use quickcheck::{QuickCheck, StdThreadGen, Testable};
 
 pub fn quickcheck<A: Testable>(f: A) {
-    const N_TESTS: u64 = 10_000;
+    // Miri interprets every operation, so runs far fewer cases.
+    const N_TESTS: u64 = if cfg!(miri) { 8 } else { 10_000 };
 
     QuickCheck::with_gen(StdThreadGen::new(usize::MAX))
         .tests(N_TESTS)
@@ -0,0 +1,107 @@
+#![cfg(feature = "rand")]
+
+use apa::ApInt;
+use apa::number_theory::factorize;
+use rand::RngCore;
+
+mod qc;
+
+/// A small deterministic generator, so that the tests are reproducible.
+struct XorShift(u64);
+
+impl RngCore for XorShift {
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        rand::rngs::mock::StepRng::new(self.next_u64(), 1).fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+fn is_prime(n: &ApInt) -> bool {
+    let f = factorize(n);
+    f.prime_powers().len() == 1 && f.prime_powers()[0].1 == 1
+}
+
+#[test]
+fn prop_random_with_weight() {
+    fn prop(seed: u64, bits: u16, ones: u16) -> bool {
+        let mut rng = XorShift(seed | 1);
+        let bits = bits as usize % 1000;
+        let ones = ones as usize % (bits + 1);
+
+        let int = ApInt::random_with_weight(&mut rng, bits, ones);
+        int.count_ones() == Some(ones) && int < ApInt::ONE << bits
+    }
+    qc::quickcheck(prop as fn(u64, u16, u16) -> bool)
+}
+
+#[test]
+fn random_with_weight_covers_positions() {
+    // Every position is eventually chosen.
+    let mut rng = XorShift(1);
+    let mut seen = ApInt::ZERO;
+    for _ in 0..2000 {
+        seen |= ApInt::random_with_weight(&mut rng, 100, 1);
+    }
+    assert_eq!(seen, (ApInt::ONE << 100) - ApInt::ONE);
+}
+
+#[test]
+#[should_panic(expected = "weight must not exceed the number of bits")]
+fn random_with_weight_too_heavy() {
+    ApInt::random_with_weight(&mut XorShift(1), 8, 9);
+}
+
+#[test]
+fn random_safe_prime() {
+    let mut rng = XorShift(7);
+    for bits in 3..40 {
+        let p = ApInt::random_safe_prime(&mut rng, bits);
+        let q = (&p - ApInt::ONE) >> 1;
+
+        assert!(
+            p >= ApInt::ONE << (bits - 1) && p < ApInt::ONE << bits,
+            "{}",
+            p
+        );
+        assert!(is_prime(&p) && is_prime(&q), "{}", p);
+    }
+}
+
+#[test]
+fn prop_random_coprime_below() {
+    fn prop(seed: u64, n: u128, shift: u8) -> bool {
+        let n = (ApInt::from(n) << usize::from(shift % 100)) + ApInt::TWO;
+        let mut rng = XorShift(seed | 1);
+        let r = ApInt::random_coprime_below(&mut rng, &n);
+        r > ApInt::ZERO && r < n && r.gcd(&n) == ApInt::ONE
+    }
+    qc::quickcheck(prop as fn(u64, u128, u8) -> bool)
+}
+
+#[test]
+fn random_coprime_below_covers_units() {
+    // The units modulo 12 are 1, 5, 7 and 11.
+    let mut rng = XorShift(0x5eed);
+    let mut seen = [false; 12];
+    for _ in 0..200 {
+        let r = ApInt::random_coprime_below(&mut rng, &ApInt::from(12));
+        seen[usize::from(&r)] = true;
+    }
+    let units: Vec<usize> = (0..12).filter(|&i| seen[i]).collect();
+    assert_eq!(units, [1, 5, 7, 11]);
+}
@@ -0,0 +1,67 @@
+use apa::ApInt;
+
+mod qc;
+
+#[test]
+fn prop_range() {
+    fn prop(start: i8, end: i8, step: u8) -> bool {
+        let step = step.max(1);
+        let range =
+            ApInt::range(ApInt::from(start), ApInt::from(end)).step_by_int(ApInt::from(step));
+        let inclusive = ApInt::range_inclusive(ApInt::from(start), ApInt::from(end))
+            .step_by_int(ApInt::from(step));
+
+        let expected: Vec<ApInt> = (start..end)
+            .step_by(step as usize)
+            .map(ApInt::from)
+            .collect();
+        let expected_inclusive: Vec<ApInt> = (start..=end)
+            .step_by(step as usize)
+            .map(ApInt::from)
+            .collect();
+
+        range.size_hint() == (expected.len(), Some(expected.len()))
+            && range.collect::<Vec<_>>() == expected
+            && inclusive.remaining() == ApInt::from(expected_inclusive.len())
+            && inclusive.collect::<Vec<_>>() == expected_inclusive
+    }
+    qc::quickcheck(prop as fn(i8, i8, u8) -> bool)
+}
+
+#[test]
+fn range_nth() {
+    let start = ApInt::from(u64::MAX);
+    let mut range = ApInt::range(start.clone(), ApInt::from(u128::MAX)).step_by_int(ApInt::from(3));
+    assert_eq!(range.nth(5), Some(&start + ApInt::from(15)));
+    assert_eq!(range.next(), Some(&start + ApInt::from(18)));
+}
+
+#[test]
+fn range_huge() {
+    let end = ApInt::from(u128::MAX) * ApInt::from(u128::MAX);
+    let range = ApInt::range(ApInt::ZERO, end.clone());
+    assert_eq!(range.size_hint(), (usize::MAX, None));
+    assert_eq!(range.remaining(), end);
+}
+
+#[test]
+fn range_empty() {
+    assert_eq!(ApInt::range(ApInt::ONE, ApInt::ONE).next(), None);
+    assert_eq!(ApInt::range_inclusive(ApInt::ONE, ApInt::ONE).count(), 1);
+    assert_eq!(ApInt::range_inclusive(ApInt::ONE, ApInt::ZERO).next(), None);
+}
+
+#[test]
+#[should_panic(expected = "step must be positive")]
+fn range_zero_step() {
+    let _ = ApInt::range(ApInt::ZERO, ApInt::ONE).step_by_int(ApInt::ZERO);
+}
+
+#[test]
+fn iterator_step_by() {
+    // The inherent stepping must not shadow `Iterator::step_by`.
+    let v: Vec<ApInt> = ApInt::range(ApInt::ZERO, ApInt::from(7))
+        .step_by(3)
+        .collect();
+    assert_eq!(v, [ApInt::from(0), ApInt::from(3), ApInt::from(6)]);
+}
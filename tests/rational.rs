@@ -0,0 +1,95 @@
+use apa::ApInt;
+use apa::rational::{Rational, bernoulli, harmonic};
+
+mod qc;
+
+fn ratio(n: i64, d: i64) -> Rational {
+    Rational::new(ApInt::from(n), ApInt::from(d))
+}
+
+#[test]
+fn new_reduces() {
+    let r = ratio(6, -4);
+    assert_eq!(r.numer(), &ApInt::from(-3));
+    assert_eq!(r.denom(), &ApInt::from(2));
+    assert_eq!(ratio(0, -5), Rational::from(ApInt::ZERO));
+    assert_eq!(ratio(0, -5).denom(), &ApInt::ONE);
+}
+
+#[test]
+#[should_panic(expected = "attempt to create a rational with a zero denominator")]
+fn new_zero_denom() {
+    ratio(1, 0);
+}
+
+#[test]
+fn prop_field_ops() {
+    fn prop(a: i32, b: i32, c: i32, d: i32) -> bool {
+        let (a, b, c, d) = (i64::from(a), i64::from(b), i64::from(c), i64::from(d));
+        if b == 0 || d == 0 {
+            return true;
+        }
+        let (x, y) = (ratio(a, b), ratio(c, d));
+        let ok = &x + &y == ratio(a * d + c * b, b * d)
+            && &x - &y == ratio(a * d - c * b, b * d)
+            && &x * &y == ratio(a * c, b * d)
+            && -&x == ratio(-a, b)
+            && x.cmp(&y)
+                == ((a * d) as i128 * (b * d).signum() as i128)
+                    .cmp(&((c * b) as i128 * (b * d).signum() as i128));
+        ok && (c == 0 || (&x / &y == ratio(a * d, b * c) && y.recip() == ratio(d, c)))
+    }
+    qc::quickcheck(prop as fn(i32, i32, i32, i32) -> bool);
+}
+
+#[test]
+fn display() {
+    assert_eq!(ratio(-3, 6).to_string(), "-1/2");
+    assert_eq!(ratio(8, 4).to_string(), "2");
+}
+
+#[test]
+fn harmonic_matches_sum() {
+    let mut sum = Rational::from(ApInt::ZERO);
+    for n in 1..200 {
+        sum = sum + ratio(1, n as i64);
+        assert_eq!(harmonic(n), sum);
+    }
+}
+
+#[test]
+fn bernoulli_values() {
+    let expected = [
+        (0, 1, 1),
+        (1, -1, 2),
+        (2, 1, 6),
+        (3, 0, 1),
+        (4, -1, 30),
+        (6, 1, 42),
+        (8, -1, 30),
+        (10, 5, 66),
+        (12, -691, 2730),
+        (14, 7, 6),
+        (16, -3617, 510),
+        (18, 43867, 798),
+        (20, -174611, 330),
+    ];
+    for &(n, p, q) in &expected {
+        assert_eq!(bernoulli(n), ratio(p, q), "B({})", n);
+    }
+}
+
+#[test]
+fn bernoulli_recurrence() {
+    // `sum(binomial(m + 1, k) * B(k) for k in 0..=m) == 0` for `m > 0`.
+    let b: Vec<Rational> = (0..60).map(bernoulli).collect();
+    for m in 1..60usize {
+        let mut binom = ApInt::ONE;
+        let mut sum = Rational::from(ApInt::ZERO);
+        for (k, bk) in b.iter().enumerate().take(m + 1) {
+            sum = sum + Rational::from(binom.clone()) * bk;
+            binom = binom * ApInt::from((m + 1 - k) as u64) / ApInt::from((k + 1) as u64);
+        }
+        assert_eq!(sum, Rational::from(ApInt::ZERO), "m = {}", m);
+    }
+}
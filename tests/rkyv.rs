@@ -0,0 +1,53 @@
+#![cfg(feature = "rkyv")]
+
+use apa::{ApInt, ArchivedApInt};
+use rkyv::rancor::Error;
+
+mod qc;
+
+#[test]
+fn prop_archive_round_trip() {
+    fn prop(n: i128, shift: u8) -> bool {
+        let int = ApInt::from(n) << shift as usize;
+        let bytes = rkyv::to_bytes::<Error>(&int).unwrap();
+
+        let archived = rkyv::access::<ArchivedApInt, Error>(&bytes).unwrap();
+        let deserialized = rkyv::deserialize::<ApInt, Error>(archived).unwrap();
+
+        *archived == int
+            && archived.is_negative() == (n < 0)
+            && deserialized == int
+            && archived.to_apint() == int
+    }
+    qc::quickcheck(prop as fn(i128, u8) -> bool)
+}
+
+#[test]
+fn archived_words() {
+    let int = -(ApInt::ONE << 64);
+    let bytes = rkyv::to_bytes::<Error>(&int).unwrap();
+    let archived = rkyv::access::<ArchivedApInt, Error>(&bytes).unwrap();
+
+    let words: Vec<u64> = archived
+        .words()
+        .iter()
+        .map(|word| word.to_native())
+        .collect();
+    assert_eq!(words, [0, u64::MAX]);
+    assert_ne!(*archived, ApInt::ONE << 64);
+    assert_eq!(format!("{:?}", archived), "-18446744073709551616");
+}
+
+#[test]
+fn archived_vec_of_ints() {
+    let ints: Vec<ApInt> = (0..100).map(|i| ApInt::from(i) << (i * 7)).collect();
+    let bytes = rkyv::to_bytes::<Error>(&ints).unwrap();
+    let archived = rkyv::access::<rkyv::Archived<Vec<ApInt>>, Error>(&bytes).unwrap();
+
+    assert!(
+        archived
+            .iter()
+            .zip(&ints)
+            .all(|(archived, int)| archived == int)
+    );
+}
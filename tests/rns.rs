@@ -0,0 +1,67 @@
+use apa::ApInt;
+use apa::rns::Basis;
+
+mod qc;
+
+/// Primes just below `2^62`.
+const PRIMES: [u64; 4] = [
+    4611686018427387847,
+    4611686018427387817,
+    4611686018427387787,
+    4611686018427387733,
+];
+
+#[test]
+fn prop_round_trip() {
+    fn prop(n: i128, high: i64) -> bool {
+        let basis = Basis::new(PRIMES.to_vec());
+        let n = (ApInt::from(high) << 128) + ApInt::from(n);
+
+        let residues = basis.to_residues(&n);
+        let expected: Vec<u64> = PRIMES
+            .iter()
+            .map(|&p| u64::from(n.mod_floor(&ApInt::from(p))))
+            .collect();
+
+        residues == expected && basis.from_residues_signed(&residues) == n
+    }
+    qc::quickcheck(prop as fn(i128, i64) -> bool)
+}
+
+#[test]
+fn prop_residue_arithmetic() {
+    fn prop(a: i32, b: i32, c: i32) -> bool {
+        let basis = Basis::new(vec![65521, 65519, 65497, 65479, 65449]);
+        let (ra, rb, rc) = (
+            basis.to_residues(&ApInt::from(a)),
+            basis.to_residues(&ApInt::from(b)),
+            basis.to_residues(&ApInt::from(c)),
+        );
+
+        // `a * b - c` residue by residue.
+        let r: Vec<u64> = basis
+            .moduli()
+            .iter()
+            .enumerate()
+            .map(|(i, &m)| (ra[i] * rb[i] % m + m - rc[i]) % m)
+            .collect();
+        let expected = ApInt::from(a) * ApInt::from(b) - ApInt::from(c);
+        basis.from_residues_signed(&r) == expected
+    }
+    qc::quickcheck(prop as fn(i32, i32, i32) -> bool)
+}
+
+#[test]
+fn from_residues_range() {
+    let basis = Basis::new(vec![3, 5, 7]);
+    assert_eq!(*basis.product(), ApInt::from(105));
+    assert_eq!(basis.from_residues(&[2, 4, 6]), ApInt::from(104));
+    assert_eq!(basis.from_residues_signed(&[2, 4, 6]), ApInt::from(-1));
+    assert_eq!(basis.to_residues(&ApInt::from(-1)), [2, 4, 6]);
+}
+
+#[test]
+#[should_panic(expected = "moduli must be pairwise coprime")]
+fn not_coprime() {
+    Basis::new(vec![6, 35, 10]);
+}
@@ -0,0 +1,93 @@
+use apa::ApInt;
+
+mod qc;
+
+fn is_square_i128(n: i128) -> bool {
+    if n < 0 {
+        return false;
+    }
+    // Correct the floating point estimate of the root.
+    let mut r = (n as f64).sqrt() as i128;
+    while r * r > n {
+        r -= 1;
+    }
+    while (r + 1) * (r + 1) <= n {
+        r += 1;
+    }
+    r * r == n
+}
+
+#[test]
+fn prop_is_perfect_square() {
+    fn prop(a: i64, b: i8) -> bool {
+        let square = i128::from(a) * i128::from(a);
+        let near = square + i128::from(b);
+
+        ApInt::from(square).is_perfect_square()
+            && ApInt::from(near).is_perfect_square() == is_square_i128(near)
+    }
+    qc::quickcheck(prop as fn(i64, i8) -> bool)
+}
+
+#[test]
+fn perfect_squares_small() {
+    let squares: Vec<i64> = (0..100).map(|r| r * r).collect();
+    for n in -100..10_000 {
+        assert_eq!(
+            ApInt::from(n).is_perfect_square(),
+            squares.contains(&n),
+            "n = {}",
+            n
+        );
+    }
+}
+
+#[test]
+fn prop_is_perfect_power() {
+    fn prop(root: i16, k: u8) -> bool {
+        let k = u32::from(k % 12 + 2);
+        let root = i128::from(root);
+        if root.abs() < 2 {
+            return true;
+        }
+
+        let mut n = ApInt::ONE;
+        for _ in 0..k {
+            n *= ApInt::from(root);
+        }
+
+        match n.is_perfect_power() {
+            Some((r, e)) => {
+                let mut p = ApInt::ONE;
+                for _ in 0..e {
+                    p *= &r;
+                }
+                // The exponent found is at least the one used.
+                p == n && (e >= k || root < 0 && k % 2 == 0)
+            }
+            None => false,
+        }
+    }
+    qc::quickcheck(prop as fn(i16, u8) -> bool)
+}
+
+#[test]
+fn perfect_powers() {
+    let power = |n: i128| ApInt::from(n).is_perfect_power();
+    let some = |r: i128, k: u32| Some((ApInt::from(r), k));
+
+    assert_eq!(power(0), some(0, 2));
+    assert_eq!(power(1), some(1, 2));
+    assert_eq!(power(-1), some(-1, 3));
+    assert_eq!(power(2), None);
+    assert_eq!(power(-4), None);
+    assert_eq!(power(72), None);
+    assert_eq!(power(1 << 60), some(2, 60));
+    assert_eq!(power(-(1 << 60)), some(-(1 << 4), 15));
+    assert_eq!(power(6i128.pow(30)), some(6, 30));
+    assert_eq!(power(3i128.pow(40) * 5i128.pow(20)), some(45, 20));
+    assert_eq!(power(-(7i128.pow(21))), some(-7, 21));
+
+    let big = ApInt::from(u128::MAX) * ApInt::from(u128::MAX);
+    assert_eq!(big.is_perfect_power(), Some((ApInt::from(u128::MAX), 2)));
+}
@@ -0,0 +1,79 @@
+#![cfg(feature = "subtle")]
+
+use apa::{ApInt, SecretInt};
+use subtle::{Choice, ConstantTimeEq, ConstantTimeGreater, ConstantTimeLess};
+
+mod qc;
+
+/// Wraps `n` to a signed value of `limbs` limbs.
+fn wrap(n: &ApInt, limbs: usize) -> ApInt {
+    let bits = limbs * 64;
+    let low = n.mod_floor(&(ApInt::ONE << bits));
+    if low.bit(bits - 1) {
+        low - (ApInt::ONE << bits)
+    } else {
+        low
+    }
+}
+
+#[test]
+fn prop_secret_arithmetic() {
+    fn prop(x: i128, y: i128, choice: bool) -> bool {
+        let (xi, yi) = (ApInt::from(x), ApInt::from(y));
+        let c = Choice::from(choice as u8);
+        let s = |n: &ApInt| SecretInt::from_int(n, 2).unwrap();
+
+        let mut sum = s(&xi);
+        sum.ct_add_assign_if(&s(&yi), c);
+        let mut diff = s(&xi);
+        diff.ct_sub_assign_if(&s(&yi), c);
+        let mut assigned = s(&xi);
+        assigned.ct_assign_if(&s(&yi), c);
+
+        let (sum_i, diff_i, assigned_i) = if choice {
+            (wrap(&(&xi + &yi), 2), wrap(&(&xi - &yi), 2), yi.clone())
+        } else {
+            (xi.clone(), xi.clone(), xi.clone())
+        };
+        sum.to_int() == sum_i
+            && diff.to_int() == diff_i
+            && assigned.to_int() == assigned_i
+            && sum.limbs() == 2
+    }
+    qc::quickcheck(prop as fn(i128, i128, bool) -> bool)
+}
+
+#[test]
+fn prop_secret_cmp() {
+    fn prop(x: i128, y: i128) -> bool {
+        let sx = SecretInt::from_int(&ApInt::from(x), 3).unwrap();
+        let sy = SecretInt::from_int(&ApInt::from(y), 3).unwrap();
+
+        bool::from(sx.ct_eq(&sy)) == (x == y)
+            && bool::from(sx.ct_gt(&sy)) == (x > y)
+            && bool::from(sx.ct_lt(&sy)) == (x < y)
+    }
+    qc::quickcheck(prop as fn(i128, i128) -> bool)
+}
+
+#[test]
+fn secret_from_int() {
+    let big = ApInt::ONE << 100;
+    assert!(SecretInt::from_int(&big, 1).is_none());
+    assert_eq!(SecretInt::from_int(&big, 4).unwrap().to_int(), big);
+    assert_eq!(SecretInt::from_int(&-&big, 2).unwrap().to_int(), -big);
+    assert_eq!(SecretInt::new(3).to_int(), ApInt::ZERO);
+
+    // The width is fixed, so the value wraps instead of growing.
+    let mut max = SecretInt::from_int(&ApInt::from(i64::MAX), 1).unwrap();
+    max.add_assign(&SecretInt::from_int(&ApInt::ONE, 1).unwrap());
+    assert_eq!(max.to_int(), ApInt::from(i64::MIN));
+    assert_eq!(format!("{:?}", max), "SecretInt { limbs: 1 }");
+}
+
+#[test]
+#[should_panic(expected = "secret ints must have the same number of limbs")]
+fn secret_width_mismatch() {
+    let mut x = SecretInt::new(2);
+    x.add_assign(&SecretInt::new(1));
+}
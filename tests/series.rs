@@ -0,0 +1,64 @@
+use apa::ApInt;
+use apa::rational::Rational;
+use apa::series::binary_split;
+
+mod qc;
+
+/// Sums the series term by term.
+fn naive_sum(
+    n: u64,
+    a: impl Fn(u64) -> ApInt,
+    b: impl Fn(u64) -> ApInt,
+    p: impl Fn(u64) -> ApInt,
+    q: impl Fn(u64) -> ApInt,
+) -> Rational {
+    let mut sum = Rational::from(ApInt::ZERO);
+    let mut ratio = Rational::from(ApInt::ONE);
+    for i in 0..n {
+        ratio = ratio * Rational::new(p(i), q(i));
+        sum = sum + Rational::new(a(i), b(i)) * &ratio;
+    }
+    sum
+}
+
+#[test]
+fn prop_matches_naive_sum() {
+    fn prop(n: u8, seeds: (i8, i8, i8, i8)) -> bool {
+        let n = u64::from(n % 40);
+        let (sa, sb, sp, sq) = (
+            i64::from(seeds.0),
+            i64::from(seeds.1),
+            i64::from(seeds.2),
+            i64::from(seeds.3),
+        );
+        // Term functions with no zero `b(n)` or `q(n)`.
+        let a = move |i: u64| ApInt::from(sa * i as i64 - 3);
+        let b = move |i: u64| ApInt::from(sb.abs() + i as i64 + 1);
+        let p = move |i: u64| ApInt::from(sp + 2 * i as i64);
+        let q = move |i: u64| ApInt::from(-(sq.abs() + i as i64 + 1));
+
+        binary_split(n, a, b, p, q).to_rational() == naive_sum(n, a, b, p, q)
+    }
+    qc::quickcheck(prop as fn(u8, (i8, i8, i8, i8)) -> bool);
+}
+
+#[test]
+fn empty_sum() {
+    let one = |_| ApInt::ONE;
+    let sum = binary_split(0, one, one, one, one);
+    assert_eq!(sum.to_rational(), Rational::from(ApInt::ZERO));
+    assert_eq!(sum.scaled(&ApInt::from(1000)), ApInt::ZERO);
+}
+
+#[test]
+fn scaled_rounds_down() {
+    // -1/3 in thousandths.
+    let sum = binary_split(
+        1,
+        |_| ApInt::from(-1),
+        |_| ApInt::from(3),
+        |_| ApInt::ONE,
+        |_| ApInt::ONE,
+    );
+    assert_eq!(sum.scaled(&ApInt::from(1000)), ApInt::from(-334));
+}
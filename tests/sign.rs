@@ -0,0 +1,44 @@
+use core::cmp::Ordering;
+
+use apa::{ApInt, Sign};
+
+mod qc;
+
+#[test]
+fn prop_sign() {
+    fn prop(n: i128) -> bool {
+        let sign = ApInt::from(n).sign();
+        sign == Sign::from(n.cmp(&0)) && sign.to_i8() as i128 == n.signum()
+    }
+    qc::quickcheck(prop as fn(i128) -> bool)
+}
+
+#[test]
+fn prop_sign_mul() {
+    fn prop(l: i8, r: i8) -> bool {
+        let product = (l as i16 * r as i16).signum() as i8;
+        Sign::from_i8(l) * Sign::from_i8(r) == Sign::from_i8(product)
+    }
+    qc::quickcheck(prop as fn(i8, i8) -> bool)
+}
+
+#[test]
+fn neg() {
+    assert_eq!(-Sign::Negative, Sign::Positive);
+    assert_eq!(-Sign::Zero, Sign::Zero);
+    assert_eq!(-Sign::Positive, Sign::Negative);
+}
+
+#[test]
+fn ordering() {
+    for &ordering in &[Ordering::Less, Ordering::Equal, Ordering::Greater] {
+        assert_eq!(Ordering::from(Sign::from(ordering)), ordering);
+    }
+}
+
+#[test]
+fn display() {
+    assert_eq!(format!("{}", Sign::Negative), "-");
+    assert_eq!(format!("{}", Sign::Zero), "0");
+    assert_eq!(format!("{:>2}", Sign::Positive), " +");
+}
@@ -0,0 +1,91 @@
+use apa::{ApInt, LimbRepr};
+
+mod qc;
+
+const LIMB_BITS: usize = 8 * core::mem::size_of::<LimbRepr>();
+
+type Limbs = (LimbRepr, LimbRepr, LimbRepr, LimbRepr);
+
+/// Returns the int of the first `len % 5` limbs of `limbs`.
+fn int(limbs: Limbs, len: u8) -> ApInt {
+    let limbs = [limbs.0, limbs.1, limbs.2, limbs.3];
+    ApInt::from_limbs(&limbs[..usize::from(len % 5)])
+}
+
+#[test]
+fn prop_split_recombines() {
+    fn prop(limbs: Limbs, len: u8, mid: usize) -> bool {
+        let a = int(limbs, len);
+        let slice = a.as_slice();
+        let mid = mid % (slice.len() + 1);
+        let (low, high) = slice.split_at(mid);
+        (high.to_int() << (mid * LIMB_BITS)) + low.to_int() == a
+            && !low.is_negative()
+            && high.is_negative() == (a < ApInt::ZERO)
+            && low.len() + high.len() == slice.len()
+    }
+    qc::quickcheck(prop as fn(Limbs, u8, usize) -> bool);
+}
+
+#[test]
+fn prop_nested_windows() {
+    fn prop(limbs: Limbs, len: u8, i: usize, j: usize) -> bool {
+        let a = int(limbs, len);
+        let slice = a.as_slice();
+        let i = i % (slice.len() + 1);
+        let j = j % (slice.len() - i + 1);
+        // The limbs `i..i + j`, read as an unsigned value.
+        let window = slice.high(i).low(j);
+        let expected = (&a >> (i * LIMB_BITS)).mod_pow2(j * LIMB_BITS);
+        window.to_int() == expected && window.limbs() == &slice.limbs()[i..i + j]
+    }
+    qc::quickcheck(prop as fn(Limbs, u8, usize, usize) -> bool);
+}
+
+#[test]
+fn limbs_match_int() {
+    let a = ApInt::from_limbs(&[1, 2, LimbRepr::MAX]);
+    assert_eq!(a.as_slice().limbs(), &[1, 2, LimbRepr::MAX]);
+    assert_eq!(ApInt::ZERO.as_slice().limbs(), &[0]);
+}
+
+#[test]
+#[should_panic]
+fn split_out_of_bounds() {
+    ApInt::ONE.as_slice().split_at(2);
+}
+
+#[test]
+fn prop_heap_limbs_aligned() {
+    fn prop(limbs: Limbs, len: u8) -> bool {
+        let a = int(limbs, len);
+        let slice = a.as_slice();
+        slice.len() <= ApInt::INLINE_LIMBS
+            || slice.limbs().as_ptr() as usize % ApInt::LIMB_ALIGN == 0
+    }
+    qc::quickcheck(prop as fn(Limbs, u8) -> bool);
+}
+
+#[test]
+fn limb_align() {
+    let expected = if cfg!(feature = "align-64") {
+        64
+    } else if cfg!(feature = "align-32") {
+        32
+    } else {
+        core::mem::align_of::<LimbRepr>()
+    };
+    assert_eq!(ApInt::LIMB_ALIGN, expected);
+}
+
+#[test]
+fn inline_limbs() {
+    let expected = if cfg!(feature = "inline-4") {
+        4
+    } else if cfg!(feature = "inline-2") {
+        2
+    } else {
+        1
+    };
+    assert_eq!(ApInt::INLINE_LIMBS, expected);
+}
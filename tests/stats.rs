@@ -0,0 +1,52 @@
+#![cfg(feature = "stats")]
+
+use apa::ApInt;
+use apa::stats;
+
+#[test]
+fn counts_limb_muls() {
+    let x = ApInt::from(u128::MAX);
+    let y = ApInt::from(u128::MAX) << 64;
+
+    let before = stats::snapshot();
+    let _ = &x * &y;
+    let work = stats::snapshot().since(&before);
+
+    // Two limbs by three limbs, with the result allocated once.
+    assert_eq!(work.limb_muls, 6);
+    assert_eq!(work.allocs, 1);
+}
+
+#[test]
+fn counts_reallocs() {
+    // Large enough to be heap allocated however many limbs are inline.
+    let mut x = ApInt::from(u128::MAX) << 256;
+
+    stats::reset();
+    x <<= 1000;
+    x >>= 1000;
+    let work = stats::snapshot();
+
+    assert_eq!(work.limb_muls, 0);
+    assert!(work.allocs >= 2);
+}
+
+#[test]
+fn counters_are_per_thread() {
+    stats::reset();
+    let _ = ApInt::from(u128::MAX) * ApInt::from(u128::MAX);
+
+    let other = std::thread::spawn(stats::snapshot).join().unwrap();
+    assert_eq!(other, stats::Snapshot::default());
+    assert_ne!(stats::snapshot(), stats::Snapshot::default());
+}
+
+#[test]
+fn since_saturates_after_reset() {
+    let _ = ApInt::from(u128::MAX) * ApInt::from(u128::MAX);
+    let before = stats::snapshot();
+
+    stats::reset();
+    let work = stats::snapshot().since(&before);
+    assert_eq!(work, stats::Snapshot::default());
+}
@@ -0,0 +1,105 @@
+use apa::ApInt;
+use apa::tuning;
+
+/// Returns a pseudorandom int of `limbs` 64-bit limbs.
+fn noise(limbs: usize, seed: u64) -> ApInt {
+    let mut state = seed;
+    (0..limbs).fold(ApInt::ZERO, |acc, _| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (acc << 64) | ApInt::from(state)
+    })
+}
+
+/// Returns every product, quotient, remainder and decimal string of pairs of
+/// `operands`.
+fn results(operands: &[ApInt]) -> (Vec<ApInt>, Vec<(ApInt, ApInt)>, Vec<String>) {
+    let products = operands
+        .iter()
+        .flat_map(|x| operands.iter().map(move |y| x * y))
+        .collect();
+    let quotients = operands
+        .iter()
+        .flat_map(|x| operands.iter().map(move |y| (x * x + y).div_rem(y)))
+        .collect();
+    let strings = operands
+        .iter()
+        .flat_map(|x| operands.iter().map(move |y| (x * y).to_string()))
+        .collect();
+    (products, quotients, strings)
+}
+
+fn set_defaults() {
+    tuning::set_karatsuba_threshold(tuning::DEFAULT_KARATSUBA_THRESHOLD);
+    tuning::set_toom_threshold(tuning::DEFAULT_TOOM_THRESHOLD);
+    tuning::set_fft_threshold(tuning::DEFAULT_FFT_THRESHOLD);
+    tuning::set_dc_div_threshold(tuning::DEFAULT_DC_DIV_THRESHOLD);
+    tuning::set_dc_radix_threshold(tuning::DEFAULT_DC_RADIX_THRESHOLD);
+}
+
+// The thresholds are global, so every check that changes them is made in a
+// single test.
+#[test]
+fn thresholds_match_schoolbook() {
+    let sizes = [1, 2, 3, 5, 8, 13, 21, 34, 55, 89];
+    let operands: Vec<ApInt> = sizes
+        .iter()
+        .enumerate()
+        .flat_map(|(i, &n)| vec![noise(n, i as u64 + 1), -noise(n, i as u64 + 100)])
+        .collect();
+
+    tuning::set_karatsuba_threshold(usize::MAX);
+    tuning::set_dc_div_threshold(usize::MAX);
+    tuning::set_dc_radix_threshold(usize::MAX);
+    let expected = results(&operands);
+
+    // Each algorithm is checked alone, then with every algorithm at once.
+    let cases: &[(usize, usize, usize, usize, usize)] = &[
+        (0, usize::MAX, usize::MAX, usize::MAX, usize::MAX),
+        (3, usize::MAX, usize::MAX, usize::MAX, usize::MAX),
+        (7, usize::MAX, usize::MAX, usize::MAX, usize::MAX),
+        (2, 0, usize::MAX, usize::MAX, usize::MAX),
+        (2, 5, usize::MAX, usize::MAX, usize::MAX),
+        (16, 17, usize::MAX, usize::MAX, usize::MAX),
+        (2, usize::MAX, 1, usize::MAX, usize::MAX),
+        (2, usize::MAX, 13, usize::MAX, usize::MAX),
+        (usize::MAX, usize::MAX, usize::MAX, 0, usize::MAX),
+        (usize::MAX, usize::MAX, usize::MAX, 5, usize::MAX),
+        (usize::MAX, usize::MAX, usize::MAX, 12, usize::MAX),
+        (usize::MAX, usize::MAX, usize::MAX, usize::MAX, 0),
+        (usize::MAX, usize::MAX, usize::MAX, usize::MAX, 3),
+        (usize::MAX, usize::MAX, usize::MAX, usize::MAX, 8),
+        (4, 6, 30, 6, 4),
+    ];
+    for &(karatsuba, toom, fft, dc_div, dc_radix) in cases {
+        tuning::set_karatsuba_threshold(karatsuba);
+        tuning::set_toom_threshold(toom);
+        tuning::set_fft_threshold(fft);
+        tuning::set_dc_div_threshold(dc_div);
+        tuning::set_dc_radix_threshold(dc_radix);
+        assert_eq!(tuning::karatsuba_threshold(), karatsuba.max(2));
+        assert_eq!(tuning::toom_threshold(), toom.max(3));
+        assert_eq!(tuning::fft_threshold(), fft);
+        assert_eq!(tuning::dc_div_threshold(), dc_div.max(4));
+        assert_eq!(tuning::dc_radix_threshold(), dc_radix.max(2));
+
+        assert!(
+            results(&operands) == expected,
+            "thresholds {:?}",
+            (karatsuba, toom, fft, dc_div, dc_radix)
+        );
+    }
+
+    #[cfg(feature = "std")]
+    {
+        let thresholds = tuning::tune();
+        assert_eq!(tuning::karatsuba_threshold(), thresholds.karatsuba);
+        assert_eq!(tuning::toom_threshold(), thresholds.toom);
+        assert_eq!(tuning::fft_threshold(), thresholds.fft);
+        assert_eq!(tuning::dc_div_threshold(), thresholds.dc_div);
+        assert_eq!(tuning::dc_radix_threshold(), thresholds.dc_radix);
+    }
+
+    set_defaults();
+}
@@ -0,0 +1,26 @@
+// JavaScript `BigInt`s are only available when running in a JavaScript host,
+// for example with `wasm-pack test --node -- --features wasm`.
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+use apa::ApInt;
+use js_sys::BigInt;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen_test]
+fn bigint_round_trip() {
+    for &n in &[0, 1, -1, 255, -256, i128::MAX, i128::MIN] {
+        let int = ApInt::from(n) << 100;
+        let big = BigInt::from(&int);
+        assert_eq!(big.to_string(10).unwrap(), int.to_string().as_str());
+        assert_eq!(ApInt::from(big), int);
+    }
+}
+
+#[wasm_bindgen_test]
+fn bigint_from_js() {
+    let big = BigInt::new(&"-123456789012345678901234567890".into()).unwrap();
+    assert_eq!(
+        ApInt::from(&big).to_string(),
+        "-123456789012345678901234567890"
+    );
+}
@@ -0,0 +1,190 @@
+use apa::{ApInt, FixedInt};
+
+mod qc;
+
+macro_rules! quickcheck_width {
+    ($($uty:ident, $ity:ident: $width:expr;)*) => {
+        $(
+            paste::item! {
+               #[test]
+               fn [< prop_ext_ $uty >] () {
+                    fn prop(n: i128) -> bool {
+                        let int = ApInt::from(n);
+
+                        int.trunc($width) == ApInt::from(n as $uty)
+                            && int.zext($width) == ApInt::from(n as $uty)
+                            && int.sext($width) == ApInt::from(n as $ity)
+                    }
+                    qc::quickcheck(prop as fn(i128) -> bool)
+               }
+
+               #[test]
+               fn [< prop_unsigned_ov_ $uty >] () {
+                    fn prop(l: $uty, r: $uty) -> bool {
+                        // Pass signed operands, since only the low bits matter.
+                        let (li, ri) = (ApInt::from(l as $ity), ApInt::from(r as $ity));
+
+                        let check = |(val, ov): ($uty, bool), int: (ApInt, bool)| int == (ApInt::from(val), ov);
+
+                        check(l.overflowing_add(r), li.uadd_ov(&ri, $width))
+                            && check(l.overflowing_sub(r), li.usub_ov(&ri, $width))
+                            && check(l.overflowing_mul(r), li.umul_ov(&ri, $width))
+                            && (r == 0 || (li.udiv(&ri, $width) == ApInt::from(l / r)
+                                && li.urem(&ri, $width) == ApInt::from(l % r)))
+                    }
+                    qc::quickcheck(prop as fn($uty, $uty) -> bool)
+               }
+
+               #[test]
+               fn [< prop_signed_ov_ $ity >] () {
+                    fn prop(l: $ity, r: $ity) -> bool {
+                        // Pass unsigned operands, since only the low bits matter.
+                        let (li, ri) = (ApInt::from(l as $uty), ApInt::from(r as $uty));
+
+                        let check = |(val, ov): ($ity, bool), int: (ApInt, bool)| int == (ApInt::from(val), ov);
+
+                        check(l.overflowing_add(r), li.sadd_ov(&ri, $width))
+                            && check(l.overflowing_sub(r), li.ssub_ov(&ri, $width))
+                            && check(l.overflowing_mul(r), li.smul_ov(&ri, $width))
+                            && (r == 0 || (check(l.overflowing_div(r), li.sdiv_ov(&ri, $width))
+                                && li.sdiv(&ri, $width) == ApInt::from(l.wrapping_div(r))
+                                && li.srem(&ri, $width) == ApInt::from(l.wrapping_rem(r))))
+                    }
+                    qc::quickcheck(prop as fn($ity, $ity) -> bool)
+               }
+
+               #[test]
+               fn [< prop_fixed_ $uty >] () {
+                    fn prop(l: $ity, r: $ity) -> bool {
+                        let (lf, rf) = (FixedInt::new(&ApInt::from(l), $width), FixedInt::new(&ApInt::from(r), $width));
+
+                        let check = |val: $uty, int: FixedInt| int.width() == $width && int.as_unsigned() == &ApInt::from(val);
+                        let (lu, ru) = (l as $uty, r as $uty);
+
+                        lf.to_signed() == ApInt::from(l)
+                            && check(lu, lf.clone())
+                            && check(lu.wrapping_add(ru), &lf + &rf)
+                            && check(lu.wrapping_sub(ru), &lf - &rf)
+                            && check(lu.wrapping_mul(ru), &lf * &rf)
+                            && check(lu.wrapping_neg(), -&lf)
+                            && lf.sadd_ov(&rf).1 == l.overflowing_add(r).1
+                            && lf.umul_ov(&rf).1 == lu.overflowing_mul(ru).1
+                            && (r == 0 || (check(lu / ru, lf.udiv(&rf))
+                                && check(l.wrapping_div(r) as $uty, lf.sdiv(&rf))
+                                && check(lu % ru, lf.urem(&rf))
+                                && check(l.wrapping_rem(r) as $uty, lf.srem(&rf))))
+                    }
+                    qc::quickcheck(prop as fn($ity, $ity) -> bool)
+               }
+            }
+        )*
+    };
+}
+
+quickcheck_width! {
+    u8, i8: 8;
+    u16, i16: 16;
+    u32, i32: 32;
+    u64, i64: 64;
+    u128, i128: 128;
+}
+
+macro_rules! quickcheck_rotate {
+    ($($uty:ident, $wide:ident: $width:expr;)*) => {
+        $(
+            paste::item! {
+               #[test]
+               fn [< prop_rotate_ $uty >] () {
+                    fn prop(n: $uty, amount: u16) -> bool {
+                        let int = ApInt::from(n);
+                        let amount = amount as usize;
+
+                        int.rotl(amount, $width) == ApInt::from(n.rotate_left(amount as u32 % $width))
+                            && int.rotr(amount, $width) == ApInt::from(n.rotate_right(amount as u32 % $width))
+                    }
+                    qc::quickcheck(prop as fn($uty, u16) -> bool)
+               }
+
+               #[test]
+               fn [< prop_funnel_shift_ $uty >] () {
+                    fn prop(high: $uty, low: $uty, amount: u16) -> bool {
+                        let (hi, li) = (ApInt::from(high), ApInt::from(low));
+                        let amount = amount as usize;
+
+                        let concat = ((high as $wide) << $width) | low as $wide;
+                        let shift = amount % $width;
+                        let fshl = ((concat << shift) >> $width) as $uty;
+                        let fshr = (concat >> shift) as $uty;
+
+                        hi.fshl(&li, amount, $width) == ApInt::from(fshl)
+                            && hi.fshr(&li, amount, $width) == ApInt::from(fshr)
+                    }
+                    qc::quickcheck(prop as fn($uty, $uty, u16) -> bool)
+               }
+            }
+        )*
+    };
+}
+
+quickcheck_rotate! {
+    u8, u16: 8;
+    u16, u32: 16;
+    u32, u64: 32;
+    u64, u128: 64;
+}
+
+#[test]
+fn rotate_wide() {
+    let int = ApInt::from(u128::MAX - 1);
+    assert_eq!(int.rotl(1, 128), ApInt::from(u128::MAX - 2));
+    assert_eq!(int.rotr(129, 128), ApInt::from(u128::MAX >> 1));
+    assert_eq!(
+        ApInt::from(1).rotr(1, 200),
+        ApInt::ONE.zext(200) + ApInt::from(-1).zext(199)
+    );
+}
+
+#[test]
+fn odd_widths() {
+    assert_eq!(ApInt::from(0b10110).sext(5), ApInt::from(-10));
+    assert_eq!(ApInt::from(0b10110).zext(5), ApInt::from(0b10110));
+    assert_eq!(ApInt::from(-1).zext(100), ApInt::from((1u128 << 100) - 1));
+    assert_eq!(
+        ApInt::from(1u128 << 99).sext(100),
+        ApInt::from(-(1i128 << 99))
+    );
+}
+
+#[test]
+fn wide_overflow() {
+    let max = ApInt::from(u128::MAX);
+    let (sum, ov) = max.uadd_ov(&ApInt::ONE, 192);
+    assert_eq!(sum, ApInt::from(u128::MAX) + ApInt::ONE);
+    assert!(!ov);
+
+    let (sum, ov) = max.uadd_ov(&ApInt::ONE, 128);
+    assert_eq!(sum, ApInt::ZERO);
+    assert!(ov);
+}
+
+#[test]
+#[should_panic(expected = "bit width must be non-zero")]
+fn zero_width() {
+    let _ = ApInt::ONE.trunc(0);
+}
+
+#[test]
+fn fixed_resize() {
+    let x = FixedInt::new(&ApInt::from(-3), 100);
+    assert_eq!(x.trunc(8).as_unsigned(), &ApInt::from(253));
+    assert_eq!(x.zext(200).to_signed(), ApInt::from(-3).zext(100));
+    assert_eq!(x.sext(200).to_signed(), ApInt::from(-3));
+    assert_eq!(x.rotl(1).to_signed(), ApInt::from(-5));
+    assert_eq!(x.rotr(1).to_signed(), ApInt::from(-2));
+}
+
+#[test]
+#[should_panic(expected = "bit widths must match: 8 and 16")]
+fn fixed_width_mismatch() {
+    let _ = &FixedInt::zero(8) + &FixedInt::zero(16);
+}